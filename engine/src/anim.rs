@@ -0,0 +1,459 @@
+//! Animation state machine.
+//!
+//! [`AnimGraph`] defines animation nodes and transitions between them
+//! with rules implementing [`AnimTransitionRule`].
+//! It is agnostic to what is animated, be it sprite frames, skeletal clips or sound cues.
+//! [`GraphAnimation`] component pairs shared graph with per-entity state.
+
+use std::{ops::Range, sync::Arc};
+
+use edict::{component::Component, system::Res, world::QueryRef};
+
+use crate::clocks::{ClockIndex, TimeSpan};
+
+/// General purpose animation graph.
+/// Runs any kind of animations in agnostic way.
+/// Specialized graph based animation systems can be built upon this.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AnimGraph<A, R, T = ()> {
+    /// Set of animation nodes of the graph.
+    /// Contain animation data and transition rules.
+    pub animations: Vec<AnimNode<A>>,
+
+    /// Set of transitions between animation nodes.
+    pub transitions: Vec<Transition<R, T>>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AnimNode<A> {
+    /// Animation data.
+    pub animation: A,
+
+    /// Duration of the animation.
+    pub span: TimeSpan,
+
+    /// Transitions associated with this node.
+    pub transitions: Vec<usize>,
+
+    /// How animation plays when its time is over.
+    #[serde(default)]
+    pub playback: PlaybackMode,
+}
+
+/// Playback of animation node when its time is over
+/// and no transition is performed.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{anim::{AnimGraph, AnimGraphState, AnimNode, CurrentAnimInfo, PlaybackMode}, clocks::TimeSpan};
+/// let positions = |playback| {
+///     let graph = AnimGraph::<(), fn(&(), &CurrentAnimInfo) -> bool> {
+///         animations: vec![AnimNode {
+///             animation: (),
+///             span: TimeSpan::from_millis(300),
+///             transitions: vec![],
+///             playback,
+///         }],
+///         transitions: vec![],
+///     };
+///
+///     let mut state = AnimGraphState::new(0);
+///     (0..6)
+///         .map(|_| {
+///             let result = state.animate(&(), &graph, TimeSpan::from_millis(100));
+///             result.position.as_millis()
+///         })
+///         .collect::<Vec<_>>()
+/// };
+///
+/// assert_eq!(positions(PlaybackMode::Once), [100, 200, 300, 300, 300, 300]);
+/// assert_eq!(positions(PlaybackMode::Loop), [100, 200, 0, 100, 200, 0]);
+/// assert_eq!(positions(PlaybackMode::PingPong), [100, 200, 300, 200, 100, 0]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlaybackMode {
+    /// Animation stops at the end.
+    #[default]
+    Once,
+
+    /// Animation restarts from the beginning.
+    Loop,
+
+    /// Animation plays backward to the beginning after reaching the end, then repeats.
+    PingPong,
+}
+
+impl<A> AnimNode<A> {
+    /// Returns duration of single playback cycle.
+    /// For [`PlaybackMode::PingPong`] cycle includes playing backward.
+    pub fn cycle(&self) -> TimeSpan {
+        match self.playback {
+            PlaybackMode::Once | PlaybackMode::Loop => self.span,
+            PlaybackMode::PingPong => self.span * 2,
+        }
+    }
+
+    /// Returns position in animation at time `elapsed` within the cycle.
+    pub fn position(&self, elapsed: TimeSpan) -> TimeSpan {
+        match self.playback {
+            PlaybackMode::PingPong if elapsed > self.span => self.cycle() - elapsed,
+            _ => elapsed,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Transition<R, T = ()> {
+    /// Rule to perform this transition.
+    pub rule: R,
+
+    /// Target animation node of the transition.
+    pub target: usize,
+
+    /// Transition data.
+    pub transition: T,
+}
+
+mod transition_serde {
+    use serde::{de::*, ser::*};
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    #[serde(bound(deserialize = "R: Deserialize<'de>, T: Deserialize<'de>"))]
+    struct Partial<R, T> {
+        pub rule: R,
+        pub target: usize,
+
+        #[serde(default)]
+        pub transition: Option<T>,
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    struct Full<R, T> {
+        pub rule: R,
+        pub target: usize,
+        pub transition: T,
+    }
+
+    impl<R, T> Serialize for super::Transition<R, T>
+    where
+        R: Serialize,
+        T: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            if serde_nothing::is_nothing(&self.transition) {
+                let mut serializer = serializer.serialize_struct("Transition", 2)?;
+                serializer.serialize_field("rule", &self.rule)?;
+                serializer.serialize_field("target", &self.target)?;
+                serializer.end()
+            } else {
+                let mut serializer = serializer.serialize_struct("Transition", 2)?;
+                serializer.serialize_field("rule", &self.rule)?;
+                serializer.serialize_field("target", &self.target)?;
+                serializer.serialize_field("transition", &self.transition)?;
+                serializer.end()
+            }
+        }
+    }
+
+    impl<'de, R, T> Deserialize<'de> for super::Transition<R, T>
+    where
+        R: Deserialize<'de>,
+        T: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            match serde_nothing::from_nothing() {
+                None => {
+                    let value = Full::deserialize(deserializer)?;
+
+                    Ok(Self {
+                        rule: value.rule,
+                        target: value.target,
+                        transition: value.transition,
+                    })
+                }
+                Some(default_transition) => {
+                    let value = Partial::deserialize(deserializer)?;
+
+                    Ok(Self {
+                        rule: value.rule,
+                        target: value.target,
+                        transition: value.transition.unwrap_or(default_transition),
+                    })
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CurrentAnimInfo {
+    pub span: TimeSpan,
+    pub elapsed: TimeSpan,
+}
+
+impl CurrentAnimInfo {
+    pub fn is_complete(&self) -> bool {
+        self.span == self.elapsed
+    }
+}
+
+/// Trait for animation transition rules.
+pub trait AnimTransitionRule<S> {
+    /// Checks if this rules matches state and current animation info.
+    fn matches(&self, state: &S, info: &CurrentAnimInfo) -> bool;
+}
+
+impl<F, S> AnimTransitionRule<S> for F
+where
+    F: Fn(&S, &CurrentAnimInfo) -> bool,
+{
+    fn matches(&self, state: &S, info: &CurrentAnimInfo) -> bool {
+        (*self)(state, info)
+    }
+}
+
+/// State which combined with graph gives animation state machine.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AnimGraphState {
+    /// Currently running animation node.
+    pub current_animation: usize,
+
+    /// How far into current animation's cycle.
+    pub current_animation_elapsed: TimeSpan,
+}
+
+pub struct AnimateResult<'a, A, T = ()> {
+    pub animation: &'a A,
+    pub span: TimeSpan,
+
+    /// Time elapsed in current playback cycle.
+    pub elapsed: TimeSpan,
+
+    /// Position in animation, see [`AnimNode::position`].
+    pub position: TimeSpan,
+    pub transition: Option<&'a T>,
+}
+
+impl AnimGraphState {
+    /// Returns new animation graph state instance with specified state
+    /// and started with `entry_animation`.
+    pub fn new(entry_animation: usize) -> Self {
+        AnimGraphState {
+            current_animation: entry_animation,
+            current_animation_elapsed: TimeSpan::ZERO,
+        }
+    }
+
+    /// Runs animation and transitions.
+    ///
+    /// Uses current state to decide on transitions according to rules in the graph.
+    pub fn animate<'a, S, A, R, T>(
+        &mut self,
+        state: &S,
+        graph: &'a AnimGraph<A, R, T>,
+        span: TimeSpan,
+    ) -> AnimateResult<'a, A, T>
+    where
+        R: AnimTransitionRule<S>,
+    {
+        self.animate_with(state, graph, span, |_, _, _| {})
+    }
+
+    /// Runs animation and transitions, reporting played time.
+    ///
+    /// `played` is called in order with index of animation node, the node
+    /// and non-empty range of node's cycle time played,
+    /// including nodes passed through with transitions within `span`
+    /// and every repeated cycle.
+    /// Consecutive calls report adjacent ranges, so points in time are never reported twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::{anim::{AnimGraph, AnimGraphState, AnimNode, CurrentAnimInfo, PlaybackMode, Transition}, clocks::TimeSpan};
+    /// let graph = AnimGraph {
+    ///     animations: vec![AnimNode {
+    ///         animation: (),
+    ///         span: TimeSpan::SECOND,
+    ///         transitions: vec![0],
+    ///         playback: PlaybackMode::Once,
+    ///     }],
+    ///     transitions: vec![Transition {
+    ///         rule: |_: &(), info: &CurrentAnimInfo| info.is_complete(),
+    ///         target: 0,
+    ///         transition: (),
+    ///     }],
+    /// };
+    ///
+    /// let mut state = AnimGraphState::new(0);
+    /// let mut played = Vec::new();
+    /// state.animate_with(&(), &graph, TimeSpan::from_millis(1500), |_, _, range| played.push(range));
+    ///
+    /// assert_eq!(
+    ///     played,
+    ///     [TimeSpan::ZERO..TimeSpan::SECOND, TimeSpan::ZERO..TimeSpan::from_millis(500)],
+    /// );
+    /// ```
+    pub fn animate_with<'a, S, A, R, T>(
+        &mut self,
+        state: &S,
+        graph: &'a AnimGraph<A, R, T>,
+        span: TimeSpan,
+        mut played: impl FnMut(usize, &'a AnimNode<A>, Range<TimeSpan>),
+    ) -> AnimateResult<'a, A, T>
+    where
+        R: AnimTransitionRule<S>,
+    {
+        let mut span = span;
+        let mut last_transition = None;
+
+        'l: loop {
+            let current_animation = &graph.animations[self.current_animation];
+            let cycle = current_animation.cycle();
+            let time_left = cycle - self.current_animation_elapsed;
+            let start = self.current_animation_elapsed;
+            if span > time_left {
+                self.current_animation_elapsed = cycle;
+                span -= time_left;
+            } else {
+                self.current_animation_elapsed += span;
+                span = TimeSpan::ZERO;
+            }
+
+            if start < self.current_animation_elapsed {
+                played(
+                    self.current_animation,
+                    current_animation,
+                    start..self.current_animation_elapsed,
+                );
+            }
+
+            for &idx in &current_animation.transitions {
+                let transition = &graph.transitions[idx];
+                let matches = transition.rule.matches(
+                    state,
+                    &CurrentAnimInfo {
+                        span: cycle,
+                        elapsed: self.current_animation_elapsed,
+                    },
+                );
+
+                if matches {
+                    self.current_animation = transition.target;
+                    self.current_animation_elapsed = TimeSpan::ZERO;
+                    last_transition = Some(&transition.transition);
+                    continue 'l;
+                }
+            }
+
+            let repeats = current_animation.playback != PlaybackMode::Once;
+            if repeats && cycle > TimeSpan::ZERO && self.current_animation_elapsed == cycle {
+                self.current_animation_elapsed = TimeSpan::ZERO;
+                if span > TimeSpan::ZERO {
+                    continue 'l;
+                }
+            }
+
+            break;
+        }
+
+        let current_animation = &graph.animations[self.current_animation];
+
+        AnimateResult {
+            animation: &graph.animations[self.current_animation].animation,
+            span: current_animation.span,
+            elapsed: self.current_animation_elapsed,
+            position: current_animation.position(self.current_animation_elapsed),
+            transition: last_transition,
+        }
+    }
+}
+
+/// Component that runs animation graph.
+///
+/// Graph is shared between entities and state is per-entity.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Component)]
+#[edict(where A: 'static, R: 'static, T: 'static)]
+pub struct GraphAnimation<A, R, T = ()> {
+    graph: Arc<AnimGraph<A, R, T>>,
+    state: AnimGraphState,
+}
+
+impl<A, R, T> GraphAnimation<A, R, T> {
+    /// Returns new graph animation started with `entry_animation` node.
+    pub fn new(graph: Arc<AnimGraph<A, R, T>>, entry_animation: usize) -> Self {
+        assert!(
+            entry_animation < graph.animations.len(),
+            "Entry animation is out of bounds"
+        );
+
+        GraphAnimation {
+            graph,
+            state: AnimGraphState::new(entry_animation),
+        }
+    }
+
+    /// Returns animation graph.
+    pub fn graph(&self) -> &Arc<AnimGraph<A, R, T>> {
+        &self.graph
+    }
+
+    /// Returns animation graph state.
+    pub fn state(&self) -> &AnimGraphState {
+        &self.state
+    }
+
+    /// Returns currently running animation node.
+    pub fn current(&self) -> &AnimNode<A> {
+        &self.graph.animations[self.state.current_animation]
+    }
+
+    /// Advances animation by `span` performing transitions matching `state`.
+    pub fn animate<S>(&mut self, state: &S, span: TimeSpan) -> AnimateResult<'_, A, T>
+    where
+        R: AnimTransitionRule<S>,
+    {
+        self.state.animate(state, &self.graph, span)
+    }
+
+    /// Advances animation by `span` performing transitions matching `state`
+    /// and reporting played time as [`AnimGraphState::animate_with`] does.
+    pub fn animate_with<'a, S>(
+        &'a mut self,
+        state: &S,
+        span: TimeSpan,
+        played: impl FnMut(usize, &'a AnimNode<A>, Range<TimeSpan>),
+    ) -> AnimateResult<'a, A, T>
+    where
+        R: AnimTransitionRule<S>,
+    {
+        self.state.animate_with(state, &self.graph, span, played)
+    }
+}
+
+/// Advances all graph animations driven by state component `S`.
+///
+/// Animated values should be read from [`GraphAnimation::current`] and [`AnimGraphState`]
+/// by systems that apply them.
+pub fn graph_animation_system<S, A, R, T>(
+    query: QueryRef<(&S, &mut GraphAnimation<A, R, T>)>,
+    clock: Res<ClockIndex>,
+) where
+    S: Send + Sync + 'static,
+    A: Send + Sync + 'static,
+    R: AnimTransitionRule<S> + Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    let delta = clock.delta;
+    query.for_each(|(state, anim)| {
+        anim.animate(state, delta);
+    })
+}
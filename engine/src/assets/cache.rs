@@ -106,6 +106,11 @@ impl<A> AssetCache<A> {
         }
     }
 
+    /// Drops cached state of the asset.
+    pub fn remove(&mut self, id: AssetId) -> bool {
+        self.assets.remove(&id).is_some()
+    }
+
     pub fn cleanup(&mut self) {
         self.assets.retain(|_, state| match state {
             AssetState::Requested { polled, .. } => {
@@ -122,6 +127,8 @@ pub(super) trait AnyAssetCache: Send + Sync {
     fn type_id(&self) -> TypeId;
 
     fn cleanup(&mut self);
+
+    fn remove(&mut self, id: AssetId) -> bool;
 }
 
 impl<A> AnyAssetCache for AssetCache<A>
@@ -135,6 +142,10 @@ where
     fn cleanup(&mut self) {
         self.cleanup();
     }
+
+    fn remove(&mut self, id: AssetId) -> bool {
+        self.remove(id)
+    }
 }
 
 impl dyn AnyAssetCache {
@@ -1,11 +1,11 @@
+use std::sync::Arc;
+
 use fontdue::Font;
 use goods::{Asset, AssetId, TrivialAsset};
 use hashbrown::hash_map::{Entry, HashMap};
 use sierra::ImageView;
 
-use crate::rect::Rect;
-
-use super::ImageAsset;
+use crate::{graphics::Texture, rect::Rect};
 
 #[derive(Clone, Debug)]
 pub struct FontAsset {
@@ -33,14 +33,58 @@ impl TrivialAsset for FontAsset {
     }
 }
 
+/// Metrics of a glyph in MSDF font atlas.
+///
+/// Lengths are in ems, multiply by font size in pixels to get pixels.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GlyphMetrics {
+    /// Horizontal advance of the pen.
+    pub advance: f32,
+
+    /// Offset of the outline's bottom-left corner from the pen position.
+    pub bearing: [f32; 2],
+
+    /// Bounds of the glyph quad relative to the pen position.
+    /// Includes distance range padding around the outline.
+    pub plane: Rect,
+
+    /// Normalized rect of the glyph quad in the atlas.
+    /// Empty for glyphs without outline, e.g. space.
+    pub atlas: Rect,
+}
+
+/// Kerning adjustment between two glyphs, in ems.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct KerningPair {
+    pub left: u16,
+    pub right: u16,
+    pub kern: f32,
+}
+
+/// Font with MSDF atlas and glyph metrics.
+///
+/// Produced by `FontImporter` from TTF and OTF files.
 #[derive(Clone, Debug, Asset)]
 #[asset(name = "arcana.font-faces")]
 pub struct FontFaces {
     #[asset(external)]
     font: FontAsset,
-    #[asset(external)]
-    msdf: ImageAsset,
-    glyphs_uv: HashMap<u16, Rect>,
+
+    #[asset(container)]
+    msdf: Texture,
+
+    glyphs: HashMap<u16, GlyphMetrics>,
+
+    /// Sorted by left and right glyph.
+    #[serde(default = "no_kerning")]
+    kerning: Arc<[KerningPair]>,
+
+    #[serde(rename = "distance-range")]
+    distance_range: f32,
+}
+
+fn no_kerning() -> Arc<[KerningPair]> {
+    Arc::new([])
 }
 
 impl FontFaces {
@@ -48,12 +92,39 @@ impl FontFaces {
         &self.font.inner
     }
 
+    /// Returns MSDF atlas with glyph images.
     pub fn texture(&self) -> &ImageView {
-        self.msdf.get_ref()
+        &self.msdf.image
+    }
+
+    /// Returns MSDF atlas texture with its sampler.
+    pub fn atlas(&self) -> &Texture {
+        &self.msdf
+    }
+
+    /// Returns metrics of the glyph if it is present in the atlas.
+    pub fn glyph(&self, glyph: u16) -> Option<&GlyphMetrics> {
+        self.glyphs.get(&glyph)
     }
 
     pub fn glyph_uv(&self, glyph: u16) -> Option<Rect> {
-        self.glyphs_uv.get(&glyph).copied()
+        self.glyphs.get(&glyph).map(|metrics| metrics.atlas)
+    }
+
+    /// Returns kerning adjustment between two glyphs, in ems.
+    pub fn kerning(&self, left: u16, right: u16) -> f32 {
+        match self
+            .kerning
+            .binary_search_by_key(&(left, right), |pair| (pair.left, pair.right))
+        {
+            Ok(idx) => self.kerning[idx].kern,
+            Err(_) => 0.0,
+        }
+    }
+
+    /// Width of the distance field around glyph outlines in atlas pixels.
+    pub fn distance_range(&self) -> f32 {
+        self.distance_range
     }
 }
 
@@ -87,7 +158,8 @@ impl FontFacesCache {
                     );
                 }
 
-                for (glyph, uv) in &faces.glyphs_uv {
+                for (glyph, metrics) in &faces.glyphs {
+                    let uv = &metrics.atlas;
                     match self.glyphs_uv.get(&(font_idx, *glyph)) {
                         None => {
                             tracing::warn!("Missing glyph '{}' in cached font '{}'", glyph, id);
@@ -115,8 +187,9 @@ impl FontFacesCache {
                     Some(msdf_idx) => msdf_idx,
                 };
 
-                for (glyph, uv) in &faces.glyphs_uv {
-                    self.glyphs_uv.insert((font_idx, *glyph), (msdf_idx, *uv));
+                for (glyph, metrics) in &faces.glyphs {
+                    self.glyphs_uv
+                        .insert((font_idx, *glyph), (msdf_idx, metrics.atlas));
                 }
 
                 entry.insert((font_idx, msdf_idx));
@@ -1,7 +1,14 @@
 use std::sync::Arc;
 
 use goods::TrivialAsset;
-use rapid_qoi::DecodeError;
+use rapid_qoi::{Colors, DecodeError, Qoi};
+use sierra::Format;
+
+const QOI_HEADER_SIZE: usize = 14;
+const QOI_END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/// Maximum number of pixels encoded by single byte of QOI stream (`QOI_OP_RUN`).
+const QOI_MAX_PIXELS_PER_BYTE: usize = 62;
 
 #[derive(Clone)]
 pub struct QoiImage {
@@ -9,17 +16,172 @@ pub struct QoiImage {
     pub pixels: Arc<[u8]>,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum QoiDecodeError {
+    #[error("Invalid QOI header. {error:?}")]
+    Header { error: DecodeError },
+
+    #[error("QOI image has zero size {width}x{height}")]
+    ZeroSize { width: u32, height: u32 },
+
+    #[error("QOI image size {width}x{height} with {channels} channels overflows")]
+    SizeOverflow {
+        width: u32,
+        height: u32,
+        channels: usize,
+    },
+
+    #[error("QOI data of {len} bytes is too small for {width}x{height} image")]
+    TooSmall { len: usize, width: u32, height: u32 },
+
+    #[error("QOI data is truncated. End marker is missing")]
+    Truncated,
+
+    #[error("Failed to decode QOI image. {error:?}")]
+    Decode { error: DecodeError },
+}
+
+/// Decodes QOI image validating the data first.
+///
+/// Header and image size are checked before pixel buffer is allocated,
+/// so corrupted or truncated data results in error instead of huge allocation or garbage image.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::assets::image::{decode_qoi, QoiDecodeError};
+/// let qoi = rapid_qoi::Qoi {
+///     width: 4,
+///     height: 4,
+///     colors: rapid_qoi::Colors::Rgba,
+/// }
+/// .encode_alloc(&[0x7f; 4 * 4 * 4])
+/// .unwrap();
+///
+/// assert!(decode_qoi(&qoi).is_ok());
+///
+/// let truncated = &qoi[..qoi.len() - 4];
+/// assert!(matches!(decode_qoi(truncated), Err(QoiDecodeError::Truncated)));
+/// ```
+pub fn decode_qoi(bytes: &[u8]) -> Result<QoiImage, QoiDecodeError> {
+    let qoi = Qoi::decode_header(bytes).map_err(|error| QoiDecodeError::Header { error })?;
+
+    if qoi.width == 0 || qoi.height == 0 {
+        return Err(QoiDecodeError::ZeroSize {
+            width: qoi.width,
+            height: qoi.height,
+        });
+    }
+
+    let channels = match qoi.colors {
+        Colors::Rgb | Colors::Srgb => 3,
+        Colors::Rgba | Colors::SrgbLinA => 4,
+    };
+
+    let pixels = (qoi.width as usize)
+        .checked_mul(qoi.height as usize)
+        .filter(|pixels| pixels.checked_mul(channels).is_some())
+        .ok_or(QoiDecodeError::SizeOverflow {
+            width: qoi.width,
+            height: qoi.height,
+            channels,
+        })?;
+
+    if !bytes.ends_with(&QOI_END_MARKER) || bytes.len() < QOI_HEADER_SIZE + QOI_END_MARKER.len()
+    {
+        return Err(QoiDecodeError::Truncated);
+    }
+
+    // Every byte of the stream encodes a bounded number of pixels.
+    let stream_len = bytes.len() - QOI_HEADER_SIZE - QOI_END_MARKER.len();
+    if stream_len.saturating_mul(QOI_MAX_PIXELS_PER_BYTE) < pixels {
+        return Err(QoiDecodeError::TooSmall {
+            len: bytes.len(),
+            width: qoi.width,
+            height: qoi.height,
+        });
+    }
+
+    let (qoi, pixels) =
+        Qoi::decode_alloc(bytes).map_err(|error| QoiDecodeError::Decode { error })?;
+
+    Ok(QoiImage {
+        qoi,
+        pixels: pixels.into(),
+    })
+}
+
 impl TrivialAsset for QoiImage {
-    type Error = DecodeError;
+    type Error = QoiDecodeError;
 
     fn name() -> &'static str {
         "qoi"
     }
 
-    fn decode(bytes: Box<[u8]>) -> Result<Self, DecodeError> {
-        rapid_qoi::Qoi::decode_alloc(&bytes).map(|(qoi, pixels)| QoiImage {
-            qoi,
-            pixels: pixels.into(),
-        })
+    fn decode(bytes: Box<[u8]>) -> Result<Self, QoiDecodeError> {
+        decode_qoi(&bytes)
+    }
+}
+
+/// Image with pixels in GPU-compressed format and full mip chain.
+///
+/// Pixels are uploaded as is, without conversion.
+/// Produced by [`Ktx2Importer`](crate::assets::import::Ktx2Importer)
+/// as `arcana.compressed-image` artifact.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CompressedImage {
+    magic: u32,
+    pub format: Format,
+    pub width: u32,
+    pub height: u32,
+
+    /// Blocks of each mip level, starting from the largest.
+    pub levels: Vec<Arc<[u8]>>,
+}
+
+impl CompressedImage {
+    pub const MAGIC: u32 = u32::from_le_bytes(*b"arci");
+
+    pub fn new(format: Format, width: u32, height: u32, levels: Vec<Arc<[u8]>>) -> Self {
+        CompressedImage {
+            magic: Self::MAGIC,
+            format,
+            width,
+            height,
+            levels,
+        }
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum CompressedImageDecodeError {
+    #[error("Failed to verify magic number")]
+    MagicError,
+
+    #[error("Failed to deserialize compressed image")]
+    Deserialize {
+        #[source]
+        source: bincode::Error,
+    },
+
+    #[error("Compressed image has no mip levels")]
+    NoLevels,
+}
+
+/// Decodes compressed image produced by importer.
+pub fn decode_compressed_image(
+    bytes: &[u8],
+) -> Result<CompressedImage, CompressedImageDecodeError> {
+    if !bytes.starts_with(&CompressedImage::MAGIC.to_le_bytes()) {
+        return Err(CompressedImageDecodeError::MagicError);
+    }
+
+    let image: CompressedImage = bincode::deserialize(bytes)
+        .map_err(|source| CompressedImageDecodeError::Deserialize { source })?;
+
+    if image.levels.is_empty() {
+        return Err(CompressedImageDecodeError::NoLevels);
+    }
+
+    Ok(image)
+}
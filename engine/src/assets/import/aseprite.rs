@@ -4,6 +4,7 @@ use arcana_time::TimeSpan;
 use treasury_import::{Dependencies, Dependency, ImportError, Importer, Sources};
 
 use crate::{
+    anim::PlaybackMode,
     graphics::TextureInfo,
     sprite::{SpriteAnimation, SpriteFrame, SpriteRect, SpriteSheetInfo, SpriteSize},
 };
@@ -33,11 +34,16 @@ enum Format {
     RGBA8888,
 }
 
+/// Tagged range of frames.
 #[derive(serde::Deserialize)]
 struct FrameTag {
     name: String,
     from: usize,
     to: usize,
+
+    /// One of "forward", "reverse", "pingpong" or "pingpong_reverse".
+    #[serde(default)]
+    direction: String,
 }
 
 #[derive(serde::Deserialize)]
@@ -55,6 +61,126 @@ struct AsepriteSpriteSheet {
     meta: SpriteSheetMeta,
 }
 
+/// Error that can occur when parsing Aseprite spritesheet.
+#[derive(Debug, thiserror::Error)]
+pub enum AsepriteError {
+    #[error("Failed to parse Aseprite spritesheet JSON")]
+    Parse {
+        #[from]
+        source: serde_json::Error,
+    },
+
+    #[error("Frame '{index}' size does not match source")]
+    FrameSize { index: usize },
+}
+
+/// Frames and animations of Aseprite spritesheet.
+pub struct AsepriteSheet {
+    /// Path to the sheet image as written by Aseprite.
+    pub image: String,
+    pub tex_size: SpriteSize,
+    pub frames: Vec<SpriteFrame>,
+    pub animations: Vec<SpriteAnimation>,
+}
+
+/// Parses spritesheet JSON exported by Aseprite with "Array" frames layout.
+///
+/// Every frame tag becomes an animation.
+/// Tag direction is kept in animation features.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{anim::PlaybackMode, assets::import::parse_aseprite, TimeSpan};
+/// let json = r#"{
+///     "frames": [
+///         { "frame": { "x": 0, "y": 0, "w": 16, "h": 16 }, "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 }, "sourceSize": { "w": 16, "h": 16 }, "duration": 100 },
+///         { "frame": { "x": 16, "y": 0, "w": 16, "h": 16 }, "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 }, "sourceSize": { "w": 16, "h": 16 }, "duration": 150 },
+///         { "frame": { "x": 32, "y": 0, "w": 16, "h": 16 }, "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 }, "sourceSize": { "w": 16, "h": 16 }, "duration": 200 }
+///     ],
+///     "meta": {
+///         "image": "hero.png",
+///         "size": { "w": 48, "h": 16 },
+///         "frameTags": [
+///             { "name": "idle", "from": 0, "to": 0, "direction": "forward" },
+///             { "name": "walk", "from": 1, "to": 2, "direction": "pingpong" }
+///         ]
+///     }
+/// }"#;
+///
+/// let sheet = parse_aseprite(json.as_bytes()).unwrap();
+/// assert_eq!(sheet.image, "hero.png");
+///
+/// let spans = sheet.frames.iter().map(|frame| frame.span).collect::<Vec<_>>();
+/// assert_eq!(spans, [100u64, 150, 200].map(|ms| ms * TimeSpan::MILLISECOND));
+///
+/// assert_eq!(sheet.animations.len(), 2);
+/// let (idle, walk) = (&sheet.animations[0], &sheet.animations[1]);
+/// assert_eq!((&*idle.name, idle.from, idle.to), ("idle", 0, 0));
+/// assert_eq!(idle.playback, PlaybackMode::Once);
+/// assert_eq!((&*walk.name, walk.from, walk.to), ("walk", 1, 2));
+/// assert_eq!(walk.playback, PlaybackMode::PingPong);
+/// assert_eq!(walk.features["direction"], "pingpong");
+/// ```
+pub fn parse_aseprite(json: &[u8]) -> Result<AsepriteSheet, AsepriteError> {
+    let sprite_sheet: AsepriteSpriteSheet = serde_json::from_slice(json)?;
+
+    let frames = sprite_sheet
+        .frames
+        .iter()
+        .enumerate()
+        .map(|(index, frame)| {
+            if frame.frame.w != frame.sprite_source_size.w
+                || frame.frame.h != frame.sprite_source_size.h
+            {
+                return Err(AsepriteError::FrameSize { index });
+            }
+
+            Ok(SpriteFrame {
+                tex: frame.frame,
+                src: frame.sprite_source_size,
+                src_size: frame.source_size,
+                span: frame.duration_ms * TimeSpan::MILLISECOND,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    let animations = sprite_sheet
+        .meta
+        .frame_tags
+        .into_iter()
+        .map(|tag| {
+            let playback = match &*tag.direction {
+                "pingpong" | "pingpong_reverse" => PlaybackMode::PingPong,
+                _ => PlaybackMode::Once,
+            };
+
+            // Keep original direction so that runtime may play
+            // "reverse" and "pingpong_reverse" tags backward.
+            let features = if tag.direction.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::json!({ "direction": tag.direction })
+            };
+
+            SpriteAnimation {
+                name: tag.name.into(),
+                from: tag.from,
+                to: tag.to,
+                playback,
+                features,
+            }
+        })
+        .collect();
+
+    Ok(AsepriteSheet {
+        image: sprite_sheet.meta.image,
+        tex_size: sprite_sheet.meta.size,
+        frames,
+        animations,
+    })
+}
+
 impl Importer for SpriteSheetImporter {
     fn name(&self) -> &str {
         "Aseprite spritesheet"
@@ -87,16 +213,15 @@ impl Importer for SpriteSheetImporter {
             ),
         })?;
 
-        let sprite_sheet: AsepriteSpriteSheet =
-            serde_json::from_slice(&source).map_err(|err| ImportError::Other {
-                reason: format!(
-                    "Failed to parse file: '{}' as AsepriteSpriteSheet. {:#}",
-                    source_path.display(),
-                    err,
-                ),
-            })?;
+        let sheet = parse_aseprite(&source).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to import Aseprite spritesheet '{}'. {:#}",
+                source_path.display(),
+                err,
+            ),
+        })?;
 
-        let image = match dependencies.get(&sprite_sheet.meta.image, "qoi") {
+        let image = match dependencies.get(&sheet.image, "qoi") {
             Err(err) => {
                 return Err(ImportError::Other {
                     reason: format!("Failed to fetch image of the spritesheet. {:#}", err),
@@ -105,7 +230,7 @@ impl Importer for SpriteSheetImporter {
             Ok(None) => {
                 return Err(ImportError::RequireDependencies {
                     dependencies: vec![Dependency {
-                        source: sprite_sheet.meta.image.clone(),
+                        source: sheet.image.clone(),
                         target: "qoi".to_owned(),
                     }],
                 })
@@ -113,48 +238,10 @@ impl Importer for SpriteSheetImporter {
             Ok(Some(id)) => id,
         };
 
-        let frames = sprite_sheet
-            .frames
-            .iter()
-            .enumerate()
-            .map(|(index, frame)| {
-                if frame.frame.w != frame.sprite_source_size.w {
-                    return Err(ImportError::Other {
-                        reason: format!("Frame '{}' width does not match source", index),
-                    });
-                }
-
-                if frame.frame.h != frame.sprite_source_size.h {
-                    return Err(ImportError::Other {
-                        reason: format!("Frame '{}' height does not match source", index),
-                    });
-                }
-
-                Ok(SpriteFrame {
-                    tex: frame.frame,
-                    src: frame.sprite_source_size,
-                    src_size: frame.source_size,
-                    span: frame.duration_ms * TimeSpan::MILLISECOND,
-                })
-            })
-            .collect::<Result<_, _>>()?;
-
-        let animations = sprite_sheet
-            .meta
-            .frame_tags
-            .into_iter()
-            .map(|tag| SpriteAnimation {
-                name: tag.name.into(),
-                from: tag.from,
-                to: tag.to,
-                features: serde_json::Value::Null,
-            })
-            .collect();
-
         let sprite_sheet = SpriteSheetInfo {
-            tex_size: sprite_sheet.meta.size,
-            frames,
-            animations,
+            tex_size: sheet.tex_size,
+            frames: sheet.frames,
+            animations: sheet.animations,
             texture: TextureInfo::image(goods::AssetId(image.value())),
             frame_distances: Arc::new([]),
         };
@@ -0,0 +1,557 @@
+//! Packing of separate images into single texture atlas.
+//!
+//! Atlas is described by a TOML file with `.atlas` extension
+//! that lists images and packing parameters.
+//!
+//! ```toml
+//! images = ["ship.png", "bullet.png"]
+//! directory = "effects"
+//! padding = 1
+//! extrude = 1
+//! power-of-two = true
+//! ```
+//!
+//! [`AtlasImporter`] produces atlas image and [`AtlasSpriteSheetImporter`]
+//! produces sprite sheet with a frame and single-frame animation per image,
+//! named after image file stem.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use arcana_time::TimeSpan;
+use image::RgbaImage;
+use treasury_import::{Dependencies, Dependency, ImportError, Importer, Sources};
+
+use crate::{
+    anim::PlaybackMode,
+    graphics::TextureInfo,
+    sprite::{SpriteAnimation, SpriteFrame, SpriteRect, SpriteSheetInfo, SpriteSize},
+};
+
+/// Default maximum width and height of the atlas.
+pub const DEFAULT_ATLAS_MAX_SIZE: u32 = 4096;
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct AtlasConfig {
+    /// Images to pack.
+    #[serde(default)]
+    images: Vec<String>,
+
+    /// Directory relative to the atlas file.
+    /// All PNG images in it are packed.
+    #[serde(default)]
+    directory: Option<PathBuf>,
+
+    /// Transparent pixels between images.
+    #[serde(default)]
+    padding: u32,
+
+    /// Number of times edge pixels of each image are repeated around it.
+    /// Prevents bleeding of neighbour images with linear filtering.
+    #[serde(default)]
+    extrude: u32,
+
+    /// Makes atlas dimensions powers of two.
+    #[serde(default)]
+    power_of_two: bool,
+
+    #[serde(default = "default_max_size")]
+    max_size: u32,
+}
+
+fn default_max_size() -> u32 {
+    DEFAULT_ATLAS_MAX_SIZE
+}
+
+/// Parameters of atlas packing.
+#[derive(Clone, Copy, Debug)]
+pub struct AtlasPacking {
+    /// Transparent pixels between images.
+    pub padding: u32,
+
+    /// Pixels reserved around each image for extrusion.
+    pub extrude: u32,
+
+    /// Makes atlas dimensions powers of two.
+    pub power_of_two: bool,
+
+    /// Maximum width and height of the atlas.
+    pub max_size: u32,
+}
+
+impl Default for AtlasPacking {
+    fn default() -> Self {
+        AtlasPacking {
+            padding: 0,
+            extrude: 0,
+            power_of_two: false,
+            max_size: DEFAULT_ATLAS_MAX_SIZE,
+        }
+    }
+}
+
+/// Images don't fit into atlas of maximum size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Images don't fit into {max_size}x{max_size} atlas")]
+pub struct AtlasOverflow {
+    pub max_size: u32,
+}
+
+/// Packs rectangles of specified sizes into atlas using shelf packing.
+///
+/// Returns size of the atlas and rect of each image in the atlas,
+/// excluding padding and extrusion.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{assets::import::{pack_atlas, AtlasPacking}, sprite::SpriteSize};
+/// let sizes = [SpriteSize { w: 32, h: 32 }; 3];
+/// let packing = AtlasPacking {
+///     padding: 1,
+///     extrude: 1,
+///     power_of_two: true,
+///     ..AtlasPacking::default()
+/// };
+///
+/// let (atlas, rects) = pack_atlas(&sizes, packing).unwrap();
+/// assert!(atlas.w.is_power_of_two() && atlas.h.is_power_of_two());
+///
+/// for (i, a) in rects.iter().enumerate() {
+///     assert_eq!((a.w, a.h), (32, 32));
+///     assert!(a.x >= 1 && a.x + a.w < atlas.w);
+///     assert!(a.y >= 1 && a.y + a.h < atlas.h);
+///
+///     for b in &rects[i + 1..] {
+///         let apart = a.x + a.w <= b.x || b.x + b.w <= a.x || a.y + a.h <= b.y || b.y + b.h <= a.y;
+///         assert!(apart);
+///     }
+/// }
+/// ```
+pub fn pack_atlas(
+    sizes: &[SpriteSize],
+    packing: AtlasPacking,
+) -> Result<(SpriteSize, Vec<SpriteRect>), AtlasOverflow> {
+    let overflow = AtlasOverflow {
+        max_size: packing.max_size,
+    };
+
+    // Cell occupied by an image includes extrusion on both sides
+    // and padding on the far side.
+    let border = packing.extrude * 2 + packing.padding;
+    let cell = |size: &SpriteSize| (size.w + border, size.h + border);
+
+    let area: u64 = sizes
+        .iter()
+        .map(|size| {
+            let (w, h) = cell(size);
+            w as u64 * h as u64
+        })
+        .sum();
+
+    let widest = sizes.iter().map(|size| cell(size).0).max().unwrap_or(0);
+
+    // Leading padding before the first column and row.
+    let mut width = ((area as f64).sqrt().ceil() as u32).max(widest) + packing.padding;
+    if packing.power_of_two {
+        width = width.next_power_of_two();
+    }
+    if width > packing.max_size {
+        return Err(overflow);
+    }
+
+    // Taller images first, so that shelves are filled tightly.
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&idx| std::cmp::Reverse((sizes[idx].h, sizes[idx].w)));
+
+    let mut rects = vec![
+        SpriteRect {
+            x: 0,
+            y: 0,
+            w: 0,
+            h: 0
+        };
+        sizes.len()
+    ];
+
+    let mut x = packing.padding;
+    let mut y = packing.padding;
+    let mut shelf = 0;
+
+    for idx in order {
+        let (w, h) = cell(&sizes[idx]);
+
+        if x + w > width {
+            x = packing.padding;
+            y += shelf;
+            shelf = 0;
+        }
+
+        rects[idx] = SpriteRect {
+            x: x + packing.extrude,
+            y: y + packing.extrude,
+            w: sizes[idx].w,
+            h: sizes[idx].h,
+        };
+
+        x += w;
+        shelf = shelf.max(h);
+    }
+
+    let mut height = y + shelf;
+    if packing.power_of_two {
+        height = height.next_power_of_two();
+    }
+    if height > packing.max_size {
+        return Err(overflow);
+    }
+
+    Ok((
+        SpriteSize {
+            w: width,
+            h: height,
+        },
+        rects,
+    ))
+}
+
+struct Atlas {
+    image: RgbaImage,
+    names: Vec<String>,
+    rects: Vec<SpriteRect>,
+}
+
+fn load_atlas(
+    source_path: &Path,
+    sources: &mut (impl Sources + ?Sized),
+) -> Result<Atlas, ImportError> {
+    let config = std::fs::read(source_path).map_err(|err| ImportError::Other {
+        reason: format!(
+            "Failed to open file: '{}'. {:#}",
+            source_path.display(),
+            err
+        ),
+    })?;
+
+    let config: AtlasConfig = toml::from_slice(&config).map_err(|err| ImportError::Other {
+        reason: format!(
+            "Failed to parse file: '{}' as atlas. {:#}",
+            source_path.display(),
+            err
+        ),
+    })?;
+
+    let mut images = config.images.clone();
+
+    if let Some(directory) = &config.directory {
+        let directory_path = match source_path.parent() {
+            Some(parent) => parent.join(directory),
+            None => directory.clone(),
+        };
+
+        let entries = std::fs::read_dir(&directory_path).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to read atlas directory '{}'. {:#}",
+                directory_path.display(),
+                err
+            ),
+        })?;
+
+        let mut directory_images = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|err| ImportError::Other {
+                    reason: format!(
+                        "Failed to read atlas directory '{}'. {:#}",
+                        directory_path.display(),
+                        err
+                    ),
+                })?
+                .path();
+
+            let is_png = path
+                .extension()
+                .map_or(false, |ext| ext.eq_ignore_ascii_case("png"));
+
+            if is_png {
+                // Images are registered as sources relative to atlas file,
+                // same as images listed explicitly.
+                let image = directory.join(path.file_name().unwrap());
+                let image = image.to_str().ok_or_else(|| ImportError::Other {
+                    reason: format!("Non UTF-8 atlas image path '{}'", image.display()),
+                })?;
+                directory_images.push(image.to_owned());
+            }
+        }
+
+        // Directory order is not specified. Keep atlas stable between imports.
+        directory_images.sort();
+        images.extend(directory_images);
+    }
+
+    let mut paths = Vec::new();
+    let mut missing_sources = Vec::new();
+
+    for image in &images {
+        let path = sources
+            .get_or_append(image, &mut missing_sources)
+            .map_err(|reason| ImportError::Other { reason })?;
+
+        if let Some(path) = path {
+            paths.push((image.clone(), path));
+        }
+    }
+
+    if !missing_sources.is_empty() {
+        return Err(ImportError::RequireSources {
+            sources: missing_sources,
+        });
+    }
+
+    let mut names = Vec::with_capacity(paths.len());
+    let mut images = Vec::with_capacity(paths.len());
+
+    for (source, path) in paths {
+        let image = image::open(&path).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to load image '{}' from file '{}'. {:#}",
+                source,
+                path.display(),
+                err
+            ),
+        })?;
+
+        let name = path.file_stem().map_or_else(
+            || source.clone(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+
+        names.push(name);
+        images.push(image.into_rgba8());
+    }
+
+    let sizes: Vec<_> = images
+        .iter()
+        .map(|image| SpriteSize {
+            w: image.width(),
+            h: image.height(),
+        })
+        .collect();
+
+    let packing = AtlasPacking {
+        padding: config.padding,
+        extrude: config.extrude,
+        power_of_two: config.power_of_two,
+        max_size: config.max_size,
+    };
+
+    let (size, rects) = pack_atlas(&sizes, packing).map_err(|err| ImportError::Other {
+        reason: format!("Failed to pack '{}'. {:#}", source_path.display(), err),
+    })?;
+
+    let mut atlas = RgbaImage::new(size.w, size.h);
+    for (image, rect) in images.iter().zip(&rects) {
+        blit_extruded(&mut atlas, image, rect, config.extrude);
+    }
+
+    Ok(Atlas {
+        image: atlas,
+        names,
+        rects,
+    })
+}
+
+/// Copies image into the rect of the atlas,
+/// repeating edge pixels `extrude` times around it.
+fn blit_extruded(atlas: &mut RgbaImage, image: &RgbaImage, rect: &SpriteRect, extrude: u32) {
+    if rect.w == 0 || rect.h == 0 {
+        return;
+    }
+
+    let extrude = extrude as i64;
+
+    for y in -extrude..rect.h as i64 + extrude {
+        for x in -extrude..rect.w as i64 + extrude {
+            let sx = x.clamp(0, rect.w as i64 - 1) as u32;
+            let sy = y.clamp(0, rect.h as i64 - 1) as u32;
+
+            let px = (rect.x as i64 + x) as u32;
+            let py = (rect.y as i64 + y) as u32;
+
+            atlas.put_pixel(px, py, *image.get_pixel(sx, sy));
+        }
+    }
+}
+
+/// Imports atlas image packed from images listed in `.atlas` file.
+pub struct AtlasImporter;
+
+impl Importer for AtlasImporter {
+    fn name(&self) -> &str {
+        "Atlas-to-QOI"
+    }
+
+    fn formats(&self) -> &[&str] {
+        &["arcana.atlas"]
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas"]
+    }
+
+    fn target(&self) -> &str {
+        "qoi"
+    }
+
+    fn import(
+        &self,
+        source_path: &Path,
+        output_path: &Path,
+        sources: &mut (impl Sources + ?Sized),
+        _dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> Result<(), ImportError> {
+        let atlas = load_atlas(source_path, sources)?;
+
+        let qoi = rapid_qoi::Qoi {
+            width: atlas.image.width(),
+            height: atlas.image.height(),
+            colors: rapid_qoi::Colors::SrgbLinA,
+        }
+        .encode_alloc(atlas.image.as_raw())
+        .map_err(|err| ImportError::Other {
+            reason: format!("Failed to encode QOI image. {:#}", err),
+        })?;
+
+        std::fs::write(output_path, &qoi).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to write atlas image into file: '{}'. {:#}",
+                output_path.display(),
+                err
+            ),
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Imports sprite sheet for atlas packed from images listed in `.atlas` file.
+///
+/// Texture of the sprite sheet is the atlas image imported by [`AtlasImporter`].
+pub struct AtlasSpriteSheetImporter;
+
+impl Importer for AtlasSpriteSheetImporter {
+    fn name(&self) -> &str {
+        "Atlas spritesheet"
+    }
+
+    fn formats(&self) -> &[&str] {
+        &["arcana.atlas"]
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas"]
+    }
+
+    fn target(&self) -> &str {
+        "arcana.spritesheet"
+    }
+
+    fn import(
+        &self,
+        source_path: &Path,
+        native_path: &Path,
+        sources: &mut (impl Sources + ?Sized),
+        dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> Result<(), ImportError> {
+        let file_name = source_path
+            .file_name()
+            .ok_or_else(|| ImportError::Other {
+                reason: format!("Atlas path '{}' has no file name", source_path.display()),
+            })?
+            .to_string_lossy()
+            .into_owned();
+
+        let image = match dependencies.get(&file_name, "qoi") {
+            Err(err) => {
+                return Err(ImportError::Other {
+                    reason: format!("Failed to fetch image of the atlas. {:#}", err),
+                })
+            }
+            Ok(None) => {
+                return Err(ImportError::RequireDependencies {
+                    dependencies: vec![Dependency {
+                        source: file_name,
+                        target: "qoi".to_owned(),
+                    }],
+                })
+            }
+            Ok(Some(id)) => id,
+        };
+
+        let atlas = load_atlas(source_path, sources)?;
+
+        let frames = atlas
+            .rects
+            .iter()
+            .map(|rect| SpriteFrame {
+                tex: *rect,
+                src: SpriteRect {
+                    x: 0,
+                    y: 0,
+                    w: rect.w,
+                    h: rect.h,
+                },
+                src_size: SpriteSize {
+                    w: rect.w,
+                    h: rect.h,
+                },
+                span: TimeSpan::ZERO,
+            })
+            .collect();
+
+        let animations = atlas
+            .names
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| SpriteAnimation {
+                name: name.into(),
+                from: index,
+                to: index,
+                playback: PlaybackMode::Once,
+                features: serde_json::Value::Null,
+            })
+            .collect();
+
+        let sprite_sheet = SpriteSheetInfo {
+            tex_size: SpriteSize {
+                w: atlas.image.width(),
+                h: atlas.image.height(),
+            },
+            frames,
+            animations,
+            texture: TextureInfo::image(goods::AssetId(image.value())),
+            frame_distances: Arc::new([]),
+        };
+
+        let mut output = std::fs::File::create(native_path).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to open file: '{}'. {:#}",
+                native_path.display(),
+                err
+            ),
+        })?;
+
+        serde_json::to_writer(&mut output, &sprite_sheet).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to write SpriteSheet into file: '{}'. {:#}",
+                native_path.display(),
+                err,
+            ),
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,65 +1,512 @@
+//! Import of TTF and OTF fonts.
+//!
+//! Glyph outlines are rendered into multi-channel signed distance field (MSDF) atlas,
+//! so that text stays sharp at any scale.
+//!
+//! [`FontAtlasImporter`] produces atlas image,
+//! [`TtfImporter`] produces font file used for text layout
+//! and [`FontImporter`] produces `arcana.font-faces` asset with glyph metrics
+//! that references both.
+
+use std::path::Path;
+
+use hashbrown::HashMap;
+use image::RgbaImage;
+use msdfgen::{Bitmap, FontExt, Framing, Rgb, Vector2, EDGE_THRESHOLD, OVERLAP_SUPPORT};
 use msdfgen_lib as _; // forces linking with msdfgen library
+use treasury_import::{Dependencies, Dependency, ImportError, Importer, Sources};
+
+use crate::{
+    assets::font::{FontFacesInfo, GlyphMetrics, KerningPair},
+    graphics::TextureInfo,
+    rect::Rect,
+    sprite::SpriteSize,
+};
+
+use super::{pack_atlas, AtlasOverflow, AtlasPacking, DEFAULT_ATLAS_MAX_SIZE};
 
-use notosans::REGULAR_TTF as FONT;
-use std::fs::File;
-use ttf_parser::Font;
+/// Configuration for font importers.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct FontImportConfig {
+    /// Size of one em in atlas pixels.
+    #[serde(default = "default_glyph_size")]
+    pub glyph_size: u32,
 
-use msdfgen::{Bitmap, FontExt, Range, EDGE_THRESHOLD, OVERLAP_SUPPORT};
+    /// Width of the distance field around glyph outlines in atlas pixels.
+    #[serde(default = "default_distance_range")]
+    pub distance_range: f32,
+
+    /// Characters to put into atlas in addition to printable ASCII.
+    #[serde(default)]
+    pub extra_chars: String,
+
+    /// Maximum width and height of the atlas.
+    #[serde(default = "default_max_size")]
+    pub max_size: u32,
+}
+
+fn default_glyph_size() -> u32 {
+    32
+}
+
+fn default_distance_range() -> f32 {
+    4.0
+}
+
+fn default_max_size() -> u32 {
+    DEFAULT_ATLAS_MAX_SIZE
+}
 
-fn main() {
-    let font = Font::from_data(&FONT, 0).unwrap();
-    let glyph = font.glyph_index('A').unwrap();
-    let mut shape = font.glyph_shape(glyph).unwrap();
+impl Default for FontImportConfig {
+    fn default() -> Self {
+        FontImportConfig {
+            glyph_size: default_glyph_size(),
+            distance_range: default_distance_range(),
+            extra_chars: String::new(),
+            max_size: default_max_size(),
+        }
+    }
+}
 
-    if !shape.validate() {
-        panic!("Invalid shape");
+impl FontImportConfig {
+    /// Returns characters to put into atlas.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        (' '..='~').chain(self.extra_chars.chars())
     }
-    shape.normalize();
+}
+
+/// MSDF atlas with glyph metrics generated from a font.
+pub struct FontAtlas {
+    pub image: RgbaImage,
+    pub glyphs: HashMap<u16, GlyphMetrics>,
+
+    /// Sorted by left and right glyph.
+    pub kerning: Vec<KerningPair>,
+
+    /// Width of the distance field around glyph outlines in atlas pixels.
+    pub distance_range: f32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FontAtlasError {
+    #[error("Failed to parse font. {reason}")]
+    Parse { reason: String },
+
+    #[error(transparent)]
+    Overflow(#[from] AtlasOverflow),
+}
+
+struct GlyphCell {
+    glyph: u16,
+    shape: Option<(msdfgen::Shape, msdfgen::Bound<f64>)>,
+    size: SpriteSize,
+}
+
+/// Generates MSDF atlas for characters listed in config.
+///
+/// Characters missing in the font are skipped.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::assets::import::{generate_font_atlas, FontImportConfig};
+/// let ttf = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/assets/4077TH.TTF")).unwrap();
+/// let atlas = generate_font_atlas(&ttf, &FontImportConfig::default()).unwrap();
+///
+/// let font = fontdue::Font::from_bytes(&ttf[..], Default::default()).unwrap();
+/// let a = atlas.glyphs[&font.lookup_glyph_index('A')];
+///
+/// assert!(a.atlas.width() > 0.0 && a.atlas.height() > 0.0);
+/// assert!(a.plane.width() > 0.0 && a.plane.height() > 0.0);
+/// assert!(a.advance > 0.1 && a.advance < 2.0);
+/// ```
+pub fn generate_font_atlas(
+    ttf: &[u8],
+    config: &FontImportConfig,
+) -> Result<FontAtlas, FontAtlasError> {
+    let font = fontdue::Font::from_bytes(ttf, fontdue::FontSettings::default()).map_err(|err| {
+        FontAtlasError::Parse {
+            reason: err.to_owned(),
+        }
+    })?;
 
-    let bounds = shape.get_bounds();
+    let face = ttf_parser::Face::from_slice(ttf, 0).map_err(|err| FontAtlasError::Parse {
+        reason: err.to_string(),
+    })?;
 
-    let width = 32;
-    let height = 32;
+    // Atlas pixels per font unit.
+    let scale = config.glyph_size as f64 / face.units_per_em() as f64;
+    let range = config.distance_range as f64;
 
-    let mut bitmap = Bitmap::new(width, height);
+    let mut glyph_ids = config
+        .chars()
+        .map(|ch| font.lookup_glyph_index(ch))
+        .filter(|&glyph| glyph != 0)
+        .collect::<Vec<_>>();
+    glyph_ids.sort_unstable();
+    glyph_ids.dedup();
 
-    println!("bounds: {:?}", bounds);
+    let cells = glyph_ids
+        .iter()
+        .map(|&glyph| {
+            let shape = face
+                .glyph_shape(ttf_parser::GlyphId(glyph))
+                .filter(|shape| shape.validate())
+                .map(|mut shape| {
+                    shape.normalize();
+                    shape.edge_coloring_simple(3.0, 0);
+                    let bounds = shape.get_bounds();
+                    (shape, bounds)
+                })
+                .filter(|(_, bounds)| bounds.right > bounds.left && bounds.top > bounds.bottom);
 
-    shape.edge_coloring_simple(3.0, 0);
+            let size = match &shape {
+                None => SpriteSize { w: 0, h: 0 },
+                Some((_, bounds)) => SpriteSize {
+                    w: ((bounds.right - bounds.left) * scale + 2.0 * range).ceil() as u32,
+                    h: ((bounds.top - bounds.bottom) * scale + 2.0 * range).ceil() as u32,
+                },
+            };
 
-    let framing = bounds
-        .autoframe(width, height, Range::Px(4.0), None)
-        .unwrap();
+            GlyphCell { glyph, shape, size }
+        })
+        .collect::<Vec<_>>();
 
-    println!("framing: {:?}", framing);
+    let outlined = cells
+        .iter()
+        .filter(|cell| cell.shape.is_some())
+        .collect::<Vec<_>>();
 
-    shape.generate_msdf(&mut bitmap, &framing, EDGE_THRESHOLD, OVERLAP_SUPPORT);
+    let sizes = outlined.iter().map(|cell| cell.size).collect::<Vec<_>>();
 
-    let mut output = File::create("A-msdf.png").unwrap();
-    bitmap.write_png(&mut output).unwrap();
+    let (atlas_size, rects) = pack_atlas(
+        &sizes,
+        AtlasPacking {
+            padding: 1,
+            extrude: 0,
+            power_of_two: true,
+            max_size: config.max_size,
+        },
+    )?;
+
+    let mut image = RgbaImage::new(atlas_size.w.max(1), atlas_size.h.max(1));
+    let mut atlas_rects = HashMap::with_capacity(rects.len());
+
+    for (cell, rect) in outlined.iter().zip(&rects) {
+        let (shape, bounds) = cell.shape.as_ref().unwrap();
+
+        // Outline is placed `range` pixels away from cell edges.
+        let framing = Framing::new(
+            range / scale,
+            Vector2::new(scale, scale),
+            Vector2::new(range / scale - bounds.left, range / scale - bounds.bottom),
+        );
+
+        let mut bitmap = Bitmap::<Rgb<f32>>::new(rect.w, rect.h);
+        shape.generate_msdf(&mut bitmap, &framing, EDGE_THRESHOLD, OVERLAP_SUPPORT);
+
+        let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        // Rows of the bitmap go from bottom to top,
+        // matching rect with bottom at smaller texture coordinate.
+        for y in 0..rect.h {
+            for x in 0..rect.w {
+                let pixel = bitmap.pixels()[(y * rect.w + x) as usize];
+                image.put_pixel(
+                    rect.x + x,
+                    rect.y + y,
+                    image::Rgba([to_u8(pixel.r), to_u8(pixel.g), to_u8(pixel.b), 255]),
+                );
+            }
+        }
+
+        let left = (bounds.left * scale - range) as f32 / config.glyph_size as f32;
+        let bottom = (bounds.bottom * scale - range) as f32 / config.glyph_size as f32;
+
+        let plane = Rect {
+            left,
+            right: left + rect.w as f32 / config.glyph_size as f32,
+            bottom,
+            top: bottom + rect.h as f32 / config.glyph_size as f32,
+        };
+
+        atlas_rects.insert(cell.glyph, (plane, Rect::from_pixels(*rect, atlas_size)));
+    }
+
+    let glyphs = cells
+        .iter()
+        .map(|cell| {
+            // Metrics for font size of 1 pixel are in ems.
+            let metrics = font.metrics_indexed(cell.glyph, 1.0);
+            let (plane, atlas) = atlas_rects.get(&cell.glyph).copied().unwrap_or_default();
+
+            let metrics = GlyphMetrics {
+                advance: metrics.advance_width,
+                bearing: [metrics.bounds.xmin, metrics.bounds.ymin],
+                plane,
+                atlas,
+            };
+            (cell.glyph, metrics)
+        })
+        .collect();
+
+    // Only pairs from the `kern` table where both glyphs are in the atlas are kept.
+    let units_per_em = face.units_per_em() as f32;
+    let mut kerning = Vec::new();
+    if let Some(kern) = face.tables().kern {
+        for subtable in kern.subtables {
+            if !subtable.horizontal || subtable.variable || subtable.has_cross_stream {
+                continue;
+            }
+            if let ttf_parser::kern::Format::Format0(subtable) = subtable.format {
+                for pair in subtable.pairs {
+                    let (left, right) = (pair.left().0, pair.right().0);
+                    if pair.value != 0
+                        && glyph_ids.binary_search(&left).is_ok()
+                        && glyph_ids.binary_search(&right).is_ok()
+                    {
+                        kerning.push(KerningPair {
+                            left,
+                            right,
+                            kern: pair.value as f32 / units_per_em,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    kerning.sort_by_key(|pair| (pair.left, pair.right));
+    kerning.dedup_by_key(|pair| (pair.left, pair.right));
+
+    Ok(FontAtlas {
+        image,
+        glyphs,
+        kerning,
+        distance_range: config.distance_range,
+    })
 }
 
-impl Importer for FontImporter {
+fn read_font(source_path: &Path) -> Result<Vec<u8>, ImportError> {
+    std::fs::read(source_path).map_err(|err| ImportError::Other {
+        reason: format!(
+            "Failed to read font file '{}'. {:#}",
+            source_path.display(),
+            err
+        ),
+    })
+}
+
+fn font_atlas(source_path: &Path, config: &FontImportConfig) -> Result<FontAtlas, ImportError> {
+    let ttf = read_font(source_path)?;
+    generate_font_atlas(&ttf, config).map_err(|err| ImportError::Other {
+        reason: format!(
+            "Failed to generate atlas for font '{}'. {:#}",
+            source_path.display(),
+            err
+        ),
+    })
+}
+
+/// Imports font file used for text layout.
+pub struct TtfImporter;
+
+impl Importer for TtfImporter {
     fn name(&self) -> &str {
-        "arcana.font"
+        "TTF"
     }
 
-    fn source(&self) -> &str {
-        "ttf"
+    fn formats(&self) -> &[&str] {
+        &["ttf"]
     }
 
-    fn native(&self) -> &str {
+    fn extensions(&self) -> &[&str] {
+        &["ttf", "otf"]
+    }
+
+    fn target(&self) -> &str {
         "arcana.font"
     }
 
+    fn import(
+        &self,
+        source_path: &Path,
+        output_path: &Path,
+        _sources: &mut (impl Sources + ?Sized),
+        _dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> Result<(), ImportError> {
+        let ttf = read_font(source_path)?;
+
+        if let Err(err) = fontdue::Font::from_bytes(&*ttf, fontdue::FontSettings::default()) {
+            return Err(ImportError::Other {
+                reason: format!("Failed to parse font '{}'. {}", source_path.display(), err),
+            });
+        }
+
+        std::fs::write(output_path, &ttf).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to write font into file: '{}'. {:#}",
+                output_path.display(),
+                err
+            ),
+        })
+    }
+}
+
+/// Imports MSDF atlas image of a font.
+#[derive(Default)]
+pub struct FontAtlasImporter {
+    config: FontImportConfig,
+}
+
+impl FontAtlasImporter {
+    pub fn new(config: FontImportConfig) -> Self {
+        FontAtlasImporter { config }
+    }
+}
+
+impl Importer for FontAtlasImporter {
+    fn name(&self) -> &str {
+        "Font-to-MSDF"
+    }
+
+    fn formats(&self) -> &[&str] {
+        &["ttf"]
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ttf", "otf"]
+    }
+
+    fn target(&self) -> &str {
+        "qoi"
+    }
+
+    fn import(
+        &self,
+        source_path: &Path,
+        output_path: &Path,
+        _sources: &mut (impl Sources + ?Sized),
+        _dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> Result<(), ImportError> {
+        let atlas = font_atlas(source_path, &self.config)?;
+
+        let qoi = rapid_qoi::Qoi {
+            width: atlas.image.width(),
+            height: atlas.image.height(),
+            colors: rapid_qoi::Colors::Rgba,
+        }
+        .encode_alloc(atlas.image.as_raw())
+        .map_err(|err| ImportError::Other {
+            reason: format!("Failed to encode QOI image. {:#}", err),
+        })?;
+
+        std::fs::write(output_path, &qoi).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to write font atlas into file: '{}'. {:#}",
+                output_path.display(),
+                err
+            ),
+        })
+    }
+}
+
+/// Imports font with glyph metrics.
+///
+/// Atlas is imported by [`FontAtlasImporter`]
+/// and font file by [`TtfImporter`] from the same source.
+#[derive(Default)]
+pub struct FontImporter {
+    config: FontImportConfig,
+}
+
+impl FontImporter {
+    pub fn new(config: FontImportConfig) -> Self {
+        FontImporter { config }
+    }
+}
+
+impl Importer for FontImporter {
+    fn name(&self) -> &str {
+        "Font faces"
+    }
+
+    fn formats(&self) -> &[&str] {
+        &["ttf"]
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ttf", "otf"]
+    }
+
+    fn target(&self) -> &str {
+        "arcana.font-faces"
+    }
+
     fn import(
         &self,
         source_path: &Path,
         native_path: &Path,
-        _registry: &mut dyn Registry,
-    ) -> eyre::Result<()> {
+        _sources: &mut (impl Sources + ?Sized),
+        dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> Result<(), ImportError> {
+        let file_name = source_path
+            .file_name()
+            .ok_or_else(|| ImportError::Other {
+                reason: format!("Font path '{}' has no file name", source_path.display()),
+            })?
+            .to_string_lossy()
+            .into_owned();
+
+        let mut missing = Vec::new();
+        let mut ids = Vec::new();
+
+        for target in ["qoi", "arcana.font"] {
+            match dependencies.get(&file_name, target) {
+                Err(err) => {
+                    return Err(ImportError::Other {
+                        reason: format!("Failed to fetch '{}' of the font. {:#}", target, err),
+                    })
+                }
+                Ok(None) => missing.push(Dependency {
+                    source: file_name.clone(),
+                    target: target.to_owned(),
+                }),
+                Ok(Some(id)) => ids.push(goods::AssetId(id.value())),
+            }
+        }
+
+        if !missing.is_empty() {
+            return Err(ImportError::RequireDependencies {
+                dependencies: missing,
+            });
+        }
+
+        let atlas = font_atlas(source_path, &self.config)?;
+
+        let font = FontFacesInfo {
+            font: ids[1],
+            msdf: TextureInfo::image(ids[0]),
+            glyphs: atlas.glyphs,
+            kerning: atlas.kerning.into(),
+            distance_range: atlas.distance_range,
+        };
+
+        let mut output = std::fs::File::create(native_path).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to open file: '{}'. {:#}",
+                native_path.display(),
+                err
+            ),
+        })?;
 
-        
+        serde_json::to_writer(&mut output, &font).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to write font into file: '{}'. {:#}",
+                native_path.display(),
+                err,
+            ),
+        })?;
 
+        Ok(())
     }
 }
@@ -1,36 +1,135 @@
-#[derive(serde::Serialize)]
-enum SamplerOutput {
-    Scalar(Vec<f32>),
-    Vec2(Vec<[f32; 2]>),
-    Vec3(Vec<[f32; 3]>),
-    Vec4(Vec<[f32; 4]>),
-}
+use std::collections::HashMap;
 
-#[derive(serde::Serialize)]
-enum Property {
-    Translation,
-    Rotation,
-    Scale,
-    MorphTargetWeights,
-}
+use byteorder::{ByteOrder, LittleEndian};
+use gltf::{
+    accessor::{DataType, Dimensions},
+    animation::Property,
+    Gltf,
+};
 
-#[derive(serde::Serialize)]
-enum Interpolation {
-    Linear,
-    Step,
-    CubicSpline,
-}
+use crate::model::{
+    AnimationChannel, AnimationFileHeader, AnimationTarget, ChannelValues, Interpolation,
+};
+
+use super::{read_accessor, Error};
+
+/// Loads channels of the animation that target model's node or joints of its skin.
+/// Other channels and morph target weights are skipped.
+pub(super) fn load_animation(
+    animation: gltf::Animation,
+    mesh_node: &gltf::Node,
+    gltf: &Gltf,
+    buffers: &HashMap<usize, Box<[u8]>>,
+) -> Result<AnimationFileHeader, Error> {
+    let mut channels = Vec::new();
+
+    for channel in animation.channels() {
+        let node = channel.target().node();
+
+        let target = if node.index() == mesh_node.index() {
+            AnimationTarget::Root
+        } else {
+            let joint = mesh_node.skin().and_then(|skin| {
+                skin.joints()
+                    .position(|joint| joint.index() == node.index())
+            });
+
+            match joint {
+                Some(joint) => AnimationTarget::Joint(joint),
+                None => {
+                    tracing::warn!(
+                        "Animation {} targets node {} outside of the model",
+                        animation.index(),
+                        node.index(),
+                    );
+                    continue;
+                }
+            }
+        };
+
+        let sampler = channel.sampler();
 
-#[derive(serde::Serialize)]
-pub struct Channel {
-    joint: usize,
-    property: Property,
-    input: Vec<f32>,
-    output: SamplerOutput,
-    interpolation: Interpolation,
+        let interpolation = match sampler.interpolation() {
+            gltf::animation::Interpolation::Linear => Interpolation::Linear,
+            gltf::animation::Interpolation::Step => Interpolation::Step,
+            gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+        };
+
+        let times = read_floats::<1>(sampler.input(), &[Dimensions::Scalar], gltf, buffers)?
+            .into_iter()
+            .map(|[t]| t)
+            .collect();
+
+        let output = sampler.output();
+
+        let values = match channel.target().property() {
+            Property::Translation => ChannelValues::Translation(
+                read_floats::<3>(output, &[Dimensions::Vec3], gltf, buffers)?
+                    .into_iter()
+                    .map(na::Vector3::from)
+                    .collect(),
+            ),
+            Property::Rotation => ChannelValues::Rotation(
+                read_floats::<4>(output, &[Dimensions::Vec4], gltf, buffers)?
+                    .into_iter()
+                    .map(|[x, y, z, w]| na::Quaternion::new(w, x, y, z))
+                    .collect(),
+            ),
+            Property::Scale => ChannelValues::Scale(
+                read_floats::<3>(output, &[Dimensions::Vec3], gltf, buffers)?
+                    .into_iter()
+                    .map(na::Vector3::from)
+                    .collect(),
+            ),
+            Property::MorphTargetWeights => {
+                tracing::warn!("Morph target animations are not supported");
+                continue;
+            }
+        };
+
+        channels.push(AnimationChannel {
+            target,
+            interpolation,
+            times,
+            values,
+        });
+    }
+
+    Ok(AnimationFileHeader {
+        name: animation.name().map(str::to_owned),
+        channels,
+    })
 }
 
-#[derive(serde::Serialize)]
-pub struct Animation {
-    channels: Vec<Channel>,
+/// Reads accessor of float elements with `N` components.
+fn read_floats<const N: usize>(
+    accessor: gltf::Accessor,
+    expected: &'static [Dimensions],
+    gltf: &Gltf,
+    buffers: &HashMap<usize, Box<[u8]>>,
+) -> Result<Vec<[f32; N]>, Error> {
+    if !expected.contains(&accessor.dimensions()) {
+        return Err(Error::UnexpectedDimensions {
+            unexpected: accessor.dimensions(),
+            expected,
+        });
+    }
+
+    if accessor.data_type() != DataType::F32 {
+        return Err(Error::UnexpectedDataType {
+            unexpected: accessor.data_type(),
+            expected: &[DataType::F32],
+        });
+    }
+
+    let count = accessor.count();
+    let (bytes, stride) = read_accessor(accessor, gltf, buffers)?;
+
+    let mut elements = Vec::with_capacity(count);
+    for bytes in bytes.chunks(stride) {
+        let mut element = [0.0; N];
+        LittleEndian::read_f32_into(&bytes[..N * 4], &mut element);
+        elements.push(element);
+    }
+    Ok(elements)
 }
@@ -4,13 +4,38 @@ use gltf::Gltf;
 
 use crate::model::Collider;
 
-use super::Error;
+use super::{Error, GltfImportConfig};
 
 #[derive(Clone, Copy)]
 pub enum ColliderKind {
     Aabb,
     Convex,
     TriMesh,
+    ConvexDecomposition,
+}
+
+/// Parameters for approximate convex decomposition of concave collider meshes.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct VhacdConfig {
+    /// Resolution of the voxelization used for decomposition.
+    pub resolution: u32,
+
+    /// Maximum concavity of each convex part.
+    pub concavity: f32,
+
+    /// Maximum number of convex parts.
+    pub max_convex_hulls: u32,
+}
+
+impl Default for VhacdConfig {
+    fn default() -> Self {
+        VhacdConfig {
+            resolution: 64,
+            concavity: 0.01,
+            max_convex_hulls: 1024,
+        }
+    }
 }
 
 pub(super) fn load_collider(
@@ -18,6 +43,7 @@ pub(super) fn load_collider(
     kind: ColliderKind,
     gltf: &Gltf,
     buffers: &HashMap<usize, Box<[u8]>>,
+    config: &GltfImportConfig,
 ) -> Result<Collider, Error> {
     let reader = prim.reader(|buffer| match buffer.source() {
         gltf::buffer::Source::Bin => gltf.blob.as_deref(),
@@ -66,6 +92,45 @@ pub(super) fn load_collider(
 
             Ok(Collider::TriMesh { vertices, indices })
         }
+
+        ColliderKind::ConvexDecomposition => {
+            use parry3d::transformation::vhacd::{VHACDParameters, VHACD};
+
+            let vertices: Vec<_> = positions
+                .map(|[x, y, z]| na::Point3::from([x, y, z]))
+                .collect();
+
+            let indices: Vec<_> = match reader.read_indices() {
+                Some(indices) => triplets(indices.into_u32()),
+                None => triplets(0u32..vertices.len() as u32),
+            };
+
+            if indices.is_empty() {
+                return Err(Error::InvalidConvexShape);
+            }
+
+            let params = VHACDParameters {
+                resolution: config.vhacd.resolution,
+                concavity: config.vhacd.concavity,
+                max_convex_hulls: config.vhacd.max_convex_hulls,
+                ..VHACDParameters::default()
+            };
+
+            let decomposition = VHACD::decompose(&params, &vertices, &indices, true);
+
+            let shapes: Vec<_> = decomposition
+                .compute_exact_convex_hulls(&vertices, &indices)
+                .into_iter()
+                .map(|(points, _)| Collider::Convex { points })
+                .collect();
+
+            tracing::info!(
+                "Concave mesh decomposed into {} convex parts",
+                shapes.len()
+            );
+
+            Ok(Collider::Compound { shapes })
+        }
     }
 }
 
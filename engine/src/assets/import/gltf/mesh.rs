@@ -7,18 +7,20 @@ use crate::model::{Collider, PrimitiveInfo};
 use super::{
     collider::{load_collider, ColliderKind},
     primitive::load_primitive,
-    Error,
+    Error, GltfImportConfig,
 };
 
 pub struct Mesh {
     pub primitives: Vec<PrimitiveInfo>,
     pub colliders: Vec<Collider>,
+    pub morph_weights: Vec<f32>,
 }
 
 pub(super) fn load_mesh(
     mesh: gltf::Mesh,
     gltf: &Gltf,
     buffers: &HashMap<usize, Box<[u8]>>,
+    config: &GltfImportConfig,
     output: &mut Vec<u8>,
 ) -> Result<Mesh, Error> {
     let purpose = purpose(&mesh);
@@ -26,23 +28,38 @@ pub(super) fn load_mesh(
     let mut output_mesh = Mesh {
         primitives: Vec::new(),
         colliders: Vec::new(),
+        morph_weights: default_morph_weights(&mesh),
     };
 
     if purpose.render {
         output_mesh.primitives = mesh
             .primitives()
-            .map(|prim| load_primitive(prim, gltf, buffers, output))
+            .map(|prim| load_primitive(prim, gltf, buffers, config, output))
             .collect::<Result<_, _>>()?;
     }
     if let Some(collider) = purpose.collider {
         output_mesh.colliders = mesh
             .primitives()
-            .map(|prim| load_collider(prim, collider, gltf, buffers))
+            .map(|prim| load_collider(prim, collider, gltf, buffers, config))
             .collect::<Result<_, _>>()?;
     }
     Ok(output_mesh)
 }
 
+/// Returns default weights of morph targets.
+/// Weights not specified in the mesh are zero.
+fn default_morph_weights(mesh: &gltf::Mesh) -> Vec<f32> {
+    let count = mesh
+        .primitives()
+        .map(|prim| prim.morph_targets().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut weights = mesh.weights().map_or_else(Vec::new, <[f32]>::to_vec);
+    weights.resize(count.max(weights.len()), 0.0);
+    weights
+}
+
 #[derive(Clone, Copy)]
 struct MeshPurpose {
     render: bool,
@@ -72,6 +89,10 @@ fn purpose(mesh: &gltf::Mesh) -> MeshPurpose {
                         render: false,
                         collider: Some(ColliderKind::TriMesh),
                     },
+                    "vhacd" => MeshPurpose {
+                        render: false,
+                        collider: Some(ColliderKind::ConvexDecomposition),
+                    },
                     "draw+aabb" => MeshPurpose {
                         render: true,
                         collider: Some(ColliderKind::Aabb),
@@ -84,6 +105,10 @@ fn purpose(mesh: &gltf::Mesh) -> MeshPurpose {
                         render: true,
                         collider: Some(ColliderKind::TriMesh),
                     },
+                    "draw+vhacd" => MeshPurpose {
+                        render: true,
+                        collider: Some(ColliderKind::ConvexDecomposition),
+                    },
                     _ => MeshPurpose {
                         render: true,
                         collider: None,
@@ -1,13 +1,15 @@
-// mod animation;
+mod animation;
 mod collider;
 mod image;
 mod material;
 mod mesh;
+mod optimize;
 mod primitive;
 mod sampler;
+mod scene;
 mod skin;
 
-use std::{collections::HashMap, fs::File, io::Write, path::Path};
+use std::{collections::HashMap, path::Path};
 
 use gltf::{
     accessor::{DataType, Dimensions},
@@ -18,15 +20,45 @@ use sierra::SamplerInfo;
 use treasury_import::{Dependencies, Dependency, ImportError, Importer, Sources};
 
 use crate::{
-    assets::import::gltf::{material::load_material, mesh::load_mesh, skin::load_skin},
-    graphics::TextureInfo,
+    assets::import::gltf::{
+        animation::load_animation, material::load_material, mesh::load_mesh, skin::load_skin,
+    },
+    graphics::{MaterialInfo, TextureInfo},
     model::ModelFileHeader,
 };
 
 use self::sampler::load_sampler;
 
-/// Imports single object with one or more mesh primitives, colliders and animations (not yet).
-pub struct GltfModelImporter;
+pub use self::{collider::VhacdConfig, scene::GltfSceneImporter};
+
+/// Configuration for [`GltfModelImporter`].
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct GltfImportConfig {
+    /// Merge vertices with identical attributes and emit index buffer.
+    #[serde(default)]
+    pub weld_vertices: bool,
+
+    /// Reorder triangles to improve post-transform vertex cache hit rate.
+    /// Implies `weld_vertices`.
+    #[serde(default)]
+    pub optimize_vertex_cache: bool,
+
+    /// Parameters for convex decomposition of `.vhacd` collider meshes.
+    #[serde(default)]
+    pub vhacd: VhacdConfig,
+}
+
+/// Imports single object with one or more mesh primitives, colliders and animations.
+#[derive(Default)]
+pub struct GltfModelImporter {
+    config: GltfImportConfig,
+}
+
+impl GltfModelImporter {
+    pub fn new(config: GltfImportConfig) -> Self {
+        GltfModelImporter { config }
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 enum Error {
@@ -66,6 +98,9 @@ enum Error {
     #[error("Integer overflow")]
     IntegerOverflow,
 
+    #[error("Vertex index `{index}` is out of bounds of `{count}` vertices")]
+    IndexOutOfBound { index: u32, count: usize },
+
     #[error("Buffer refers to missing bin part of glTF")]
     MissingBin,
 }
@@ -80,7 +115,7 @@ impl Importer for GltfModelImporter {
     }
 
     fn extensions(&self) -> &[&str] {
-        &["json", "bin", "gltf"]
+        &["json", "bin", "gltf", "glb"]
     }
 
     fn target(&self) -> &str {
@@ -94,86 +129,18 @@ impl Importer for GltfModelImporter {
         sources: &mut (impl Sources + ?Sized),
         dependencies: &mut (impl Dependencies + ?Sized),
     ) -> Result<(), ImportError> {
-        let gltf = gltf::Gltf::open(source).map_err(|err| ImportError::Other {
-            reason: format!("Failed to open glTF file '{}'. {:#}", source.display(), err),
-        })?;
-
-        let mut missing_sources = Vec::new();
-        let mut missing_dependencies = Vec::new();
-
-        let mut buffers = HashMap::new();
-
-        for buffer in gltf.buffers() {
-            match buffer.source() {
-                gltf::buffer::Source::Bin => {}
-                gltf::buffer::Source::Uri(uri) => {
-                    let source_path = sources
-                        .get_or_append(uri, &mut missing_sources)
-                        .map_err(|reason| ImportError::Other { reason })?;
-
-                    if missing_sources.is_empty() {
-                        let source_path = source_path.as_deref().unwrap();
-                        let data =
-                            std::fs::read(source_path).map_err(|err| ImportError::Other {
-                                reason: format!(
-                                    "Failed to load source '{}' from file '{}'. {:#}",
-                                    uri,
-                                    source_path.display(),
-                                    err
-                                ),
-                            })?;
-                        buffers.insert(buffer.index(), data.into_boxed_slice());
-                    }
-                }
-            }
-        }
-
-        if !missing_sources.is_empty() {
-            return Err(ImportError::RequireSources {
-                sources: missing_sources,
-            });
-        }
-
-        let mut samplers = Vec::new();
-
-        for sampler in gltf.samplers() {
-            samplers.push(load_sampler(sampler));
-        }
-
-        let mut textures = Vec::new();
-
-        for texture in gltf.textures() {
-            if missing_dependencies.is_empty() {
-                if let Some(texture) =
-                    load_texture(texture, &samplers, dependencies, &mut missing_dependencies)
-                        .map_err(|reason| ImportError::Other { reason })?
-                {
-                    textures.push(texture);
-                }
-            } else {
-                get_missing_texture_dependencies(texture, dependencies, &mut missing_dependencies)
-                    .map_err(|reason| ImportError::Other { reason })?;
-            }
-        }
+        let document = load_document(source, sources, dependencies)?;
 
-        if !missing_dependencies.is_empty() {
-            return Err(ImportError::RequireDependencies {
-                dependencies: missing_dependencies,
-            });
-        }
-
-        let scene = gltf.default_scene().ok_or_else(|| ImportError::Other {
-            reason: "Unexpected glTF structure".to_owned(),
-        })?;
+        let scene = document
+            .gltf
+            .default_scene()
+            .ok_or_else(|| ImportError::Other {
+                reason: "Unexpected glTF structure".to_owned(),
+            })?;
         let root = scene.nodes().next().ok_or_else(|| ImportError::Other {
             reason: "Unexpected glTF structure".to_owned(),
         })?;
 
-        let materials: Vec<_> = gltf
-            .materials()
-            .map(|material| load_material(material, &textures))
-            .collect();
-
         let mesh_node = match root.mesh() {
             None => root
                 .children()
@@ -184,88 +151,219 @@ impl Importer for GltfModelImporter {
             Some(_) => root,
         };
 
-        let mut mesh_data = vec![0u8; 0];
+        let model = load_model(mesh_node, &document, &self.config, source)?;
 
-        let mesh = load_mesh(mesh_node.mesh().unwrap(), &gltf, &buffers, &mut mesh_data).map_err(
-            |err| ImportError::Other {
-                reason: format!(
-                    "Failed to load mesh {} from glTF '{}'. {:#}",
-                    mesh_node.index(),
-                    source.display(),
-                    err
-                ),
-            },
-        )?;
-
-        let mut model = match mesh_node.skin() {
-            None => ModelFileHeader {
-                magic: ModelFileHeader::MAGIC,
-                primitives: mesh.primitives,
-                colliders: mesh.colliders,
-                skin: None,
-                materials,
-            },
-            Some(skin) => {
-                let skin =
-                    load_skin(skin.clone(), &gltf, &buffers).map_err(|err| ImportError::Other {
+        std::fs::write(output, &model).map_err(|err| ImportError::Other {
+            reason: format!("Failed write model to '{}'. {:#}", output.display(), err),
+        })?;
+
+        Ok(())
+    }
+}
+
+/// glTF document with loaded buffers and resolved textures.
+struct Document {
+    gltf: Gltf,
+    buffers: HashMap<usize, Box<[u8]>>,
+    textures: Vec<TextureInfo>,
+}
+
+impl Document {
+    fn materials(&self) -> Vec<MaterialInfo> {
+        self.gltf
+            .materials()
+            .map(|material| load_material(material, &self.textures))
+            .collect()
+    }
+}
+
+fn load_document(
+    source: &Path,
+    sources: &mut (impl Sources + ?Sized),
+    dependencies: &mut (impl Dependencies + ?Sized),
+) -> Result<Document, ImportError> {
+    let gltf = gltf::Gltf::open(source).map_err(|err| ImportError::Other {
+        reason: format!("Failed to open glTF file '{}'. {:#}", source.display(), err),
+    })?;
+
+    let mut missing_sources = Vec::new();
+    let mut missing_dependencies = Vec::new();
+
+    let mut buffers = HashMap::new();
+
+    for buffer in gltf.buffers() {
+        match buffer.source() {
+            gltf::buffer::Source::Bin => {}
+            gltf::buffer::Source::Uri(uri) => {
+                let source_path = sources
+                    .get_or_append(uri, &mut missing_sources)
+                    .map_err(|reason| ImportError::Other { reason })?;
+
+                if missing_sources.is_empty() {
+                    let source_path = source_path.as_deref().unwrap();
+                    let data = std::fs::read(source_path).map_err(|err| ImportError::Other {
                         reason: format!(
-                            "Failed to load skin {} from glTF '{}'. {:#}",
-                            skin.index(),
-                            source.display(),
+                            "Failed to load source '{}' from file '{}'. {:#}",
+                            uri,
+                            source_path.display(),
                             err
                         ),
                     })?;
-                ModelFileHeader {
-                    magic: ModelFileHeader::MAGIC,
-                    primitives: mesh.primitives,
-                    colliders: mesh.colliders,
-                    skin: Some(skin),
-                    materials,
+                    buffers.insert(buffer.index(), data.into_boxed_slice());
                 }
             }
-        };
-
-        let mut output_file = File::create(output).map_err(|err| ImportError::Other {
-            reason: format!("Failed open output file '{}'. {:#}", output.display(), err),
-        })?;
+        }
+    }
 
-        let header_size = bincode::serialized_size(&model).map_err(|err| ImportError::Other {
-            reason: format!("Failed to determine size of the object header. {:#}", err),
-        })?;
+    if !missing_sources.is_empty() {
+        return Err(ImportError::RequireSources {
+            sources: missing_sources,
+        });
+    }
 
-        assert_eq!(header_size as usize as u64, header_size);
-        let header_size = header_size as usize;
+    let mut samplers = Vec::new();
 
-        for primitive in &mut model.primitives {
-            if let Some(indices) = &mut primitive.indices {
-                indices.offset += header_size;
-            }
+    for sampler in gltf.samplers() {
+        samplers.push(load_sampler(sampler));
+    }
 
-            for binding in &mut primitive.bindings {
-                binding.offset += header_size;
+    let mut textures = Vec::new();
+
+    for texture in gltf.textures() {
+        if missing_dependencies.is_empty() {
+            if let Some(texture) = load_texture(
+                texture,
+                source,
+                &samplers,
+                &gltf,
+                &buffers,
+                dependencies,
+                &mut missing_dependencies,
+            )
+            .map_err(|reason| ImportError::Other { reason })?
+            {
+                textures.push(texture);
             }
+        } else {
+            get_missing_texture_dependencies(
+                texture,
+                source,
+                &gltf,
+                &buffers,
+                dependencies,
+                &mut missing_dependencies,
+            )
+            .map_err(|reason| ImportError::Other { reason })?;
         }
+    }
 
-        bincode::serialize_into(&mut output_file, &model).map_err(|err| ImportError::Other {
-            reason: format!(
-                "Failed to serialize object header to '{}'. {:#}",
-                output.display(),
-                err
-            ),
-        })?;
+    if !missing_dependencies.is_empty() {
+        return Err(ImportError::RequireDependencies {
+            dependencies: missing_dependencies,
+        });
+    }
+
+    Ok(Document {
+        gltf,
+        buffers,
+        textures,
+    })
+}
+
+/// Loads model from node with mesh and serializes it into model file bytes.
+fn load_model(
+    mesh_node: gltf::Node,
+    document: &Document,
+    config: &GltfImportConfig,
+    source: &Path,
+) -> Result<Vec<u8>, ImportError> {
+    let mut mesh_data = vec![0u8; 0];
+
+    let mesh = load_mesh(
+        mesh_node.mesh().unwrap(),
+        &document.gltf,
+        &document.buffers,
+        config,
+        &mut mesh_data,
+    )
+    .map_err(|err| ImportError::Other {
+        reason: format!(
+            "Failed to load mesh {} from glTF '{}'. {:#}",
+            mesh_node.index(),
+            source.display(),
+            err
+        ),
+    })?;
+
+    let skin = match mesh_node.skin() {
+        None => None,
+        Some(skin) => Some(
+            load_skin(skin.clone(), &document.gltf, &document.buffers).map_err(|err| {
+                ImportError::Other {
+                    reason: format!(
+                        "Failed to load skin {} from glTF '{}'. {:#}",
+                        skin.index(),
+                        source.display(),
+                        err
+                    ),
+                }
+            })?,
+        ),
+    };
 
-        output_file
-            .write_all(&mesh_data)
+    let mut animations = Vec::new();
+    for animation in document.gltf.animations() {
+        let index = animation.index();
+        let animation = load_animation(animation, &mesh_node, &document.gltf, &document.buffers)
             .map_err(|err| ImportError::Other {
                 reason: format!(
-                    "Failed write mesh data to '{}'. {:#}",
-                    output.display(),
+                    "Failed to load animation {} from glTF '{}'. {:#}",
+                    index,
+                    source.display(),
                     err
                 ),
             })?;
 
-        Ok(())
+        if !animation.channels.is_empty() {
+            animations.push(animation);
+        }
+    }
+
+    let mut model = ModelFileHeader {
+        magic: ModelFileHeader::MAGIC,
+        primitives: mesh.primitives,
+        colliders: mesh.colliders,
+        animations,
+        skin,
+        materials: document.materials(),
+        morph_weights: mesh.morph_weights,
+    };
+
+    let header_size = bincode::serialized_size(&model).map_err(|err| ImportError::Other {
+        reason: format!("Failed to determine size of the object header. {:#}", err),
+    })?;
+
+    assert_eq!(header_size as usize as u64, header_size);
+    let header_size = header_size as usize;
+
+    for primitive in &mut model.primitives {
+        if let Some(indices) = &mut primitive.indices {
+            indices.offset += header_size;
+        }
+
+        for binding in &mut primitive.bindings {
+            binding.offset += header_size;
+        }
     }
+
+    let mut bytes = Vec::with_capacity(header_size + mesh_data.len());
+
+    bincode::serialize_into(&mut bytes, &model).map_err(|err| ImportError::Other {
+        reason: format!("Failed to serialize object header. {:#}", err),
+    })?;
+
+    bytes.extend_from_slice(&mesh_data);
+    Ok(bytes)
 }
 
 fn read_accessor<'a>(
@@ -302,6 +400,16 @@ fn read_accessor<'a>(
         return Err(Error::AccessorOutOfBound);
     }
 
+    let bytes = read_view(view, gltf, buffers)?;
+    let bytes = &bytes[accessor.offset()..][..accessor_size];
+    Ok((bytes, stride))
+}
+
+fn read_view<'a>(
+    view: gltf::buffer::View<'_>,
+    gltf: &'a Gltf,
+    buffers: &'a HashMap<usize, Box<[u8]>>,
+) -> Result<&'a [u8], Error> {
     let bytes = match view.buffer().source() {
         gltf::buffer::Source::Bin => gltf.blob.as_deref().ok_or(Error::MissingBin)?,
         gltf::buffer::Source::Uri(_) => &buffers[&view.buffer().index()],
@@ -312,8 +420,7 @@ fn read_accessor<'a>(
         return Err(Error::ViewOutOfBound);
     }
 
-    let bytes = &bytes[view.offset() + accessor.offset()..][..accessor_size];
-    Ok((bytes, stride))
+    Ok(&bytes[view.offset()..][..view.length()])
 }
 
 fn align_vec(bytes: &mut Vec<u8>, align_mask: usize) {
@@ -323,41 +430,122 @@ fn align_vec(bytes: &mut Vec<u8>, align_mask: usize) {
 
 fn load_texture(
     texture: gltf::Texture,
+    gltf_path: &Path,
     samplers: &[Option<SamplerInfo>],
+    gltf: &Gltf,
+    buffers: &HashMap<usize, Box<[u8]>>,
     dependencies: &mut (impl Dependencies + ?Sized),
     missing: &mut Vec<Dependency>,
 ) -> Result<Option<TextureInfo>, String> {
-    match texture.source().source() {
-        gltf::image::Source::View { .. } => unimplemented!(),
-        gltf::image::Source::Uri { uri, .. } => {
-            let image = dependencies.get_or_append(uri, "qoi", missing)?;
-
-            match image {
-                None => Ok(None),
-                Some(image) => {
-                    let image = goods::AssetId(image.value());
-                    let sampler = texture.sampler().index().and_then(|idx| samplers[idx]);
-                    let texture = match sampler {
-                        None => TextureInfo::image(image),
-                        Some(sampler) => TextureInfo { image, sampler },
-                    };
-                    Ok(Some(texture))
-                }
-            }
+    let source = image_source(texture.source(), gltf_path, gltf, buffers)?;
+    let image = dependencies.get_or_append(&source, "qoi", missing)?;
+
+    match image {
+        None => Ok(None),
+        Some(image) => {
+            let image = goods::AssetId(image.value());
+            let sampler = texture.sampler().index().and_then(|idx| samplers[idx]);
+            let texture = match sampler {
+                None => TextureInfo::image(image),
+                Some(sampler) => TextureInfo { image, sampler },
+            };
+            Ok(Some(texture))
         }
     }
 }
 
 fn get_missing_texture_dependencies(
     texture: gltf::Texture,
+    gltf_path: &Path,
+    gltf: &Gltf,
+    buffers: &HashMap<usize, Box<[u8]>>,
     dependencies: &mut (impl Dependencies + ?Sized),
     missing: &mut Vec<Dependency>,
 ) -> Result<(), String> {
-    match texture.source().source() {
-        gltf::image::Source::View { .. } => unimplemented!(),
-        gltf::image::Source::Uri { uri, .. } => {
-            dependencies.get_or_append(uri, "qoi", missing)?;
-            Ok(())
+    let source = image_source(texture.source(), gltf_path, gltf, buffers)?;
+    dependencies.get_or_append(&source, "qoi", missing)?;
+    Ok(())
+}
+
+/// Returns source of the image to import as texture dependency.
+///
+/// Images embedded into buffer views are written next to the glTF file
+/// into `<name>.images` directory and imported from there like any other image file.
+/// File name is derived from image content, so reimport finds the same source.
+fn image_source(
+    image: gltf::Image,
+    gltf_path: &Path,
+    gltf: &Gltf,
+    buffers: &HashMap<usize, Box<[u8]>>,
+) -> Result<String, String> {
+    match image.source() {
+        gltf::image::Source::Uri { uri, .. } => Ok(uri.to_owned()),
+        gltf::image::Source::View { view, mime_type } => {
+            let bytes = read_view(view, gltf, buffers).map_err(|err| {
+                format!(
+                    "Failed to read image {} from buffer view. {:#}",
+                    image.index(),
+                    err
+                )
+            })?;
+
+            // Image importer detects format by content, extension is a hint.
+            let extension = match mime_type {
+                "image/png" => "png",
+                "image/jpeg" => "jpg",
+                _ => {
+                    tracing::warn!(
+                        "Image {} has unexpected mime type '{}'",
+                        image.index(),
+                        mime_type
+                    );
+                    "png"
+                }
+            };
+
+            let stem = gltf_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| format!("Non UTF-8 glTF path '{}'", gltf_path.display()))?;
+
+            let uri = format!("{}.images/{:016x}.{}", stem, content_hash(bytes), extension);
+
+            // Existing file is rewritten if it was damaged.
+            let path = gltf_path.with_file_name(&uri);
+            if std::fs::read(&path).ok().as_deref() != Some(bytes) {
+                write_image(&path, bytes).map_err(|err| {
+                    format!(
+                        "Failed to write image {} to '{}'. {:#}",
+                        image.index(),
+                        path.display(),
+                        err
+                    )
+                })?;
+            }
+
+            Ok(uri)
         }
     }
 }
+
+/// Writes file via temporary file in the same directory,
+/// so that concurrent imports never observe partially written image.
+fn write_image(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let dir = path.parent().unwrap();
+    std::fs::create_dir_all(dir)?;
+
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path).map_err(|err| {
+        let _ = std::fs::remove_file(&tmp);
+        err
+    })
+}
+
+/// FNV-1a hash of the content.
+/// Unlike `DefaultHasher` it is stable across builds and platforms.
+fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,253 @@
+//! Vertex welding and index optimization for imported primitives.
+
+use std::collections::HashMap;
+
+use super::Error;
+
+/// Vertex attribute stream.
+pub(super) struct Stream<'a> {
+    pub bytes: &'a [u8],
+    pub element_size: usize,
+}
+
+/// Result of vertex welding.
+pub(super) struct Welded {
+    /// Original index of each unique vertex.
+    pub vertices: Vec<u32>,
+
+    /// Indices into unique vertices.
+    pub indices: Vec<u32>,
+}
+
+/// Merges vertices with bitwise identical attributes in all streams.
+///
+/// Unique vertices are ordered by first reference.
+/// Fails if any index is out of `count` vertices.
+pub(super) fn weld(
+    streams: &[Stream<'_>],
+    count: usize,
+    indices: Option<&[u32]>,
+) -> Result<Welded, Error> {
+    if let Some(&index) = indices
+        .into_iter()
+        .flatten()
+        .find(|&&index| index as usize >= count)
+    {
+        return Err(Error::IndexOutOfBound { index, count });
+    }
+
+    let mut unique = HashMap::<Vec<u8>, u32>::with_capacity(count);
+    let mut remap = vec![u32::MAX; count];
+
+    let mut vertices = Vec::new();
+    let mut key = Vec::new();
+
+    let mut weld_vertex = |index: u32| -> u32 {
+        let idx = index as usize;
+        if remap[idx] != u32::MAX {
+            return remap[idx];
+        }
+
+        key.clear();
+        for stream in streams {
+            key.extend_from_slice(
+                &stream.bytes[idx * stream.element_size..][..stream.element_size],
+            );
+        }
+
+        let new_index = *unique.entry(key.clone()).or_insert_with(|| {
+            vertices.push(index);
+            vertices.len() as u32 - 1
+        });
+
+        remap[idx] = new_index;
+        new_index
+    };
+
+    let indices = match indices {
+        None => (0..count as u32).map(&mut weld_vertex).collect(),
+        Some(indices) => indices.iter().map(|&index| weld_vertex(index)).collect(),
+    };
+
+    Ok(Welded { vertices, indices })
+}
+
+const CACHE_SIZE: usize = 32;
+const CACHE_DECAY_POWER: f32 = 1.5;
+const LAST_TRI_SCORE: f32 = 0.75;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+fn vertex_score(cache_position: usize, remaining: u32) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+
+    let cache_score = if cache_position >= CACHE_SIZE {
+        0.0
+    } else if cache_position < 3 {
+        LAST_TRI_SCORE
+    } else {
+        let scaled = (cache_position - 3) as f32 / (CACHE_SIZE - 3) as f32;
+        (1.0 - scaled).powf(CACHE_DECAY_POWER)
+    };
+
+    cache_score + VALENCE_BOOST_SCALE * (remaining as f32).powf(-VALENCE_BOOST_POWER)
+}
+
+/// Reorders triangles of a triangle list to improve post-transform vertex cache hit rate.
+///
+/// Uses Tom Forsyth's linear-speed vertex cache optimization.
+pub(super) fn optimize_vertex_cache(indices: &mut [u32], vertex_count: usize) {
+    let triangle_count = indices.len() / 3;
+    if triangle_count == 0 {
+        return;
+    }
+
+    let mut remaining = vec![0u32; vertex_count];
+    for &index in &indices[..triangle_count * 3] {
+        remaining[index as usize] += 1;
+    }
+
+    // Triangles adjacent to each vertex.
+    // Live triangles of vertex `v` are in `adjacency[offsets[v]..][..remaining[v]]`.
+    let mut offsets = Vec::with_capacity(vertex_count);
+    let mut total = 0;
+    for &count in &remaining {
+        offsets.push(total);
+        total += count as usize;
+    }
+
+    let mut adjacency = vec![0u32; total];
+    let mut filled = vec![0usize; vertex_count];
+    for (triangle, vertices) in indices.chunks_exact(3).enumerate() {
+        for &v in vertices {
+            let v = v as usize;
+            adjacency[offsets[v] + filled[v]] = triangle as u32;
+            filled[v] += 1;
+        }
+    }
+    drop(filled);
+
+    let mut cache_position = vec![usize::MAX; vertex_count];
+    let mut vertex_scores: Vec<f32> = remaining
+        .iter()
+        .map(|&remaining| vertex_score(usize::MAX, remaining))
+        .collect();
+
+    let mut emitted = vec![false; triangle_count];
+    let mut output = Vec::with_capacity(triangle_count * 3);
+
+    let mut cache = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut next_cache = Vec::with_capacity(CACHE_SIZE + 3);
+
+    let mut best = None;
+    let mut scan = 0;
+
+    for _ in 0..triangle_count {
+        let triangle = match best {
+            Some(triangle) => triangle,
+            None => {
+                while emitted[scan] {
+                    scan += 1;
+                }
+                scan
+            }
+        };
+
+        emitted[triangle] = true;
+        let vertices = [
+            indices[triangle * 3],
+            indices[triangle * 3 + 1],
+            indices[triangle * 3 + 2],
+        ];
+        output.extend_from_slice(&vertices);
+
+        for &v in &vertices {
+            let v = v as usize;
+            let live = &mut adjacency[offsets[v]..][..remaining[v] as usize];
+            if let Some(pos) = live.iter().position(|&t| t as usize == triangle) {
+                let last = live.len() - 1;
+                live.swap(pos, last);
+                remaining[v] -= 1;
+            }
+        }
+
+        next_cache.clear();
+        next_cache.extend_from_slice(&vertices);
+        next_cache.extend(cache.iter().copied().filter(|v| !vertices.contains(v)));
+
+        for (pos, &v) in next_cache.iter().enumerate() {
+            let v = v as usize;
+            cache_position[v] = if pos < CACHE_SIZE { pos } else { usize::MAX };
+            vertex_scores[v] = vertex_score(cache_position[v], remaining[v]);
+        }
+
+        best = None;
+        let mut best_score = f32::NEG_INFINITY;
+
+        for &v in &next_cache {
+            let v = v as usize;
+            for &t in &adjacency[offsets[v]..][..remaining[v] as usize] {
+                let t = t as usize;
+                let score: f32 = indices[t * 3..][..3]
+                    .iter()
+                    .map(|&v| vertex_scores[v as usize])
+                    .sum();
+
+                if score > best_score {
+                    best_score = score;
+                    best = Some(t);
+                }
+            }
+        }
+
+        next_cache.truncate(CACHE_SIZE);
+        std::mem::swap(&mut cache, &mut next_cache);
+    }
+
+    indices[..triangle_count * 3].copy_from_slice(&output);
+}
+
+/// Renumbers vertices in order of first reference in the index buffer
+/// to improve vertex fetch locality.
+///
+/// `vertices` maps new vertex index to the original one and is reordered accordingly.
+pub(super) fn optimize_vertex_fetch(indices: &mut [u32], vertices: &mut Vec<u32>) {
+    let mut remap = vec![u32::MAX; vertices.len()];
+    let mut reordered = Vec::with_capacity(vertices.len());
+
+    for index in indices.iter_mut() {
+        let old = *index as usize;
+        if remap[old] == u32::MAX {
+            remap[old] = reordered.len() as u32;
+            reordered.push(vertices[old]);
+        }
+        *index = remap[old];
+    }
+
+    *vertices = reordered;
+}
+
+/// Average number of vertex shader invocations per triangle for FIFO cache of specified size.
+pub(super) fn average_cache_miss_ratio(indices: &[u32], cache_size: usize) -> f32 {
+    let triangle_count = indices.len() / 3;
+    if triangle_count == 0 {
+        return 0.0;
+    }
+
+    let mut cache = std::collections::VecDeque::with_capacity(cache_size);
+    let mut misses = 0;
+
+    for &index in indices {
+        if !cache.contains(&index) {
+            misses += 1;
+            if cache.len() == cache_size {
+                cache.pop_front();
+            }
+            cache.push_back(index);
+        }
+    }
+
+    misses as f32 / triangle_count as f32
+}
@@ -1,9 +1,18 @@
 use crate::{
-    graphics::{BindingFileHeader, IndicesFileHeader, MeshFileVertexLayout},
+    graphics::{
+        BindingFileHeader, IndicesFileHeader, MeshBounds, MeshFileVertexLayout,
+        MorphTargetFileHeader,
+    },
     model::PrimitiveInfo,
 };
 
-use super::{align_vec, read_accessor, Error};
+use super::{
+    align_vec,
+    optimize::{
+        average_cache_miss_ratio, optimize_vertex_cache, optimize_vertex_fetch, weld, Stream,
+    },
+    read_accessor, Error, GltfImportConfig,
+};
 
 use byteorder::{ByteOrder, LittleEndian};
 use gltf::{
@@ -22,6 +31,7 @@ pub(super) fn load_primitive(
     prim: gltf::Primitive,
     gltf: &Gltf,
     buffers: &HashMap<usize, Box<[u8]>>,
+    config: &GltfImportConfig,
     output: &mut Vec<u8>,
 ) -> Result<PrimitiveInfo, Error> {
     let topology = match prim.mode() {
@@ -38,6 +48,7 @@ pub(super) fn load_primitive(
         gltf::mesh::Mode::TriangleFan => PrimitiveTopology::TriangleFan,
     };
 
+    let base = output.len();
     let vertices = load_vertices(gltf, buffers, prim.clone(), output)?;
 
     let mut count = vertices.count;
@@ -52,6 +63,15 @@ pub(super) fn load_primitive(
         })
         .transpose()?;
 
+    let (vertices, indices) = if config.weld_vertices || config.optimize_vertex_cache {
+        let (vertices, indices) =
+            optimize_primitive(vertices, indices, topology, config, base, output)?;
+        count = indices.count();
+        (vertices, Some(indices))
+    } else {
+        (vertices, indices)
+    };
+
     let count = count.try_into().map_err(|_| Error::IntegerOverflow)?;
     let vertex_count = vertices
         .count
@@ -105,6 +125,21 @@ pub(super) fn load_primitive(
         });
     }
 
+    let morph_targets = vertices
+        .morph_targets
+        .into_iter()
+        .map(|target| MorphTargetFileHeader {
+            positions: target.positions.map(|positions| BindingFileHeader {
+                offset: positions.start,
+                layout: MeshFileVertexLayout::Position3,
+            }),
+            normals: target.normals.map(|normals| BindingFileHeader {
+                offset: normals.start,
+                layout: MeshFileVertexLayout::Normal3,
+            }),
+        })
+        .collect();
+
     let indices = match indices {
         None => None,
         Some(IndicesAux::U16(range)) => Some(IndicesFileHeader {
@@ -125,6 +160,14 @@ pub(super) fn load_primitive(
         indices,
         topology,
         material: prim.material().index(),
+        morph_targets,
+        bounds: {
+            let bounds = prim.bounding_box();
+            Some(MeshBounds {
+                min: bounds.min.into(),
+                max: bounds.max.into(),
+            })
+        },
     })
 }
 
@@ -154,6 +197,28 @@ enum IndicesAux {
     U32(Range<usize>),
 }
 
+impl IndicesAux {
+    fn count(&self) -> usize {
+        match self {
+            IndicesAux::U16(range) => range.len() / size_of::<u16>(),
+            IndicesAux::U32(range) => range.len() / size_of::<u32>(),
+        }
+    }
+
+    fn read(&self, output: &[u8]) -> Vec<u32> {
+        match self {
+            IndicesAux::U16(range) => output[range.clone()]
+                .chunks_exact(size_of::<u16>())
+                .map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]]) as u32)
+                .collect(),
+            IndicesAux::U32(range) => output[range.clone()]
+                .chunks_exact(size_of::<u32>())
+                .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect(),
+        }
+    }
+}
+
 fn load_indices(
     gltf: &Gltf,
     buffers: &HashMap<usize, Box<[u8]>>,
@@ -520,9 +585,16 @@ struct Vertices {
     colors: Option<Range<usize>>,
     joints: Option<Range<usize>>,
     weights: Option<Range<usize>>,
+    morph_targets: Vec<MorphTargetRanges>,
     count: usize,
 }
 
+/// Position and normal deltas of single morph target.
+struct MorphTargetRanges {
+    positions: Option<Range<usize>>,
+    normals: Option<Range<usize>>,
+}
+
 fn load_vertices(
     gltf: &Gltf,
     buffers: &HashMap<usize, Box<[u8]>>,
@@ -592,6 +664,30 @@ fn load_vertices(
         })
         .transpose()?;
 
+    // Tangent deltas are not supported and are dropped.
+    let mut morph_targets = Vec::new();
+    for target in primitive.morph_targets() {
+        align_vec(output, 15);
+        let positions = target
+            .positions()
+            .map(|accessor| {
+                count = count.min(accessor.count());
+                load_vertex_attribute(VertexAttribute::Position3, gltf, buffers, accessor, output)
+            })
+            .transpose()?;
+
+        align_vec(output, 15);
+        let normals = target
+            .normals()
+            .map(|accessor| {
+                count = count.min(accessor.count());
+                load_vertex_attribute(VertexAttribute::Normal3, gltf, buffers, accessor, output)
+            })
+            .transpose()?;
+
+        morph_targets.push(MorphTargetRanges { positions, normals });
+    }
+
     Ok(Vertices {
         positions,
         normals,
@@ -600,10 +696,147 @@ fn load_vertices(
         colors,
         joints,
         weights,
+        morph_targets,
         count,
     })
 }
 
+/// Welds vertices and optionally optimizes index order of the primitive.
+/// Primitive data at the end of `output` starting from `base` is replaced with optimized data.
+fn optimize_primitive(
+    vertices: Vertices,
+    indices: Option<IndicesAux>,
+    topology: PrimitiveTopology,
+    config: &GltfImportConfig,
+    base: usize,
+    output: &mut Vec<u8>,
+) -> Result<(Vertices, IndicesAux), Error> {
+    let morph_target_count = vertices.morph_targets.len();
+
+    // Morph target deltas are welded and reordered along with other attributes,
+    // so vertices with different deltas are kept apart.
+    let mut ranges = vec![
+        Some(vertices.positions),
+        vertices.normals,
+        vertices.tangents,
+        vertices.uvs,
+        vertices.colors,
+        vertices.joints,
+        vertices.weights,
+    ];
+
+    let mut element_sizes = vec![
+        size_of::<[f32; 3]>(),
+        size_of::<[f32; 3]>(),
+        size_of::<[f32; 4]>(),
+        size_of::<[f32; 2]>(),
+        size_of::<[f32; 4]>(),
+        size_of::<[u32; 4]>(),
+        size_of::<[f32; 4]>(),
+    ];
+
+    for target in vertices.morph_targets {
+        ranges.push(target.positions);
+        ranges.push(target.normals);
+        element_sizes.push(size_of::<[f32; 3]>());
+        element_sizes.push(size_of::<[f32; 3]>());
+    }
+
+    let old_indices = indices.as_ref().map(|indices| indices.read(output));
+    let old_index_count = old_indices.as_ref().map_or(0, Vec::len);
+
+    let streams: Vec<_> = ranges
+        .iter()
+        .zip(element_sizes.iter().copied())
+        .filter_map(|(range, element_size)| {
+            Some(Stream {
+                bytes: &output[range.clone()?],
+                element_size,
+            })
+        })
+        .collect();
+
+    let mut welded = weld(&streams, vertices.count, old_indices.as_deref())?;
+
+    if config.optimize_vertex_cache && topology == PrimitiveTopology::TriangleList {
+        let acmr_before = average_cache_miss_ratio(&welded.indices, 16);
+        optimize_vertex_cache(&mut welded.indices, welded.vertices.len());
+        optimize_vertex_fetch(&mut welded.indices, &mut welded.vertices);
+        let acmr_after = average_cache_miss_ratio(&welded.indices, 16);
+
+        tracing::info!(
+            "Vertex cache optimized. ACMR: {:.3} -> {:.3}",
+            acmr_before,
+            acmr_after
+        );
+    }
+
+    tracing::info!(
+        "Primitive optimized. Vertices: {} -> {}. Indices: {} -> {}",
+        vertices.count,
+        welded.vertices.len(),
+        old_index_count,
+        welded.indices.len(),
+    );
+
+    let rebuilt: Vec<Option<Vec<u8>>> = ranges
+        .iter()
+        .zip(element_sizes.iter().copied())
+        .map(|(range, element_size)| {
+            let bytes = &output[range.clone()?];
+            let mut rebuilt = Vec::with_capacity(welded.vertices.len() * element_size);
+            for &vertex in &welded.vertices {
+                rebuilt.extend_from_slice(&bytes[vertex as usize * element_size..][..element_size]);
+            }
+            Some(rebuilt)
+        })
+        .collect();
+
+    output.truncate(base);
+
+    let mut ranges = rebuilt.into_iter().map(|bytes| {
+        let bytes = bytes?;
+        align_vec(output, 15);
+        let start = output.len();
+        output.extend_from_slice(&bytes);
+        Some(start..output.len())
+    });
+
+    let vertices = Vertices {
+        positions: ranges.next().flatten().unwrap(),
+        normals: ranges.next().flatten(),
+        tangents: ranges.next().flatten(),
+        uvs: ranges.next().flatten(),
+        colors: ranges.next().flatten(),
+        joints: ranges.next().flatten(),
+        weights: ranges.next().flatten(),
+        morph_targets: (0..morph_target_count)
+            .map(|_| MorphTargetRanges {
+                positions: ranges.next().flatten(),
+                normals: ranges.next().flatten(),
+            })
+            .collect(),
+        count: welded.vertices.len(),
+    };
+
+    align_vec(output, 15);
+    let start = output.len();
+
+    let indices = if welded.vertices.len() <= u16::MAX as usize {
+        for &index in &welded.indices {
+            output.extend_from_slice(&(index as u16).to_ne_bytes());
+        }
+        IndicesAux::U16(start..output.len())
+    } else {
+        for &index in &welded.indices {
+            output.extend_from_slice(&index.to_ne_bytes());
+        }
+        IndicesAux::U32(start..output.len())
+    };
+
+    Ok((vertices, indices))
+}
+
 fn u8_norm(v: u8) -> f32 {
     const U8_NORM: f32 = 1.0 / u8::MAX as f32;
     v as f32 * U8_NORM
@@ -0,0 +1,128 @@
+use std::{collections::HashMap, path::Path};
+
+use treasury_import::{Dependencies, ImportError, Importer, Sources};
+
+use crate::model::{ModelSceneFileHeader, SceneNode};
+
+use super::{load_document, load_model, GltfImportConfig};
+
+/// Imports whole glTF scene with node hierarchy, transforms and all referenced meshes.
+#[derive(Default)]
+pub struct GltfSceneImporter {
+    config: GltfImportConfig,
+}
+
+impl GltfSceneImporter {
+    pub fn new(config: GltfImportConfig) -> Self {
+        GltfSceneImporter { config }
+    }
+}
+
+impl Importer for GltfSceneImporter {
+    fn name(&self) -> &str {
+        "glTF scene"
+    }
+
+    fn formats(&self) -> &[&str] {
+        &["gltf"]
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json", "bin", "gltf", "glb"]
+    }
+
+    fn target(&self) -> &str {
+        "arcana.model-scene"
+    }
+
+    fn import(
+        &self,
+        source: &Path,
+        output: &Path,
+        sources: &mut (impl Sources + ?Sized),
+        dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> Result<(), ImportError> {
+        let document = load_document(source, sources, dependencies)?;
+
+        let scene = document
+            .gltf
+            .default_scene()
+            .or_else(|| document.gltf.scenes().next())
+            .ok_or_else(|| ImportError::Other {
+                reason: format!("glTF '{}' contains no scenes", source.display()),
+            })?;
+
+        let mut nodes = Vec::new();
+        let mut models = Vec::new();
+        let mut model_indices = HashMap::new();
+
+        // Nodes are stored in depth-first order so parents always precede children.
+        let mut stack: Vec<_> = scene.nodes().map(|node| (None, node)).collect();
+        stack.reverse();
+
+        while let Some((parent, node)) = stack.pop() {
+            let model = match node.mesh() {
+                None => None,
+                Some(mesh) => {
+                    let key = (mesh.index(), node.skin().map(|skin| skin.index()));
+                    match model_indices.get(&key) {
+                        Some(&index) => Some(index),
+                        None => {
+                            let index = models.len();
+                            let model = load_model(node.clone(), &document, &self.config, source)?;
+                            models.push(model);
+                            model_indices.insert(key, index);
+                            Some(index)
+                        }
+                    }
+                }
+            };
+
+            let (translation, rotation, scale) = node.transform().decomposed();
+            let [x, y, z, w] = rotation;
+
+            let index = nodes.len();
+            nodes.push(SceneNode {
+                name: node.name().map(str::to_owned),
+                parent,
+                iso: na::Isometry3::from_parts(
+                    na::Translation3::from(translation),
+                    na::UnitQuaternion::from_quaternion(na::Quaternion::new(w, x, y, z)),
+                ),
+                scale: na::Vector3::from(scale),
+                model,
+            });
+
+            let children: Vec<_> = node.children().collect();
+            for child in children.into_iter().rev() {
+                stack.push((Some(index), child));
+            }
+        }
+
+        tracing::info!(
+            "Imported glTF scene with {} nodes and {} models",
+            nodes.len(),
+            models.len()
+        );
+
+        let header = ModelSceneFileHeader {
+            magic: ModelSceneFileHeader::MAGIC,
+            nodes,
+            models: models.iter().map(Vec::len).collect(),
+        };
+
+        let mut bytes = bincode::serialize(&header).map_err(|err| ImportError::Other {
+            reason: format!("Failed to serialize scene header. {:#}", err),
+        })?;
+
+        for model in &models {
+            bytes.extend_from_slice(model);
+        }
+
+        std::fs::write(output, &bytes).map_err(|err| ImportError::Other {
+            reason: format!("Failed write scene to '{}'. {:#}", output.display(), err),
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,326 @@
+//! Incremental import.
+//!
+//! [`Incremental`] wraps an importer and skips re-import when the source,
+//! all sources it requested and all dependencies it referenced are unchanged.
+//! Import records and artifact copies are persisted in the cache directory.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::Hasher,
+    io::Read,
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use treasury_id::AssetId;
+use treasury_import::{Dependencies, ImportError, Importer, Sources};
+
+/// Persistent storage of import records.
+pub struct ImportCache {
+    root: PathBuf,
+}
+
+impl ImportCache {
+    pub fn new(root: PathBuf) -> Arc<Self> {
+        Arc::new(ImportCache { root })
+    }
+
+    fn key(source: &Path, target: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(source.as_os_str().to_string_lossy().as_bytes());
+        hasher.write_u8(0);
+        hasher.write(target.as_bytes());
+        hasher.finish()
+    }
+
+    fn record_path(&self, key: u64) -> PathBuf {
+        self.root.join(format!("{:016x}.json", key))
+    }
+
+    fn artifact_path(&self, key: u64) -> PathBuf {
+        self.root.join(format!("{:016x}.artifact", key))
+    }
+
+    fn load_record(&self, key: u64) -> Option<ImportRecord> {
+        let bytes = std::fs::read(self.record_path(key)).ok()?;
+        match serde_json::from_slice(&bytes) {
+            Ok(record) => Some(record),
+            Err(err) => {
+                tracing::warn!("Ignoring corrupted import record. {:#}", err);
+                None
+            }
+        }
+    }
+
+    fn store(&self, key: u64, record: &ImportRecord, output: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.root)?;
+        std::fs::copy(output, self.artifact_path(key))?;
+        let bytes = serde_json::to_vec(record)?;
+        std::fs::write(self.record_path(key), bytes)
+    }
+
+    /// Returns hash of the artifact last produced for the source and target.
+    fn artifact_hash(&self, source: &Path, target: &str) -> Option<u64> {
+        let record = self.load_record(Self::key(source, target))?;
+        Some(record.artifact)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SourceRecord {
+    name: String,
+    hash: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DependencyRecord {
+    source: String,
+    target: String,
+    id: NonZeroU64,
+
+    /// Hash of the dependency artifact, if it was imported incrementally.
+    artifact: Option<u64>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ImportRecord {
+    importer: String,
+    source: u64,
+    sources: Vec<SourceRecord>,
+    dependencies: Vec<DependencyRecord>,
+    artifact: u64,
+}
+
+/// Importer wrapper that re-imports only when inputs changed.
+pub struct Incremental<I> {
+    importer: I,
+    cache: Arc<ImportCache>,
+}
+
+impl<I> Incremental<I> {
+    pub fn new(importer: I, cache: Arc<ImportCache>) -> Self {
+        Incremental { importer, cache }
+    }
+}
+
+impl<I> Importer for Incremental<I>
+where
+    I: Importer,
+{
+    fn name(&self) -> &str {
+        self.importer.name()
+    }
+
+    fn formats(&self) -> &[&str] {
+        self.importer.formats()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.importer.extensions()
+    }
+
+    fn target(&self) -> &str {
+        self.importer.target()
+    }
+
+    fn import(
+        &self,
+        source: &Path,
+        output: &Path,
+        sources: &mut (impl Sources + ?Sized),
+        dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> Result<(), ImportError> {
+        let key = ImportCache::key(source, self.importer.target());
+        let source_hash = hash_file(source).map_err(|err| ImportError::Other {
+            reason: format!("Failed to read source '{}'. {:#}", source.display(), err),
+        })?;
+
+        if let Some(record) = self.cache.load_record(key) {
+            if self.is_up_to_date(&record, source, source_hash, sources, dependencies) {
+                match std::fs::copy(self.cache.artifact_path(key), output) {
+                    Ok(_) => {
+                        tracing::debug!(
+                            "Skipping import of '{}'. Sources and dependencies are unchanged",
+                            source.display()
+                        );
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed to restore cached artifact for '{}'. {:#}",
+                            source.display(),
+                            err
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut recording_sources = RecordingSources {
+            inner: sources,
+            requested: Vec::new(),
+        };
+
+        let mut recording_dependencies = RecordingDependencies {
+            inner: dependencies,
+            requested: Vec::new(),
+        };
+
+        self.importer.import(
+            source,
+            output,
+            &mut recording_sources,
+            &mut recording_dependencies,
+        )?;
+
+        let mut record = ImportRecord {
+            importer: self.importer.name().to_owned(),
+            source: source_hash,
+            sources: Vec::new(),
+            dependencies: Vec::new(),
+            artifact: 0,
+        };
+
+        for (name, path) in recording_sources.requested {
+            match hash_file(&path) {
+                Ok(hash) => record.sources.push(SourceRecord { name, hash }),
+                Err(err) => {
+                    tracing::warn!("Failed to hash source '{}'. {:#}", path.display(), err);
+                    return Ok(());
+                }
+            }
+        }
+
+        for (dep_source, target, id) in recording_dependencies.requested {
+            let artifact = self
+                .cache
+                .artifact_hash(&dependency_path(source, &dep_source), &target);
+
+            record.dependencies.push(DependencyRecord {
+                source: dep_source,
+                target,
+                id: id.value(),
+                artifact,
+            });
+        }
+
+        let result = hash_file(output).and_then(|hash| {
+            record.artifact = hash;
+            self.cache.store(key, &record, output)
+        });
+
+        if let Err(err) = result {
+            tracing::warn!(
+                "Failed to store import record for '{}'. {:#}",
+                source.display(),
+                err
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl<I> Incremental<I>
+where
+    I: Importer,
+{
+    fn is_up_to_date(
+        &self,
+        record: &ImportRecord,
+        source: &Path,
+        source_hash: u64,
+        sources: &mut (impl Sources + ?Sized),
+        dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> bool {
+        if record.importer != self.importer.name() || record.source != source_hash {
+            return false;
+        }
+
+        for recorded in &record.sources {
+            match sources.get(&recorded.name) {
+                Ok(Some(path)) => match hash_file(&path) {
+                    Ok(hash) if hash == recorded.hash => {}
+                    _ => return false,
+                },
+                _ => return false,
+            }
+        }
+
+        for recorded in &record.dependencies {
+            match dependencies.get(&recorded.source, &recorded.target) {
+                Ok(Some(id)) if id.value() == recorded.id => {}
+                _ => return false,
+            }
+
+            let artifact = self
+                .cache
+                .artifact_hash(&dependency_path(source, &recorded.source), &recorded.target);
+
+            if artifact != recorded.artifact {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+struct RecordingSources<'a, S: ?Sized> {
+    inner: &'a mut S,
+    requested: Vec<(String, PathBuf)>,
+}
+
+impl<S> Sources for RecordingSources<'_, S>
+where
+    S: Sources + ?Sized,
+{
+    fn get(&mut self, source: &str) -> Result<Option<PathBuf>, String> {
+        let path = self.inner.get(source)?;
+        if let Some(path) = &path {
+            self.requested.push((source.to_owned(), path.clone()));
+        }
+        Ok(path)
+    }
+}
+
+struct RecordingDependencies<'a, D: ?Sized> {
+    inner: &'a mut D,
+    requested: Vec<(String, String, AssetId)>,
+}
+
+impl<D> Dependencies for RecordingDependencies<'_, D>
+where
+    D: Dependencies + ?Sized,
+{
+    fn get(&mut self, source: &str, target: &str) -> Result<Option<AssetId>, String> {
+        let id = self.inner.get(source, target)?;
+        if let Some(id) = id {
+            self.requested.push((source.to_owned(), target.to_owned(), id));
+        }
+        Ok(id)
+    }
+}
+
+/// Resolves dependency source path relative to the importing source.
+fn dependency_path(source: &Path, dependency: &str) -> PathBuf {
+    match source.parent() {
+        Some(dir) => dir.join(dependency),
+        None => PathBuf::from(dependency),
+    }
+}
+
+fn hash_file(path: &Path) -> std::io::Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = [0; 8192];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buffer[..read]);
+    }
+}
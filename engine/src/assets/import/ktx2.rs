@@ -0,0 +1,248 @@
+use std::{convert::TryFrom, path::Path, sync::Arc};
+
+use sierra::Format;
+use treasury_import::{Dependencies, ImportError, Importer, Sources};
+
+use crate::assets::image::CompressedImage;
+
+const KTX2_IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+/// Size of header and index preceding level index.
+const KTX2_HEADER_SIZE: usize = 80;
+
+/// Size of single entry of level index.
+const KTX2_LEVEL_SIZE: usize = 24;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Ktx2Error {
+    #[error("Not a KTX2 file")]
+    Identifier,
+
+    #[error("KTX2 data is truncated")]
+    Truncated,
+
+    #[error("KTX2 supercompression scheme '{0}' is unsupported. Transcode to BC or ASTC format")]
+    Supercompression(u32),
+
+    #[error("KTX2 format '{0}' is unsupported")]
+    UnsupportedFormat(u32),
+
+    #[error("Only single 2D image is supported, KTX2 has depth {depth}, {layers} layers and {faces} faces")]
+    UnsupportedShape { depth: u32, layers: u32, faces: u32 },
+
+    #[error("KTX2 mip level {level} has {len} bytes instead of {expected}")]
+    LevelSize {
+        level: usize,
+        len: usize,
+        expected: usize,
+    },
+}
+
+/// Image data from KTX2 file.
+pub struct Ktx2Image<'a> {
+    pub format: Format,
+    pub width: u32,
+    pub height: u32,
+
+    /// Blocks of each mip level, starting from the largest.
+    pub levels: Vec<&'a [u8]>,
+}
+
+/// Parses KTX2 file with single 2D image without supercompression.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{assets::import::parse_ktx2, sierra::Format};
+/// let mut ktx2 = vec![
+///     0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+/// ];
+///
+/// // BC7 image 8x8 with two mip levels.
+/// // Format, type size, width, height, depth, layers, faces, levels, supercompression.
+/// for value in [145u32, 1, 8, 8, 0, 0, 1, 2, 0] {
+///     ktx2.extend(value.to_le_bytes());
+/// }
+///
+/// // No format descriptor, key-value data or supercompression data.
+/// ktx2.extend([0; 32]);
+///
+/// // Level index. Level data is stored from smallest level to largest.
+/// let data = 80 + 2 * 24;
+/// for value in [data + 16, 64, 64, data, 16, 16] {
+///     ktx2.extend((value as u64).to_le_bytes());
+/// }
+/// ktx2.resize(data + 80, 0);
+///
+/// let image = parse_ktx2(&ktx2).unwrap();
+/// assert_eq!(image.format, Format::BC7Unorm);
+/// assert_eq!((image.width, image.height), (8, 8));
+/// assert_eq!(image.levels.len(), 2);
+/// assert_eq!(image.levels[0].len(), 64);
+/// assert_eq!(image.levels[1].len(), 16);
+/// ```
+pub fn parse_ktx2(bytes: &[u8]) -> Result<Ktx2Image<'_>, Ktx2Error> {
+    if !bytes.starts_with(&KTX2_IDENTIFIER) {
+        return Err(Ktx2Error::Identifier);
+    }
+
+    if bytes.len() < KTX2_HEADER_SIZE {
+        return Err(Ktx2Error::Truncated);
+    }
+
+    let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..][..4].try_into().unwrap());
+    let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..][..8].try_into().unwrap());
+
+    let vk_format = u32_at(12);
+    let width = u32_at(20);
+    let height = u32_at(24).max(1);
+    let depth = u32_at(28);
+    let layers = u32_at(32);
+    let faces = u32_at(36);
+    let level_count = u32_at(40).max(1) as usize;
+    let supercompression = u32_at(44);
+
+    if supercompression != 0 {
+        return Err(Ktx2Error::Supercompression(supercompression));
+    }
+
+    if depth > 1 || layers > 1 || faces != 1 {
+        return Err(Ktx2Error::UnsupportedShape {
+            depth,
+            layers,
+            faces,
+        });
+    }
+
+    let (format, block_size, block_bytes) =
+        vk_format_info(vk_format).ok_or(Ktx2Error::UnsupportedFormat(vk_format))?;
+
+    let index_end = KTX2_HEADER_SIZE + level_count * KTX2_LEVEL_SIZE;
+    if bytes.len() < index_end {
+        return Err(Ktx2Error::Truncated);
+    }
+
+    let mut levels = Vec::with_capacity(level_count);
+
+    for level in 0..level_count {
+        let entry = KTX2_HEADER_SIZE + level * KTX2_LEVEL_SIZE;
+        let offset = usize::try_from(u64_at(entry)).map_err(|_| Ktx2Error::Truncated)?;
+        let len = usize::try_from(u64_at(entry + 8)).map_err(|_| Ktx2Error::Truncated)?;
+
+        let data = bytes
+            .get(offset..)
+            .and_then(|data| data.get(..len))
+            .ok_or(Ktx2Error::Truncated)?;
+
+        let blocks = |size: u32| {
+            let size = (size >> level).max(1);
+            ((size + block_size - 1) / block_size) as usize
+        };
+        let expected = blocks(width) * blocks(height) * block_bytes;
+
+        if len != expected {
+            return Err(Ktx2Error::LevelSize {
+                level,
+                len,
+                expected,
+            });
+        }
+
+        levels.push(data);
+    }
+
+    Ok(Ktx2Image {
+        format,
+        width,
+        height,
+        levels,
+    })
+}
+
+/// Returns format, block dimension and block size in bytes for supported Vulkan formats.
+fn vk_format_info(vk_format: u32) -> Option<(Format, u32, usize)> {
+    let info = match vk_format {
+        37 => (Format::RGBA8Unorm, 1, 4),
+        43 => (Format::RGBA8Srgb, 1, 4),
+        133 => (Format::BC1RGBAUnorm, 4, 8),
+        134 => (Format::BC1RGBASrgb, 4, 8),
+        137 => (Format::BC3Unorm, 4, 16),
+        138 => (Format::BC3Srgb, 4, 16),
+        139 => (Format::BC4Unorm, 4, 8),
+        141 => (Format::BC5Unorm, 4, 16),
+        145 => (Format::BC7Unorm, 4, 16),
+        146 => (Format::BC7Srgb, 4, 16),
+        157 => (Format::ASTC4x4Unorm, 4, 16),
+        158 => (Format::ASTC4x4Srgb, 4, 16),
+        _ => return None,
+    };
+    Some(info)
+}
+
+/// Imports KTX2 textures with GPU-compressed formats.
+///
+/// Compressed blocks of all mip levels are stored as [`CompressedImage`]
+/// and uploaded without conversion.
+/// Artifacts are loaded as [`CompressedTexture`](crate::graphics::CompressedTexture).
+pub struct Ktx2Importer;
+
+impl Importer for Ktx2Importer {
+    fn name(&self) -> &str {
+        "KTX2"
+    }
+
+    fn formats(&self) -> &[&str] {
+        &["ktx2"]
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ktx2"]
+    }
+
+    fn target(&self) -> &str {
+        "arcana.compressed-image"
+    }
+
+    fn import(
+        &self,
+        source_path: &Path,
+        output_path: &Path,
+        _sources: &mut (impl Sources + ?Sized),
+        _dependencies: &mut (impl Dependencies + ?Sized),
+    ) -> Result<(), ImportError> {
+        let bytes = std::fs::read(source_path).map_err(|err| ImportError::Other {
+            reason: format!("Failed to read file '{}'. {:#}", source_path.display(), err),
+        })?;
+
+        let ktx2 = parse_ktx2(&bytes).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to parse KTX2 file '{}'. {:#}",
+                source_path.display(),
+                err
+            ),
+        })?;
+
+        let image = CompressedImage::new(
+            ktx2.format,
+            ktx2.width,
+            ktx2.height,
+            ktx2.levels.into_iter().map(Arc::from).collect(),
+        );
+
+        let data = bincode::serialize(&image).map_err(|err| ImportError::Other {
+            reason: format!("Failed to serialize compressed image. {:#}", err),
+        })?;
+
+        std::fs::write(output_path, &data).map_err(|err| ImportError::Other {
+            reason: format!(
+                "Failed to write compressed image to '{}'. {:#}",
+                output_path.display(),
+                err
+            ),
+        })?;
+
+        Ok(())
+    }
+}
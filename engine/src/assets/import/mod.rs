@@ -1,21 +1,49 @@
 mod image;
+mod incremental;
+
+#[cfg(feature = "graphics")]
+mod ktx2;
 
 #[cfg(all(feature = "graphics", feature = "2d"))]
 mod aseprite;
 
+#[cfg(all(feature = "graphics", feature = "2d"))]
+mod atlas;
+
+#[cfg(all(feature = "graphics", feature = "2d"))]
+mod font;
+
 #[cfg(feature = "2d")]
 mod tiles;
 
 #[cfg(all(feature = "graphics", feature = "3d"))]
 mod gltf;
 
-pub use self::image::ImageImporter;
+pub use self::{
+    image::ImageImporter,
+    incremental::{ImportCache, Incremental},
+};
+
+#[cfg(feature = "graphics")]
+pub use self::ktx2::{parse_ktx2, Ktx2Error, Ktx2Image, Ktx2Importer};
+
+#[cfg(all(feature = "graphics", feature = "2d"))]
+pub use self::aseprite::{parse_aseprite, AsepriteError, AsepriteSheet, SpriteSheetImporter};
+
+#[cfg(all(feature = "graphics", feature = "2d"))]
+pub use self::atlas::{
+    pack_atlas, AtlasImporter, AtlasOverflow, AtlasPacking, AtlasSpriteSheetImporter,
+    DEFAULT_ATLAS_MAX_SIZE,
+};
 
 #[cfg(all(feature = "graphics", feature = "2d"))]
-pub use self::aseprite::SpriteSheetImporter;
+pub use self::font::{
+    generate_font_atlas, FontAtlas, FontAtlasError, FontAtlasImporter, FontImportConfig,
+    FontImporter, TtfImporter,
+};
 
 #[cfg(feature = "2d")]
 pub use self::tiles::{TileMapImporter, TileSetImporter};
 
 #[cfg(all(feature = "graphics", feature = "3d"))]
-pub use self::gltf::GltfModelImporter;
+pub use self::gltf::{GltfImportConfig, GltfModelImporter, GltfSceneImporter, VhacdConfig};
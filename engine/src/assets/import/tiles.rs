@@ -1,5 +1,6 @@
 use std::{fs::File, path::Path};
 
+use arcana_time::TimeSpan;
 use treasury_import::{ensure_dependencies, AssetId, Dependencies, ImportError, Importer, Sources};
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -20,6 +21,20 @@ pub struct Tile {
     #[serde(default)]
     pub collider: Option<ColliderKind>,
     pub texture: Option<Key>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation: Option<TileAnimation>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TileAnimation {
+    pub frames: Vec<TileAnimationFrame>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TileAnimationFrame {
+    pub tile: usize,
+    pub span: TimeSpan,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -78,6 +93,20 @@ impl Importer for TileSetImporter {
                 ),
             })?;
 
+        let tile_count = set.tiles.len();
+        for (index, tile) in set.tiles.iter().enumerate() {
+            if let Some(animation) = &tile.animation {
+                if let Some(frame) = animation.frames.iter().find(|f| f.tile >= tile_count) {
+                    return Err(ImportError::Other {
+                        reason: format!(
+                            "Animation of tile '{}' refers to missing tile '{}'",
+                            index, frame.tile
+                        ),
+                    });
+                }
+            }
+        }
+
         let mut missing_deps = Vec::new();
 
         for tile in &mut set.tiles {
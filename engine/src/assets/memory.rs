@@ -0,0 +1,115 @@
+//! In-memory asset source.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    convert::Infallible,
+    hash::{Hash, Hasher},
+    num::NonZeroU64,
+    sync::Arc,
+};
+
+use futures::future::BoxFuture;
+use goods::{
+    source::{AssetData, Source},
+    AssetId,
+};
+use hashbrown::HashMap;
+use parking_lot::RwLock;
+
+struct Entry {
+    bytes: Arc<[u8]>,
+    version: u64,
+}
+
+/// Asset source that serves assets from memory.
+///
+/// Useful for tests and embedded builds where assets should not be read from disk.
+/// Register it with `Loader::builder().add(source)`.
+/// Loader consults sources in order they were added,
+/// so memory source can be combined with treasury or other sources.
+///
+/// Assets are found by key regardless of requested asset type.
+#[derive(Default)]
+pub struct MemorySource {
+    ids: RwLock<HashMap<String, AssetId>>,
+    entries: RwLock<HashMap<AssetId, Entry>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        MemorySource::default()
+    }
+
+    /// Adds asset data with specified key.
+    /// Replaces data if key is already registered and bumps its version
+    /// so the change is picked up on update.
+    pub fn insert(&self, key: &str, bytes: impl Into<Arc<[u8]>>) -> AssetId {
+        let id = *self
+            .ids
+            .write()
+            .entry(key.to_owned())
+            .or_insert_with(|| id_for_key(key));
+
+        let mut entries = self.entries.write();
+        let version = entries.get(&id).map_or(0, |entry| entry.version + 1);
+        entries.insert(
+            id,
+            Entry {
+                bytes: bytes.into(),
+                version,
+            },
+        );
+        id
+    }
+
+    /// Builder-style version of [`MemorySource::insert`].
+    pub fn with(self, key: &str, bytes: impl Into<Arc<[u8]>>) -> Self {
+        self.insert(key, bytes);
+        self
+    }
+
+    /// Removes asset data with specified key.
+    pub fn remove(&self, key: &str) -> bool {
+        match self.ids.write().remove(key) {
+            None => false,
+            Some(id) => self.entries.write().remove(&id).is_some(),
+        }
+    }
+
+    fn data(&self, id: AssetId) -> Option<AssetData> {
+        let entries = self.entries.read();
+        let entry = entries.get(&id)?;
+        Some(AssetData {
+            bytes: Box::from(&*entry.bytes),
+            version: entry.version,
+        })
+    }
+}
+
+/// Derives stable asset id from the key.
+/// Highest bit is set to keep memory ids apart from ids issued by other sources.
+fn id_for_key(key: &str) -> AssetId {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    let value = hasher.finish() | (1 << 63);
+    AssetId(NonZeroU64::new(value).unwrap())
+}
+
+impl Source for MemorySource {
+    type Error = Infallible;
+
+    fn find(&self, path: &str, _asset: &str) -> BoxFuture<Option<AssetId>> {
+        let id = self.ids.read().get(path).copied();
+        Box::pin(async move { id })
+    }
+
+    fn load(&self, id: AssetId) -> BoxFuture<Result<Option<AssetData>, Infallible>> {
+        let data = self.data(id);
+        Box::pin(async move { Ok(data) })
+    }
+
+    fn update(&self, id: AssetId, version: u64) -> BoxFuture<Result<Option<AssetData>, Infallible>> {
+        let data = self.data(id).filter(|data| data.version != version);
+        Box::pin(async move { Ok(data) })
+    }
+}
@@ -1,6 +1,8 @@
 //! Asset loading facility.
 
 mod cache;
+mod memory;
+mod preload;
 
 #[cfg(feature = "asset-pipeline")]
 pub mod treasury;
@@ -11,6 +13,9 @@ pub mod import;
 #[cfg(feature = "graphics")]
 pub mod image;
 
+#[cfg(feature = "graphics")]
+pub mod font;
+
 use std::{
     any::TypeId,
     borrow::Borrow,
@@ -27,10 +32,21 @@ pub use goods::{
 };
 use hashbrown::hash_map::{Entry, HashMap};
 
+#[cfg(feature = "asset-pipeline")]
+use {
+    hashbrown::HashSet,
+    std::{
+        path::{Path, PathBuf},
+        time::SystemTime,
+    },
+};
+
 use crate::noophash::NoopHasherBuilder;
 
 use self::cache::{AnyAssetCache, AssetCache};
 
+pub use self::{memory::MemorySource, preload::Preload};
+
 // #[cfg(feature = "visible")]
 // pub use self::{
 //     font::{
@@ -156,6 +172,15 @@ where
 pub struct Assets {
     pub loader: Loader,
     caches: HashMap<TypeId, Box<dyn AnyAssetCache>, NoopHasherBuilder>,
+
+    #[cfg(feature = "asset-pipeline")]
+    watched: HashMap<AssetId, WatchedFile>,
+}
+
+#[cfg(feature = "asset-pipeline")]
+struct WatchedFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
 }
 
 impl Assets {
@@ -163,7 +188,77 @@ impl Assets {
         Assets {
             loader,
             caches: HashMap::with_hasher(NoopHasherBuilder),
+
+            #[cfg(feature = "asset-pipeline")]
+            watched: HashMap::new(),
+        }
+    }
+
+    /// Drops cached asset of any type with specified id.
+    /// Next [`Assets::build`] or [`Assets::get`] requests it from the loader again,
+    /// which serves new data if source reports newer version of the asset.
+    ///
+    /// Values already built from the asset are not affected.
+    /// Systems keep using old value until they request asset again.
+    /// Returns `false` if asset was not cached.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let old = assets.get::<Config>(id).unwrap()?.clone();
+    ///
+    /// // Source of the asset changes, e.g. it is reimported.
+    ///
+    /// assets.reload(id);
+    /// let new = assets.get::<Config>(id).unwrap()?.clone();
+    /// assert_ne!(old, new);
+    /// ```
+    pub fn reload(&mut self, id: AssetId) -> bool {
+        let mut reloaded = false;
+        for cache in self.caches.values_mut() {
+            reloaded |= cache.remove(id);
         }
+        reloaded
+    }
+
+    /// Starts watching source file of the asset.
+    /// Asset is reloaded by [`Assets::poll_reloads`] when file is modified.
+    /// Replaces file previously watched for the asset.
+    #[cfg(feature = "asset-pipeline")]
+    pub fn watch(&mut self, id: AssetId, path: impl Into<PathBuf>) {
+        let path = path.into();
+        let modified = file_modified(&path);
+        self.watched.insert(id, WatchedFile { path, modified });
+    }
+
+    /// Stops watching source file of the asset.
+    #[cfg(feature = "asset-pipeline")]
+    pub fn unwatch(&mut self, id: AssetId) -> bool {
+        self.watched.remove(&id).is_some()
+    }
+
+    /// Checks watched files for modifications
+    /// and reloads assets which files were modified since last poll.
+    ///
+    /// Returns ids of reloaded assets.
+    #[cfg(feature = "asset-pipeline")]
+    pub fn poll_reloads(&mut self) -> HashSet<AssetId> {
+        let mut changed = HashSet::new();
+
+        for (&id, file) in &mut self.watched {
+            let modified = file_modified(&file.path);
+            if modified != file.modified {
+                file.modified = modified;
+                changed.insert(id);
+            }
+        }
+
+        for &id in &changed {
+            tracing::info!("Reloading asset {}", id);
+            self.reload(id);
+        }
+
+        changed
     }
 
     pub fn cleanup(&mut self) {
@@ -227,3 +322,9 @@ impl Assets {
         Ok(result.build(builder)?.clone())
     }
 }
+
+/// Returns modification time of the file, `None` if file cannot be accessed.
+#[cfg(feature = "asset-pipeline")]
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).ok()?.modified().ok()
+}
@@ -0,0 +1,65 @@
+//! Batch loading of assets.
+
+use goods::{Asset, AssetHandle, AssetId};
+
+use super::Assets;
+
+/// Assets requested with [`Assets::preload`].
+///
+/// Loading screen may poll it each frame to show progress.
+/// Assets stay cached by loader, so they are ready
+/// when requested from [`Assets`] after preloading is complete.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut preload = assets.preload::<SpriteSheet>(&[hero, enemy]);
+/// assert_eq!(preload.progress(), (0, 2));
+///
+/// // Later, in loading screen system.
+/// if preload.is_complete() {
+///     assert_eq!(preload.progress(), (2, 2));
+///     switch_to_game(world);
+/// }
+/// ```
+pub struct Preload<A> {
+    pending: Vec<AssetHandle<A>>,
+    total: usize,
+}
+
+impl<A> Preload<A>
+where
+    A: Asset,
+{
+    /// Returns number of loaded assets and total number of assets.
+    ///
+    /// Assets that failed to load are counted as loaded,
+    /// errors are reported when asset is built.
+    pub fn progress(&mut self) -> (usize, usize) {
+        self.pending
+            .retain_mut(|handle| handle.get_ready().is_none());
+        (self.total - self.pending.len(), self.total)
+    }
+
+    /// Returns `true` if all assets are loaded.
+    pub fn is_complete(&mut self) -> bool {
+        let (loaded, total) = self.progress();
+        loaded == total
+    }
+}
+
+impl Assets {
+    /// Starts loading of all specified assets.
+    /// Returns [`Preload`] to track progress.
+    pub fn preload<A>(&mut self, ids: &[AssetId]) -> Preload<A>
+    where
+        A: Asset,
+    {
+        let pending = ids.iter().map(|&id| self.loader.load::<A, _>(id)).collect();
+
+        Preload {
+            pending,
+            total: ids.len(),
+        }
+    }
+}
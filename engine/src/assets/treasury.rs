@@ -1,4 +1,9 @@
-use std::{error::Error, fmt, io, path::PathBuf};
+use std::{
+    error::Error,
+    fmt, io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
 
 use futures::future::BoxFuture;
 use goods::{
@@ -71,22 +76,43 @@ impl Source for TreasurySource {
                     tracing::debug!("Asset '{}' was not found", id);
                     Ok(None)
                 }
-                Some(path) => match std::fs::read(&path) {
-                    Err(error) => Err(TreasuryError::File { path, error }),
-                    Ok(data) => Ok(Some(AssetData {
-                        bytes: data.into_boxed_slice(),
-                        version: 0,
-                    })),
-                },
+                Some(path) => read_artifact(path).map(Some),
             }
         })
     }
 
     fn update(
         &self,
-        _id: AssetId,
-        _version: u64,
+        id: AssetId,
+        version: u64,
     ) -> BoxFuture<Result<Option<AssetData>, TreasuryError>> {
-        Box::pin(async { Ok(None) })
+        Box::pin(async move {
+            match self.store.fetch(id.0.into()).await {
+                None => Ok(None),
+                Some(path) if artifact_version(&path) == version => Ok(None),
+                Some(path) => read_artifact(path).map(Some),
+            }
+        })
+    }
+}
+
+/// Version of artifact is its modification time,
+/// so reimported artifacts are picked up on update.
+fn artifact_version(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |since| since.as_nanos() as u64)
+}
+
+fn read_artifact(path: PathBuf) -> Result<AssetData, TreasuryError> {
+    let version = artifact_version(&path);
+    match std::fs::read(&path) {
+        Err(error) => Err(TreasuryError::File { path, error }),
+        Ok(data) => Ok(AssetData {
+            bytes: data.into_boxed_slice(),
+            version,
+        }),
     }
 }
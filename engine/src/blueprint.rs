@@ -0,0 +1,212 @@
+//! Data-driven entity templates.
+//!
+//! Blueprint is a JSON object mapping component names to serialized component values.
+//!
+//! ```json
+//! {
+//!     "Tank": { "speed": 2.5 },
+//!     "Health": { "max": 100 }
+//! }
+//! ```
+//!
+//! Component names are resolved with [`BlueprintRegistry`]
+//! where each name is mapped to deserializable component type.
+//! This lets designers create entity variants without code.
+
+use std::{collections::HashMap, path::Path};
+
+use edict::{
+    component::Component,
+    entity::EntityId,
+    world::{NoSuchEntity, World},
+};
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BlueprintError {
+    #[error("Failed to read blueprint file '{}'", path.display())]
+    Io {
+        path: Box<Path>,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse blueprint")]
+    Parse {
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Component '{name}' is not registered")]
+    UnknownComponent { name: String },
+
+    #[error("Failed to deserialize component '{name}'")]
+    Component {
+        name: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Failed to insert blueprint into non-existing entity ({entity:?})")]
+    NoSuchEntity { entity: EntityId },
+}
+
+/// Parsed blueprint.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Blueprint {
+    components: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Blueprint {
+    /// Parses blueprint from JSON string.
+    pub fn from_json(json: &str) -> Result<Self, BlueprintError> {
+        serde_json::from_str(json).map_err(|source| BlueprintError::Parse { source })
+    }
+
+    /// Reads blueprint from file.
+    pub fn load(path: &Path) -> Result<Self, BlueprintError> {
+        let json = std::fs::read_to_string(path).map_err(|source| BlueprintError::Io {
+            path: path.into(),
+            source,
+        })?;
+        Blueprint::from_json(&json)
+    }
+
+    /// Returns names of components in this blueprint.
+    pub fn component_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.components.keys().map(String::as_str)
+    }
+
+    /// Spawns new entity with components from this blueprint.
+    ///
+    /// If any component fails to deserialize the entity is despawned.
+    pub fn spawn(
+        &self,
+        world: &mut World,
+        registry: &BlueprintRegistry,
+    ) -> Result<EntityId, BlueprintError> {
+        let entity = world.spawn(());
+
+        match self.insert(entity, world, registry) {
+            Ok(()) => Ok(entity),
+            Err(err) => {
+                let _ = world.despawn(entity);
+                Err(err)
+            }
+        }
+    }
+
+    /// Inserts components from this blueprint to existing entity.
+    ///
+    /// Fails with [`BlueprintError::NoSuchEntity`] if entity is not alive.
+    pub fn insert(
+        &self,
+        entity: EntityId,
+        world: &mut World,
+        registry: &BlueprintRegistry,
+    ) -> Result<(), BlueprintError> {
+        for (name, value) in &self.components {
+            let insert = registry
+                .components
+                .get(name)
+                .ok_or_else(|| BlueprintError::UnknownComponent { name: name.clone() })?;
+
+            match insert(value, entity, world) {
+                Ok(()) => {}
+                Err(InsertError::Deserialize(source)) => {
+                    return Err(BlueprintError::Component {
+                        name: name.clone(),
+                        source,
+                    })
+                }
+                Err(InsertError::NoSuchEntity) => {
+                    return Err(BlueprintError::NoSuchEntity { entity })
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+enum InsertError {
+    Deserialize(serde_json::Error),
+    NoSuchEntity,
+}
+
+type InsertFn = fn(&serde_json::Value, EntityId, &mut World) -> Result<(), InsertError>;
+
+/// Maps component names used in blueprints to component types.
+#[derive(Clone, Default)]
+pub struct BlueprintRegistry {
+    components: HashMap<String, InsertFn>,
+}
+
+impl BlueprintRegistry {
+    pub fn new() -> Self {
+        BlueprintRegistry::default()
+    }
+
+    /// Returns registry with serializable engine components registered.
+    pub fn with_engine_components() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = BlueprintRegistry::new();
+
+        #[cfg(feature = "2d")]
+        registry.register::<crate::scene::Global2>("Global2");
+
+        #[cfg(feature = "3d")]
+        registry.register::<crate::scene::Global3>("Global3");
+
+        registry
+    }
+
+    /// Registers component type under specified name.
+    /// Replaces type previously registered under the same name.
+    pub fn register<T>(&mut self, name: impl Into<String>) -> &mut Self
+    where
+        T: Component + DeserializeOwned,
+    {
+        self.components.insert(name.into(), insert_component::<T>);
+        self
+    }
+
+    /// Returns `true` if component with specified name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.components.contains_key(name)
+    }
+}
+
+fn insert_component<T>(
+    value: &serde_json::Value,
+    entity: EntityId,
+    world: &mut World,
+) -> Result<(), InsertError>
+where
+    T: Component + DeserializeOwned,
+{
+    let component = T::deserialize(value).map_err(InsertError::Deserialize)?;
+    world
+        .insert(entity, component)
+        .map_err(|NoSuchEntity| InsertError::NoSuchEntity)
+}
+
+/// Extension trait to spawn blueprints directly from [`World`].
+pub trait WorldBlueprintExt {
+    /// Reads blueprint file and spawns new entity with its components.
+    fn spawn_blueprint(
+        &mut self,
+        path: impl AsRef<Path>,
+        registry: &BlueprintRegistry,
+    ) -> Result<EntityId, BlueprintError>;
+}
+
+impl WorldBlueprintExt for World {
+    fn spawn_blueprint(
+        &mut self,
+        path: impl AsRef<Path>,
+        registry: &BlueprintRegistry,
+    ) -> Result<EntityId, BlueprintError> {
+        Blueprint::load(path.as_ref())?.spawn(self, registry)
+    }
+}
@@ -2,11 +2,27 @@ use edict::prelude::Component;
 
 use crate::rect::Rect;
 
+/// Pixel-perfect mode parameters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelPerfect {
+    /// Number of texels per world unit.
+    pub pixels_per_unit: f32,
+
+    /// Whether each texel should cover integer number of screen pixels.
+    pub integer_scale: bool,
+}
+
 /// Camera in 2 dimensions.
 #[derive(Debug, Component)]
 pub struct Camera2 {
     /// Vertical scale
     scaley: f32,
+
+    /// Fixed aspect ratio of the view.
+    /// Aspect ratio of the viewport is used when not set.
+    aspect: Option<f32>,
+
+    pixel_perfect: Option<PixelPerfect>,
 }
 
 impl Default for Camera2 {
@@ -18,12 +34,105 @@ impl Default for Camera2 {
 
 impl Camera2 {
     pub fn new(scaley: f32) -> Self {
-        Camera2 { scaley }
+        Camera2 {
+            scaley,
+            aspect: None,
+            pixel_perfect: None,
+        }
+    }
+
+    /// Switches camera into pixel-perfect mode.
+    ///
+    /// View translation is snapped to texel grid and,
+    /// if `integer_scale` is set, scale is reduced to the largest integer zoom
+    /// that does not exceed the one defined by vertical scale,
+    /// so that the requested area always fits the window.
+    ///
+    /// Sprites should be sampled with nearest filtering in this mode.
+    pub fn pixel_perfect(mut self, pixels_per_unit: f32, integer_scale: bool) -> Self {
+        self.set_pixel_perfect(Some(PixelPerfect {
+            pixels_per_unit,
+            integer_scale,
+        }));
+        self
+    }
+
+    /// Enables or disables pixel-perfect mode.
+    pub fn set_pixel_perfect(&mut self, pixel_perfect: Option<PixelPerfect>) {
+        if let Some(pixel_perfect) = &pixel_perfect {
+            assert!(
+                pixel_perfect.pixels_per_unit > 0.0,
+                "Pixels per unit must be positive"
+            );
+        }
+        self.pixel_perfect = pixel_perfect;
+    }
+
+    /// Returns pixel-perfect mode parameters if enabled.
+    pub fn pixel_perfect_mode(&self) -> Option<PixelPerfect> {
+        self.pixel_perfect
+    }
+
+    /// Returns view isometry and projection for viewport of specified size in pixels.
+    ///
+    /// In pixel-perfect mode translation of the view is snapped to texels
+    /// and scale is snapped to integer zoom if requested.
+    /// Otherwise returns `iso` and [`Camera2::affine`] unchanged.
+    pub fn view_proj(
+        &self,
+        iso: &na::Isometry2<f32>,
+        width: u32,
+        height: u32,
+    ) -> (na::Isometry2<f32>, na::Affine2<f32>) {
+        let width = width.max(1);
+        let height = height.max(1);
+
+        let pixel_perfect = match self.pixel_perfect {
+            None => return (*iso, self.affine(width as f32 / height as f32)),
+            Some(pixel_perfect) => pixel_perfect,
+        };
+
+        let ppu = pixel_perfect.pixels_per_unit;
+
+        // Screen pixels per texel.
+        let mut zoom = self.scaley * height as f32 / (2.0 * ppu);
+        if pixel_perfect.integer_scale {
+            zoom = zoom.floor().max(1.0);
+        }
+
+        let snap = |value: f32, pixels: u32| {
+            let snapped = (value * ppu).round() / ppu;
+
+            // With odd number of pixels view center is in the middle of a pixel.
+            if pixels % 2 == 1 {
+                snapped + 0.5 / (ppu * zoom)
+            } else {
+                snapped
+            }
+        };
+
+        let mut iso = *iso;
+        iso.translation.x = snap(iso.translation.x, width);
+        iso.translation.y = snap(iso.translation.y, height);
+
+        let affine = na::Affine2::from_matrix_unchecked(na::Matrix3::new(
+            2.0 * ppu * zoom / width as f32,
+            0.0,
+            0.0,
+            0.0,
+            2.0 * ppu * zoom / height as f32,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+        ));
+
+        (iso, affine)
     }
 
     pub fn affine(&self, aspect: f32) -> na::Affine2<f32> {
         na::Affine2::from_matrix_unchecked(na::Matrix3::new(
-            self.scaley / aspect,
+            self.scalex(aspect),
             0.0,
             0.0,
             0.0,
@@ -35,20 +144,79 @@ impl Camera2 {
         ))
     }
 
+    /// Returns horizontal scale for viewport with specified aspect ratio.
+    pub fn scalex(&self, aspect: f32) -> f32 {
+        self.scaley / self.aspect.unwrap_or(aspect)
+    }
+
+    /// Returns vertical scale.
+    pub fn scaley(&self) -> f32 {
+        self.scaley
+    }
+
+    /// Returns size of the half of the view in world units.
     pub fn scale(&self, aspect: f32) -> na::Vector2<f32> {
-        na::Vector2::new(self.scaley * aspect, self.scaley)
+        na::Vector2::new(1.0 / self.scalex(aspect), 1.0 / self.scaley)
     }
 
+    /// Returns inverse of the [`Camera2::scale`].
     pub fn inverse_scale(&self, aspect: f32) -> na::Vector2<f32> {
-        na::Vector2::new(1.0 / self.scaley / aspect, 1.0 / self.scaley)
+        na::Vector2::new(self.scalex(aspect), self.scaley)
     }
 
-    /// Update aspect ration of the camera.
+    /// Update vertical scale of the camera.
     pub fn set_scaley(&mut self, scaley: f32) {
         self.scaley = scaley;
     }
 
+    /// Sets horizontal scale of the camera independently from the viewport.
+    ///
+    /// This fixes aspect ratio of the view to `scaley / scalex`,
+    /// so world units are stretched if viewport has different aspect ratio.
+    pub fn set_scalex(&mut self, scalex: f32) {
+        assert!(scalex > 0.0, "Horizontal scale must be positive");
+        self.aspect = Some(self.scaley / scalex);
+    }
+
+    /// Fixes aspect ratio of the view, or resets it to follow viewport if `None`.
+    pub fn set_aspect(&mut self, aspect: Option<f32>) {
+        if let Some(aspect) = aspect {
+            assert!(aspect > 0.0, "Aspect ratio must be positive");
+        }
+        self.aspect = aspect;
+    }
+
+    /// Returns fixed aspect ratio of the view if set.
+    pub fn aspect(&self) -> Option<f32> {
+        self.aspect
+    }
+
     /// Converts point in screen space into point in world space.
+    ///
+    /// Screen space spans from `-1` to `1` on both axes with `y` pointing up.
+    /// `iso` is the camera's global isometry
+    /// and `aspect` is the aspect ratio of the viewport.
+    ///
+    /// Pixel-perfect snapping is not taken into account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::{camera::Camera2, na};
+    /// let camera = Camera2::new(0.5);
+    /// let iso = na::Isometry2::translation(3.0, 4.0);
+    ///
+    /// let center = camera.screen_to_world(&iso, &na::Point2::origin(), 2.0);
+    /// assert_eq!(center, na::Point2::new(3.0, 4.0));
+    ///
+    /// let top_right = camera.screen_to_world(&iso, &na::Point2::new(1.0, 1.0), 2.0);
+    /// let bottom_left = camera.screen_to_world(&iso, &na::Point2::new(-1.0, -1.0), 2.0);
+    /// assert_eq!(top_right, na::Point2::new(7.0, 6.0));
+    /// assert_eq!(top_right - center, center - bottom_left);
+    ///
+    /// let screen = camera.world_to_screen(&iso, &top_right, 2.0);
+    /// assert_eq!(screen, na::Point2::new(1.0, 1.0));
+    /// ```
     pub fn screen_to_world(
         &self,
         iso: &na::Isometry2<f32>,
@@ -56,10 +224,12 @@ impl Camera2 {
         aspect: f32,
     ) -> na::Point2<f32> {
         let scale = self.scale(aspect);
-        iso.inverse_transform_point(&na::Point2::from(point.coords.component_mul(&scale)))
+        iso.transform_point(&na::Point2::from(point.coords.component_mul(&scale)))
     }
 
     /// Converts point in world space into point in screen space.
+    ///
+    /// This is inverse of [`Camera2::screen_to_world`].
     pub fn world_to_screen(
         &self,
         iso: &na::Isometry2<f32>,
@@ -67,26 +237,18 @@ impl Camera2 {
         aspect: f32,
     ) -> na::Point2<f32> {
         let inverse_scale = self.inverse_scale(aspect);
-        iso.transform_point(&na::Point2::from(
-            point.coords.component_mul(&inverse_scale),
-        ))
+        na::Point2::from(
+            iso.inverse_transform_point(point)
+                .coords
+                .component_mul(&inverse_scale),
+        )
     }
 
     pub fn transform_aabb(&self, iso: &na::Isometry2<f32>, aabb: &Rect, aspect: f32) -> Rect {
-        let scale = self.scale(aspect);
-
-        let top_left = iso.inverse_transform_point(&na::Point2::from(
-            aabb.top_left().coords.component_mul(&scale),
-        ));
-        let bottom_left = iso.inverse_transform_point(&na::Point2::from(
-            aabb.bottom_left().coords.component_mul(&scale),
-        ));
-        let top_right = iso.inverse_transform_point(&na::Point2::from(
-            aabb.top_right().coords.component_mul(&scale),
-        ));
-        let bottom_right = iso.inverse_transform_point(&na::Point2::from(
-            aabb.bottom_right().coords.component_mul(&scale),
-        ));
+        let top_left = self.screen_to_world(iso, &aabb.top_left(), aspect);
+        let bottom_left = self.screen_to_world(iso, &aabb.bottom_left(), aspect);
+        let top_right = self.screen_to_world(iso, &aabb.top_right(), aspect);
+        let bottom_right = self.screen_to_world(iso, &aabb.bottom_right(), aspect);
 
         let xs = [top_left.x, bottom_left.x, top_right.x, bottom_right.x];
         let left = xs.into_iter().reduce(f32::min).unwrap();
@@ -0,0 +1,233 @@
+//! Camera following and shaking for 2D.
+//!
+//! [`CameraFollow2`] and [`CameraShake2`] are added to camera entity
+//! and [`camera_rig_system2`] moves its [`Global2`] accordingly.
+//! Camera entity should not have `Local2` component,
+//! otherwise its position is overwritten by scene system.
+
+use edict::{entity::EntityId, query::Entities, world::World, Component};
+
+use crate::{
+    clocks::ClockIndex, rect::Rect, scene::Global2, scoped_allocator::ScopedAllocator, TimeSpan,
+};
+
+/// Makes camera follow target entity.
+///
+/// Camera is pulled toward the target by critically damped spring,
+/// so it catches up smoothly without overshooting.
+///
+/// # Example
+///
+/// Camera converges to the target.
+///
+/// ```
+/// # use arcana::{camera::spring_step, na};
+/// let target = na::Vector2::new(10.0, -5.0);
+/// let mut position = na::Vector2::zeros();
+/// let mut velocity = na::Vector2::zeros();
+///
+/// for _ in 0..120 {
+///     position = spring_step(position, &mut velocity, target, 50.0, 1.0 / 60.0);
+/// }
+///
+/// assert!((position - target).norm() < 0.01);
+/// ```
+#[derive(Clone, Debug, Component)]
+pub struct CameraFollow2 {
+    /// Entity with `Global2` to follow.
+    pub target: EntityId,
+
+    /// Stiffness of the spring. Larger values make camera follow faster.
+    pub stiffness: f32,
+
+    /// Area relative to camera position where target may move without camera following it.
+    pub deadzone: Rect,
+
+    velocity: na::Vector2<f32>,
+}
+
+impl CameraFollow2 {
+    pub fn new(target: EntityId, stiffness: f32) -> Self {
+        CameraFollow2 {
+            target,
+            stiffness,
+            deadzone: Rect {
+                left: 0.0,
+                right: 0.0,
+                bottom: 0.0,
+                top: 0.0,
+            },
+            velocity: na::Vector2::zeros(),
+        }
+    }
+
+    /// Sets area where target may move without camera following it.
+    pub fn with_deadzone(mut self, deadzone: Rect) -> Self {
+        self.deadzone = deadzone;
+        self
+    }
+
+    /// Returns new camera position after `dt` seconds.
+    pub fn step(
+        &mut self,
+        position: na::Vector2<f32>,
+        target: na::Vector2<f32>,
+        dt: f32,
+    ) -> na::Vector2<f32> {
+        // Camera position at which target touches deadzone border.
+        let offset = target - position;
+        let inside = na::Vector2::new(
+            offset.x.clamp(self.deadzone.left, self.deadzone.right),
+            offset.y.clamp(self.deadzone.bottom, self.deadzone.top),
+        );
+        let goal = position + offset - inside;
+
+        spring_step(position, &mut self.velocity, goal, self.stiffness, dt)
+    }
+}
+
+/// Advances critically damped spring by `dt` seconds.
+/// Returns new position and updates `velocity`.
+///
+/// Uses stable approximation of exact solution that does not overshoot
+/// whatever the time step is.
+pub fn spring_step(
+    position: na::Vector2<f32>,
+    velocity: &mut na::Vector2<f32>,
+    target: na::Vector2<f32>,
+    stiffness: f32,
+    dt: f32,
+) -> na::Vector2<f32> {
+    let omega = stiffness.max(0.0).sqrt();
+    let x = omega * dt;
+    let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+
+    let change = position - target;
+    let temp = (*velocity + change * omega) * dt;
+    *velocity = (*velocity - temp * omega) * exp;
+    target + (change + temp) * exp
+}
+
+/// Shakes camera with decaying noise.
+///
+/// Component is removed when shake decays completely.
+#[derive(Clone, Debug, Component)]
+pub struct CameraShake2 {
+    /// Initial maximum offset in world units.
+    pub amplitude: f32,
+
+    /// Frequency of the noise in hertz.
+    pub frequency: f32,
+
+    /// Time left until shake stops.
+    pub remaining: TimeSpan,
+
+    duration: TimeSpan,
+    phase: f32,
+    offset: na::Vector2<f32>,
+}
+
+impl CameraShake2 {
+    pub fn new(amplitude: f32, frequency: f32, duration: TimeSpan) -> Self {
+        CameraShake2 {
+            amplitude,
+            frequency,
+            remaining: duration,
+            duration,
+            phase: 0.0,
+            offset: na::Vector2::zeros(),
+        }
+    }
+
+    /// Returns current amplitude.
+    /// It decays quadratically from initial amplitude to zero.
+    pub fn current_amplitude(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 0.0;
+        }
+
+        let t = self.remaining.as_secs_f32() / self.duration.as_secs_f32();
+        self.amplitude * t * t
+    }
+
+    /// Advances shake by `delta` and returns new offset.
+    fn advance(&mut self, delta: TimeSpan) -> na::Vector2<f32> {
+        self.remaining = self.remaining.saturating_sub(delta);
+        self.phase += delta.as_secs_f32() * self.frequency;
+
+        let amplitude = self.current_amplitude();
+        na::Vector2::new(noise(self.phase, 0), noise(self.phase, 1)) * amplitude
+    }
+}
+
+/// Smooth value noise in `[-1, 1]` range.
+fn noise(t: f32, seed: u32) -> f32 {
+    fn hash(i: i32, seed: u32) -> f32 {
+        let mut h = (i as u32).wrapping_mul(0x9E37_79B9) ^ seed.wrapping_mul(0x85EB_CA6B);
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x7FEB_352D);
+        h ^= h >> 15;
+        h as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+
+    let i = t.floor();
+    let f = t - i;
+    let f = f * f * (3.0 - 2.0 * f);
+
+    let a = hash(i as i32, seed);
+    let b = hash(i as i32 + 1, seed);
+    a + (b - a) * f
+}
+
+/// Moves cameras with [`CameraFollow2`] toward their targets
+/// and applies [`CameraShake2`] offsets.
+pub fn camera_rig_system2(allocator: &mut ScopedAllocator, world: &mut World) {
+    let delta = world.expect_resource::<ClockIndex>().delta;
+    let dt = delta.as_secs_f32();
+
+    // Remove offsets of previous shake step,
+    // so that they do not accumulate in followed position.
+    for (global, shake) in world
+        .query_mut::<(&mut Global2, &mut CameraShake2)>()
+        .iter_mut()
+    {
+        global.iso.translation.vector -= shake.offset;
+        shake.offset = na::Vector2::zeros();
+    }
+
+    let mut targets = Vec::new_in(&**allocator);
+    for (entity, follow) in world.query_mut::<(Entities, &CameraFollow2)>().iter_mut() {
+        targets.push((entity, follow.target));
+    }
+
+    for (camera, target) in targets {
+        let target = match world.query_one_mut::<&Global2>(target) {
+            Ok(global) => global.iso.translation.vector,
+            Err(_) => continue,
+        };
+
+        if let Ok((global, follow)) =
+            world.query_one_mut::<(&mut Global2, &mut CameraFollow2)>(camera)
+        {
+            global.iso.translation.vector = follow.step(global.iso.translation.vector, target, dt);
+        }
+    }
+
+    let mut complete = Vec::new_in(&**allocator);
+    for (entity, global, shake) in world
+        .query_mut::<(Entities, &mut Global2, &mut CameraShake2)>()
+        .iter_mut()
+    {
+        if shake.remaining.is_zero() {
+            complete.push(entity);
+            continue;
+        }
+
+        shake.offset = shake.advance(delta);
+        global.iso.translation.vector += shake.offset;
+    }
+
+    for entity in complete {
+        let _ = world.remove::<CameraShake2>(entity);
+    }
+}
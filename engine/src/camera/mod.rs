@@ -3,7 +3,8 @@
 cfg_if::cfg_if! {
     if #[cfg(feature = "2d")] {
         mod d2;
-        pub use self::d2::*;
+        mod follow2;
+        pub use self::{d2::*, follow2::*};
     }
 }
 
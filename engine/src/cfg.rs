@@ -20,14 +20,92 @@ pub struct TreasuryConfig {
     pub temp: Option<PathBuf>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub importers: Vec<PathBuf>,
+
+    /// Directory for incremental import records.
+    /// Unchanged sources are not re-imported when set.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub import_cache: Option<PathBuf>,
+
+    #[cfg(all(feature = "graphics", feature = "3d"))]
+    #[serde(default)]
+    pub gltf: crate::assets::import::GltfImportConfig,
+
+    #[cfg(all(feature = "graphics", feature = "2d"))]
+    #[serde(default)]
+    pub font: crate::assets::import::FontImportConfig,
 }
 
 #[allow(unused)]
-#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Game {
     #[cfg(feature = "visible")]
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub window_size: Option<PhysicalSize<u32>>,
+
+    /// Main window title.
+    #[cfg(feature = "visible")]
+    #[serde(default = "default_title")]
+    pub title: String,
+
+    #[cfg(feature = "visible")]
+    #[serde(default = "default_resizable")]
+    pub resizable: bool,
+
+    /// Opens main window in borderless fullscreen mode on current monitor.
+    #[cfg(feature = "visible")]
+    #[serde(default)]
+    pub fullscreen: bool,
+
+    /// Path to main window icon image.
+    /// Relative paths are resolved against config root.
+    #[cfg(feature = "visible")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub icon: Option<PathBuf>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game {
+            #[cfg(feature = "visible")]
+            window_size: None,
+            #[cfg(feature = "visible")]
+            title: default_title(),
+            #[cfg(feature = "visible")]
+            resizable: default_resizable(),
+            #[cfg(feature = "visible")]
+            fullscreen: false,
+            #[cfg(feature = "visible")]
+            icon: None,
+        }
+    }
+}
+
+#[cfg(feature = "visible")]
+impl Game {
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = Some(PhysicalSize::new(width, height));
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn with_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    pub fn with_icon(mut self, icon: impl Into<PathBuf>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
 }
 
 #[allow(unused)]
@@ -71,6 +149,16 @@ impl Config {
     }
 }
 
+#[cfg(feature = "visible")]
+fn default_title() -> String {
+    "Arcana Game".to_owned()
+}
+
+#[cfg(feature = "visible")]
+fn default_resizable() -> bool {
+    true
+}
+
 fn default_teardown_timeout() -> TimeSpan {
     TimeSpan::from_seconds(5)
 }
@@ -2,19 +2,32 @@
 //!
 //! `TimeSpan` type is suitable for measuring difference between instances.
 
-pub use arcana_time::{TimeSpan, TimeSpanParseErr, TimeStamp};
+pub use arcana_time::{SignedTimeSpan, TimeSpan, TimeSpanParseErr, TimeStamp};
 use std::time::{Duration, Instant};
 
 /// Clocks for tracking current time, update delta time, global start time etc.
 /// Clocks are implemented using monotonously growing timer - `Instant`.
 ///
 /// Any kind of time measurement can be left to single global `Clocks` instance.
+///
+/// Clocks track two times.
+/// Real time advances monotonically with `Instant`.
+/// Game time is accumulated real time multiplied by time scale.
+/// It stops while clocks are paused.
 pub struct Clocks {
     /// Instant of clocks start.
     start: Instant,
 
-    /// TimeStamp relative to `start`.
+    /// Real time relative to `start`.
+    real_now: TimeStamp,
+
+    /// Game time. Does not advance while paused.
     now: TimeStamp,
+
+    paused: bool,
+
+    /// Multiplier of game time delta.
+    time_scale: f32,
 }
 
 /// Collection of clock measurements.
@@ -22,11 +35,20 @@ pub struct Clocks {
 /// Updated clock index is accessible in system, task and graphics contexts.
 #[derive(Clone, Copy, Debug)]
 pub struct ClockIndex {
-    /// Delta since previous step.
+    /// Game time delta since previous step.
+    /// Zero while clocks are paused.
     pub delta: TimeSpan,
 
-    /// Time elapsed from `start`.
+    /// Game time.
+    /// Accumulated scaled time elapsed from `start` excluding time spent paused.
     pub now: TimeStamp,
+
+    /// Real time delta since previous step.
+    /// Advances while clocks are paused, e.g. for menu and UI animations.
+    pub real_delta: TimeSpan,
+
+    /// Real time elapsed from `start`.
+    pub real_now: TimeStamp,
 }
 
 impl Default for Clocks {
@@ -46,7 +68,10 @@ impl Clocks {
         let now = Instant::now();
         Clocks {
             start: now,
+            real_now: TimeStamp::ORIGIN,
             now: TimeStamp::ORIGIN,
+            paused: false,
+            time_scale: 1.0,
         }
     }
 
@@ -63,13 +88,15 @@ impl Clocks {
         let elapsed = (now - start).as_nanos();
         assert!(elapsed < u64::MAX as u128);
 
-        self.now = TimeStamp::ORIGIN + TimeSpan::from_nanos(elapsed as u64);
+        self.real_now = TimeStamp::ORIGIN + TimeSpan::from_nanos(elapsed as u64);
+        self.now = self.real_now;
         self.start = start;
     }
 
     /// Restarts clocks from current instant.
     pub fn restart(&mut self) {
         self.start = Instant::now();
+        self.real_now = TimeStamp::ORIGIN;
         self.now = TimeStamp::ORIGIN;
     }
 
@@ -104,20 +131,124 @@ impl Clocks {
         assert!(elapsed < u64::MAX as u128);
 
         let elapsed = TimeSpan::from_nanos(elapsed as u64);
+        self.tick(TimeStamp::ORIGIN + elapsed)
+    }
 
-        let now = TimeStamp::ORIGIN + elapsed;
-        let delta = now.elapsed_since(self.now);
+    /// Advances clocks by fixed `delta` instead of measuring elapsed real time.
+    ///
+    /// Real time advances by `delta`, game time by `delta` scaled by time scale
+    /// unless paused.
+    /// Used to drive simulation deterministically, e.g. in tests and replays.
+    /// Clocks advanced this way should not be mixed with [`Clocks::advance`].
+    ///
+    /// # Example
+    /// ```
+    /// # use arcana::{clocks::Clocks, TimeSpan, TimeStamp};
+    /// let mut clocks = Clocks::new();
+    /// clocks.set_time_scale(0.5);
+    ///
+    /// let clock = clocks.step(TimeSpan::SECOND);
+    /// assert_eq!(clock.real_now, TimeStamp::ORIGIN + TimeSpan::SECOND);
+    /// assert_eq!(clock.delta, TimeSpan::MILLISECOND * 500);
+    /// ```
+    pub fn step(&mut self, delta: TimeSpan) -> ClockIndex {
+        self.tick(self.real_now + delta)
+    }
 
-        self.now = now;
+    /// Moves real time to `real_now` and game time by scaled real delta unless paused.
+    fn tick(&mut self, real_now: TimeStamp) -> ClockIndex {
+        let real_delta = real_now.duration_since(self.real_now);
+        self.real_now = real_now;
+
+        let delta = if self.paused {
+            TimeSpan::ZERO
+        } else if self.time_scale == 1.0 {
+            real_delta
+        } else {
+            real_delta.mul_f32(self.time_scale)
+        };
+        self.now += delta;
+
+        ClockIndex {
+            delta,
+            now: self.now,
+            real_delta,
+            real_now,
+        }
+    }
 
-        ClockIndex { delta, now }
+    /// Pauses or resumes game time.
+    ///
+    /// While paused `delta` is zero and `now` does not advance.
+    /// Fixed step systems do not accumulate ticks to catch up after resume.
+    /// Real time keeps advancing.
+    ///
+    /// Game loop keeps `Clocks` as a resource, so systems may pause the game
+    /// with `world.expect_resource_mut::<Clocks>().set_paused(true)`.
+    ///
+    /// # Example
+    /// ```
+    /// # use arcana::{clocks::Clocks, TimeSpan};
+    /// let mut clocks = Clocks::new();
+    /// let before = clocks.step(TimeSpan::SECOND);
+    ///
+    /// clocks.set_paused(true);
+    /// let paused = clocks.step(TimeSpan::SECOND);
+    ///
+    /// assert!(paused.delta.is_zero());
+    /// assert_eq!(paused.now, before.now, "Game time does not advance while paused");
+    /// assert_eq!(paused.real_delta, TimeSpan::SECOND);
+    ///
+    /// clocks.set_paused(false);
+    /// let resumed = clocks.step(TimeSpan::MILLISECOND);
+    /// assert_eq!(resumed.delta, TimeSpan::MILLISECOND, "Time spent paused is not caught up");
+    /// assert_eq!(resumed.now, before.now + TimeSpan::MILLISECOND);
+    /// ```
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
     }
 
+    /// Returns `true` if game time is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Sets multiplier of game time delta.
+    /// Values below `1.0` slow the game down, above `1.0` speed it up.
+    ///
+    /// Negative and NaN values are clamped to `0.0`.
+    /// Fixed step systems tick in scaled game time,
+    /// unless created with [`FixSystem::realtime`](crate::system::FixSystem::realtime).
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = if time_scale > 0.0 { time_scale } else { 0.0 };
+    }
+
+    /// Returns multiplier of game time delta.
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Returns instant at which game time reaches `timestamp`.
+    ///
+    /// If game time is paused or stopped by zero time scale
+    /// instant is computed as if time scale is `1.0`.
     pub fn time_stamp_to_instant(&self, timestamp: TimeStamp) -> Instant {
-        self.start + Duration::from(timestamp.elapsed())
+        let mut ahead = timestamp.saturating_duration_since(self.now);
+        if !self.paused && self.time_scale > 0.0 {
+            ahead = ahead.div_f32(self.time_scale);
+        }
+
+        let real = self.start + Duration::from(self.real_now.elapsed());
+        real.checked_add(Duration::from(ahead)).unwrap_or(real)
     }
 
+    /// Returns game time of the last step.
     pub fn now(&self) -> TimeStamp {
         self.now
     }
+
+    /// Returns real time of the last step.
+    pub fn real_now(&self) -> TimeStamp {
+        self.real_now
+    }
 }
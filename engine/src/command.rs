@@ -40,6 +40,39 @@ impl<T> CommandQueue<T> {
         self.commands.iter()
     }
 
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.commands.iter_mut()
+    }
+
+    /// Removes and yields commands for which `filter` returns `true`.
+    ///
+    /// Other commands are left in the queue in original order,
+    /// e.g. to be executed on later tick.
+    /// Commands not visited before the iterator is dropped are left in the queue as well.
+    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        DrainFilter {
+            commands: &mut self.commands,
+            index: 0,
+            filter,
+        }
+    }
+
+    /// Retains only commands for which `f` returns `true`.
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.commands.retain(f)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
     pub fn add(&mut self, command: T) {
         self.commands.push_back(command)
     }
@@ -48,3 +81,31 @@ impl<T> CommandQueue<T> {
         self.commands.extend(commands)
     }
 }
+
+/// Iterator returned by [`CommandQueue::drain_filter`].
+pub struct DrainFilter<'a, T, F> {
+    commands: &'a mut VecDeque<T>,
+    index: usize,
+    filter: F,
+}
+
+impl<T, F> Iterator for DrainFilter<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.index < self.commands.len() {
+            if (self.filter)(&mut self.commands[self.index]) {
+                return self.commands.remove(self.index);
+            }
+            self.index += 1;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.commands.len() - self.index))
+    }
+}
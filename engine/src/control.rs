@@ -1,8 +1,9 @@
 use std::{
     collections::hash_map::{Entry, HashMap},
-    fmt::Debug,
+    fmt::{self, Debug},
     hash::Hash,
     ops::Neg,
+    sync::Arc,
 };
 
 use edict::{
@@ -14,12 +15,16 @@ use winit::event::VirtualKeyCode;
 use crate::{
     command::CommandQueue,
     event::{
-        AxisId, ButtonId, DeviceEvent, DeviceId, ElementState, Event, KeyboardInput, MouseButton,
-        MouseScrollDelta, WindowEvent,
+        AxisId, ButtonId, DeviceEvent, DeviceId, ElementState, Event, GamepadAxis, GamepadButton,
+        GamepadEvent, GamepadId, KeyboardInput, MouseButton, MouseScrollDelta, WindowEvent,
     },
     funnel::Funnel,
 };
 
+mod bindings;
+
+pub use self::bindings::*;
+
 #[derive(Clone, Copy, Debug)]
 pub enum InputEvent {
     Focused(bool),
@@ -47,6 +52,16 @@ pub enum InputEvent {
         button: ButtonId,
         state: ElementState,
     },
+    GamepadButton {
+        gamepad: GamepadId,
+        button: GamepadButton,
+        state: ElementState,
+    },
+    GamepadAxis {
+        gamepad: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
 }
 
 /// Device is already associated with a controller.
@@ -154,7 +169,28 @@ impl Funnel<Event> for ControlFunnel {
                     DeviceEvent::Button { button, state } => InputEvent::Button { button, state },
                     _ => return Some(event),
                 };
-                (input_event, device_id)
+                (input_event, Some(device_id))
+            }
+
+            // Gamepads are not associated with devices and are handled by global controllers.
+            Event::Gamepad {
+                gamepad,
+                event: ref gamepad_event,
+            } => {
+                let input_event = match *gamepad_event {
+                    GamepadEvent::Button { button, state } => InputEvent::GamepadButton {
+                        gamepad,
+                        button,
+                        state,
+                    },
+                    GamepadEvent::Axis { axis, value } => InputEvent::GamepadAxis {
+                        gamepad,
+                        axis,
+                        value,
+                    },
+                    _ => return Some(event),
+                };
+                (input_event, None)
             }
 
             Event::WindowEvent {
@@ -166,10 +202,10 @@ impl Funnel<Event> for ControlFunnel {
                     button,
                     state,
                     ..
-                } => (InputEvent::MouseInput { state, button }, device_id),
+                } => (InputEvent::MouseInput { state, button }, Some(device_id)),
                 WindowEvent::KeyboardInput {
                     device_id, input, ..
-                } => (InputEvent::KeyboardInput(input), device_id),
+                } => (InputEvent::KeyboardInput(input), Some(device_id)),
                 WindowEvent::CursorMoved {
                     device_id,
                     position,
@@ -178,7 +214,7 @@ impl Funnel<Event> for ControlFunnel {
                     InputEvent::CursorMoved {
                         position: (position.x, position.y),
                     },
-                    device_id,
+                    Some(device_id),
                 ),
                 WindowEvent::Focused(v) => {
                     // This event is always broadcast to every controller.
@@ -216,14 +252,17 @@ impl Funnel<Event> for ControlFunnel {
             _ => return Some(event),
         };
 
-        let mut consumed = match control.devices.get_mut(&device_id) {
-            Some(controller) => match controller.control(input_event, world) {
-                ControlResult::ControlLost => {
-                    control.devices.remove(&device_id);
-                    false
-                }
-                ControlResult::Consumed => true,
-                ControlResult::Ignored => false,
+        let mut consumed = match device_id {
+            Some(device_id) => match control.devices.get_mut(&device_id) {
+                Some(controller) => match controller.control(input_event, world) {
+                    ControlResult::ControlLost => {
+                        control.devices.remove(&device_id);
+                        false
+                    }
+                    ControlResult::Consumed => true,
+                    ControlResult::Ignored => false,
+                },
+                None => false,
             },
             None => false,
         };
@@ -338,9 +377,28 @@ where
 /// When configured to produce signal pressing the key switches state to signalling and event is emitted repeatedly until key is unpressed.
 ///
 /// Only keys with key code can be configured.
-#[derive(Clone, Debug, Default)]
+///
+/// Gamepad buttons are configured the same way as keys.
+/// Gamepad axes and pairs of keys produce events when their value changes,
+/// see [`AxisFilter`] for details.
+/// Gamepads are not distinguished, input from all of them is handled alike.
+#[derive(Clone, Debug)]
 pub struct SimpleKeyBinder<T> {
     bindings: HashMap<VirtualKeyCode, SimpleKeyBinding<T>>,
+    buttons: HashMap<GamepadButton, SimpleKeyBinding<T>>,
+    axes: HashMap<GamepadAxis, SimpleAxisBinding<T>>,
+    key_axes: Vec<SimpleKeyAxisBinding<T>>,
+}
+
+impl<T> Default for SimpleKeyBinder<T> {
+    fn default() -> Self {
+        SimpleKeyBinder {
+            bindings: HashMap::new(),
+            buttons: HashMap::new(),
+            axes: HashMap::new(),
+            key_axes: Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -349,6 +407,197 @@ struct SimpleKeyBinding<T> {
     action: SimpleKeyEventAction<T>,
 }
 
+impl<T> SimpleKeyBinding<T> {
+    fn new(action: SimpleKeyEventAction<T>) -> Self {
+        SimpleKeyBinding {
+            action,
+            pressed: false,
+        }
+    }
+
+    fn handle(&mut self, state: ElementState) -> Option<&T> {
+        match state {
+            ElementState::Pressed => {
+                if self.pressed {
+                    None
+                } else {
+                    self.pressed = true;
+                    self.action.on_press.as_ref()
+                }
+            }
+            ElementState::Released => {
+                if self.pressed {
+                    self.pressed = false;
+                    self.action.on_release.as_ref()
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Default deadzone of gamepad axes.
+pub const DEFAULT_AXIS_DEADZONE: f32 = 0.1;
+
+/// Default minimal change of axis value that produces an action.
+pub const DEFAULT_AXIS_THRESHOLD: f32 = 0.02;
+
+/// Controls how raw axis values are turned into actions.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AxisFilter {
+    /// Values with magnitude not exceeding deadzone are treated as zero.
+    /// Values outside deadzone are rescaled to keep `[-1, 1]` range.
+    pub deadzone: f32,
+
+    /// Minimal change of value since last action that produces new action.
+    /// Reaching zero or full tilt always produces an action.
+    pub threshold: f32,
+}
+
+impl Default for AxisFilter {
+    fn default() -> Self {
+        AxisFilter {
+            deadzone: DEFAULT_AXIS_DEADZONE,
+            threshold: DEFAULT_AXIS_THRESHOLD,
+        }
+    }
+}
+
+impl AxisFilter {
+    /// Applies deadzone to raw axis value.
+    fn apply(&self, value: f32) -> f32 {
+        let deadzone = self.deadzone.clamp(0.0, 1.0 - f32::EPSILON);
+        let magnitude = value.abs();
+        if magnitude <= deadzone {
+            0.0
+        } else {
+            ((magnitude - deadzone) / (1.0 - deadzone))
+                .min(1.0)
+                .copysign(value)
+        }
+    }
+
+    /// Checks if change from last value to new one should produce an action.
+    fn passes(&self, last: f32, value: f32) -> bool {
+        if last == value {
+            return false;
+        }
+        value == 0.0 || value.abs() == 1.0 || (value - last).abs() >= self.threshold
+    }
+}
+
+/// Action bound to an axis.
+/// Makes action from axis value.
+struct SimpleAxisAction<T> {
+    filter: AxisFilter,
+    make: Arc<dyn Fn(f32) -> T + Send + Sync>,
+}
+
+impl<T> SimpleAxisAction<T> {
+    fn new<F, A>(filter: AxisFilter, f: F) -> Self
+    where
+        F: Fn(f32) -> A + Send + Sync + 'static,
+        A: Into<T>,
+        T: 'static,
+    {
+        SimpleAxisAction {
+            filter,
+            make: Arc::new(move |value| f(value).into()),
+        }
+    }
+}
+
+impl<T> Clone for SimpleAxisAction<T> {
+    fn clone(&self) -> Self {
+        SimpleAxisAction {
+            filter: self.filter,
+            make: self.make.clone(),
+        }
+    }
+}
+
+impl<T> Debug for SimpleAxisAction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimpleAxisAction")
+            .field("filter", &self.filter)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Actions are equal only if they share the same function.
+impl<T> PartialEq for SimpleAxisAction<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.filter.deadzone.to_bits() == other.filter.deadzone.to_bits()
+            && self.filter.threshold.to_bits() == other.filter.threshold.to_bits()
+            && Arc::ptr_eq(&self.make, &other.make)
+    }
+}
+
+impl<T> Eq for SimpleAxisAction<T> {}
+
+#[derive(Clone, Debug)]
+struct SimpleAxisBinding<T> {
+    value: f32,
+    action: SimpleAxisAction<T>,
+}
+
+impl<T> SimpleAxisBinding<T> {
+    fn new(action: SimpleAxisAction<T>) -> Self {
+        SimpleAxisBinding { value: 0.0, action }
+    }
+
+    /// Updates axis with raw value.
+    /// Returns action if filtered value changed enough.
+    fn update(&mut self, value: f32) -> Option<T> {
+        let filter = &self.action.filter;
+        let value = filter.apply(value);
+        if !filter.passes(self.value, value) {
+            return None;
+        }
+        self.value = value;
+        Some((self.action.make)(value))
+    }
+}
+
+/// Action bound to a pair of keys that act as an axis.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SimpleKeyAxisAction<T> {
+    positive: VirtualKeyCode,
+    negative: VirtualKeyCode,
+    action: SimpleAxisAction<T>,
+}
+
+#[derive(Clone, Debug)]
+struct SimpleKeyAxisBinding<T> {
+    positive: VirtualKeyCode,
+    negative: VirtualKeyCode,
+    positive_pressed: bool,
+    negative_pressed: bool,
+    axis: SimpleAxisBinding<T>,
+}
+
+impl<T> SimpleKeyAxisBinding<T> {
+    fn new(action: SimpleKeyAxisAction<T>) -> Self {
+        SimpleKeyAxisBinding {
+            positive: action.positive,
+            negative: action.negative,
+            positive_pressed: false,
+            negative_pressed: false,
+            axis: SimpleAxisBinding::new(action.action),
+        }
+    }
+
+    fn to_action(&self) -> SimpleKeyAxisAction<T> {
+        SimpleKeyAxisAction {
+            positive: self.positive,
+            negative: self.negative,
+            action: self.axis.action.clone(),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct SimpleKeyEventAction<T> {
     #[serde(default = "none", skip_serializing_if = "Option::is_none")]
@@ -409,14 +658,22 @@ impl<T> SimpleKeyBinder<T> {
             bindings: builder
                 .bindings
                 .into_iter()
-                .map(|(key, action)| {
-                    let binding = SimpleKeyBinding {
-                        action,
-                        pressed: false,
-                    };
-
-                    (key, binding)
-                })
+                .map(|(key, action)| (key, SimpleKeyBinding::new(action)))
+                .collect(),
+            buttons: builder
+                .buttons
+                .into_iter()
+                .map(|(button, action)| (button, SimpleKeyBinding::new(action)))
+                .collect(),
+            axes: builder
+                .axes
+                .into_iter()
+                .map(|(axis, action)| (axis, SimpleAxisBinding::new(action)))
+                .collect(),
+            key_axes: builder
+                .key_axes
+                .into_iter()
+                .map(SimpleKeyAxisBinding::new)
                 .collect(),
         }
     }
@@ -434,14 +691,22 @@ impl<T> SimpleKeyBinder<T> {
             bindings: builder
                 .bindings
                 .iter()
-                .map(|(key, action)| {
-                    let binding = SimpleKeyBinding {
-                        action: action.clone(),
-                        pressed: false,
-                    };
-
-                    (*key, binding)
-                })
+                .map(|(key, action)| (*key, SimpleKeyBinding::new(action.clone())))
+                .collect(),
+            buttons: builder
+                .buttons
+                .iter()
+                .map(|(button, action)| (*button, SimpleKeyBinding::new(action.clone())))
+                .collect(),
+            axes: builder
+                .axes
+                .iter()
+                .map(|(axis, action)| (*axis, SimpleAxisBinding::new(action.clone())))
+                .collect(),
+            key_axes: builder
+                .key_axes
+                .iter()
+                .map(|action| SimpleKeyAxisBinding::new(action.clone()))
                 .collect(),
         }
     }
@@ -457,54 +722,266 @@ impl<T> SimpleKeyBinder<T> {
                 .iter()
                 .map(|(key, binding)| (*key, binding.action.clone()))
                 .collect(),
+            buttons: self
+                .buttons
+                .iter()
+                .map(|(button, binding)| (*button, binding.action.clone()))
+                .collect(),
+            axes: self
+                .axes
+                .iter()
+                .map(|(axis, binding)| (*axis, binding.action.clone()))
+                .collect(),
+            key_axes: self
+                .key_axes
+                .iter()
+                .map(SimpleKeyAxisBinding::to_action)
+                .collect(),
         }
     }
 
     /// Handle input key event.
     pub fn handle_input(&mut self, input: &KeyboardInput) -> Option<&T> {
         let binding = self.bindings.get_mut(input.virtual_keycode.as_ref()?)?;
-        match input.state {
-            ElementState::Pressed => {
-                if binding.pressed {
-                    None
-                } else {
-                    binding.pressed = true;
-                    binding.action.on_press.as_ref()
-                }
-            }
-            ElementState::Released => {
-                if binding.pressed {
-                    binding.pressed = false;
-                    binding.action.on_release.as_ref()
-                } else {
-                    None
-                }
+        binding.handle(input.state)
+    }
+
+    /// Handle gamepad button event.
+    pub fn handle_button(&mut self, button: GamepadButton, state: ElementState) -> Option<&T> {
+        let binding = self.buttons.get_mut(&button)?;
+        binding.handle(state)
+    }
+
+    /// Handle gamepad axis event.
+    ///
+    /// Returns action only if axis value changes enough after deadzone is applied.
+    pub fn handle_axis(&mut self, axis: GamepadAxis, value: f32) -> Option<T> {
+        let binding = self.axes.get_mut(&axis)?;
+        binding.update(value)
+    }
+
+    /// Handle input key event for keys bound as axis.
+    ///
+    /// Returns action only if combined value of the keys changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::{control::SimpleKeyBinder, event::{ElementState, KeyboardInput, VirtualKeyCode}};
+    /// #[allow(deprecated)]
+    /// fn key(key: VirtualKeyCode, state: ElementState) -> KeyboardInput {
+    ///     KeyboardInput {
+    ///         scancode: 0,
+    ///         state,
+    ///         virtual_keycode: Some(key),
+    ///         modifiers: Default::default(),
+    ///     }
+    /// }
+    ///
+    /// let mut binder = SimpleKeyBinder::builder()
+    ///     .on_axis_keys(VirtualKeyCode::W, VirtualKeyCode::S, |value| value)
+    ///     .build();
+    ///
+    /// let events = [
+    ///     key(VirtualKeyCode::W, ElementState::Pressed),
+    ///     key(VirtualKeyCode::W, ElementState::Pressed), // Key repeat.
+    ///     key(VirtualKeyCode::S, ElementState::Pressed),
+    ///     key(VirtualKeyCode::W, ElementState::Released),
+    ///     key(VirtualKeyCode::S, ElementState::Released),
+    /// ];
+    ///
+    /// let values: Vec<f32> = events
+    ///     .iter()
+    ///     .filter_map(|input| binder.handle_key_axis(input))
+    ///     .collect();
+    ///
+    /// // Both keys pressed resolve to zero.
+    /// assert_eq!(values, [1.0, 0.0, -1.0, 0.0]);
+    /// ```
+    pub fn handle_key_axis(&mut self, input: &KeyboardInput) -> Option<T> {
+        let key = input.virtual_keycode?;
+        let pressed = input.state == ElementState::Pressed;
+
+        let binding = self
+            .key_axes
+            .iter_mut()
+            .find(|binding| binding.positive == key || binding.negative == key)?;
+
+        if binding.positive == key {
+            binding.positive_pressed = pressed;
+        } else {
+            binding.negative_pressed = pressed;
+        }
+
+        let value = binding.positive_pressed as u8 as f32 - binding.negative_pressed as u8 as f32;
+        binding.axis.update(value)
+    }
+
+    /// Handle any supported input event.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::{control::{InputEvent, SimpleKeyBinder}, event::{ElementState, GamepadAxis, GamepadButton, GamepadId}};
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Command {
+    ///     Fire,
+    ///     Drive(f32),
+    /// }
+    ///
+    /// let mut binder = SimpleKeyBinder::builder()
+    ///     .on_button(GamepadButton::South, Command::Fire)
+    ///     .on_axis_with_deadzone(GamepadAxis::LeftStickY, 0.25, Command::Drive)
+    ///     .build();
+    ///
+    /// let gamepad = GamepadId(0);
+    /// let button = |state| InputEvent::GamepadButton {
+    ///     gamepad,
+    ///     button: GamepadButton::South,
+    ///     state,
+    /// };
+    /// let axis = |value| InputEvent::GamepadAxis {
+    ///     gamepad,
+    ///     axis: GamepadAxis::LeftStickY,
+    ///     value,
+    /// };
+    ///
+    /// let events = [
+    ///     axis(0.125), // Within deadzone.
+    ///     button(ElementState::Pressed),
+    ///     button(ElementState::Pressed),
+    ///     button(ElementState::Released),
+    ///     axis(0.625),
+    ///     axis(0.625),
+    ///     axis(1.0),
+    ///     axis(-0.125),
+    /// ];
+    ///
+    /// let commands: Vec<_> = events
+    ///     .iter()
+    ///     .filter_map(|event| binder.handle_event(event))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     commands,
+    ///     [
+    ///         Command::Fire,
+    ///         Command::Drive(0.5),
+    ///         Command::Drive(1.0),
+    ///         Command::Drive(0.0),
+    ///     ]
+    /// );
+    /// ```
+    pub fn handle_event(&mut self, event: &InputEvent) -> Option<T>
+    where
+        T: Clone,
+    {
+        match *event {
+            InputEvent::KeyboardInput(ref input) => match self.handle_input(input) {
+                Some(action) => Some(action.clone()),
+                None => self.handle_key_axis(input),
+            },
+            InputEvent::GamepadButton { button, state, .. } => {
+                self.handle_button(button, state).cloned()
             }
+            InputEvent::GamepadAxis { axis, value, .. } => self.handle_axis(axis, value),
+            _ => None,
         }
     }
 
     /// Returns an iterator over current `on_hold` actions.
     pub fn iter_holds(&self) -> impl Iterator<Item = &T> + '_ {
-        self.bindings.values().filter_map(|binding| {
-            if binding.pressed {
-                binding.action.on_hold.as_ref()
-            } else {
-                None
-            }
-        })
+        self.bindings
+            .values()
+            .chain(self.buttons.values())
+            .filter_map(|binding| {
+                if binding.pressed {
+                    binding.action.on_hold.as_ref()
+                } else {
+                    None
+                }
+            })
     }
 }
 
+/// Builder for [`SimpleKeyBinder`].
+///
+/// Serialized with key bindings under `keys` and gamepad buttons under `buttons`.
+/// Plain map from key to actions, written before gamepad bindings were added,
+/// is still accepted.
+/// Axis bindings, including keys bound as axis, are not serialized.
+/// Use [`SimpleAxisBindings`] to store them.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{control::SimpleKeyBuilder, event::{GamepadButton, VirtualKeyCode}};
+/// let legacy: SimpleKeyBuilder<u32> =
+///     serde_json::from_str(r#"{ "Space": { "on_press": 1 } }"#).unwrap();
+/// assert_eq!(legacy, SimpleKeyBuilder::new().on_press(VirtualKeyCode::Space, 1));
+///
+/// let builder = SimpleKeyBuilder::<u32>::new()
+///     .on_press(VirtualKeyCode::Space, 1)
+///     .on_button(GamepadButton::South, 2);
+///
+/// let json = serde_json::to_string(&builder).unwrap();
+/// let restored: SimpleKeyBuilder<u32> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, builder);
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-#[serde(transparent)]
+#[serde(from = "SimpleKeyBuilderRepr<T>")]
 pub struct SimpleKeyBuilder<T> {
+    #[serde(rename = "keys")]
     bindings: HashMap<VirtualKeyCode, SimpleKeyEventAction<T>>,
+
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    buttons: HashMap<GamepadButton, SimpleKeyEventAction<T>>,
+
+    #[serde(skip)]
+    axes: HashMap<GamepadAxis, SimpleAxisAction<T>>,
+
+    #[serde(skip)]
+    key_axes: Vec<SimpleKeyAxisAction<T>>,
+}
+
+/// Accepted serialized forms of [`SimpleKeyBuilder`].
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum SimpleKeyBuilderRepr<T> {
+    Full {
+        keys: HashMap<VirtualKeyCode, SimpleKeyEventAction<T>>,
+
+        #[serde(default)]
+        buttons: HashMap<GamepadButton, SimpleKeyEventAction<T>>,
+    },
+
+    /// Plain map from key to actions.
+    Keys(HashMap<VirtualKeyCode, SimpleKeyEventAction<T>>),
+}
+
+impl<T> From<SimpleKeyBuilderRepr<T>> for SimpleKeyBuilder<T> {
+    fn from(repr: SimpleKeyBuilderRepr<T>) -> Self {
+        match repr {
+            SimpleKeyBuilderRepr::Full { keys, buttons } => SimpleKeyBuilder {
+                bindings: keys,
+                buttons,
+                ..SimpleKeyBuilder::new()
+            },
+            SimpleKeyBuilderRepr::Keys(bindings) => SimpleKeyBuilder {
+                bindings,
+                ..SimpleKeyBuilder::new()
+            },
+        }
+    }
 }
 
 impl<T> Default for SimpleKeyBuilder<T> {
     fn default() -> Self {
         SimpleKeyBuilder {
             bindings: HashMap::default(),
+            buttons: HashMap::default(),
+            axes: HashMap::default(),
+            key_axes: Vec::new(),
         }
     }
 }
@@ -514,11 +991,24 @@ pub struct KeyAlreadyBound {
     key: VirtualKeyCode,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ButtonAlreadyBound {
+    button: GamepadButton,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AxisAlreadyBound {
+    axis: GamepadAxis,
+}
+
 impl<T> SimpleKeyBuilder<T> {
     /// Returns new empty mapping.
     pub fn new() -> Self {
         SimpleKeyBuilder {
             bindings: HashMap::new(),
+            buttons: HashMap::new(),
+            axes: HashMap::new(),
+            key_axes: Vec::new(),
         }
     }
 
@@ -846,6 +1336,205 @@ impl<T> SimpleKeyBuilder<T> {
         }
     }
 
+    /// Binds action to a gamepad button press.
+    ///
+    /// Panics if button press is already bound.
+    pub fn on_button<A>(mut self, button: GamepadButton, action: A) -> Self
+    where
+        A: Into<T>,
+    {
+        self.try_on_button(button, action).unwrap();
+        self
+    }
+
+    /// Binds action to a gamepad button press and release.
+    ///
+    /// Panics if button press or release is already bound.
+    pub fn on_button_switch<P, R>(mut self, button: GamepadButton, press: P, release: R) -> Self
+    where
+        P: Into<T>,
+        R: Into<T>,
+    {
+        self.try_on_button_switch(button, press, release).unwrap();
+        self
+    }
+
+    /// Binds action to a gamepad axis with default [`AxisFilter`].
+    ///
+    /// Panics if axis is already bound.
+    pub fn on_axis<F, A>(self, axis: GamepadAxis, f: F) -> Self
+    where
+        F: Fn(f32) -> A + Send + Sync + 'static,
+        A: Into<T>,
+        T: 'static,
+    {
+        self.on_axis_filtered(axis, AxisFilter::default(), f)
+    }
+
+    /// Binds action to a gamepad axis.
+    /// Axis values with magnitude not exceeding `deadzone` are treated as zero.
+    ///
+    /// Panics if axis is already bound.
+    pub fn on_axis_with_deadzone<F, A>(self, axis: GamepadAxis, deadzone: f32, f: F) -> Self
+    where
+        F: Fn(f32) -> A + Send + Sync + 'static,
+        A: Into<T>,
+        T: 'static,
+    {
+        let filter = AxisFilter {
+            deadzone,
+            ..AxisFilter::default()
+        };
+        self.on_axis_filtered(axis, filter, f)
+    }
+
+    /// Binds action to a gamepad axis with specified filter.
+    ///
+    /// Panics if axis is already bound.
+    pub fn on_axis_filtered<F, A>(mut self, axis: GamepadAxis, filter: AxisFilter, f: F) -> Self
+    where
+        F: Fn(f32) -> A + Send + Sync + 'static,
+        A: Into<T>,
+        T: 'static,
+    {
+        self.try_on_axis(axis, filter, f).unwrap();
+        self
+    }
+
+    /// Binds action to a pair of keys that act as an axis.
+    /// Pressing `positive` key yields `1.0`, pressing `negative` key yields `-1.0`,
+    /// and pressing both or none yields `0.0`.
+    ///
+    /// Unlike [`SimpleKeyBuilder::on_key_axis`] action is made from combined value of the keys,
+    /// and is produced only when that value changes.
+    ///
+    /// Panics if any of the two keys is already bound.
+    pub fn on_axis_keys<F, A>(
+        mut self,
+        positive: VirtualKeyCode,
+        negative: VirtualKeyCode,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(f32) -> A + Send + Sync + 'static,
+        A: Into<T>,
+        T: 'static,
+    {
+        self.try_on_axis_keys(positive, negative, f).unwrap();
+        self
+    }
+
+    /// Binds action to a gamepad button press.
+    ///
+    /// Fails if button press is already bound.
+    pub fn try_on_button<A>(
+        &mut self,
+        button: GamepadButton,
+        action: A,
+    ) -> Result<(), ButtonAlreadyBound>
+    where
+        A: Into<T>,
+    {
+        let bind = self.buttons.entry(button).or_default();
+
+        match &mut bind.on_press {
+            Some(_) => Err(ButtonAlreadyBound { button }),
+            slot => {
+                *slot = Some(action.into());
+                Ok(())
+            }
+        }
+    }
+
+    /// Binds action to a gamepad button press and release events.
+    ///
+    /// Fails if button press or release is already bound.
+    pub fn try_on_button_switch<P, R>(
+        &mut self,
+        button: GamepadButton,
+        press: P,
+        release: R,
+    ) -> Result<(), ButtonAlreadyBound>
+    where
+        P: Into<T>,
+        R: Into<T>,
+    {
+        let bind = self.buttons.entry(button).or_default();
+
+        match (&mut bind.on_press, &mut bind.on_release) {
+            (Some(_), _) | (_, Some(_)) => Err(ButtonAlreadyBound { button }),
+            (on_press, on_release) => {
+                *on_press = Some(press.into());
+                *on_release = Some(release.into());
+                Ok(())
+            }
+        }
+    }
+
+    /// Binds action to a gamepad axis with specified filter.
+    ///
+    /// Fails if axis is already bound.
+    pub fn try_on_axis<F, A>(
+        &mut self,
+        axis: GamepadAxis,
+        filter: AxisFilter,
+        f: F,
+    ) -> Result<(), AxisAlreadyBound>
+    where
+        F: Fn(f32) -> A + Send + Sync + 'static,
+        A: Into<T>,
+        T: 'static,
+    {
+        match self.axes.entry(axis) {
+            Entry::Occupied(_) => Err(AxisAlreadyBound { axis }),
+            Entry::Vacant(entry) => {
+                entry.insert(SimpleAxisAction::new(filter, f));
+                Ok(())
+            }
+        }
+    }
+
+    /// Binds action to a pair of keys that act as an axis.
+    ///
+    /// Fails if any of the two keys is already bound.
+    pub fn try_on_axis_keys<F, A>(
+        &mut self,
+        positive: VirtualKeyCode,
+        negative: VirtualKeyCode,
+        f: F,
+    ) -> Result<(), KeyAlreadyBound>
+    where
+        F: Fn(f32) -> A + Send + Sync + 'static,
+        A: Into<T>,
+        T: 'static,
+    {
+        for key in [positive, negative] {
+            let bound = self.bindings.contains_key(&key)
+                || self
+                    .key_axes
+                    .iter()
+                    .any(|axis| axis.positive == key || axis.negative == key);
+
+            if bound {
+                return Err(KeyAlreadyBound { key });
+            }
+        }
+
+        self.key_axes.push(SimpleKeyAxisAction {
+            positive,
+            negative,
+            action: SimpleAxisAction::new(AxisFilter::default(), f),
+        });
+        Ok(())
+    }
+
+    /// Clears gamepad axis binding if there any.
+    ///
+    /// Returns `true` if axis was bound.
+    pub fn clear_on_axis(&mut self, axis: GamepadAxis) -> bool {
+        self.axes.remove(&axis).is_some()
+    }
+
     /// Converts builder into binder.
     pub fn build(self) -> SimpleKeyBinder<T> {
         SimpleKeyBinder::from_builder(self)
@@ -861,3 +1550,152 @@ impl<T> SimpleKeyBuilder<T> {
         SimpleKeyBinder::from_borrowed_builder(self)
     }
 }
+
+/// Action that carries value of the axis it is bound to.
+///
+/// Required for actions stored in [`SimpleAxisBindings`].
+pub trait AxisAction {
+    /// Returns action with specified axis value.
+    fn with_axis_value(&self, value: f32) -> Self;
+}
+
+impl AxisAction for f32 {
+    fn with_axis_value(&self, value: f32) -> f32 {
+        value
+    }
+}
+
+/// Serializable axis bindings.
+///
+/// Unlike [`SimpleKeyBuilder::on_axis`] that makes actions with a function,
+/// actions here are templates filled with axis value using [`AxisAction`].
+/// Bindings are added to [`SimpleKeyBuilder`] with [`SimpleAxisBindings::bind`].
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{control::{SimpleAxisBindings, SimpleKeyBuilder}, event::{GamepadAxis, VirtualKeyCode}};
+/// let bindings = SimpleAxisBindings::<f32>::new()
+///     .on_axis(GamepadAxis::LeftStickY, 0.0)
+///     .on_axis_keys(VirtualKeyCode::W, VirtualKeyCode::S, 0.0);
+///
+/// let json = serde_json::to_string(&bindings).unwrap();
+/// let restored: SimpleAxisBindings<f32> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, bindings);
+///
+/// let mut binder = restored.bind(SimpleKeyBuilder::new()).build();
+/// assert_eq!(binder.handle_axis(GamepadAxis::LeftStickY, 1.0), Some(1.0));
+/// ```
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SimpleAxisBindings<T> {
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    axes: HashMap<GamepadAxis, SimpleAxisTemplate<T>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    key_axes: Vec<SimpleKeyAxisTemplate<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct SimpleAxisTemplate<T> {
+    action: T,
+
+    #[serde(default)]
+    filter: AxisFilter,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct SimpleKeyAxisTemplate<T> {
+    positive: VirtualKeyCode,
+    negative: VirtualKeyCode,
+    action: T,
+}
+
+impl<T> Default for SimpleAxisBindings<T> {
+    fn default() -> Self {
+        SimpleAxisBindings {
+            axes: HashMap::new(),
+            key_axes: Vec::new(),
+        }
+    }
+}
+
+impl<T> SimpleAxisBindings<T> {
+    /// Returns new empty bindings.
+    pub fn new() -> Self {
+        SimpleAxisBindings::default()
+    }
+
+    /// Binds action to a gamepad axis with default [`AxisFilter`].
+    ///
+    /// Replaces previous binding of the axis.
+    pub fn on_axis<A>(self, axis: GamepadAxis, action: A) -> Self
+    where
+        A: Into<T>,
+    {
+        self.on_axis_filtered(axis, AxisFilter::default(), action)
+    }
+
+    /// Binds action to a gamepad axis with specified filter.
+    ///
+    /// Replaces previous binding of the axis.
+    pub fn on_axis_filtered<A>(mut self, axis: GamepadAxis, filter: AxisFilter, action: A) -> Self
+    where
+        A: Into<T>,
+    {
+        self.axes.insert(
+            axis,
+            SimpleAxisTemplate {
+                action: action.into(),
+                filter,
+            },
+        );
+        self
+    }
+
+    /// Binds action to a pair of keys that act as an axis.
+    /// See [`SimpleKeyBuilder::on_axis_keys`].
+    pub fn on_axis_keys<A>(
+        mut self,
+        positive: VirtualKeyCode,
+        negative: VirtualKeyCode,
+        action: A,
+    ) -> Self
+    where
+        A: Into<T>,
+    {
+        self.key_axes.push(SimpleKeyAxisTemplate {
+            positive,
+            negative,
+            action: action.into(),
+        });
+        self
+    }
+
+    /// Adds bindings to the builder.
+    ///
+    /// Panics if any axis or key is already bound in the builder.
+    pub fn bind(&self, mut builder: SimpleKeyBuilder<T>) -> SimpleKeyBuilder<T>
+    where
+        T: AxisAction + Clone + Send + Sync + 'static,
+    {
+        for (&axis, template) in &self.axes {
+            let action = template.action.clone();
+            builder
+                .try_on_axis(axis, template.filter, move |value| {
+                    action.with_axis_value(value)
+                })
+                .unwrap();
+        }
+
+        for template in &self.key_axes {
+            let action = template.action.clone();
+            builder
+                .try_on_axis_keys(template.positive, template.negative, move |value| {
+                    action.with_axis_value(value)
+                })
+                .unwrap();
+        }
+
+        builder
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    collections::hash_map::{Entry, HashMap},
+    hash::Hash,
+    sync::Arc,
+};
+
+use parking_lot::RwLock;
+use winit::event::VirtualKeyCode;
+
+use crate::event::KeyboardInput;
+
+use super::{SimpleKeyBinding, SimpleKeyEventAction};
+
+/// Map of game actions to keys.
+///
+/// Unlike [`SimpleKeyBinder`](super::SimpleKeyBinder) it can be changed at runtime,
+/// e.g. from controls settings menu, and saved with the rest of game settings.
+/// Each key is bound to at most one action.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{control::KeyBindings, event::VirtualKeyCode};
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+/// enum Action {
+///     Jump,
+///     Fire,
+/// }
+///
+/// let mut bindings = KeyBindings::new()
+///     .with(Action::Jump, VirtualKeyCode::Space)
+///     .with(Action::Fire, VirtualKeyCode::F);
+///
+/// // Space is taken from `Jump`.
+/// assert_eq!(bindings.rebind(Action::Fire, VirtualKeyCode::Space), Some(Action::Jump));
+/// assert_eq!(bindings.key(Action::Jump), None);
+/// assert_eq!(bindings.action(VirtualKeyCode::Space), Some(&Action::Fire));
+/// assert_eq!(bindings.action(VirtualKeyCode::F), None);
+///
+/// let json = serde_json::to_string(&bindings).unwrap();
+/// let restored: KeyBindings<Action> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored, bindings);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+#[serde(bound(
+    serialize = "A: serde::Serialize",
+    deserialize = "A: serde::Deserialize<'de> + Eq + Hash"
+))]
+pub struct KeyBindings<A> {
+    keys: HashMap<A, VirtualKeyCode>,
+}
+
+impl<A> Default for KeyBindings<A> {
+    fn default() -> Self {
+        KeyBindings {
+            keys: HashMap::new(),
+        }
+    }
+}
+
+impl<A> KeyBindings<A>
+where
+    A: Clone + Eq + Hash,
+{
+    /// Returns empty bindings.
+    pub fn new() -> Self {
+        KeyBindings::default()
+    }
+
+    /// Binds action to the key.
+    /// Action previously bound to the key is unbound.
+    pub fn with(mut self, action: A, key: VirtualKeyCode) -> Self {
+        self.rebind(action, key);
+        self
+    }
+
+    /// Returns key bound to the action.
+    pub fn key(&self, action: A) -> Option<VirtualKeyCode> {
+        self.keys.get(&action).copied()
+    }
+
+    /// Returns action bound to the key.
+    pub fn action(&self, key: VirtualKeyCode) -> Option<&A> {
+        self.keys
+            .iter()
+            .find_map(|(action, bound)| (*bound == key).then_some(action))
+    }
+
+    /// Binds action to the key, replacing key previously bound to the action.
+    ///
+    /// If the key was bound to another action, that action is unbound and returned.
+    pub fn rebind(&mut self, action: A, key: VirtualKeyCode) -> Option<A> {
+        let conflict = self.action(key).filter(|bound| **bound != action).cloned();
+
+        if let Some(conflict) = &conflict {
+            self.keys.remove(conflict);
+        }
+
+        self.keys.insert(action, key);
+        conflict
+    }
+
+    /// Unbinds the action.
+    ///
+    /// Returns key that was bound to the action.
+    pub fn unbind(&mut self, action: A) -> Option<VirtualKeyCode> {
+        self.keys.remove(&action)
+    }
+
+    /// Returns an iterator over bound actions and their keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&A, VirtualKeyCode)> + '_ {
+        self.keys.iter().map(|(action, key)| (action, *key))
+    }
+}
+
+/// Key binder that produces commands for actions bound in shared [`KeyBindings`].
+///
+/// Bindings are read on each input event,
+/// so changes made through the shared handle take effect immediately.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use arcana::{control::{KeyBindings, KeyBindingsBinder}, event::{ElementState, KeyboardInput, VirtualKeyCode}};
+/// # use parking_lot::RwLock;
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// enum Action {
+///     Fire,
+/// }
+///
+/// #[allow(deprecated)]
+/// fn press(key: VirtualKeyCode) -> KeyboardInput {
+///     KeyboardInput {
+///         scancode: 0,
+///         state: ElementState::Pressed,
+///         virtual_keycode: Some(key),
+///         modifiers: Default::default(),
+///     }
+/// }
+///
+/// let bindings = Arc::new(RwLock::new(
+///     KeyBindings::new().with(Action::Fire, VirtualKeyCode::Space),
+/// ));
+///
+/// let mut binder = KeyBindingsBinder::new(bindings.clone()).on_press(Action::Fire, "fire");
+/// assert_eq!(binder.handle_input(&press(VirtualKeyCode::Space)), Some(&"fire"));
+///
+/// bindings.write().rebind(Action::Fire, VirtualKeyCode::F);
+/// binder.reset();
+///
+/// assert_eq!(binder.handle_input(&press(VirtualKeyCode::Space)), None);
+/// assert_eq!(binder.handle_input(&press(VirtualKeyCode::F)), Some(&"fire"));
+/// ```
+#[derive(Debug)]
+pub struct KeyBindingsBinder<A, T> {
+    bindings: Arc<RwLock<KeyBindings<A>>>,
+    actions: HashMap<A, SimpleKeyBinding<T>>,
+}
+
+impl<A, T> KeyBindingsBinder<A, T>
+where
+    A: Clone + Eq + Hash,
+{
+    /// Returns new binder that reads specified bindings.
+    pub fn new(bindings: Arc<RwLock<KeyBindings<A>>>) -> Self {
+        KeyBindingsBinder {
+            bindings,
+            actions: HashMap::new(),
+        }
+    }
+
+    /// Returns shared bindings read by this binder.
+    pub fn bindings(&self) -> &Arc<RwLock<KeyBindings<A>>> {
+        &self.bindings
+    }
+
+    /// Sets command produced when key bound to the action is pressed.
+    pub fn on_press(mut self, action: A, command: impl Into<T>) -> Self {
+        self.event_action(action).on_press = Some(command.into());
+        self
+    }
+
+    /// Sets command produced when key bound to the action is released.
+    pub fn on_release(mut self, action: A, command: impl Into<T>) -> Self {
+        self.event_action(action).on_release = Some(command.into());
+        self
+    }
+
+    /// Sets commands produced when key bound to the action is pressed and released.
+    pub fn on_switch(mut self, action: A, press: impl Into<T>, release: impl Into<T>) -> Self {
+        let event_action = self.event_action(action);
+        event_action.on_press = Some(press.into());
+        event_action.on_release = Some(release.into());
+        self
+    }
+
+    /// Sets command produced while key bound to the action is held.
+    pub fn on_hold(mut self, action: A, command: impl Into<T>) -> Self {
+        self.event_action(action).on_hold = Some(command.into());
+        self
+    }
+
+    /// Handle input key event.
+    pub fn handle_input(&mut self, input: &KeyboardInput) -> Option<&T> {
+        let key = input.virtual_keycode?;
+        let action = self.bindings.read().action(key).cloned()?;
+        let binding = self.actions.get_mut(&action)?;
+        binding.handle(input.state)
+    }
+
+    /// Returns an iterator over current `on_hold` commands.
+    pub fn iter_holds(&self) -> impl Iterator<Item = &T> + '_ {
+        self.actions.values().filter_map(|binding| {
+            if binding.pressed {
+                binding.action.on_hold.as_ref()
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Forgets which actions are pressed.
+    ///
+    /// Should be called after bindings are changed,
+    /// since release of previously bound key is no longer seen by the binder.
+    pub fn reset(&mut self) {
+        for binding in self.actions.values_mut() {
+            binding.pressed = false;
+        }
+    }
+
+    fn event_action(&mut self, action: A) -> &mut SimpleKeyEventAction<T> {
+        match self.actions.entry(action) {
+            Entry::Occupied(entry) => &mut entry.into_mut().action,
+            Entry::Vacant(entry) => {
+                &mut entry
+                    .insert(SimpleKeyBinding::new(Default::default()))
+                    .action
+            }
+        }
+    }
+}
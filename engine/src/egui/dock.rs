@@ -0,0 +1,218 @@
+use std::path::{Path, PathBuf};
+
+use egui::{CentralPanel, Context, SidePanel, TopBottomPanel, Ui};
+use eyre::WrapErr;
+
+/// Side of the window where [`EguiDock`] panel is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DockSide {
+    Left,
+    Right,
+    Bottom,
+}
+
+/// State of a single dock panel.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DockPanel {
+    /// Whether panel is shown.
+    pub open: bool,
+
+    /// Width of side panel or height of bottom panel, in points.
+    pub size: f32,
+}
+
+/// Layout of [`EguiDock`] panels.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::egui::{DockLayout, DockSide};
+/// let mut layout = DockLayout::default();
+/// layout.panel_mut(DockSide::Right).open = false;
+/// layout.panel_mut(DockSide::Bottom).size = 320.0;
+///
+/// let toml = toml::to_string(&layout).unwrap();
+/// let restored: DockLayout = toml::from_str(&toml).unwrap();
+/// assert_eq!(restored, layout);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DockLayout {
+    pub left: DockPanel,
+    pub right: DockPanel,
+    pub bottom: DockPanel,
+}
+
+impl Default for DockLayout {
+    fn default() -> Self {
+        DockLayout {
+            left: DockPanel {
+                open: true,
+                size: 240.0,
+            },
+            right: DockPanel {
+                open: true,
+                size: 240.0,
+            },
+            bottom: DockPanel {
+                open: true,
+                size: 160.0,
+            },
+        }
+    }
+}
+
+impl DockLayout {
+    /// Returns state of the panel on the side.
+    pub fn panel(&self, side: DockSide) -> &DockPanel {
+        match side {
+            DockSide::Left => &self.left,
+            DockSide::Right => &self.right,
+            DockSide::Bottom => &self.bottom,
+        }
+    }
+
+    /// Returns mutable state of the panel on the side.
+    pub fn panel_mut(&mut self, side: DockSide) -> &mut DockPanel {
+        match side {
+            DockSide::Left => &mut self.left,
+            DockSide::Right => &mut self.right,
+            DockSide::Bottom => &mut self.bottom,
+        }
+    }
+}
+
+/// Resource with common editor shell layout:
+/// left, right and bottom panels around central area.
+///
+/// Open state and sizes of the panels can be persisted to a file,
+/// so that tools built on the engine restore layout between sessions.
+///
+/// Call [`EguiDock::show`] from the closure passed to [`EguiResource::run`](super::EguiResource::run).
+///
+/// ```ignore
+/// let mut dock = world.expect_resource_mut::<EguiDock>();
+/// egui.run(&window, |ctx| {
+///     dock.show(
+///         ctx,
+///         |side, ui| match side {
+///             DockSide::Left => hierarchy(ui),
+///             DockSide::Right => inspector(ui),
+///             DockSide::Bottom => console(ui),
+///         },
+///         |ui| viewport(ui),
+///     );
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct EguiDock {
+    layout: DockLayout,
+    path: Option<PathBuf>,
+}
+
+impl EguiDock {
+    /// Returns dock with default layout that is not persisted.
+    pub fn new() -> Self {
+        EguiDock::default()
+    }
+
+    /// Returns dock with layout loaded from the file.
+    /// Default layout is used if the file does not exist.
+    ///
+    /// Layout is saved back to the same file with [`EguiDock::save`].
+    pub fn load(path: impl Into<PathBuf>) -> eyre::Result<Self> {
+        let path = path.into();
+
+        let layout = match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text)
+                .wrap_err_with(|| format!("Failed to parse dock layout '{}'", path.display()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => DockLayout::default(),
+            Err(err) => {
+                return Err(err)
+                    .wrap_err_with(|| format!("Failed to read dock layout '{}'", path.display()))
+            }
+        };
+
+        Ok(EguiDock {
+            layout,
+            path: Some(path),
+        })
+    }
+
+    /// Saves layout to the file it was loaded from.
+    /// Does nothing if dock was not loaded from a file.
+    pub fn save(&self) -> eyre::Result<()> {
+        match &self.path {
+            None => Ok(()),
+            Some(path) => self.save_to(path),
+        }
+    }
+
+    /// Saves layout to specified file.
+    pub fn save_to(&self, path: &Path) -> eyre::Result<()> {
+        let text = toml::to_string(&self.layout)?;
+        std::fs::write(path, text)
+            .wrap_err_with(|| format!("Failed to write dock layout '{}'", path.display()))
+    }
+
+    pub fn layout(&self) -> &DockLayout {
+        &self.layout
+    }
+
+    pub fn layout_mut(&mut self) -> &mut DockLayout {
+        &mut self.layout
+    }
+
+    /// Returns `true` if panel on the side is shown.
+    pub fn is_open(&self, side: DockSide) -> bool {
+        self.layout.panel(side).open
+    }
+
+    /// Shows or hides panel on the side.
+    pub fn set_open(&mut self, side: DockSide, open: bool) {
+        self.layout.panel_mut(side).open = open;
+    }
+
+    /// Toggles panel on the side.
+    pub fn toggle(&mut self, side: DockSide) {
+        let panel = self.layout.panel_mut(side);
+        panel.open = !panel.open;
+    }
+
+    /// Shows open panels with content produced by `panel`
+    /// and central area with content produced by `central`.
+    ///
+    /// Sizes of panels resized by user are recorded into the layout.
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        mut panel: impl FnMut(DockSide, &mut Ui),
+        central: impl FnOnce(&mut Ui),
+    ) {
+        if self.layout.left.open {
+            let response = SidePanel::left("arcana-dock-left")
+                .resizable(true)
+                .default_width(self.layout.left.size)
+                .show(ctx, |ui| panel(DockSide::Left, ui));
+            self.layout.left.size = response.response.rect.width();
+        }
+
+        if self.layout.right.open {
+            let response = SidePanel::right("arcana-dock-right")
+                .resizable(true)
+                .default_width(self.layout.right.size)
+                .show(ctx, |ui| panel(DockSide::Right, ui));
+            self.layout.right.size = response.response.rect.width();
+        }
+
+        if self.layout.bottom.open {
+            let response = TopBottomPanel::bottom("arcana-dock-bottom")
+                .resizable(true)
+                .default_height(self.layout.bottom.size)
+                .show(ctx, |ui| panel(DockSide::Bottom, ui));
+            self.layout.bottom.size = response.response.rect.height();
+        }
+
+        CentralPanel::default().show(ctx, central);
+    }
+}
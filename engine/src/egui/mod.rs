@@ -1,6 +1,11 @@
-pub use self::{funnel::EguiFunnel, res::EguiResource};
+pub use self::{
+    dock::{DockLayout, DockPanel, DockSide, EguiDock},
+    funnel::EguiFunnel,
+    res::EguiResource,
+};
 // pub use crate::graphics::renderer::egui::*;
 pub use egui::*;
 
+mod dock;
 mod funnel;
 mod res;
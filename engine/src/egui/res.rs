@@ -1,12 +1,12 @@
 use egui::{ClippedPrimitive, ColorImage, Context, FontImage, ImageData, TextureId, TexturesDelta};
 use hashbrown::hash_map::{Entry, HashMap};
 use sierra::{
-    Access, Encoder, Extent2, Extent3, ImageInfo, ImageView, ImageViewInfo, Offset3, OutOfMemory,
+    Access, Encoder, Extent2, Extent3, ImageInfo, ImageView, ImageViewInfo, Offset3,
     SubresourceLayers,
 };
 use winit::{event::WindowEvent, event_loop::EventLoopWindowTarget, window::Window};
 
-use crate::graphics::{Graphics, UploadImage};
+use crate::graphics::{Graphics, UploadError, UploadImage};
 
 pub struct EguiResource {
     ctx: Context,
@@ -63,7 +63,7 @@ impl EguiResource {
         &mut self,
         encoder: &mut Encoder,
         graphics: &mut Graphics,
-    ) -> Result<(), OutOfMemory> {
+    ) -> Result<(), UploadError> {
         if let Some(textures_delta) = self.textures_delta.take() {
             for (id, delta) in &textures_delta.set {
                 let id = match *id {
@@ -128,6 +128,7 @@ impl EguiResource {
                         format,
                         row_length: 0,
                         image_height: 0,
+                        generate_mips: false,
                     },
                     &pixels[..],
                     encoder,
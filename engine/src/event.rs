@@ -40,6 +40,15 @@ pub enum Event {
         event: DeviceEvent,
     },
 
+    /// Emitted when gamepad is connected, disconnected or its state changes.
+    Gamepad {
+        /// Gamepad with which event is associated.
+        gamepad: GamepadId,
+
+        /// Gamepad event.
+        event: GamepadEvent,
+    },
+
     /// Emitted when redraw for specified window is requested.
     RedrawRequested(WindowId),
 
@@ -47,6 +56,63 @@ pub enum Event {
     Loop,
 }
 
+/// Identifier of connected gamepad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GamepadId(pub usize);
+
+/// Gamepad button named by its position on standard layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Analog gamepad axis.
+/// Values are in `[-1, 1]` range, positive for right and up directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    LeftZ,
+    RightStickX,
+    RightStickY,
+    RightZ,
+}
+
+/// Describes gamepad event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GamepadEvent {
+    /// Gamepad was connected.
+    Connected,
+
+    /// Gamepad was disconnected.
+    Disconnected,
+
+    /// Gamepad button was pressed or released.
+    Button {
+        button: GamepadButton,
+        state: ElementState,
+    },
+
+    /// Gamepad axis value changed.
+    Axis { axis: GamepadAxis, value: f32 },
+}
+
 impl Event {
     fn from_winit<T>(event: winit::event::Event<'_, T>) -> Option<Self> {
         match event {
@@ -1,5 +1,30 @@
 use {crate::clocks::TimeSpan, std::collections::VecDeque};
 
+/// Resource with frame statistics over a rolling window.
+///
+/// Game loop adds real time of each frame,
+/// so statistics can be read by overlays and logging systems.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{fps::FpsMeter, TimeSpan};
+/// let mut fps = FpsMeter::new(TimeSpan::SECOND);
+/// for ms in [10, 20, 20, 40] {
+///     fps.add_frame_time(TimeSpan::MILLISECOND * ms);
+/// }
+///
+/// let approx = |a: f32, b: f32| (a - b).abs() < 0.01;
+///
+/// assert_eq!(fps.frame_time(), TimeSpan::MILLISECOND * 40);
+/// assert!(approx(fps.instant(), 25.0));
+/// assert!(approx(fps.min(), 25.0));
+/// assert!(approx(fps.max(), 100.0));
+/// assert!(approx(fps.fps(), 4.0 / 0.09));
+///
+/// // Last two frames fill 60ms.
+/// assert!(approx(fps.average(TimeSpan::MILLISECOND * 60), 2.0 / 0.06));
+/// ```
 pub struct FpsMeter {
     frames: VecDeque<TimeSpan>,
     total: TimeSpan,
@@ -15,6 +40,11 @@ impl FpsMeter {
         }
     }
 
+    /// Returns span of time over which statistics are kept.
+    pub fn window(&self) -> TimeSpan {
+        self.window
+    }
+
     pub fn add_frame_time(&mut self, span: TimeSpan) {
         self.frames.push_back(span);
         self.total += span;
@@ -25,6 +55,7 @@ impl FpsMeter {
         }
     }
 
+    /// Returns average frames per second over the whole window.
     pub fn fps(&self) -> f32 {
         if self.frames.is_empty() {
             0.0
@@ -32,4 +63,59 @@ impl FpsMeter {
             self.frames.len() as f32 / self.total.as_secs_f32()
         }
     }
+
+    /// Returns time of the last frame.
+    pub fn frame_time(&self) -> TimeSpan {
+        self.frames.back().copied().unwrap_or(TimeSpan::ZERO)
+    }
+
+    /// Returns frames per second computed from the last frame time.
+    pub fn instant(&self) -> f32 {
+        per_second(self.frame_time())
+    }
+
+    /// Returns average frames per second over the last frames that fill `window`.
+    /// Uses all frames if `window` is larger than the meter's window.
+    pub fn average(&self, window: TimeSpan) -> f32 {
+        let mut total = TimeSpan::ZERO;
+        let mut count = 0;
+
+        for span in self.frames.iter().rev() {
+            if total >= window {
+                break;
+            }
+            total += *span;
+            count += 1;
+        }
+
+        if total.is_zero() {
+            0.0
+        } else {
+            count as f32 / total.as_secs_f32()
+        }
+    }
+
+    /// Returns frames per second of the slowest frame within the window.
+    pub fn min(&self) -> f32 {
+        self.frames
+            .iter()
+            .max()
+            .map_or(0.0, |span| per_second(*span))
+    }
+
+    /// Returns frames per second of the fastest frame within the window.
+    pub fn max(&self) -> f32 {
+        self.frames
+            .iter()
+            .min()
+            .map_or(0.0, |span| per_second(*span))
+    }
+}
+
+fn per_second(span: TimeSpan) -> f32 {
+    if span.is_zero() {
+        0.0
+    } else {
+        1.0 / span.as_secs_f32()
+    }
 }
@@ -1,19 +1,29 @@
 use std::future::Future;
 
-#[cfg(feature = "asset-pipeline")]
+#[cfg(any(feature = "asset-pipeline", feature = "visible"))]
 use std::path::Path;
 
+#[cfg(not(feature = "visible"))]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
 use edict::{scheduler::Scheduler, system::Res, world::World, EntityId};
 use eyre::WrapErr;
 use goods::Loader;
 
 #[cfg(feature = "visible")]
-use winit::{
-    dpi::PhysicalSize,
-    window::{self, WindowBuilder},
-};
+use winit::window::{self, Fullscreen, Icon, WindowBuilder};
 
-use crate::{assets::Assets, cfg::Config, clocks::Clocks, control::ControlFunnel, window::Windows};
+use crate::{
+    assets::Assets,
+    cfg::Config,
+    clocks::{Clocks, TimeSpan},
+    control::ControlFunnel,
+    rng::SeededRng,
+    window::Windows,
+};
 
 #[cfg(feature = "2d")]
 use crate::scene::scene_system2;
@@ -23,20 +33,22 @@ use crate::scene::scene_system3;
 
 #[cfg(feature = "visible")]
 use crate::{
-    clocks::TimeSpan,
     control::Control,
     edict::bundle::DynamicComponentBundle,
     event::{Event, Loop, WindowEvent},
     fps::FpsMeter,
     funnel::Funnel,
     lifespan::lifetime_system,
-    system::ToFixSystem,
+    system::{FixAlpha, ToFixSystem},
     task::teardown_tasks,
 };
 
 #[cfg(feature = "graphics")]
 use crate::graphics::{renderer::Renderer, Graphics};
 
+#[cfg(feature = "gamepad")]
+use crate::gamepad::Gamepads;
+
 // #[cfg(all(any(feature = "2d", feature = "3d"), feature = "graphics"))]
 // use crate::graphics::renderer::simple::SimpleRenderer;
 
@@ -63,19 +75,44 @@ impl std::ops::Deref for MainWindow {
 
 #[cfg(feature = "visible")]
 impl MainWindow {
-    fn new(event_loop: &Loop, size: Option<PhysicalSize<u32>>) -> eyre::Result<Self> {
-        let mut builder = WindowBuilder::new().with_title("Arcana Game");
+    fn new(event_loop: &Loop, cfg: &crate::cfg::Game, root: &Path) -> eyre::Result<Self> {
+        let mut builder = WindowBuilder::new()
+            .with_title(&cfg.title)
+            .with_resizable(cfg.resizable);
 
-        if let Some(size) = size {
+        if let Some(size) = cfg.window_size {
             builder = builder.with_inner_size(size);
         }
 
+        if cfg.fullscreen {
+            builder = builder.with_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+
+        if let Some(icon) = &cfg.icon {
+            // Missing icon should not prevent the game from starting.
+            match load_icon(&root.join(icon)) {
+                Ok(icon) => builder = builder.with_window_icon(Some(icon)),
+                Err(err) => tracing::warn!("Failed to load window icon. {:#}", err),
+            }
+        }
+
         Ok(MainWindow {
             window: builder.build(event_loop)?,
         })
     }
 }
 
+#[cfg(feature = "visible")]
+fn load_icon(path: &Path) -> eyre::Result<Icon> {
+    let image = image::open(path)
+        .wrap_err_with(|| format!("Failed to decode icon '{}'", path.display()))?
+        .into_rgba8();
+
+    let (width, height) = image.dimensions();
+    let icon = Icon::from_rgba(image.into_raw(), width, height)?;
+    Ok(icon)
+}
+
 #[cfg(feature = "visible")]
 struct MainWindowFunnel;
 
@@ -119,6 +156,148 @@ pub struct Game {
     pub camera: EntityId,
 }
 
+impl Game {
+    /// Returns game that is advanced manually with [`Game::step`]
+    /// instead of wall-clock driven game loop.
+    ///
+    /// World contains [`SeededRng`] resource started from the `seed`.
+    /// There is no window, renderer or asset loader.
+    ///
+    /// Game driven this way produces the same world state on each run,
+    /// as long as systems draw randomness only from [`SeededRng`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::{collections::hash_map::DefaultHasher, hash::{Hash, Hasher}};
+    /// # use arcana::{clocks::ClockIndex, game::Game, rand::Rng, rng::SeededRng, TimeSpan};
+    /// # use edict::{component::Component, system::{Res, ResMut}, world::QueryRef};
+    /// #[derive(Component)]
+    /// struct Position(u64);
+    ///
+    /// fn wander(clock: Res<ClockIndex>, mut rng: ResMut<SeededRng>, mut query: QueryRef<&mut Position>) {
+    ///     for pos in query.iter_mut() {
+    ///         pos.0 += clock.delta.as_nanos() * rng.gen_range(0..10);
+    ///     }
+    /// }
+    ///
+    /// fn run(seed: u64) -> u64 {
+    ///     let mut game = Game::deterministic(seed);
+    ///     for _ in 0..10 {
+    ///         game.world.spawn((Position(0),));
+    ///     }
+    ///     game.scheduler.add_system(wander);
+    ///
+    ///     for _ in 0..100 {
+    ///         game.step(TimeSpan::MILLISECOND * 20);
+    ///     }
+    ///
+    ///     let mut hasher = DefaultHasher::new();
+    ///     for pos in game.world.query_mut::<&Position>().iter() {
+    ///         pos.0.hash(&mut hasher);
+    ///     }
+    ///     hasher.finish()
+    /// }
+    ///
+    /// assert_eq!(run(42), run(42));
+    /// assert_ne!(run(42), run(43));
+    /// ```
+    pub fn deterministic(seed: u64) -> Self {
+        let mut world = World::new();
+        world.insert_resource(Clocks::new());
+        world.insert_resource(SeededRng::new(seed));
+
+        #[cfg(feature = "visible")]
+        let camera = world.spawn(());
+
+        Game {
+            world,
+            scheduler: Scheduler::new(),
+
+            #[cfg(feature = "visible")]
+            funnel: None,
+
+            #[cfg(feature = "graphics")]
+            renderer: None,
+
+            #[cfg(feature = "visible")]
+            camera,
+        }
+    }
+
+    /// Runs one simulation step synchronously, advancing game clocks by `delta`.
+    ///
+    /// [`ClockIndex`](crate::clocks::ClockIndex) resource is updated before systems run,
+    /// so time observed by systems depends only on number of steps and their deltas.
+    /// Systems run sequentially in the order they were added.
+    pub fn step(&mut self, delta: TimeSpan) {
+        self.step_clocks(delta);
+        self.scheduler.run_sequential(&mut self.world);
+    }
+
+    /// Runs one simulation step, advancing game clocks by `delta`,
+    /// with systems executed on rayon thread pool.
+    ///
+    /// Scheduler derives component and resource accesses from system queries
+    /// and runs systems that don't conflict concurrently.
+    /// Systems that take `&mut World` conflict with all others and run alone.
+    /// Conflicting systems run in the order they were added, so the result
+    /// is the same as with [`Game::step`] as long as systems don't share state
+    /// outside of the world.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+    /// # use arcana::{game::Game, TimeSpan};
+    /// # use edict::{component::Component, world::QueryRef};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// #[derive(Component)]
+    /// struct Ammo(u32);
+    ///
+    /// let runs = Arc::new(AtomicUsize::new(0));
+    ///
+    /// let mut game = Game::deterministic(0);
+    /// game.world.spawn((Health(10), Ammo(10)));
+    ///
+    /// // Systems access disjoint components and may run on different threads.
+    /// let counter = runs.clone();
+    /// game.scheduler.add_system(move |mut query: QueryRef<&mut Health>| {
+    ///     query.iter_mut().for_each(|health| health.0 -= 1);
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// let counter = runs.clone();
+    /// game.scheduler.add_system(move |mut query: QueryRef<&mut Ammo>| {
+    ///     query.iter_mut().for_each(|ammo| ammo.0 -= 1);
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    ///
+    /// for _ in 0..5 {
+    ///     game.step_parallel(TimeSpan::MILLISECOND * 20);
+    /// }
+    ///
+    /// assert_eq!(runs.load(Ordering::Relaxed), 10);
+    /// for (health, ammo) in game.world.query_mut::<(&Health, &Ammo)>().iter() {
+    ///     assert_eq!((health.0, ammo.0), (5, 5));
+    /// }
+    /// ```
+    pub fn step_parallel(&mut self, delta: TimeSpan) {
+        self.step_clocks(delta);
+        self.scheduler.run_rayon(&mut self.world);
+    }
+
+    fn step_clocks(&mut self, delta: TimeSpan) {
+        if self.world.get_resource::<Clocks>().is_none() {
+            self.world.insert_resource(Clocks::new());
+        }
+
+        let clock = self.world.expect_resource_mut::<Clocks>().step(delta);
+        self.world.insert_resource(clock);
+    }
+}
+
 #[cfg(all(feature = "visible", feature = "graphics", feature = "2d"))]
 pub fn game2<F, Fut>(f: F) -> !
 where
@@ -145,8 +324,30 @@ where
     todo!()
 }
 
+/// Runs the game with config loaded from default location.
 #[cfg(all(feature = "visible", feature = "graphics"))]
 pub fn game<F, Fut, R, C>(f: F, r: R) -> !
+where
+    F: FnOnce(Game) -> Fut + 'static,
+    Fut: Future<Output = eyre::Result<Game>>,
+    R: FnOnce(&mut Graphics) -> eyre::Result<Box<dyn Renderer>> + Send + 'static,
+    C: DynamicComponentBundle + Default,
+{
+    game_with_config::<F, Fut, R, C>(None, f, r)
+}
+
+/// Runs the game with provided config.
+/// Config is loaded from default location if `None`.
+///
+/// Main window is configured with [`cfg::Game`](crate::cfg::Game) section.
+///
+/// ```ignore
+/// let mut cfg = Config::load_default();
+/// cfg.game = cfg.game.with_title("Tanks").with_window_size(1280, 720);
+/// game_with_config::<_, _, _, (Camera2, Global2)>(Some(cfg), setup, renderer);
+/// ```
+#[cfg(all(feature = "visible", feature = "graphics"))]
+pub fn game_with_config<F, Fut, R, C>(cfg: Option<Config>, f: F, r: R) -> !
 where
     F: FnOnce(Game) -> Fut + 'static,
     Fut: Future<Output = eyre::Result<Game>>,
@@ -160,7 +361,7 @@ where
 
     Loop::run(|event_loop| async move {
         // Load config.
-        let cfg = Config::load_default();
+        let cfg = cfg.unwrap_or_else(Config::load_default);
 
         // Create new world with camera.
         let mut world = World::new();
@@ -170,7 +371,7 @@ where
         world.insert_resource(Assets::new(loader));
 
         // Open game window.
-        let window = MainWindow::new(&event_loop, cfg.game.window_size)
+        let window = MainWindow::new(&event_loop, &cfg.game, &cfg.root)
             .wrap_err_with(|| "Failed to initialize main window")?;
 
         let mut windows = Windows::new();
@@ -186,6 +387,19 @@ where
 
         world.insert_resource(Control::new());
 
+        // Game setup may replace it with generator started from known seed.
+        world.insert_resource(SeededRng::from_entropy());
+
+        // Gamepads are optional, game should run without them.
+        #[cfg(feature = "gamepad")]
+        let mut gamepads = match Gamepads::new() {
+            Ok(gamepads) => Some(gamepads),
+            Err(err) => {
+                tracing::warn!("{:#}", err);
+                None
+            }
+        };
+
         // Configure the game with user-provided closure.
         let game = f(Game {
             world,
@@ -215,7 +429,7 @@ where
         };
 
         // Start the clocks.
-        let mut clocks = Clocks::new();
+        world.insert_resource(Clocks::new());
 
         scheduler.add_system(lifetime_system);
 
@@ -226,6 +440,10 @@ where
         #[cfg(feature = "3d")]
         scheduler.add_system(scene_system3);
 
+        world.insert_resource(FixAlpha::default());
+
+        #[cfg(feature = "profile-systems")]
+        world.insert_resource(crate::system::SystemProfile::new());
         world.insert_resource(FpsMeter::new(TimeSpan::SECOND));
         scheduler.add_system(
             (move |fps: Res<FpsMeter>| {
@@ -236,19 +454,26 @@ where
 
         // Begin game loop.
         loop {
+            let mut funnel = GameFunnel {
+                windows: &mut windows,
+                custom: match &mut funnel {
+                    None => None,
+                    Some(funnel) => Some(&mut **funnel),
+                },
+                control: &mut ControlFunnel,
+            };
+
+            // Funnel gamepad events polled since last loop.
+            #[cfg(feature = "gamepad")]
+            if let Some(gamepads) = &mut gamepads {
+                while let Some(event) = gamepads.next_event() {
+                    funnel.filter(&mut world, event);
+                }
+            }
+
             loop {
                 let event = event_loop.next_event(TimeSpan::MILLISECOND).await;
 
-                // Loop through new  events.
-                let mut funnel = GameFunnel {
-                    windows: &mut windows,
-                    custom: match &mut funnel {
-                        None => None,
-                        Some(funnel) => Some(&mut **funnel),
-                    },
-                    control: &mut ControlFunnel,
-                };
-
                 // Filter event
                 let event = funnel.filter(&mut world, event);
 
@@ -265,13 +490,22 @@ where
                 return Ok(());
             }
 
-            let clock = clocks.advance();
+            if world.expect_resource::<Graphics>().is_device_lost() {
+                drop(renderer);
+                drop(world);
+                return Err(eyre::eyre!(
+                    "Graphics device lost. It may happen after GPU hang, driver reset or GPU switch"
+                ));
+            }
+
+            let clock = world.expect_resource_mut::<Clocks>().advance();
+            world.insert_resource(clock);
 
             scheduler.run_rayon(&mut world);
 
             world
                 .expect_resource_mut::<FpsMeter>()
-                .add_frame_time(clock.delta);
+                .add_frame_time(clock.real_delta);
 
             world.expect_resource_mut::<Assets>().cleanup();
         }
@@ -287,8 +521,34 @@ where
     panic!("This function must be used only with \"visible\" feature disabled")
 }
 
+#[cfg(feature = "visible")]
+pub fn headless_with_config<F, Fut>(_cfg: Option<Config>, _f: F)
+where
+    F: FnOnce(Game) -> Fut + 'static,
+    Fut: Future<Output = eyre::Result<Game>>,
+{
+    panic!("This function must be used only with \"visible\" feature disabled")
+}
+
+/// Runs headless game with config loaded from default location.
 #[cfg(not(feature = "visible"))]
 pub fn headless<F, Fut>(f: F)
+where
+    F: FnOnce(Game) -> Fut + 'static,
+    Fut: Future<Output = eyre::Result<Game>>,
+{
+    headless_with_config(None, f)
+}
+
+/// Runs headless game with provided config.
+/// Config is loaded from default location if `None`.
+///
+/// Game ticks with `main_step` interval of the config.
+/// Game exits when [`Exit`] resource is inserted or when process receives
+/// Ctrl-C or SIGTERM. Outstanding tasks are given `teardown_timeout` to finish
+/// before client and server are closed.
+#[cfg(not(feature = "visible"))]
+pub fn headless_with_config<F, Fut>(cfg: Option<Config>, f: F)
 where
     F: FnOnce(Game) -> Fut + 'static,
     Fut: Future<Output = eyre::Result<Game>>,
@@ -302,7 +562,7 @@ where
         .expect("Failed to build tokio runtime");
 
     // Load config.
-    let cfg = Config::load_default();
+    let cfg = cfg.unwrap_or_else(Config::load_default);
 
     let teardown_timeout = cfg.teardown_timeout;
     let main_step = cfg.main_step;
@@ -363,7 +623,14 @@ where
 
             scheduler.add_ticking_system(LifeSpanSystem);
 
+            let shutdown = spawn_shutdown_listener();
+
             loop {
+                if shutdown.load(Ordering::Relaxed) && res.get::<Exit>().is_none() {
+                    tracing::info!("Shutdown signal received");
+                    res.insert(Exit);
+                }
+
                 if res.get::<Exit>().is_some() {
                     // Try to finish outstanding async tasks.
                     Spawner::teardown(
@@ -385,6 +652,13 @@ where
                     )
                     .await;
 
+                    // Close connections and listener.
+                    #[cfg(feature = "client")]
+                    drop(client);
+
+                    #[cfg(feature = "server")]
+                    drop(server);
+
                     drop(world);
 
                     return Ok::<(), eyre::Report>(());
@@ -457,6 +731,38 @@ where
         .unwrap()
 }
 
+/// Spawns tasks that raise returned flag on Ctrl-C or SIGTERM.
+#[cfg(not(feature = "visible"))]
+fn spawn_shutdown_listener() -> Arc<AtomicBool> {
+    let shutdown = Arc::new(AtomicBool::new(false));
+
+    let flag = shutdown.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            flag.store(true, Ordering::Relaxed);
+        }
+    });
+
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                let flag = shutdown.clone();
+                tokio::spawn(async move {
+                    if terminate.recv().await.is_some() {
+                        flag.store(true, Ordering::Relaxed);
+                    }
+                });
+            }
+            Err(err) => tracing::warn!("Failed to listen for SIGTERM. {:#}", err),
+        }
+    }
+
+    shutdown
+}
+
 #[cfg(all(feature = "visible", feature = "graphics"))]
 struct GameFunnel<'a> {
     windows: &'a mut Windows,
@@ -517,22 +823,57 @@ fn init_treasury(
 
     let mut store = treasury_store::Treasury::new(&base, info)?;
 
-    store.register_importer(ImageImporter);
+    let cache = cfg
+        .import_cache
+        .as_ref()
+        .map(|path| ImportCache::new(root.join(path)));
+
+    register_importer(&mut store, ImageImporter, &cache);
+
+    #[cfg(feature = "graphics")]
+    register_importer(&mut store, Ktx2Importer, &cache);
 
     #[cfg(feature = "asset-pipeline")]
     {
         #[cfg(feature = "2d")]
         {
             #[cfg(feature = "graphics")]
-            store.register_importer(SpriteSheetImporter);
+            {
+                register_importer(&mut store, SpriteSheetImporter, &cache);
+                register_importer(&mut store, AtlasImporter, &cache);
+                register_importer(&mut store, AtlasSpriteSheetImporter, &cache);
+                register_importer(&mut store, TtfImporter, &cache);
+                register_importer(&mut store, FontAtlasImporter::new(cfg.font.clone()), &cache);
+                register_importer(&mut store, FontImporter::new(cfg.font.clone()), &cache);
+            }
 
-            store.register_importer(TileMapImporter);
-            store.register_importer(TileSetImporter);
+            register_importer(&mut store, TileMapImporter, &cache);
+            register_importer(&mut store, TileSetImporter, &cache);
         }
 
         #[cfg(all(feature = "graphics", feature = "3d"))]
-        store.register_importer(GltfModelImporter);
+        {
+            register_importer(&mut store, GltfModelImporter::new(cfg.gltf.clone()), &cache);
+            register_importer(&mut store, GltfSceneImporter::new(cfg.gltf.clone()), &cache);
+        }
     }
 
     Ok(crate::assets::treasury::TreasurySource::new(store))
 }
+
+#[cfg(feature = "asset-pipeline")]
+fn register_importer<I>(
+    store: &mut treasury_store::Treasury,
+    importer: I,
+    cache: &Option<std::sync::Arc<crate::assets::import::ImportCache>>,
+) where
+    I: treasury_import::Importer + Send + Sync + 'static,
+{
+    match cache {
+        None => store.register_importer(importer),
+        Some(cache) => store.register_importer(crate::assets::import::Incremental::new(
+            importer,
+            cache.clone(),
+        )),
+    }
+}
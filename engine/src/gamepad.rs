@@ -0,0 +1,103 @@
+//!
+//! Gamepad input source.
+//!
+//! Polls connected gamepads with `gilrs` and converts their input into [`Event::Gamepad`]
+//! that is funneled with the rest of the events.
+//!
+
+use crate::event::{ElementState, Event, GamepadAxis, GamepadButton, GamepadEvent, GamepadId};
+
+/// Failed to initialize gamepad support.
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to initialize gamepad support. {source}")]
+pub struct GamepadsError {
+    #[from]
+    source: gilrs::Error,
+}
+
+/// Source of gamepad events.
+pub struct Gamepads {
+    gilrs: gilrs::Gilrs,
+}
+
+impl Gamepads {
+    pub fn new() -> Result<Self, GamepadsError> {
+        let gilrs = gilrs::Gilrs::new()?;
+        Ok(Gamepads { gilrs })
+    }
+
+    /// Returns next pending gamepad event.
+    ///
+    /// Events of unknown buttons and axes are skipped.
+    /// Analog values of buttons are not reported, only presses and releases.
+    pub fn next_event(&mut self) -> Option<Event> {
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let event = match event {
+                gilrs::EventType::Connected => GamepadEvent::Connected,
+                gilrs::EventType::Disconnected => GamepadEvent::Disconnected,
+                gilrs::EventType::ButtonPressed(button, _) => match gamepad_button(button) {
+                    Some(button) => GamepadEvent::Button {
+                        button,
+                        state: ElementState::Pressed,
+                    },
+                    None => continue,
+                },
+                gilrs::EventType::ButtonReleased(button, _) => match gamepad_button(button) {
+                    Some(button) => GamepadEvent::Button {
+                        button,
+                        state: ElementState::Released,
+                    },
+                    None => continue,
+                },
+                gilrs::EventType::AxisChanged(axis, value, _) => match gamepad_axis(axis) {
+                    Some(axis) => GamepadEvent::Axis { axis, value },
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            return Some(Event::Gamepad {
+                gamepad: GamepadId(id.into()),
+                event,
+            });
+        }
+        None
+    }
+}
+
+fn gamepad_button(button: gilrs::Button) -> Option<GamepadButton> {
+    let button = match button {
+        gilrs::Button::South => GamepadButton::South,
+        gilrs::Button::East => GamepadButton::East,
+        gilrs::Button::North => GamepadButton::North,
+        gilrs::Button::West => GamepadButton::West,
+        gilrs::Button::LeftTrigger => GamepadButton::LeftTrigger,
+        gilrs::Button::LeftTrigger2 => GamepadButton::LeftTrigger2,
+        gilrs::Button::RightTrigger => GamepadButton::RightTrigger,
+        gilrs::Button::RightTrigger2 => GamepadButton::RightTrigger2,
+        gilrs::Button::Select => GamepadButton::Select,
+        gilrs::Button::Start => GamepadButton::Start,
+        gilrs::Button::Mode => GamepadButton::Mode,
+        gilrs::Button::LeftThumb => GamepadButton::LeftThumb,
+        gilrs::Button::RightThumb => GamepadButton::RightThumb,
+        gilrs::Button::DPadUp => GamepadButton::DPadUp,
+        gilrs::Button::DPadDown => GamepadButton::DPadDown,
+        gilrs::Button::DPadLeft => GamepadButton::DPadLeft,
+        gilrs::Button::DPadRight => GamepadButton::DPadRight,
+        _ => return None,
+    };
+    Some(button)
+}
+
+fn gamepad_axis(axis: gilrs::Axis) -> Option<GamepadAxis> {
+    let axis = match axis {
+        gilrs::Axis::LeftStickX => GamepadAxis::LeftStickX,
+        gilrs::Axis::LeftStickY => GamepadAxis::LeftStickY,
+        gilrs::Axis::LeftZ => GamepadAxis::LeftZ,
+        gilrs::Axis::RightStickX => GamepadAxis::RightStickX,
+        gilrs::Axis::RightStickY => GamepadAxis::RightStickY,
+        gilrs::Axis::RightZ => GamepadAxis::RightZ,
+        _ => return None,
+    };
+    Some(axis)
+}
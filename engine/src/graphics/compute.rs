@@ -0,0 +1,134 @@
+//! Helper for recording compute dispatches.
+//!
+//! # Synchronization
+//!
+//! [`ComputePass`] records commands into an [`Encoder`] and does not insert any barriers on its own.
+//! Resources written by the shader must be synchronized before they are consumed:
+//!
+//! * Buffers written by compute shader and read by later commands require
+//!   memory barrier from `COMPUTE_SHADER` stage with `SHADER_STORAGE_WRITE` access
+//!   to the consuming stage and access.
+//!   [`ComputePass::barrier`] records such barrier for common cases.
+//! * Images must be in `General` layout while accessed as storage images.
+//!   Transition them with `Encoder::image_barriers` before dispatch and again
+//!   before they are sampled or copied.
+//! * Results read back on host require [`Access::HOST_READ`] barrier and waiting
+//!   for the fence of the submission.
+
+use sierra::{
+    Access, ComputePipeline, Encoder, Extent3, PipelinePushConstants, PipelineStages,
+    TypedPipelineLayout, UpdatedPipelineDescriptors,
+};
+
+/// Records compute pipeline binding, descriptors and dispatches into an encoder.
+///
+/// Pipeline is bound on creation.
+/// Descriptors and push constants are bound with the layout
+/// generated by `#[derive(PipelineInput)]`.
+///
+/// ```no_run
+/// # use arcana::{
+/// #     graphics::{ComputePass, Graphics, GraphicsError},
+/// #     scoped_arena::Scope,
+/// #     sierra::{Access, ComputePipeline, Extent3, PipelineStages, TypedPipelineLayout, UpdatedPipelineDescriptors},
+/// # };
+/// # fn dispatch<'a, P, D>(
+/// #     graphics: &mut Graphics,
+/// #     pipeline: &ComputePipeline,
+/// #     layout: &P,
+/// #     updated: &D,
+/// #     (width, height): (u32, u32),
+/// #     scope: &'a Scope<'a>,
+/// # ) -> Result<(), GraphicsError>
+/// # where
+/// #     P: TypedPipelineLayout,
+/// #     D: UpdatedPipelineDescriptors<P>,
+/// # {
+/// let mut encoder = graphics.create_encoder(scope)?;
+///
+/// // `updated` are descriptors updated with shader input.
+/// let mut pass = ComputePass::new(&mut encoder, pipeline);
+/// pass.bind_descriptors(layout, updated);
+/// pass.dispatch_threads(Extent3::new(width, height, 1), Extent3::new(8, 8, 1));
+/// pass.barrier(PipelineStages::VERTEX_INPUT, Access::VERTEX_ATTRIBUTE_READ);
+///
+/// graphics.submit(&mut [], Some(encoder.finish()), &mut [], None, scope)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ComputePass<'a, 'b> {
+    encoder: &'b mut Encoder<'a>,
+}
+
+impl<'a, 'b> ComputePass<'a, 'b> {
+    /// Binds compute pipeline and returns pass recording into the encoder.
+    pub fn new(encoder: &'b mut Encoder<'a>, pipeline: &ComputePipeline) -> Self {
+        encoder.bind_compute_pipeline(pipeline);
+        ComputePass { encoder }
+    }
+
+    /// Binds updated descriptors.
+    pub fn bind_descriptors<P, D>(&mut self, layout: &P, descriptors: &D) -> &mut Self
+    where
+        P: TypedPipelineLayout,
+        D: UpdatedPipelineDescriptors<P>,
+    {
+        self.encoder.bind_compute_descriptors(layout, descriptors);
+        self
+    }
+
+    /// Sets push constants.
+    pub fn push_constants<P, C>(&mut self, layout: &P, constants: &C) -> &mut Self
+    where
+        P: TypedPipelineLayout,
+        C: PipelinePushConstants<P>,
+    {
+        self.encoder.push_constants(layout, constants);
+        self
+    }
+
+    /// Dispatches specified number of workgroups.
+    pub fn dispatch(&mut self, x: u32, y: u32, z: u32) -> &mut Self {
+        self.encoder.dispatch(x, y, z);
+        self
+    }
+
+    /// Dispatches enough workgroups of `group` size to cover `threads`.
+    ///
+    /// Shader must discard invocations outside of `threads`
+    /// when it is not a multiple of `group`.
+    pub fn dispatch_threads(&mut self, threads: Extent3, group: Extent3) -> &mut Self {
+        self.dispatch(
+            workgroups(threads.width, group.width),
+            workgroups(threads.height, group.height),
+            workgroups(threads.depth, group.depth),
+        )
+    }
+
+    /// Records memory barrier making storage writes of previous dispatches
+    /// visible to specified stages and access.
+    ///
+    /// Use `PipelineStages::COMPUTE_SHADER` and `Access::SHADER_READ` when next dispatch reads results.
+    pub fn barrier(&mut self, dst_stages: PipelineStages, dst_access: Access) -> &mut Self {
+        self.encoder.memory_barrier(
+            PipelineStages::COMPUTE_SHADER,
+            Access::SHADER_STORAGE_WRITE,
+            dst_stages,
+            dst_access,
+        );
+        self
+    }
+
+    /// Returns underlying encoder to record other commands,
+    /// e.g. image barriers.
+    pub fn encoder(&mut self) -> &mut Encoder<'a> {
+        self.encoder
+    }
+}
+
+/// Number of workgroups of `group` size needed to cover `threads` invocations.
+#[inline]
+pub fn workgroups(threads: u32, group: u32) -> u32 {
+    assert_ne!(group, 0, "Workgroup size must be non-zero");
+    threads / group + u32::from(threads % group != 0)
+}
@@ -0,0 +1,229 @@
+use edict::{Component, EntityId, World};
+
+#[cfg(feature = "2d")]
+use crate::{rect::Rect, sprite::Sprite};
+
+/// Number of objects drawn and culled by a draw node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CullStats {
+    /// Objects that passed culling and were drawn.
+    pub drawn: u32,
+
+    /// Objects skipped because they are out of view.
+    pub culled: u32,
+}
+
+impl CullStats {
+    /// Counts tested object and returns `visible` back.
+    #[inline]
+    pub fn record(&mut self, visible: bool) -> bool {
+        if visible {
+            self.drawn += 1;
+        } else {
+            self.culled += 1;
+        }
+        visible
+    }
+
+    /// Returns total number of tested objects.
+    #[inline]
+    pub fn total(&self) -> u32 {
+        self.drawn + self.culled
+    }
+}
+
+/// Culling statistics of the last frame rendered by the camera.
+///
+/// Draw nodes insert this component to the camera entity
+/// and overwrite their counters each time they draw with it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Component)]
+pub struct DrawStats {
+    /// Counters of sprite draw node.
+    pub sprites: CullStats,
+
+    /// Counters of mesh draw node.
+    pub meshes: CullStats,
+}
+
+impl DrawStats {
+    /// Updates statistics of the camera entity.
+    /// Component is inserted if missing.
+    pub fn update(world: &mut World, camera: EntityId, f: impl FnOnce(&mut DrawStats)) {
+        match world.query_one_mut::<&mut DrawStats>(camera) {
+            Ok(stats) => f(stats),
+            Err(_) => {
+                let mut stats = DrawStats::default();
+                f(&mut stats);
+                let _ = world.insert(camera, stats);
+            }
+        }
+    }
+}
+
+/// Culls sprites against view rect of 2d camera.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{graphics::SpriteCuller, na, rect::Rect, sprite::Sprite};
+/// // Camera at origin, showing 10x10 units.
+/// let mut culler = SpriteCuller::from_view_proj(
+///     &na::Isometry2::identity(),
+///     &na::Affine2::from_matrix_unchecked(na::Matrix3::new_nonuniform_scaling(
+///         &na::Vector2::new(0.2, 0.2),
+///     )),
+/// );
+///
+/// let sprite = Sprite {
+///     world: Rect { left: -0.5, right: 0.5, bottom: -0.5, top: 0.5 },
+///     ..Sprite::default()
+/// };
+///
+/// let positions = [
+///     na::Vector2::new(0.0, 0.0),
+///     na::Vector2::new(4.0, -4.0),
+///     na::Vector2::new(5.4, 0.0),
+///     na::Vector2::new(20.0, 0.0),
+///     na::Vector2::new(0.0, -6.0),
+/// ];
+///
+/// let visible: Vec<_> = positions
+///     .iter()
+///     .filter(|pos| culler.is_visible(&sprite, &na::Isometry2::new(**pos, 0.0)))
+///     .collect();
+///
+/// // Sprite at `5.4` still overlaps view edge with its left half.
+/// assert_eq!(visible, [&positions[0], &positions[1], &positions[2]]);
+/// assert_eq!(culler.stats().drawn, 3);
+/// assert_eq!(culler.stats().culled, 2);
+/// ```
+#[cfg(feature = "2d")]
+#[derive(Clone, Copy, Debug)]
+pub struct SpriteCuller {
+    view: Rect,
+    stats: CullStats,
+}
+
+#[cfg(feature = "2d")]
+impl SpriteCuller {
+    /// Returns culler for the view rect in world space.
+    pub fn new(view: Rect) -> Self {
+        SpriteCuller {
+            view,
+            stats: CullStats::default(),
+        }
+    }
+
+    /// Returns culler for the view defined by camera isometry and projection,
+    /// as returned by [`Camera2::view_proj`].
+    ///
+    /// [`Camera2::view_proj`]: crate::camera::Camera2::view_proj
+    pub fn from_view_proj(iso: &na::Isometry2<f32>, affine: &na::Affine2<f32>) -> Self {
+        let ndc = Rect {
+            left: -1.0,
+            right: 1.0,
+            bottom: -1.0,
+            top: 1.0,
+        };
+
+        let unproject = affine.inverse();
+        let corners = [
+            ndc.top_left(),
+            ndc.bottom_left(),
+            ndc.top_right(),
+            ndc.bottom_right(),
+        ]
+        .map(|p| iso * (unproject * p));
+
+        let xs = corners.map(|p| p.x);
+        let ys = corners.map(|p| p.y);
+
+        SpriteCuller::new(Rect {
+            left: xs.into_iter().reduce(f32::min).unwrap(),
+            right: xs.into_iter().reduce(f32::max).unwrap(),
+            bottom: ys.into_iter().reduce(f32::min).unwrap(),
+            top: ys.into_iter().reduce(f32::max).unwrap(),
+        })
+    }
+
+    /// Returns view rect in world space.
+    pub fn view(&self) -> &Rect {
+        &self.view
+    }
+
+    /// Checks if sprite placed by `iso` overlaps the view and counts the result.
+    pub fn is_visible(&mut self, sprite: &Sprite, iso: &na::Isometry2<f32>) -> bool {
+        let visible = sprite.aabb(iso).intersects(&self.view);
+        self.stats.record(visible)
+    }
+
+    /// Returns counters of sprites tested so far.
+    pub fn stats(&self) -> CullStats {
+        self.stats
+    }
+}
+
+/// Axis-aligned bounds of entity's mesh in its local space.
+///
+/// Entities with [`Mesh`] but without this component are never culled.
+///
+/// [`Mesh`]: crate::graphics::Mesh
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{graphics::MeshBounds, na};
+/// let bounds = MeshBounds {
+///     min: na::Point3::new(-1.0, -1.0, -1.0),
+///     max: na::Point3::new(1.0, 1.0, 1.0),
+/// };
+///
+/// // Orthographic view of the 10x10x10 cube around origin.
+/// let proj = na::Orthographic3::new(-5.0, 5.0, -5.0, 5.0, -5.0, 5.0).to_homogeneous();
+///
+/// let at = |x, y, z| proj * na::Translation3::new(x, y, z).to_homogeneous();
+///
+/// assert!(bounds.is_visible(&at(0.0, 0.0, 0.0)));
+/// assert!(bounds.is_visible(&at(5.5, 0.0, 0.0)));
+/// assert!(!bounds.is_visible(&at(7.0, 0.0, 0.0)));
+/// assert!(!bounds.is_visible(&at(0.0, -7.0, 0.0)));
+/// assert!(!bounds.is_visible(&at(0.0, 0.0, 7.0)));
+/// ```
+#[cfg(feature = "3d")]
+#[derive(Clone, Copy, Debug, PartialEq, Component, serde::Serialize, serde::Deserialize)]
+pub struct MeshBounds {
+    pub min: na::Point3<f32>,
+    pub max: na::Point3<f32>,
+}
+
+#[cfg(feature = "3d")]
+impl MeshBounds {
+    /// Checks if bounds may be visible, given transformation
+    /// from local space of the mesh into clip space.
+    ///
+    /// Bounds are culled only when all corners are outside the same clip plane,
+    /// so large boxes crossing frustum corner are kept.
+    pub fn is_visible(&self, clip_from_local: &na::Matrix4<f32>) -> bool {
+        let mut outside = [true; 6];
+
+        for i in 0..8 {
+            let corner = na::Vector4::new(
+                if i & 1 == 0 { self.min.x } else { self.max.x },
+                if i & 2 == 0 { self.min.y } else { self.max.y },
+                if i & 4 == 0 { self.min.z } else { self.max.z },
+                1.0,
+            );
+
+            let clip = clip_from_local * corner;
+
+            outside[0] &= clip.x < -clip.w;
+            outside[1] &= clip.x > clip.w;
+            outside[2] &= clip.y < -clip.w;
+            outside[3] &= clip.y > clip.w;
+            outside[4] &= clip.z < -clip.w;
+            outside[5] &= clip.z > clip.w;
+        }
+
+        !outside.contains(&true)
+    }
+}
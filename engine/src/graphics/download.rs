@@ -0,0 +1,187 @@
+//! Copying of image contents back to host memory.
+
+use std::convert::TryFrom;
+
+use scoped_arena::Scope;
+use sierra::{
+    Access, BufferImageCopy, BufferInfo, BufferUsage, Extent3, Format, Image, ImageMemoryBarrier,
+    Layout, MemoryUsage, Offset3, OutOfMemory, PipelineStages, SubresourceLayers,
+};
+
+use super::{Graphics, GraphicsError};
+
+/// Error that may occur when image is downloaded.
+#[derive(Debug, thiserror::Error)]
+pub enum DownloadError {
+    #[error(transparent)]
+    Graphics(#[from] GraphicsError),
+
+    /// Size of texels of the format is unknown.
+    #[error("Downloading image of format '{0:?}' is unsupported")]
+    UnsupportedFormat(Format),
+}
+
+impl From<OutOfMemory> for DownloadError {
+    fn from(err: OutOfMemory) -> Self {
+        DownloadError::Graphics(GraphicsError::OutOfMemory(err))
+    }
+}
+
+impl Graphics {
+    /// Copies image region at zero offset back to host memory.
+    ///
+    /// Texels of all `layers` are returned one layer after another,
+    /// with rows tightly packed whatever row pitch device uses for the image.
+    /// Image must be in `layout` and is left in it when copy is complete.
+    ///
+    /// Submits all pending uploads with the copy and blocks until they are complete.
+    /// This stalls rendering, so it is suitable for screenshots and picking,
+    /// not for streaming data back every frame.
+    pub fn download_image(
+        &mut self,
+        image: &Image,
+        layers: SubresourceLayers,
+        extent: Extent3,
+        layout: Layout,
+    ) -> Result<Vec<u8>, DownloadError> {
+        let format = image.info().format;
+        let texel_size = texel_size(format).ok_or(DownloadError::UnsupportedFormat(format))?;
+
+        let size = texel_size
+            * u64::from(extent.width)
+            * u64::from(extent.height)
+            * u64::from(extent.depth)
+            * u64::from(layers.layer_count);
+
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let len = usize::try_from(size).map_err(|_| OutOfMemory)?;
+
+        let mut staging = self.device.create_mappable_buffer(
+            BufferInfo {
+                align: 15,
+                size,
+                usage: BufferUsage::TRANSFER_DST,
+            },
+            MemoryUsage::DOWNLOAD,
+        )?;
+
+        // Copy commands borrow staging buffer until the scope is dropped.
+        {
+            let scope = Scope::new();
+            let mut encoder = self.create_encoder(&scope)?;
+
+            encoder.image_barriers(
+                PipelineStages::ALL_COMMANDS,
+                PipelineStages::TRANSFER,
+                &[ImageMemoryBarrier {
+                    image,
+                    old_layout: Some(layout),
+                    new_layout: Layout::TransferSrcOptimal,
+                    old_access: Access::all(),
+                    new_access: Access::TRANSFER_READ,
+                    family_transfer: None,
+                    range: layers.into(),
+                }],
+            );
+
+            // Zero row length and image height make rows and layers tightly packed.
+            encoder.copy_image_to_buffer(
+                image,
+                Layout::TransferSrcOptimal,
+                &staging,
+                &[BufferImageCopy {
+                    buffer_offset: 0,
+                    buffer_row_length: 0,
+                    buffer_image_height: 0,
+                    image_subresource: layers,
+                    image_offset: Offset3::zeros(),
+                    image_extent: extent,
+                }],
+            );
+
+            encoder.image_barriers(
+                PipelineStages::TRANSFER,
+                PipelineStages::ALL_COMMANDS,
+                &[ImageMemoryBarrier {
+                    image,
+                    old_layout: Some(Layout::TransferSrcOptimal),
+                    new_layout: layout,
+                    old_access: Access::TRANSFER_READ,
+                    new_access: Access::all(),
+                    family_transfer: None,
+                    range: layers.into(),
+                }],
+            );
+
+            encoder.memory_barrier(
+                PipelineStages::TRANSFER,
+                Access::TRANSFER_WRITE,
+                PipelineStages::HOST,
+                Access::HOST_READ,
+            );
+
+            let mut fence = self.device.create_fence()?;
+            self.submit(
+                &mut [],
+                Some(encoder.finish()),
+                &mut [],
+                Some(&mut fence),
+                &scope,
+            )?;
+            self.try_wait_fences(&mut [&mut fence], true)?;
+        }
+
+        let mapped = self
+            .device
+            .map_memory(&mut staging, 0, len)
+            .map_err(|err| {
+                tracing::error!("Failed to map download buffer: {}", err);
+                OutOfMemory
+            })?;
+
+        // Safety: staging buffer is fully written by the copy.
+        let texels = unsafe { std::slice::from_raw_parts(mapped.as_ptr().cast::<u8>(), len) };
+        let texels = texels.to_vec();
+
+        self.device.unmap_memory(&mut staging);
+        Ok(texels)
+    }
+}
+
+/// Returns size of single texel of uncompressed color or depth format.
+fn texel_size(format: Format) -> Option<u64> {
+    use Format::*;
+
+    let size = match format {
+        R8Unorm | R8Snorm | R8Uscaled | R8Sscaled | R8Uint | R8Sint | R8Srgb => 1,
+
+        RG8Unorm | RG8Snorm | RG8Uscaled | RG8Sscaled | RG8Uint | RG8Sint | RG8Srgb | R16Unorm
+        | R16Snorm | R16Uscaled | R16Sscaled | R16Uint | R16Sint | R16Sfloat | D16Unorm => 2,
+
+        RGB8Unorm | RGB8Snorm | RGB8Uscaled | RGB8Sscaled | RGB8Uint | RGB8Sint | RGB8Srgb => 3,
+
+        RGBA8Unorm | RGBA8Snorm | RGBA8Uscaled | RGBA8Sscaled | RGBA8Uint | RGBA8Sint
+        | RGBA8Srgb | BGRA8Unorm | BGRA8Srgb | RG16Unorm | RG16Snorm | RG16Uscaled
+        | RG16Sscaled | RG16Uint | RG16Sint | RG16Sfloat | R32Uint | R32Sint | R32Sfloat => 4,
+
+        RGB16Unorm | RGB16Snorm | RGB16Uscaled | RGB16Sscaled | RGB16Uint | RGB16Sint => 6,
+
+        RGBA16Unorm | RGBA16Snorm | RGBA16Uscaled | RGBA16Sscaled | RGBA16Uint | RGBA16Sint
+        | RGBA16Sfloat | RG32Uint | RG32Sint | RG32Sfloat | R64Uint | R64Sint | R64Sfloat => 8,
+
+        RGB32Uint | RGB32Sint | RGB32Sfloat => 12,
+
+        RGBA32Uint | RGBA32Sint | RGBA32Sfloat | RG64Uint | RG64Sint | RG64Sfloat => 16,
+
+        RGB64Uint | RGB64Sint | RGB64Sfloat => 24,
+
+        RGBA64Uint | RGBA64Sint | RGBA64Sfloat => 32,
+
+        _ => return None,
+    };
+
+    Some(size)
+}
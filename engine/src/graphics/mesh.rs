@@ -1,8 +1,18 @@
 mod asset;
-
-pub use self::asset::*;
-
-use std::{borrow::Cow, convert::TryFrom as _, mem::size_of_val, ops::Range, sync::Arc};
+mod blas;
+
+pub use self::{asset::*, blas::*};
+
+use std::{
+    borrow::Cow,
+    collections::hash_map::DefaultHasher,
+    convert::TryFrom as _,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    mem::size_of_val,
+    ops::Range,
+    sync::Arc,
+};
 
 #[cfg(feature = "genmesh")]
 use std::mem::size_of;
@@ -33,6 +43,16 @@ pub struct Binding {
     pub layout: VertexLayout,
 }
 
+/// Bindings with position and normal deltas of single morph target.
+///
+/// Morphed vertex attribute is base attribute
+/// plus sum of deltas of all targets multiplied by their weights.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct MorphTarget {
+    pub positions: Option<Binding>,
+    pub normals: Option<Binding>,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Indices {
     pub buffer: Buffer,
@@ -45,6 +65,7 @@ pub struct MeshBuilder {
     pub bindings: Vec<Binding>,
     pub indices: Option<Indices>,
     pub topology: PrimitiveTopology,
+    pub morph_targets: Vec<MorphTarget>,
 }
 
 impl Default for MeshBuilder {
@@ -60,6 +81,7 @@ impl MeshBuilder {
             bindings: Vec::new(),
             indices: None,
             topology: PrimitiveTopology::TriangleList,
+            morph_targets: Vec::new(),
         }
     }
 
@@ -138,14 +160,28 @@ impl MeshBuilder {
         self
     }
 
+    #[inline]
+    pub fn with_morph_targets(mut self, morph_targets: Vec<MorphTarget>) -> Self {
+        self.set_morph_targets(morph_targets);
+        self
+    }
+
+    #[inline]
+    pub fn set_morph_targets(&mut self, morph_targets: Vec<MorphTarget>) -> &mut Self {
+        self.morph_targets = morph_targets;
+        self
+    }
+
     #[inline]
     pub fn build(self, count: u32, vertex_count: u32) -> Mesh {
         Mesh {
             bindings: self.bindings.into(),
             indices: self.indices,
             topology: self.topology,
+            morph_targets: self.morph_targets.into(),
             count,
             vertex_count,
+            content: None,
         }
     }
 }
@@ -157,6 +193,8 @@ pub struct Mesh {
     count: u32,
     vertex_count: u32,
     topology: PrimitiveTopology,
+    morph_targets: Arc<[MorphTarget]>,
+    content: Option<Arc<MeshContent>>,
 }
 
 impl Mesh {
@@ -185,6 +223,18 @@ impl Mesh {
         self.indices.as_ref()
     }
 
+    #[inline]
+    pub fn morph_targets(&self) -> &[MorphTarget] {
+        &*self.morph_targets
+    }
+
+    /// Returns data the mesh was uploaded from.
+    /// Only meshes built from [`MeshData`] for acceleration structures keep it.
+    #[inline]
+    pub fn content(&self) -> Option<&Arc<MeshContent>> {
+        self.content.as_ref()
+    }
+
     #[inline]
     pub fn build_triangles_blas<'a>(
         &self,
@@ -462,6 +512,10 @@ impl MeshData<'_> {
         vertices_usage: BufferUsage,
         indices_usage: BufferUsage,
     ) -> Result<Mesh, OutOfMemory> {
+        let content = vertices_usage
+            .contains(BufferUsage::ACCELERATION_STRUCTURE_BUILD_INPUT)
+            .then(|| Arc::new(MeshContent::new(self)));
+
         let mut min_vertex_count = !0u32;
 
         let bindings: Arc<[Binding]> = self
@@ -520,8 +574,10 @@ impl MeshData<'_> {
             bindings,
             indices,
             topology: self.topology,
+            morph_targets: Arc::new([]),
             count,
             vertex_count: min_vertex_count,
+            content,
         })
     }
 
@@ -540,6 +596,94 @@ impl MeshData<'_> {
     }
 }
 
+/// Vertex and index data mesh was uploaded from.
+///
+/// Meshes uploaded from equal data have equal content,
+/// so they can share resources built from it, e.g. acceleration structures.
+/// Content is compared by data, hash is computed once on creation.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::graphics::{MeshContent, MeshData, Position3};
+/// let positions = [Position3([0.0, 0.0, 0.0]), Position3([1.0, 0.0, 0.0]), Position3([0.0, 1.0, 0.0])];
+/// let copy = positions;
+///
+/// let a = MeshContent::new(&MeshData::new().with_binding(&positions).with_indices(&[0u16, 1, 2]));
+/// let b = MeshContent::new(&MeshData::new().with_binding(&copy).with_indices(&[0u16, 1, 2]));
+/// let c = MeshContent::new(&MeshData::new().with_binding(&copy).with_indices(&[0u16, 2, 1]));
+///
+/// assert_eq!(a, b);
+/// assert_ne!(a, c);
+/// ```
+pub struct MeshContent {
+    hash: u64,
+    data: MeshData<'static>,
+}
+
+impl MeshContent {
+    pub fn new(data: &MeshData<'_>) -> Self {
+        let data = MeshData {
+            bindings: data
+                .bindings
+                .iter()
+                .map(|binding| BindingData {
+                    data: Cow::Owned(binding.data.to_vec()),
+                    layout: binding.layout.clone(),
+                })
+                .collect(),
+            indices: data.indices.as_ref().map(|indices| IndicesData {
+                data: Cow::Owned(indices.data.to_vec()),
+                index_type: indices.index_type,
+            }),
+            topology: data.topology,
+        };
+
+        let mut hasher = DefaultHasher::new();
+        for binding in &data.bindings {
+            binding.data.hash(&mut hasher);
+            binding.layout.hash(&mut hasher);
+        }
+        if let Some(indices) = &data.indices {
+            indices.data.hash(&mut hasher);
+            indices.index_type.hash(&mut hasher);
+        }
+        data.topology.hash(&mut hasher);
+
+        MeshContent {
+            hash: hasher.finish(),
+            data,
+        }
+    }
+
+    #[inline]
+    pub fn data(&self) -> &MeshData<'static> {
+        &self.data
+    }
+}
+
+impl Debug for MeshContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MeshContent")
+            .field("hash", &self.hash)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Hash for MeshContent {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl PartialEq for MeshContent {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.data == other.data
+    }
+}
+
+impl Eq for MeshContent {}
+
 fn topology_is_triangles(topology: &PrimitiveTopology) -> bool {
     *topology == PrimitiveTopology::TriangleList
 }
@@ -829,7 +973,9 @@ impl Mesh {
             indices: Some(indices),
             count: index_count,
             topology: PrimitiveTopology::TriangleList,
+            morph_targets: Arc::new([]),
             vertex_count,
+            content: None,
         })
     }
 }
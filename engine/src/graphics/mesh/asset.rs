@@ -12,6 +12,8 @@ use crate::graphics::{
     VertexType, Weights, UV, V2, V3, V4,
 };
 
+use super::MorphTarget;
+
 #[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub enum MeshFileVertexLayout {
     Position3,
@@ -60,6 +62,14 @@ pub struct BindingFileHeader {
     pub layout: MeshFileVertexLayout,
 }
 
+/// Bindings with position and normal deltas of single morph target.
+/// Deltas use `Position3` and `Normal3` layouts respectively.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MorphTargetFileHeader {
+    pub positions: Option<BindingFileHeader>,
+    pub normals: Option<BindingFileHeader>,
+}
+
 #[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub struct IndicesFileHeader {
     pub offset: usize,
@@ -75,6 +85,7 @@ pub struct MeshFileHeader {
     pub bindings: Vec<BindingFileHeader>,
     pub indices: Option<IndicesFileHeader>,
     pub topology: PrimitiveTopology,
+    pub morph_targets: Vec<MorphTargetFileHeader>,
 }
 
 impl MeshFileHeader {
@@ -88,28 +99,49 @@ impl Mesh {
         bindings: &[BindingFileHeader],
         indices: Option<&IndicesFileHeader>,
         topology: PrimitiveTopology,
+        morph_targets: &[MorphTargetFileHeader],
         data: &[u8],
         graphics: &mut Graphics,
     ) -> Result<Self, OutOfMemory> {
+        let mut build_binding = |binding: &BindingFileHeader| -> Result<_, OutOfMemory> {
+            let layout = binding.layout.into_vertex_layout();
+
+            let size = u64::from(layout.stride) * u64::from(vertex_count);
+            let size_usize = usize::try_from(size).map_err(|_| OutOfMemory)?;
+
+            Ok(Binding {
+                buffer: graphics.create_buffer_static(
+                    BufferInfo {
+                        align: 255,
+                        size,
+                        usage: BufferUsage::VERTEX,
+                    },
+                    &data[binding.offset..][..size_usize],
+                )?,
+                offset: 0,
+                layout,
+            })
+        };
+
         let bindings = bindings
             .iter()
-            .map(|binding| -> Result<_, OutOfMemory> {
-                let layout = binding.layout.into_vertex_layout();
-
-                let size = u64::from(layout.stride) * u64::from(vertex_count);
-                let size_usize = usize::try_from(size).map_err(|_| OutOfMemory)?;
+            .map(&mut build_binding)
+            .collect::<Result<_, _>>()?;
 
-                Ok(Binding {
-                    buffer: graphics.create_buffer_static(
-                        BufferInfo {
-                            align: 255,
-                            size,
-                            usage: BufferUsage::VERTEX,
-                        },
-                        &data[binding.offset..][..size_usize],
-                    )?,
-                    offset: 0,
-                    layout,
+        let morph_targets = morph_targets
+            .iter()
+            .map(|target| -> Result<_, OutOfMemory> {
+                Ok(MorphTarget {
+                    positions: target
+                        .positions
+                        .as_ref()
+                        .map(&mut build_binding)
+                        .transpose()?,
+                    normals: target
+                        .normals
+                        .as_ref()
+                        .map(&mut build_binding)
+                        .transpose()?,
                 })
             })
             .collect::<Result<_, _>>()?;
@@ -151,6 +183,7 @@ impl Mesh {
             .with_bindings(bindings)
             .with_indices_maybe(indices)
             .with_topology(topology)
+            .with_morph_targets(morph_targets)
             .build(count, vertex_count))
     }
 }
@@ -219,6 +252,7 @@ where
             &decoded.header.bindings,
             decoded.header.indices.as_ref(),
             decoded.header.topology,
+            &decoded.header.morph_targets,
             &decoded.bytes,
             builder.borrow_mut(),
         )
@@ -0,0 +1,204 @@
+use std::{
+    hash::Hash,
+    ops::Deref,
+    sync::{Arc, Weak},
+};
+
+use edict::Component;
+use hashbrown::{hash_map::Entry, HashMap};
+use sierra::{AccelerationStructure, Device, Encoder, OutOfMemory};
+
+use super::{Mesh, MeshContent};
+
+/// Values shared by key.
+///
+/// Cache holds only weak references, so value is dropped
+/// as soon as the last strong reference returned from the cache is gone.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::graphics::SharedCache;
+/// let mut cache = SharedCache::new();
+/// let mut builds = 0;
+///
+/// let mut build = |name: &'static str| {
+///     builds += 1;
+///     Ok::<_, ()>(name)
+/// };
+///
+/// let a = cache.get_or_try_insert_with(1, || build("a")).unwrap();
+/// let b = cache.get_or_try_insert_with(1, || build("b")).unwrap();
+/// let c = cache.get_or_try_insert_with(2, || build("c")).unwrap();
+///
+/// // Equal key shares one value.
+/// assert!(std::sync::Arc::ptr_eq(&a, &b));
+/// assert_eq!(*c, "c");
+/// assert_eq!(cache.len(), 2);
+///
+/// // Dropping all references frees the value.
+/// drop((a, b));
+/// cache.cleanup();
+/// assert_eq!(cache.len(), 1);
+///
+/// let d = cache.get_or_try_insert_with(1, || build("d")).unwrap();
+/// assert_eq!(*d, "d");
+/// drop(build);
+/// assert_eq!(builds, 3);
+/// ```
+pub struct SharedCache<K, T> {
+    entries: HashMap<K, Weak<T>>,
+}
+
+impl<K, T> Default for SharedCache<K, T>
+where
+    K: Hash + Eq,
+{
+    fn default() -> Self {
+        SharedCache::new()
+    }
+}
+
+impl<K, T> SharedCache<K, T>
+where
+    K: Hash + Eq,
+{
+    pub fn new() -> Self {
+        SharedCache {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns value with specified key if it is still alive,
+    /// otherwise builds and caches new one.
+    pub fn get_or_try_insert_with<E>(
+        &mut self,
+        key: K,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Arc<T>, E> {
+        match self.entries.entry(key) {
+            Entry::Occupied(mut entry) => match entry.get().upgrade() {
+                Some(value) => Ok(value),
+                None => {
+                    let value = Arc::new(f()?);
+                    entry.insert(Arc::downgrade(&value));
+                    Ok(value)
+                }
+            },
+            Entry::Vacant(entry) => {
+                let value = Arc::new(f()?);
+                entry.insert(Arc::downgrade(&value));
+                Ok(value)
+            }
+        }
+    }
+
+    /// Removes entries which values were dropped.
+    pub fn cleanup(&mut self) {
+        self.entries.retain(|_, value| value.strong_count() > 0);
+    }
+
+    /// Returns number of cached entries,
+    /// including dropped ones not yet removed by [`SharedCache::cleanup`].
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no cached entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Bottom-level acceleration structure built for a [`Mesh`].
+/// Shared between all entities with the same mesh.
+#[derive(Clone, Debug, Component)]
+pub struct Blas(Arc<AccelerationStructure>);
+
+impl Deref for Blas {
+    type Target = AccelerationStructure;
+
+    fn deref(&self) -> &AccelerationStructure {
+        &self.0
+    }
+}
+
+/// Key of [`BlasCache`] entries.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use arcana::graphics::{BlasKey, MeshContent, MeshData, Position3, SharedCache};
+/// let positions = [Position3([0.0, 0.0, 0.0]), Position3([1.0, 0.0, 0.0]), Position3([0.0, 1.0, 0.0])];
+/// let key = || BlasKey::Content(Arc::new(MeshContent::new(&MeshData::new().with_binding(&positions))));
+///
+/// let mut cache = SharedCache::new();
+///
+/// // Two meshes uploaded from identical data share one structure.
+/// let a = cache.get_or_try_insert_with(key(), || Ok::<_, ()>("blas")).unwrap();
+/// let b = cache.get_or_try_insert_with(key(), || Ok::<_, ()>("another blas")).unwrap();
+/// assert!(Arc::ptr_eq(&a, &b));
+///
+/// // Structure is freed after the last reference is dropped.
+/// let weak = Arc::downgrade(&a);
+/// drop((a, b));
+/// assert!(weak.upgrade().is_none());
+///
+/// cache.cleanup();
+/// assert!(cache.is_empty());
+/// ```
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum BlasKey {
+    /// Meshes uploaded from equal data.
+    Content(Arc<MeshContent>),
+
+    /// Meshes without known content referencing the same buffer ranges with the same layout.
+    Mesh(Mesh),
+}
+
+impl Mesh {
+    /// Returns key of acceleration structure built for the mesh.
+    pub fn blas_key(&self) -> BlasKey {
+        match self.content() {
+            Some(content) => BlasKey::Content(content.clone()),
+            None => BlasKey::Mesh(self.clone()),
+        }
+    }
+}
+
+/// Cache of bottom-level acceleration structures keyed by [`BlasKey`].
+///
+/// Acceleration structure is freed when no [`Blas`] references it.
+#[derive(Default)]
+pub struct BlasCache {
+    cache: SharedCache<BlasKey, AccelerationStructure>,
+}
+
+impl BlasCache {
+    pub fn new() -> Self {
+        BlasCache::default()
+    }
+
+    /// Returns acceleration structure for the mesh.
+    /// New one is built only if there is no live structure
+    /// for mesh with the same [`BlasKey`].
+    pub fn get_or_build(
+        &mut self,
+        mesh: &Mesh,
+        encoder: &mut Encoder<'_>,
+        device: &Device,
+    ) -> Result<Blas, OutOfMemory> {
+        self.cache
+            .get_or_try_insert_with(mesh.blas_key(), || {
+                mesh.build_triangles_blas(encoder, device)
+            })
+            .map(Blas)
+    }
+
+    /// Removes entries of freed acceleration structures.
+    /// Should be called periodically, e.g. once per frame.
+    pub fn cleanup(&mut self) {
+        self.cache.cleanup();
+    }
+}
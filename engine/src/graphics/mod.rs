@@ -133,6 +133,9 @@ macro_rules! define_vertex_type {
 pub mod node;
 pub mod renderer;
 
+mod compute;
+mod cull;
+mod download;
 mod format;
 mod material;
 mod scale;
@@ -156,22 +159,50 @@ use edict::{EntityId, World};
 use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
 use scoped_arena::Scope;
 use sierra::{
-    Access, Buffer, BufferInfo, CommandBuffer, CreateSurfaceError, Device, Encoder, Extent3, Fence,
-    Format, Image, ImageInfo, ImageUsage, Layout, Offset3, OutOfMemory, PipelineStages,
-    PresentMode, PresentOk, Queue, Semaphore, SingleQueueQuery, SubresourceLayers, Surface,
-    SwapchainImage,
+    Access, Buffer, BufferInfo, CommandBuffer, CreateSurfaceError, Device, DeviceError, Encoder,
+    Extent3, Fence, Format, Image, ImageInfo, ImageUsage, Layout, Offset3, OutOfMemory,
+    PipelineStages, PresentMode, PresentOk, Queue, Semaphore, SingleQueueQuery, SubresourceLayers,
+    Surface, SwapchainImage,
 };
 
 pub use sierra::VertexInputRate;
 
 use crate::window::Windows;
 
+pub use self::download::DownloadError;
 use self::upload::Uploader;
-pub use self::{format::*, material::*, scale::*, target::*, texture::*, vertex::*};
+pub use self::upload::{
+    check_mips_format, mip_steps, MipStep, StagingArena, UploadError, UploadFence,
+};
+pub use self::{
+    compute::*, cull::*, format::*, material::*, scale::*, target::*, texture::*, vertex::*,
+};
 
 #[cfg(feature = "3d")]
 pub use self::mesh::*;
 
+/// Error that may occur on submission and presentation.
+#[derive(Debug, thiserror::Error)]
+pub enum GraphicsError {
+    #[error(transparent)]
+    OutOfMemory(#[from] OutOfMemory),
+
+    /// Device was lost due to GPU hang, driver reset or GPU switch.
+    /// All resources created from the device are unusable.
+    /// Game should recreate [`Graphics`] with all its resources or shut down.
+    #[error("Graphics device lost")]
+    DeviceLost,
+}
+
+impl From<DeviceError> for GraphicsError {
+    fn from(err: DeviceError) -> Self {
+        match err {
+            DeviceError::OutOfMemory(err) => GraphicsError::OutOfMemory(err),
+            DeviceError::DeviceLost => GraphicsError::DeviceLost,
+        }
+    }
+}
+
 /// Graphics context.
 /// Combines device and single queue.
 /// Suitable for not too complex graphics tasks.
@@ -179,6 +210,10 @@ pub struct Graphics {
     uploader: Uploader,
     queue: Queue,
     device: Device,
+    lost: bool,
+
+    /// Enabled texture compression features.
+    texture_compression: Vec<sierra::Feature>,
 }
 
 impl Graphics {
@@ -192,23 +227,34 @@ impl Graphics {
             .max_by_key(|d| d.info().kind)
             .ok_or_else(|| eyre::eyre!("Failed to find physical device"))?;
 
-        let (device, queue) = physical.create_device(
-            &[
-                sierra::Feature::SurfacePresentation,
-                sierra::Feature::ShaderSampledImageDynamicIndexing,
-                sierra::Feature::ShaderSampledImageNonUniformIndexing,
-                sierra::Feature::ShaderStorageImageDynamicIndexing,
-                sierra::Feature::ShaderStorageImageNonUniformIndexing,
-                sierra::Feature::RuntimeDescriptorArray,
-                sierra::Feature::ScalarBlockLayout,
-            ],
-            SingleQueueQuery::GRAPHICS,
-        )?;
+        // Compressed textures are used only where device supports them.
+        let texture_compression: Vec<_> = [
+            sierra::Feature::TextureCompressionBC,
+            sierra::Feature::TextureCompressionASTCLDR,
+        ]
+        .into_iter()
+        .filter(|feature| physical.info().features.contains(feature))
+        .collect();
+
+        let mut features = vec![
+            sierra::Feature::SurfacePresentation,
+            sierra::Feature::ShaderSampledImageDynamicIndexing,
+            sierra::Feature::ShaderSampledImageNonUniformIndexing,
+            sierra::Feature::ShaderStorageImageDynamicIndexing,
+            sierra::Feature::ShaderStorageImageNonUniformIndexing,
+            sierra::Feature::RuntimeDescriptorArray,
+            sierra::Feature::ScalarBlockLayout,
+        ];
+        features.extend_from_slice(&texture_compression);
+
+        let (device, queue) = physical.create_device(&features, SingleQueueQuery::GRAPHICS)?;
 
         Ok(Graphics {
             uploader: Uploader::new(&device)?,
             device,
             queue,
+            lost: false,
+            texture_compression,
         })
     }
 }
@@ -256,7 +302,7 @@ impl Graphics {
 
     #[inline]
     #[tracing::instrument(skip(self, data))]
-    pub fn upload_image<T>(&mut self, upload: UploadImage, data: &[T]) -> Result<(), OutOfMemory>
+    pub fn upload_image<T>(&mut self, upload: UploadImage, data: &[T]) -> Result<(), UploadError>
     where
         T: Pod,
     {
@@ -269,7 +315,7 @@ impl Graphics {
         upload: UploadImage,
         data: &[T],
         encoder: &mut Encoder<'a>,
-    ) -> Result<(), OutOfMemory>
+    ) -> Result<(), UploadError>
     where
         T: Pod,
     {
@@ -300,7 +346,7 @@ impl Graphics {
         format: Format,
         row_length: u32,
         image_height: u32,
-    ) -> Result<Image, OutOfMemory>
+    ) -> Result<Image, UploadError>
     where
         T: Pod,
     {
@@ -320,12 +366,81 @@ impl Graphics {
                 format,
                 row_length,
                 image_height,
+                generate_mips: false,
             },
             data,
         )?;
         Ok(image)
     }
 
+    /// Creates image and uploads data to all its mip levels.
+    ///
+    /// Data of each level is copied as is, without conversion,
+    /// which makes this suitable for block-compressed formats.
+    /// Fails with [`UploadError::UnsupportedFormat`]
+    /// if device does not support image format.
+    #[tracing::instrument(skip(self, levels))]
+    pub fn create_image_static_levels(
+        &mut self,
+        mut info: ImageInfo,
+        layout: Layout,
+        levels: &[&[u8]],
+    ) -> Result<Image, UploadError> {
+        if !self.supports_format(info.format) {
+            return Err(UploadError::UnsupportedFormat(info.format));
+        }
+
+        debug_assert_eq!(info.levels as usize, levels.len());
+
+        info.usage |= ImageUsage::TRANSFER_DST;
+        let image = self.device.create_image(info)?;
+
+        for (level, data) in levels.iter().enumerate() {
+            let level = level as u32;
+            let extent = info.extent.into_3d();
+
+            self.upload_image(
+                UploadImage {
+                    image: &image,
+                    offset: Offset3::zeros(),
+                    extent: Extent3 {
+                        width: (extent.width >> level).max(1),
+                        height: (extent.height >> level).max(1),
+                        depth: (extent.depth >> level).max(1),
+                    },
+                    layers: SubresourceLayers::all_layers(&info, level),
+                    old_layout: None,
+                    new_layout: layout,
+                    old_access: Access::empty(),
+                    new_access: Access::all(),
+                    format: info.format,
+                    row_length: 0,
+                    image_height: 0,
+                    generate_mips: false,
+                },
+                data,
+            )?;
+        }
+        Ok(image)
+    }
+
+    /// Returns `true` if images of the format can be sampled on this device.
+    ///
+    /// Block-compressed formats require optional device features,
+    /// other formats are assumed to be supported.
+    pub fn supports_format(&self, format: Format) -> bool {
+        use Format::*;
+
+        let feature = match format {
+            BC1RGBAUnorm | BC1RGBASrgb | BC3Unorm | BC3Srgb | BC4Unorm | BC5Unorm | BC7Unorm
+            | BC7Srgb => sierra::Feature::TextureCompressionBC,
+            ASTC4x4Unorm | ASTC4x4Srgb => sierra::Feature::TextureCompressionASTCLDR,
+            _ => return true,
+        };
+
+        self.texture_compression.contains(&feature)
+    }
+
     pub fn create_encoder<'a>(&mut self, scope: &'a Scope<'a>) -> Result<Encoder<'a>, OutOfMemory> {
         self.queue.create_encoder(scope)
     }
@@ -337,25 +452,102 @@ impl Graphics {
         signal: &mut [&mut Semaphore],
         fence: Option<&mut Fence>,
         scope: &Scope<'_>,
-    ) -> Result<(), OutOfMemory> {
-        self.flush_uploads(scope)?;
-        self.queue.submit(wait, cbufs, signal, fence, scope);
-        Ok(())
+    ) -> Result<(), GraphicsError> {
+        self.guard_device_lost(|graphics| {
+            graphics.flush_uploads(scope)?;
+            graphics
+                .queue
+                .try_submit(wait, cbufs, signal, fence, scope)?;
+            Ok(())
+        })
+    }
+
+    /// Submits all pending uploads right away.
+    /// Returned fence signals when they are complete on device,
+    /// e.g. to recycle streaming memory only after GPU is done with it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use arcana::{graphics::{Graphics, GraphicsError}, scoped_arena::Scope, sierra::Buffer};
+    /// # fn stream(graphics: &mut Graphics, buffer: &Buffer, chunk: &[u32], scope: &Scope<'_>) -> Result<(), GraphicsError> {
+    /// graphics.upload_buffer(buffer, 0, chunk)?;
+    /// let mut fence = graphics.flush_uploads_with_fence(scope)?;
+    /// fence.wait()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flush_uploads_with_fence(
+        &mut self,
+        scope: &Scope<'_>,
+    ) -> Result<UploadFence, GraphicsError> {
+        self.guard_device_lost(|graphics| {
+            let mut fence = graphics.device.create_fence()?;
+            let staging = graphics.uploader.flush_uploads(
+                &graphics.device,
+                &mut graphics.queue,
+                Some(&mut fence),
+                scope,
+            )?;
+
+            Ok(UploadFence::new(graphics.device.clone(), fence, staging))
+        })
+    }
+
+    pub fn present(&mut self, image: SwapchainImage) -> Result<PresentOk, GraphicsError> {
+        self.guard_device_lost(|graphics| Ok(graphics.queue.try_present(image)?))
+    }
+
+    /// Waits for fences to be signaled.
+    /// Fails with [`GraphicsError::DeviceLost`] if device was lost.
+    pub fn try_wait_fences(
+        &mut self,
+        fences: &mut [&mut Fence],
+        all: bool,
+    ) -> Result<(), GraphicsError> {
+        self.guard_device_lost(|graphics| {
+            graphics.device.try_wait_fences(fences, all)?;
+            Ok(())
+        })
+    }
+
+    /// Returns `true` if device was lost.
+    /// Once lost, device never recovers and all further submissions fail
+    /// with [`GraphicsError::DeviceLost`].
+    #[inline]
+    pub fn is_device_lost(&self) -> bool {
+        self.lost
     }
 
-    pub fn present(&mut self, image: SwapchainImage) -> Result<PresentOk, OutOfMemory> {
-        self.queue.present(image)
+    /// Runs device operation, marking device as lost
+    /// once operation reports `VK_ERROR_DEVICE_LOST`.
+    fn guard_device_lost<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, GraphicsError>,
+    ) -> Result<T, GraphicsError> {
+        if self.lost {
+            return Err(GraphicsError::DeviceLost);
+        }
+
+        let result = f(self);
+        if let Err(GraphicsError::DeviceLost) = result {
+            tracing::error!("Graphics device lost");
+            self.lost = true;
+        }
+        result
     }
 
-    fn flush_uploads(&mut self, scope: &Scope<'_>) -> Result<(), OutOfMemory> {
+    fn flush_uploads(&mut self, scope: &Scope<'_>) -> Result<(), GraphicsError> {
         self.uploader
-            .flush_uploads(&self.device, &mut self.queue, scope)
+            .flush_uploads(&self.device, &mut self.queue, None, scope)?;
+        Ok(())
     }
 }
 
 impl Drop for Graphics {
     fn drop(&mut self) {
-        if !std::thread::panicking() {
+        // Waiting on lost device would panic again.
+        if !std::thread::panicking() && !self.lost {
             self.wait_idle();
         }
     }
@@ -425,6 +617,12 @@ pub struct UploadImage<'a> {
     pub format: Format,
     pub row_length: u32,
     pub image_height: u32,
+
+    /// Fill mip levels after uploaded one by downsampling.
+    /// Image must have `TRANSFER_SRC` usage
+    /// and upload must cover whole level.
+    /// All levels are transitioned to `new_layout`.
+    pub generate_mips: bool,
 }
 
 /// Returns new viewport instance attached to specified camera.
@@ -12,9 +12,9 @@ use crate::{
         material::Material,
         mesh::Mesh,
         vertex::{Normal3, Position3, VertexType as _, UV, V3},
-        vertex_layouts_for_pipeline, Graphics, Scale,
+        vertex_layouts_for_pipeline, CullStats, DrawStats, Graphics, MeshBounds, Scale,
     },
-    scene::Global3,
+    scene::{Global3, RenderGlobal3},
 };
 pub struct BasicDraw {
     pipeline_layout: <BasicPipeline as PipelineInput>::Layout,
@@ -74,10 +74,12 @@ impl DrawNode for BasicDraw {
         cx: RenderContext<'a, 'b>,
         encoder: &mut Encoder<'a>,
         render_pass: &mut RenderPassEncoder<'_, 'b>,
-        camera: EntityId,
+        camera_entity: EntityId,
         viewport: Extent2,
     ) -> eyre::Result<()> {
-        let (global, camera) = cx.world.query_one_mut::<(&Global3, &Camera3)>(camera)?;
+        let (global, camera) = cx
+            .world
+            .query_one_mut::<(&Global3, &Camera3)>(camera_entity)?;
 
         let view = global.iso.inverse().to_homogeneous();
         let proj = camera
@@ -124,29 +126,42 @@ impl DrawNode for BasicDraw {
             &mut cx.world.expect_resource_mut::<Graphics>(),
         )?;
 
+        let clip_from_world = proj * view;
+        let mut stats = CullStats::default();
+
         let query = cx.world.query_mut::<(
             &Mesh,
             &Material,
             &Global3,
             &mut BasicRenderable,
             Option<&Scale>,
+            Option<&RenderGlobal3>,
+            Option<&MeshBounds>,
         )>();
 
-        // let mut drawn_count = 0;
-        for (mesh, mat, global, renderable, scale) in query.iter_mut() {
+        for (mesh, mat, global, renderable, scale, render_global, bounds) in query.iter_mut() {
             uniforms.albedo_factor = mat.albedo_factor.into();
 
+            let iso = render_global.map_or(global.iso, |render_global| render_global.iso);
+
             if let Some(albedo) = mat.albedo.clone() {
-                match scale {
+                let transform = match scale {
                     Some(scale) => {
-                        let m = na::Matrix4::<f32>::new_nonuniform_scaling(&scale.0);
-                        uniforms.transform = mat4_na_to_sierra(global.iso.to_homogeneous() * m);
+                        iso.to_homogeneous() * na::Matrix4::new_nonuniform_scaling(&scale.0)
                     }
-                    None => {
-                        uniforms.transform = mat4_na_to_sierra(global.iso.to_homogeneous());
+                    None => iso.to_homogeneous(),
+                };
+
+                if let Some(bounds) = bounds {
+                    if !stats.record(bounds.is_visible(&(clip_from_world * transform))) {
+                        continue;
                     }
+                } else {
+                    stats.drawn += 1;
                 }
 
+                uniforms.transform = mat4_na_to_sierra(transform);
+
                 let updated = renderable.descriptors.update(
                     &BasicDescriptors {
                         sampler: albedo.sampler,
@@ -162,13 +177,17 @@ impl DrawNode for BasicDraw {
                 let drawn = mesh.draw(0..1, &[V3::<Position3, Normal3, UV>::layout()], render_pass);
                 if !drawn {
                     tracing::warn!("Mesh is not drawn");
-                } else {
-                    // drawn_count += 1;
                 }
             }
         }
 
-        // tracing::info!("Meshes drawn {}", drawn_count);
+        drop(query);
+
+        tracing::debug!("Rendering {} meshes, {} culled", stats.drawn, stats.culled);
+
+        DrawStats::update(cx.world, camera_entity, |draw_stats| {
+            draw_stats.meshes = stats
+        });
 
         Ok(())
     }
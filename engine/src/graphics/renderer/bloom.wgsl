@@ -0,0 +1,60 @@
+struct VertexOutput {
+    [[builtin(position)]] pos: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+struct Uniforms {
+    inv_extent: vec2<f32>;
+    threshold: f32;
+    intensity: f32;
+    radius: f32;
+};
+
+[[group(0), binding(0)]]
+var input_sampler: sampler;
+
+[[group(0), binding(1)]]
+var input_texture: texture_2d<f32>;
+
+[[group(0), binding(2)]]
+var<uniform> uniforms: Uniforms;
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] index: u32) -> VertexOutput {
+    var out: VertexOutput;
+
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    out.pos = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = uv;
+
+    return out;
+}
+
+fn bright(uv: vec2<f32>) -> vec3<f32> {
+    let color = textureSample(input_texture, input_sampler, uv).rgb;
+    let luma = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return color * max(luma - uniforms.threshold, 0.0) / max(luma, 0.0001);
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let color = textureSample(input_texture, input_sampler, in.uv);
+
+    // Gather bright pixels on two rings around the fragment.
+    var glow = vec3<f32>(0.0);
+    var weight = 0.0;
+
+    for (var ring = 1; ring <= 2; ring = ring + 1) {
+        let distance = uniforms.radius * f32(ring) * 0.5;
+        let ring_weight = 1.0 / f32(ring);
+
+        for (var i = 0; i < 8; i = i + 1) {
+            let angle = f32(i) * 0.78539816 + f32(ring) * 0.39269908;
+            let offset = vec2<f32>(cos(angle), sin(angle)) * distance * uniforms.inv_extent;
+            glow = glow + bright(in.uv + offset) * ring_weight;
+            weight = weight + ring_weight;
+        }
+    }
+
+    return vec4<f32>(color.rgb + glow / weight * uniforms.intensity, color.a);
+}
@@ -1,7 +1,8 @@
 use std::collections::VecDeque;
 
 use edict::{
-    entity::EntityId, epoch::EpochId, query::QueryBorrowAny, world::World, Entities, State,
+    entity::EntityId, epoch::EpochId, prelude::Component, query::QueryBorrowAny, world::World,
+    Entities, State,
 };
 use hashbrown::HashSet;
 use scoped_arena::Scope;
@@ -14,9 +15,14 @@ use super::{Graphics, NeedsRedraw, RenderTarget, RendersTo, SurfaceSwapchain};
 #[cfg(feature = "3d")]
 pub mod basic;
 
+pub mod post;
+
 // #[cfg(feature = "2d")]
 // pub mod sprite;
 
+#[cfg(feature = "2d")]
+pub mod text;
+
 // #[cfg(feature = "with-egui")]
 // pub mod egui;
 
@@ -38,6 +44,15 @@ pub trait Renderer: Send + 'static {
     ) -> eyre::Result<Vec<CommandBuffer>>;
 }
 
+/// Order of renderer among renderers that don't depend on each other.
+///
+/// Renderers with lower order are submitted first.
+/// Renderers without this component have order `0`.
+/// Ties are broken by entity id.
+/// Dependencies reported by renderer are always submitted before it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Component)]
+pub struct RenderOrder(pub i32);
+
 pub trait RenderNodeInputs<'a> {
     /// Inputs required by render pass.
     /// This includes resources to which render pass will write.
@@ -184,6 +199,12 @@ pub fn rendering_system(
 ) {
     let mut graphics = world.expect_resource_mut::<Graphics>();
 
+    // Nothing can be rendered with lost device.
+    // Game loop shuts down when it detects device loss.
+    if graphics.is_device_lost() {
+        return;
+    }
+
     let mut swapchain_images = Vec::new_in(&**allocator);
     let mut render_queue = Vec::new_in(&**allocator);
 
@@ -207,7 +228,7 @@ pub fn rendering_system(
         render_queue.extend_from_slice(renderers);
     }
 
-    render_queue.sort_unstable_by_key(|e| e.id());
+    sort_renderers(world, &mut render_queue);
     render_queue.dedup();
 
     enum MaybeExecutedRender {
@@ -264,7 +285,7 @@ pub fn rendering_system(
                 drop(deps);
                 drop(renders_to);
 
-                dep_renders.sort_unstable_by_key(|e| e.id());
+                sort_renderers(world, &mut dep_renders);
                 dep_renders.dedup();
 
                 if dep_renders.is_empty() {
@@ -278,7 +299,8 @@ pub fn rendering_system(
                         buffers: command_buffers,
                     });
 
-                    for render in dep_renders {
+                    // Pushed in reverse so that renderer with lowest order is executed first.
+                    for render in dep_renders.into_iter().rev() {
                         render_queue.push_front(MaybeExecutedRender::Render(render));
                     }
                 }
@@ -300,15 +322,45 @@ pub fn rendering_system(
         signals.push(signal);
     }
 
-    graphics.submit(
-        &mut waits,
-        command_queue,
-        &mut signals,
-        Some(state.fence(&graphics)),
-        &**allocator,
-    );
+    let fence = state.fence(&graphics);
+    let result = graphics
+        .submit(&mut waits, command_queue, &mut signals, Some(fence), &**allocator)
+        .and_then(|()| {
+            for swapchain_image in swapchain_images {
+                graphics.present(swapchain_image)?;
+            }
+            Ok(())
+        });
 
-    for swapchain_image in swapchain_images {
-        graphics.queue.present(swapchain_image);
+    if let Err(err) = result {
+        tracing::error!("Failed to submit frame. {:#}", err);
     }
 }
+
+/// Sorts renderers in order of submission by their [`RenderOrder`] and entity id.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{edict::world::World, graphics::renderer::{sort_renderers, RenderOrder}};
+/// let mut world = World::new();
+///
+/// let late = world.spawn((RenderOrder(10),));
+/// let early = world.spawn((RenderOrder(-5),));
+/// let unordered = world.spawn(());
+///
+/// let mut renderers = [late, unordered, early];
+/// sort_renderers(&world, &mut renderers);
+/// assert_eq!(renderers, [early, unordered, late]);
+/// ```
+pub fn sort_renderers(world: &World, renderers: &mut [EntityId]) {
+    renderers.sort_unstable_by_key(|e| (render_order(world, *e), e.id()));
+}
+
+fn render_order(world: &World, renderer: EntityId) -> i32 {
+    world
+        .query_one::<&RenderOrder>(renderer)
+        .ok()
+        .and_then(|mut order| order.get().map(|order| order.0))
+        .unwrap_or(0)
+}
@@ -0,0 +1,452 @@
+//! Post-processing chain of full-screen passes.
+//!
+//! Scene is rendered into HDR target using [`HdrScenePass`].
+//! Then [`PostProcessChain`] runs full-screen nodes,
+//! each sampling color target of previous one and writing into new one.
+//! Last node writes into final target, e.g. swapchain image.
+
+use scoped_arena::Scope;
+use sierra::{
+    graphics_pipeline_desc, vec2, ClearColor, ClearDepth, CommandBuffer, Descriptors,
+    DynamicGraphicsPipeline, Encoder, Extent2, Filter, Format, FragmentShader, Image, ImageInfo,
+    ImageUsage, ImageView, ImageViewInfo, Layout, OutOfMemory, Pass, PipelineInput,
+    RenderPassEncoder, Sampler, SamplerInfo, ShaderModuleInfo, ShaderRepr, VertexShader,
+};
+
+use super::{RenderContext, RenderNode, RenderNodeInputs};
+use crate::graphics::Graphics;
+
+/// Format of HDR targets.
+pub const HDR_FORMAT: Format = Format::RGBA16Sfloat;
+
+/// Render pass to draw scene into HDR target.
+/// Color target is left in layout suitable for sampling by post-processing nodes.
+#[derive(Pass)]
+#[sierra(subpass(color = color, depth = depth))]
+pub struct HdrScenePass {
+    #[sierra(attachment(store = const Layout::ShaderReadOnlyOptimal, clear = const ClearColor(0.02, 0.03, 0.03, 1.0)))]
+    pub color: Image,
+
+    #[sierra(attachment(clear = const ClearDepth(1.0)))]
+    pub depth: Format,
+}
+
+#[derive(Pass)]
+#[sierra(subpass(color = color))]
+struct IntermediatePass {
+    #[sierra(attachment(store = const Layout::ShaderReadOnlyOptimal, clear = const ClearColor(0.0, 0.0, 0.0, 1.0)))]
+    color: Image,
+}
+
+#[derive(Pass)]
+#[sierra(subpass(color = color))]
+struct PresentPass {
+    #[sierra(attachment(store = const Layout::Present, clear = const ClearColor(0.0, 0.0, 0.0, 1.0)))]
+    color: Image,
+}
+
+/// Full-screen pass of the post-processing chain.
+pub trait PostProcessNode: 'static {
+    /// Draw full-screen pass sampling `input`.
+    ///
+    /// `encoder` executes before render pass and can be used to update descriptors.
+    fn draw<'a, 'b: 'a>(
+        &'b mut self,
+        graphics: &Graphics,
+        encoder: &mut Encoder<'a>,
+        render_pass: &mut RenderPassEncoder<'_, 'b>,
+        input: &ImageView,
+        extent: Extent2,
+    ) -> eyre::Result<()>;
+}
+
+impl<N> PostProcessNode for Box<N>
+where
+    N: PostProcessNode + ?Sized,
+{
+    fn draw<'a, 'b: 'a>(
+        &'b mut self,
+        graphics: &Graphics,
+        encoder: &mut Encoder<'a>,
+        render_pass: &mut RenderPassEncoder<'_, 'b>,
+        input: &ImageView,
+        extent: Extent2,
+    ) -> eyre::Result<()> {
+        (&mut **self).draw(graphics, encoder, render_pass, input, extent)
+    }
+}
+
+struct Target {
+    image: Image,
+    view: ImageView,
+}
+
+fn create_target(graphics: &Graphics, extent: Extent2) -> Result<Target, OutOfMemory> {
+    let image = graphics.create_image(ImageInfo {
+        extent: extent.into(),
+        format: HDR_FORMAT,
+        levels: 1,
+        layers: 1,
+        samples: sierra::Samples1,
+        usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+    })?;
+
+    let view = graphics.create_image_view(ImageViewInfo::new(image.clone()))?;
+    Ok(Target { image, view })
+}
+
+/// Chain of post-processing nodes.
+///
+/// Owns HDR scene target and intermediate targets between nodes,
+/// recreating them when extent changes.
+pub struct PostProcessChain {
+    nodes: Vec<Box<dyn PostProcessNode>>,
+    scene: Option<Target>,
+    intermediate: Vec<Target>,
+    intermediate_pass: IntermediatePassInstance,
+    present_pass: PresentPassInstance,
+}
+
+impl PostProcessChain {
+    pub fn new() -> Self {
+        PostProcessChain {
+            nodes: Vec::new(),
+            scene: None,
+            intermediate: Vec::new(),
+            intermediate_pass: IntermediatePass::instance(),
+            present_pass: PresentPass::instance(),
+        }
+    }
+
+    /// Appends node to the end of the chain.
+    pub fn with(mut self, node: impl PostProcessNode) -> Self {
+        self.push(node);
+        self
+    }
+
+    /// Appends node to the end of the chain.
+    pub fn push(&mut self, node: impl PostProcessNode) {
+        self.nodes.push(Box::new(node));
+    }
+
+    /// Returns HDR target of specified extent to render scene into with [`HdrScenePass`].
+    pub fn scene_target(
+        &mut self,
+        graphics: &Graphics,
+        extent: Extent2,
+    ) -> Result<Image, OutOfMemory> {
+        match &self.scene {
+            Some(target) if target.image.info().extent.into_2d() == extent => {}
+            _ => self.scene = Some(create_target(graphics, extent)?),
+        }
+        Ok(self.scene.as_ref().unwrap().image.clone())
+    }
+
+    /// Records all nodes of the chain.
+    ///
+    /// `source` must be in `ShaderReadOnlyOptimal` layout, as left by [`HdrScenePass`].
+    /// `target` is left in `Present` layout.
+    ///
+    /// Returns command buffers that must be submitted in order.
+    pub fn record<'a>(
+        &'a mut self,
+        graphics: &mut Graphics,
+        scope: &'a Scope<'a>,
+        source: &Image,
+        target: &Image,
+    ) -> eyre::Result<[CommandBuffer; 2]> {
+        if self.nodes.is_empty() {
+            return Err(eyre::eyre!("Post-processing chain is empty"));
+        }
+
+        let extent = target.info().extent.into_2d();
+
+        let intermediate_count = self.nodes.len() - 1;
+        self.intermediate
+            .retain(|intermediate| intermediate.image.info().extent.into_2d() == extent);
+        self.intermediate.truncate(intermediate_count);
+        while self.intermediate.len() < intermediate_count {
+            self.intermediate.push(create_target(graphics, extent)?);
+        }
+
+        let source = match &self.scene {
+            Some(scene) if scene.image == *source => scene.view.clone(),
+            _ => graphics.create_image_view(ImageViewInfo::new(source.clone()))?,
+        };
+
+        let mut encoder = graphics.create_encoder(scope)?;
+        let mut pass_encoder = graphics.create_encoder(scope)?;
+
+        let last = self.nodes.len() - 1;
+
+        for (index, node) in self.nodes.iter_mut().enumerate() {
+            let input = match index {
+                0 => &source,
+                _ => &self.intermediate[index - 1].view,
+            };
+
+            let mut render_pass = if index == last {
+                pass_encoder.with_render_pass(
+                    &mut self.present_pass,
+                    &PresentPass {
+                        color: target.clone(),
+                    },
+                    graphics,
+                )?
+            } else {
+                pass_encoder.with_render_pass(
+                    &mut self.intermediate_pass,
+                    &IntermediatePass {
+                        color: self.intermediate[index].image.clone(),
+                    },
+                    graphics,
+                )?
+            };
+
+            node.draw(graphics, &mut encoder, &mut render_pass, input, extent)?;
+        }
+
+        Ok([encoder.finish(), pass_encoder.finish()])
+    }
+}
+
+impl Default for PostProcessChain {
+    fn default() -> Self {
+        PostProcessChain::new()
+    }
+}
+
+/// Inputs for [`PostProcessChain`] used as [`RenderNode`].
+pub struct PostProcessInputs {
+    pub source: Image,
+    pub target: Image,
+}
+
+impl<'a> RenderNodeInputs<'a> for PostProcessChain {
+    type Inputs = PostProcessInputs;
+}
+
+impl RenderNode for PostProcessChain {
+    type Outputs = [CommandBuffer; 2];
+
+    fn render<'a>(
+        &'a mut self,
+        cx: RenderContext<'a, '_>,
+        inputs: PostProcessInputs,
+    ) -> eyre::Result<[CommandBuffer; 2]> {
+        let mut graphics = cx.world.expect_resource_mut::<Graphics>();
+        self.record(&mut graphics, cx.scope, &inputs.source, &inputs.target)
+    }
+}
+
+fn linear_sampler(graphics: &Graphics) -> Result<Sampler, OutOfMemory> {
+    graphics.create_sampler(SamplerInfo {
+        mag_filter: Filter::Linear,
+        min_filter: Filter::Linear,
+        ..SamplerInfo::new()
+    })
+}
+
+fn fullscreen_pipeline(
+    graphics: &Graphics,
+    code: &[u8],
+    layout: &sierra::PipelineLayout,
+) -> eyre::Result<DynamicGraphicsPipeline> {
+    let shader_module =
+        graphics.create_shader_module(ShaderModuleInfo::wgsl(code.to_vec().into_boxed_slice()))?;
+
+    Ok(DynamicGraphicsPipeline::new(graphics_pipeline_desc! {
+        vertex_bindings: Vec::new(),
+        vertex_attributes: Vec::new(),
+        vertex_shader: VertexShader::new(shader_module.clone(), "vs_main"),
+        fragment_shader: Some(FragmentShader::new(shader_module, "fs_main")),
+        layout: layout.clone(),
+        depth_test: None,
+    }))
+}
+
+#[derive(Clone, Copy, Default, ShaderRepr)]
+#[sierra(std140)]
+struct TonemapUniforms {
+    exposure: f32,
+}
+
+#[derive(Descriptors)]
+struct TonemapDescriptors {
+    #[sierra(sampler, fragment)]
+    sampler: Sampler,
+
+    #[sierra(image(sampled), fragment)]
+    input: ImageView,
+
+    #[sierra(uniform, fragment)]
+    uniforms: TonemapUniforms,
+}
+
+#[allow(unused)]
+#[derive(PipelineInput)]
+struct TonemapPipeline {
+    #[sierra(set)]
+    set: TonemapDescriptors,
+}
+
+/// Maps HDR colors into displayable range using ACES filmic curve.
+pub struct TonemapNode {
+    pub exposure: f32,
+    sampler: Sampler,
+    pipeline_layout: TonemapPipelineLayout,
+    pipeline: DynamicGraphicsPipeline,
+    set: TonemapDescriptorsInstance,
+}
+
+impl TonemapNode {
+    pub fn new(graphics: &Graphics) -> eyre::Result<Self> {
+        let pipeline_layout = TonemapPipeline::layout(graphics)?;
+        let pipeline = fullscreen_pipeline(
+            graphics,
+            std::include_bytes!("tonemap.wgsl"),
+            pipeline_layout.raw(),
+        )?;
+
+        Ok(TonemapNode {
+            exposure: 1.0,
+            sampler: linear_sampler(graphics)?,
+            set: pipeline_layout.set.instance(),
+            pipeline_layout,
+            pipeline,
+        })
+    }
+}
+
+impl PostProcessNode for TonemapNode {
+    fn draw<'a, 'b: 'a>(
+        &'b mut self,
+        graphics: &Graphics,
+        encoder: &mut Encoder<'a>,
+        render_pass: &mut RenderPassEncoder<'_, 'b>,
+        input: &ImageView,
+        _extent: Extent2,
+    ) -> eyre::Result<()> {
+        let updated = self.set.update(
+            &TonemapDescriptors {
+                sampler: self.sampler.clone(),
+                input: input.clone(),
+                uniforms: TonemapUniforms {
+                    exposure: self.exposure,
+                },
+            },
+            graphics,
+            encoder,
+        )?;
+
+        render_pass.bind_dynamic_graphics_pipeline(&mut self.pipeline, graphics)?;
+        render_pass.bind_graphics_descriptors(&self.pipeline_layout, updated);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Default, ShaderRepr)]
+#[sierra(std140)]
+struct BloomUniforms {
+    inv_extent: vec2,
+    threshold: f32,
+    intensity: f32,
+    radius: f32,
+}
+
+#[derive(Descriptors)]
+struct BloomDescriptors {
+    #[sierra(sampler, fragment)]
+    sampler: Sampler,
+
+    #[sierra(image(sampled), fragment)]
+    input: ImageView,
+
+    #[sierra(uniform, fragment)]
+    uniforms: BloomUniforms,
+}
+
+#[allow(unused)]
+#[derive(PipelineInput)]
+struct BloomPipeline {
+    #[sierra(set)]
+    set: BloomDescriptors,
+}
+
+/// Adds glow around pixels brighter than threshold.
+///
+/// Single-pass approximation that gathers bright pixels around each fragment.
+/// Should run before [`TonemapNode`] on HDR colors.
+pub struct BloomNode {
+    /// Luminance above which pixels start to glow.
+    pub threshold: f32,
+
+    /// Strength of the glow.
+    pub intensity: f32,
+
+    /// Glow radius in pixels.
+    pub radius: f32,
+
+    sampler: Sampler,
+    pipeline_layout: BloomPipelineLayout,
+    pipeline: DynamicGraphicsPipeline,
+    set: BloomDescriptorsInstance,
+}
+
+impl BloomNode {
+    pub fn new(graphics: &Graphics) -> eyre::Result<Self> {
+        let pipeline_layout = BloomPipeline::layout(graphics)?;
+        let pipeline = fullscreen_pipeline(
+            graphics,
+            std::include_bytes!("bloom.wgsl"),
+            pipeline_layout.raw(),
+        )?;
+
+        Ok(BloomNode {
+            threshold: 1.0,
+            intensity: 0.5,
+            radius: 8.0,
+            sampler: linear_sampler(graphics)?,
+            set: pipeline_layout.set.instance(),
+            pipeline_layout,
+            pipeline,
+        })
+    }
+}
+
+impl PostProcessNode for BloomNode {
+    fn draw<'a, 'b: 'a>(
+        &'b mut self,
+        graphics: &Graphics,
+        encoder: &mut Encoder<'a>,
+        render_pass: &mut RenderPassEncoder<'_, 'b>,
+        input: &ImageView,
+        extent: Extent2,
+    ) -> eyre::Result<()> {
+        let updated = self.set.update(
+            &BloomDescriptors {
+                sampler: self.sampler.clone(),
+                input: input.clone(),
+                uniforms: BloomUniforms {
+                    inv_extent: vec2::from([
+                        1.0 / extent.width.max(1) as f32,
+                        1.0 / extent.height.max(1) as f32,
+                    ]),
+                    threshold: self.threshold,
+                    intensity: self.intensity,
+                    radius: self.radius,
+                },
+            },
+            graphics,
+            encoder,
+        )?;
+
+        render_pass.bind_dynamic_graphics_pipeline(&mut self.pipeline, graphics)?;
+        render_pass.bind_graphics_descriptors(&self.pipeline_layout, updated);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
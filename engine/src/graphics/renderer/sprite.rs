@@ -12,22 +12,38 @@ use super::{mat3_na_to_sierra, DrawNode, RendererContext};
 use crate::{
     camera::Camera2,
     graphics::{
-        material::Material, vertex_layouts_for_pipeline, Graphics, SparseDescriptors,
-        Transformation2, VertexLocation, VertexType,
+        material::Material, vertex_layouts_for_pipeline, DrawStats, Graphics, SparseDescriptors,
+        SpriteCuller, Transformation2, VertexLocation, VertexType,
     },
     rect::Rect,
-    scene::Global2,
-    sprite::Sprite,
+    scene::{Global2, RenderGlobal2},
+    sprite::{NineSlice, Sprite, SpriteBatch, SpriteBatcher},
 };
 
+/// Number of textures in single descriptor set.
+const TEXTURES_PER_PAGE: u32 = 128;
+
 pub struct SpriteDraw {
     pipeline: DynamicGraphicsPipeline,
     pipeline_layout: <SpritePipeline as PipelineInput>::Layout,
-    descriptors: SpriteDescriptors,
-    set: SpriteDescriptorsInstance,
+    pages: Vec<TexturePage>,
+    dummy: ImageView,
+    linear_sampler: Sampler,
+    nearest_sampler: Sampler,
     textures: SparseDescriptors<ImageView>,
     sprites: Buffer,
     layer_range: Range<f32>,
+    batcher: SpriteBatcher<u32, SpriteInstance>,
+    batches: Vec<SpriteBatch<u32>>,
+    scratch: Vec<SpriteInstance>,
+    instanced: bool,
+}
+
+/// Descriptor set with a page of sprite textures.
+/// Sprites are batched by page.
+struct TexturePage {
+    descriptors: SpriteDescriptors,
+    set: SpriteDescriptorsInstance,
 }
 
 #[derive(Clone, Copy, Default, ShaderRepr)]
@@ -111,10 +127,14 @@ impl SpriteDraw {
         )?;
 
         let dummy = graphics.create_image_view(sierra::ImageViewInfo::new(dummy))?;
-        let textures = (0..128).map(|_| dummy.clone()).collect::<Vec<_>>();
-        let textures = <[ImageView; 128]>::try_from(textures).unwrap();
 
-        let sampler = graphics.create_sampler(sierra::SamplerInfo::linear())?;
+        let linear_sampler = graphics.create_sampler(sierra::SamplerInfo::linear())?;
+        let nearest_sampler = graphics.create_sampler(sierra::SamplerInfo {
+            mag_filter: sierra::Filter::Nearest,
+            min_filter: sierra::Filter::Nearest,
+            mipmap_mode: sierra::MipmapMode::Nearest,
+            ..sierra::SamplerInfo::linear()
+        })?;
 
         let sprites = graphics.create_buffer(sierra::BufferInfo {
             align: 255,
@@ -122,8 +142,6 @@ impl SpriteDraw {
             usage: sierra::BufferUsage::VERTEX | sierra::BufferUsage::TRANSFER_DST,
         })?;
 
-        let set = pipeline_layout.set.instance();
-
         let (vertex_bindings, vertex_attributes) =
             vertex_layouts_for_pipeline(&[SpriteInstance::layout()]);
 
@@ -137,18 +155,65 @@ impl SpriteDraw {
                 depth_test: Some(DepthTest::LESS_WRITE),
             }),
             pipeline_layout,
-
-            descriptors: SpriteDescriptors {
-                sampler,
-                textures,
-                uniforms: Uniforms::default(),
-            },
-            set,
+            pages: Vec::new(),
+            dummy,
+            linear_sampler,
+            nearest_sampler,
             textures: SparseDescriptors::new(),
             sprites,
             layer_range,
+            batcher: SpriteBatcher::new(),
+            batches: Vec::new(),
+            scratch: Vec::new(),
+            instanced: false,
         })
     }
+
+    /// Switches to instanced path.
+    ///
+    /// All sprites using the same texture page are drawn with single
+    /// instanced draw call regardless of their layers,
+    /// so number of draw calls depends only on number of textures.
+    /// Layers are still resolved by depth test, but semi-transparent sprites
+    /// are no longer blended back to front.
+    /// Suitable for scenes with huge number of opaque sprites.
+    pub fn instanced(mut self) -> Self {
+        self.instanced = true;
+        self
+    }
+
+    /// Returns page and slot of the texture, adding new page if needed.
+    fn texture_slot(&mut self, image: &ImageView) -> (u32, u32) {
+        let (index, new) = self.textures.index(image.clone());
+        let page = index / TEXTURES_PER_PAGE;
+        let slot = index % TEXTURES_PER_PAGE;
+
+        self.ensure_page(page);
+
+        if new {
+            self.pages[page as usize].descriptors.textures[slot as usize] = image.clone();
+        }
+
+        (page, slot)
+    }
+
+    /// Adds pages filled with dummy texture up to specified one.
+    fn ensure_page(&mut self, page: u32) {
+        while self.pages.len() <= page as usize {
+            let textures = (0..TEXTURES_PER_PAGE)
+                .map(|_| self.dummy.clone())
+                .collect::<Vec<_>>();
+
+            self.pages.push(TexturePage {
+                descriptors: SpriteDescriptors {
+                    sampler: self.linear_sampler.clone(),
+                    textures: <[ImageView; 128]>::try_from(textures).unwrap(),
+                    uniforms: Uniforms::default(),
+                },
+                set: self.pipeline_layout.set.instance(),
+            });
+        }
+    }
 }
 
 impl DrawNode for SpriteDraw {
@@ -158,30 +223,46 @@ impl DrawNode for SpriteDraw {
 
         encoder: &mut Encoder<'a>,
         render_pass: &mut RenderPassEncoder<'_, 'b>,
-        camera: EntityId,
-        _viewport: Extent2,
+        camera_entity: EntityId,
+        viewport: Extent2,
     ) -> eyre::Result<()> {
-        let (global, camera) = cx.world.query_one::<(&Global2, &Camera2)>(&camera)?;
+        let (global, camera) = cx.world.query_one::<(&Global2, &Camera2)>(&camera_entity)?;
 
-        let view = global.iso.inverse().to_homogeneous();
-        let affine = camera.affine().to_homogeneous();
+        let (camera_iso, affine) = camera.view_proj(&global.iso, viewport.width, viewport.height);
+        let view = camera_iso.inverse().to_homogeneous();
 
-        self.descriptors.uniforms.camera = mat3_na_to_sierra(affine * view);
+        let camera_uniform = mat3_na_to_sierra(affine.to_homogeneous() * view);
 
-        render_pass.bind_dynamic_graphics_pipeline(&mut self.pipeline, cx.graphics)?;
+        // Bilinear filtering blurs pixel art.
+        let sampler = match camera.pixel_perfect_mode() {
+            Some(_) => self.nearest_sampler.clone(),
+            None => self.linear_sampler.clone(),
+        };
 
-        let mut sprites = Vec::with_capacity_in(1024, &*cx.scope);
+        render_pass.bind_dynamic_graphics_pipeline(&mut self.pipeline, cx.graphics)?;
 
-        for (_, (sprite, mat, global)) in cx.world.query_mut::<(&Sprite, &Material, &Global2)>() {
-            let albedo = match &mat.albedo {
-                Some(texture) => {
-                    let (index, new) = self.textures.index(texture.image.clone());
-                    if new {
-                        self.descriptors.textures[index as usize] = texture.image.clone();
-                    }
-                    index
+        let mut culler = SpriteCuller::from_view_proj(&camera_iso, &affine);
+
+        for (_, (sprite, mat, global, render_global, nine_slice)) in cx.world.query_mut::<(
+            &Sprite,
+            &Material,
+            &Global2,
+            Option<&RenderGlobal2>,
+            Option<&NineSlice>,
+        )>() {
+            let iso = render_global.map_or(global.iso, |render_global| render_global.iso);
+
+            if !culler.is_visible(sprite, &iso) {
+                continue;
+            }
+
+            // Untextured sprites can be drawn with any page.
+            let (page, albedo) = match &mat.albedo {
+                Some(texture) => self.texture_slot(&texture.image),
+                None => {
+                    self.ensure_page(0);
+                    (0, u32::MAX)
                 }
-                None => u32::MAX,
             };
 
             let layer_start_bits = self.layer_range.start.to_bits();
@@ -189,28 +270,55 @@ impl DrawNode for SpriteDraw {
             let layer = f32::from_bits(layer_bits);
             debug_assert!(layer < self.layer_range.end);
 
-            let instance = SpriteInstance {
-                pos: sprite.src.from_relative_to(&sprite.world),
-                uv: sprite.tex,
-                layer,
-                albedo,
-                albedo_factor: {
-                    let [r, g, b, a] = mat.albedo_factor;
-                    LinSrgba::new(r, g, b, a)
-                },
-                transform: Transformation2(global.iso.to_homogeneous().into()),
-            };
+            let (src, tex) = sprite.flipped_rects();
+            let pos = src.from_relative_to(&sprite.world);
 
-            sprites.push(instance);
+            let albedo_factor = {
+                let [r, g, b, a] = mat.albedo_factor;
+                let [tr, tg, tb, ta] = sprite.tint;
+                LinSrgba::new(r * tr, g * tg, b * tb, a * ta)
+            };
+            let transform = Transformation2(iso.to_homogeneous().into());
+
+            // Instanced path ignores layers when batching.
+            let batch_layer = if self.instanced { 0 } else { sprite.layer };
+
+            let nine_slice = nine_slice.copied().unwrap_or_default();
+            for (pos, uv) in nine_slice.quads(pos, tex) {
+                self.batcher.push(
+                    batch_layer,
+                    page,
+                    SpriteInstance {
+                        pos,
+                        uv,
+                        layer,
+                        albedo,
+                        albedo_factor,
+                        transform,
+                    },
+                );
+            }
         }
 
-        tracing::debug!("Rendering {} sprites", sprites.len());
+        let stats = culler.stats();
+        tracing::debug!("Rendering {} sprites, {} culled", stats.drawn, stats.culled);
+
+        DrawStats::update(cx.world, camera_entity, |draw_stats| {
+            draw_stats.sprites = stats
+        });
+
+        if self.batcher.is_empty() {
+            return Ok(());
+        }
 
-        let updated = self.set.update(&self.descriptors, cx.graphics, encoder)?;
+        // Scratch buffer is reused between frames to avoid reallocation.
+        self.scratch.clear();
+        self.batches.clear();
+        self.batcher.finish(&mut self.scratch, &mut self.batches);
 
-        render_pass.bind_graphics_descriptors(&self.pipeline_layout, updated);
+        tracing::debug!("Sprites batched into {} draws", self.batches.len());
 
-        let sprite_count = sprites.len() as u32;
+        let sprite_count = self.scratch.len() as u32;
 
         if self.sprites.info().size < sprite_count as u64 * size_of::<SpriteInstance>() as u64 {
             self.sprites = cx.graphics.create_buffer(sierra::BufferInfo {
@@ -222,7 +330,7 @@ impl DrawNode for SpriteDraw {
         }
 
         cx.graphics
-            .upload_buffer_with(&self.sprites, 0, sprites.leak(), encoder)?;
+            .upload_buffer_with(&self.sprites, 0, &self.scratch, encoder)?;
 
         encoder.memory_barrier(
             PipelineStages::TRANSFER,
@@ -232,7 +340,16 @@ impl DrawNode for SpriteDraw {
         );
 
         render_pass.bind_vertex_buffers(0, &[(&self.sprites, 0)]);
-        render_pass.draw(0..6, 0..sprite_count);
+
+        for batch in &self.batches {
+            let page = &mut self.pages[batch.key as usize];
+            page.descriptors.uniforms.camera = camera_uniform;
+            page.descriptors.sampler = sampler.clone();
+
+            let updated = page.set.update(&page.descriptors, cx.graphics, encoder)?;
+            render_pass.bind_graphics_descriptors(&self.pipeline_layout, updated);
+            render_pass.draw(0..6, batch.range.clone());
+        }
 
         Ok(())
     }
@@ -0,0 +1,364 @@
+use std::{mem::size_of, ops::Range};
+
+use edict::{entity::EntityId, Component};
+use palette::LinSrgba;
+use sierra::{
+    graphics_pipeline_desc, Access, Buffer, Descriptors, DynamicGraphicsPipeline, Encoder, Extent2,
+    FragmentShader, ImageView, PipelineInput, PipelineStages, RenderPassEncoder, Sampler,
+    ShaderModuleInfo, ShaderRepr, VertexInputRate, VertexShader,
+};
+
+use super::{DrawNode, RenderContext};
+use crate::{
+    assets::font::FontFaces,
+    camera::Camera2,
+    graphics::{
+        vertex_layouts_for_pipeline, Graphics, Transformation2, VertexLocation, VertexType,
+    },
+    rect::Rect,
+    scene::{Global2, RenderGlobal2},
+    text::{TextConstraints, TextLayout},
+};
+
+/// Where [`Text`] is placed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextSpace {
+    /// Text is placed in the world by entity's [`Global2`] transform
+    /// and viewed through the camera.
+    /// `scale` is the number of world units per font pixel.
+    World { scale: f32 },
+
+    /// Text is placed over the viewport.
+    /// `x` and `y` are offsets in pixels of the layout box top-left corner
+    /// from the viewport top-left corner.
+    Screen { x: f32, y: f32 },
+}
+
+/// Text component drawn by [`TextDraw`].
+#[derive(Clone, Debug, Component)]
+pub struct Text {
+    /// String to render.
+    pub text: String,
+
+    /// Font used for layout and MSDF glyph images.
+    pub font: FontFaces,
+
+    /// Font size in pixels.
+    pub size: f32,
+
+    /// Color of the glyphs.
+    pub color: [f32; 4],
+
+    /// Layout constraints.
+    pub constraints: TextConstraints,
+
+    /// Placement of the text.
+    pub space: TextSpace,
+}
+
+impl Text {
+    /// Returns white text of specified pixel size, placed in the world
+    /// with one world unit per font pixel.
+    pub fn new(text: impl Into<String>, font: FontFaces, size: f32) -> Self {
+        Text {
+            text: text.into(),
+            font,
+            size,
+            color: [1.0; 4],
+            constraints: TextConstraints::default(),
+            space: TextSpace::World { scale: 1.0 },
+        }
+    }
+
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_constraints(mut self, constraints: TextConstraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    pub fn with_space(mut self, space: TextSpace) -> Self {
+        self.space = space;
+        self
+    }
+}
+
+/// Draws [`Text`] components with glyph quads sampled from MSDF font atlas.
+pub struct TextDraw {
+    pipeline: DynamicGraphicsPipeline,
+    pipeline_layout: <TextPipeline as PipelineInput>::Layout,
+    sampler: Sampler,
+    batches: Vec<FontBatch>,
+    glyphs: Buffer,
+}
+
+/// Glyphs sharing the same font atlas.
+struct FontBatch {
+    atlas: ImageView,
+    distance_range: f32,
+    set: TextDescriptorsInstance,
+    range: Range<u32>,
+}
+
+#[derive(Clone, Copy, Default, ShaderRepr)]
+#[sierra(std140)]
+struct Uniforms {
+    distance_range: f32,
+}
+
+#[derive(Descriptors)]
+struct TextDescriptors {
+    #[sierra(sampler, fragment)]
+    sampler: Sampler,
+
+    #[sierra(image(sampled), fragment)]
+    atlas: ImageView,
+
+    #[sierra(uniform, fragment)]
+    uniforms: Uniforms,
+}
+
+#[derive(PipelineInput)]
+struct TextPipeline {
+    #[allow(unused)]
+    #[sierra(set)]
+    set: TextDescriptors,
+}
+
+impl TextDraw {
+    pub fn new(graphics: &Graphics) -> eyre::Result<Self> {
+        let shader_module = graphics.create_shader_module(ShaderModuleInfo::wgsl(
+            std::include_bytes!("text.wgsl").to_vec().into_boxed_slice(),
+        ))?;
+
+        let pipeline_layout = TextPipeline::layout(graphics)?;
+
+        let sampler = graphics.create_sampler(sierra::SamplerInfo::linear())?;
+
+        let glyphs = graphics.create_buffer(sierra::BufferInfo {
+            align: 255,
+            size: size_of::<GlyphInstance>() as u64 * 256,
+            usage: sierra::BufferUsage::VERTEX | sierra::BufferUsage::TRANSFER_DST,
+        })?;
+
+        let (vertex_bindings, vertex_attributes) =
+            vertex_layouts_for_pipeline(&[GlyphInstance::layout()]);
+
+        Ok(TextDraw {
+            pipeline: DynamicGraphicsPipeline::new(graphics_pipeline_desc! {
+                vertex_bindings,
+                vertex_attributes,
+                vertex_shader: VertexShader::new(shader_module.clone(), "vs_main"),
+                fragment_shader: Some(FragmentShader::new(shader_module, "fs_main")),
+                layout: pipeline_layout.raw().clone(),
+                depth_test: None,
+            }),
+            pipeline_layout,
+            sampler,
+            batches: Vec::new(),
+            glyphs,
+        })
+    }
+
+    fn batch(&mut self, font: &FontFaces) -> usize {
+        let atlas = font.texture();
+        match self.batches.iter().position(|batch| batch.atlas == *atlas) {
+            Some(idx) => idx,
+            None => {
+                self.batches.push(FontBatch {
+                    atlas: atlas.clone(),
+                    distance_range: font.distance_range(),
+                    set: self.pipeline_layout.set.instance(),
+                    range: 0..0,
+                });
+                self.batches.len() - 1
+            }
+        }
+    }
+}
+
+impl DrawNode for TextDraw {
+    fn draw<'a, 'b: 'a>(
+        &'b mut self,
+        cx: RenderContext<'a, 'b>,
+        encoder: &mut Encoder<'a>,
+        render_pass: &mut RenderPassEncoder<'_, 'b>,
+        camera: EntityId,
+        viewport: Extent2,
+    ) -> eyre::Result<()> {
+        let (global, camera) = cx.world.query_one_mut::<(&Global2, &Camera2)>(camera)?;
+
+        let (camera_iso, affine) = camera.view_proj(&global.iso, viewport.width, viewport.height);
+        let world_to_clip = affine.to_homogeneous() * camera_iso.inverse().to_homogeneous();
+
+        let width = viewport.width.max(1) as f32;
+        let height = viewport.height.max(1) as f32;
+
+        let mut glyphs = Vec::with_capacity_in(1024, &*cx.scope);
+
+        for (text, global, render_global) in cx
+            .world
+            .query_mut::<(&Text, Option<&Global2>, Option<&RenderGlobal2>)>()
+            .iter()
+        {
+            let transform = match text.space {
+                TextSpace::World { scale } => {
+                    let iso = match (render_global, global) {
+                        (Some(render_global), _) => render_global.iso,
+                        (None, Some(global)) => global.iso,
+                        (None, None) => continue,
+                    };
+                    world_to_clip * iso.to_homogeneous() * na::Matrix3::new_scaling(scale)
+                }
+                TextSpace::Screen { x, y } => na::Matrix3::new(
+                    2.0 / width,
+                    0.0,
+                    2.0 * x / width - 1.0,
+                    0.0,
+                    2.0 / height,
+                    1.0 - 2.0 * y / height,
+                    0.0,
+                    0.0,
+                    1.0,
+                ),
+            };
+            let transform = Transformation2(transform.into());
+
+            let batch = self.batch(&text.font) as u32;
+
+            let layout =
+                TextLayout::new(&text.text, text.font.font(), text.size, &text.constraints);
+
+            let [r, g, b, a] = text.color;
+            let color = LinSrgba::new(r, g, b, a);
+
+            for glyph in layout.glyphs() {
+                let metrics = match text.font.glyph(glyph.glyph) {
+                    None => continue,
+                    Some(metrics) => metrics,
+                };
+
+                // Quad covers distance field around the outline.
+                let plane = &metrics.plane;
+                let pos = Rect {
+                    left: glyph.origin.x + plane.left * text.size,
+                    right: glyph.origin.x + plane.right * text.size,
+                    bottom: glyph.origin.y + plane.bottom * text.size,
+                    top: glyph.origin.y + plane.top * text.size,
+                };
+
+                glyphs.push((
+                    batch,
+                    GlyphInstance {
+                        pos,
+                        uv: metrics.atlas,
+                        color,
+                        transform,
+                    },
+                ));
+            }
+        }
+
+        if glyphs.is_empty() {
+            return Ok(());
+        }
+
+        tracing::debug!("Rendering {} glyphs", glyphs.len());
+
+        // Group glyphs by font atlas so that each atlas is bound once.
+        glyphs.sort_by_key(|(batch, _)| *batch);
+
+        for batch in &mut self.batches {
+            batch.range = 0..0;
+        }
+
+        let mut instances = Vec::with_capacity_in(glyphs.len(), &*cx.scope);
+        for (batch, glyph) in glyphs {
+            let idx = instances.len() as u32;
+            let range = &mut self.batches[batch as usize].range;
+            if range.is_empty() {
+                *range = idx..idx;
+            }
+            range.end = idx + 1;
+            instances.push(glyph);
+        }
+
+        let mut graphics = cx.world.expect_resource_mut::<Graphics>();
+
+        let glyph_count = instances.len() as u32;
+
+        if self.glyphs.info().size < glyph_count as u64 * size_of::<GlyphInstance>() as u64 {
+            self.glyphs = graphics.create_buffer(sierra::BufferInfo {
+                align: 255,
+                size: size_of::<GlyphInstance>() as u64 * (glyph_count as u64).next_power_of_two(),
+                usage: sierra::BufferUsage::VERTEX | sierra::BufferUsage::TRANSFER_DST,
+            })?;
+        }
+
+        graphics.upload_buffer_with(&self.glyphs, 0, instances.leak(), encoder)?;
+
+        encoder.memory_barrier(
+            PipelineStages::TRANSFER,
+            Access::TRANSFER_WRITE,
+            PipelineStages::VERTEX_INPUT,
+            Access::VERTEX_ATTRIBUTE_READ,
+        );
+
+        render_pass.bind_dynamic_graphics_pipeline(&mut self.pipeline, &mut graphics)?;
+        render_pass.bind_vertex_buffers(0, &[(&self.glyphs, 0)]);
+
+        for batch in &mut self.batches {
+            if batch.range.is_empty() {
+                continue;
+            }
+
+            let updated = batch.set.update(
+                &TextDescriptors {
+                    sampler: self.sampler.clone(),
+                    atlas: batch.atlas.clone(),
+                    uniforms: Uniforms {
+                        distance_range: batch.distance_range,
+                    },
+                },
+                &graphics,
+                &mut *encoder,
+            )?;
+
+            render_pass.bind_graphics_descriptors(&self.pipeline_layout, updated);
+            render_pass.draw(0..6, batch.range.clone());
+        }
+
+        Ok(())
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct GlyphInstance {
+    pos: Rect,
+    uv: Rect,
+    color: LinSrgba<f32>,
+    transform: Transformation2,
+}
+
+unsafe impl bytemuck::Zeroable for GlyphInstance {}
+unsafe impl bytemuck::Pod for GlyphInstance {}
+
+impl VertexType for GlyphInstance {
+    const LOCATIONS: &'static [VertexLocation] = {
+        let mut offset = 0;
+
+        let pos = vertex_location!(offset, Rect);
+        let uv = vertex_location!(offset, Rect);
+        let color = vertex_location!(offset, LinSrgba<f32>);
+        let transform0 = vertex_location!(offset, [f32; 3] as "Transform2.0");
+        let transform1 = vertex_location!(offset, [f32; 3] as "Transform2.1");
+        let transform2 = vertex_location!(offset, [f32; 3] as "Transform2.2");
+
+        &[pos, uv, color, transform0, transform1, transform2]
+    };
+    const RATE: VertexInputRate = VertexInputRate::Instance;
+}
@@ -0,0 +1,72 @@
+struct GlyphInput {
+    [[location(0)]] pos: vec4<f32>;
+    [[location(1)]] uv: vec4<f32>;
+    [[location(2)]] color: vec4<f32>;
+    [[location(3)]] tr0: vec3<f32>;
+    [[location(4)]] tr1: vec3<f32>;
+    [[location(5)]] tr2: vec3<f32>;
+};
+
+struct VertexOutput {
+    [[builtin(position)]] pos: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+    [[location(1)]] color: vec4<f32>;
+};
+
+struct Uniforms {
+    // Distance range in atlas pixels used when MSDF atlas was generated.
+    distance_range: f32;
+};
+
+[[group(0), binding(0)]]
+var atlas_sampler: sampler;
+
+[[group(0), binding(1)]]
+var atlas: texture_2d<f32>;
+
+[[group(0), binding(2)]]
+var<uniform> uniforms: Uniforms;
+
+fn pt_from_aabb(aabb: vec4<f32>, index: u32) -> vec2<f32> {
+    var xs: array<f32, 6> = array<f32, 6>(aabb.x, aabb.x, aabb.y, aabb.y, aabb.y, aabb.x);
+    var ys: array<f32, 6> = array<f32, 6>(aabb.w, aabb.z, aabb.z, aabb.z, aabb.w, aabb.w);
+    return vec2<f32>(xs[index], ys[index]);
+}
+
+[[stage(vertex)]]
+fn vs_main(
+    [[builtin(vertex_index)]] index: u32,
+    in: GlyphInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let tr = mat3x3<f32>(in.tr0, in.tr1, in.tr2);
+    let pos = tr * vec3<f32>(pt_from_aabb(in.pos, index), 1.0);
+
+    out.pos = vec4<f32>(pos.xy, 0.0, 1.0);
+    out.uv = pt_from_aabb(in.uv, index);
+    out.color = in.color;
+
+    return out;
+}
+
+fn median(r: f32, g: f32, b: f32) -> f32 {
+    return max(min(r, g), min(max(r, g), b));
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let msd = textureSample(atlas, atlas_sampler, in.uv);
+    let sd = median(msd.r, msd.g, msd.b);
+
+    let unit_range = vec2<f32>(uniforms.distance_range) / vec2<f32>(textureDimensions(atlas, 0));
+    let screen_tex_size = vec2<f32>(1.0) / fwidth(in.uv);
+    let screen_px_range = max(0.5 * dot(unit_range, screen_tex_size), 1.0);
+    let opacity = clamp(screen_px_range * (sd - 0.5) + 0.5, 0.0, 1.0);
+
+    if (opacity < 0.5) {
+        discard;
+    }
+
+    return in.color;
+}
@@ -0,0 +1,44 @@
+struct VertexOutput {
+    [[builtin(position)]] pos: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+struct Uniforms {
+    exposure: f32;
+};
+
+[[group(0), binding(0)]]
+var input_sampler: sampler;
+
+[[group(0), binding(1)]]
+var input_texture: texture_2d<f32>;
+
+[[group(0), binding(2)]]
+var<uniform> uniforms: Uniforms;
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] index: u32) -> VertexOutput {
+    var out: VertexOutput;
+
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    out.pos = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = uv;
+
+    return out;
+}
+
+// Narkowicz ACES filmic curve fit.
+fn aces(x: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let hdr = textureSample(input_texture, input_sampler, in.uv);
+    return vec4<f32>(aces(hdr.rgb * uniforms.exposure), 1.0);
+}
@@ -1,3 +1,5 @@
+use edict::Component;
+
 /// Component specifies rendering scale for the entity.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
 pub struct Scale(pub na::Vector3<f32>);
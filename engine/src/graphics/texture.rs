@@ -14,17 +14,24 @@ use goods::{
 };
 use serde::ser::SerializeStruct;
 use sierra::{
-    ImageExtent, ImageInfo, ImageUsage, ImageView, ImageViewInfo, Layout, OutOfMemory, Sampler,
-    SamplerInfo, Samples::Samples1,
+    ImageExtent, ImageInfo, ImageUsage, ImageView, ImageViewInfo, Layout, Sampler, SamplerInfo,
+    Samples::Samples1,
 };
 
-use crate::{assets::image::QoiImage, graphics::Graphics, is_default};
+use crate::{
+    assets::image::{
+        decode_compressed_image, decode_qoi, CompressedImage, CompressedImageDecodeError,
+        QoiDecodeError, QoiImage,
+    },
+    graphics::{Graphics, UploadError},
+    is_default,
+};
 
 pub fn texture_view_from_qoi_image(
     qoi: &rapid_qoi::Qoi,
     pixels: &[u8],
     graphics: &mut Graphics,
-) -> Result<ImageView, OutOfMemory> {
+) -> Result<ImageView, UploadError> {
     use rapid_qoi::Colors::*;
     use sierra::Format::*;
 
@@ -58,6 +65,33 @@ pub fn texture_view_from_qoi_image(
     Ok(view)
 }
 
+/// Creates sampled image with all mip levels of compressed image.
+pub fn texture_view_from_compressed_image(
+    image: &CompressedImage,
+    graphics: &mut Graphics,
+) -> Result<ImageView, UploadError> {
+    let levels: Vec<&[u8]> = image.levels.iter().map(|level| &**level).collect();
+
+    let image = graphics.create_image_static_levels(
+        ImageInfo {
+            extent: ImageExtent::D2 {
+                width: image.width,
+                height: image.height,
+            },
+            format: image.format,
+            levels: levels.len() as u32,
+            layers: 1,
+            samples: Samples1,
+            usage: ImageUsage::SAMPLED,
+        },
+        Layout::ShaderReadOnlyOptimal,
+        &levels,
+    )?;
+
+    let view = graphics.create_image_view(ImageViewInfo::new(image))?;
+    Ok(view)
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Texture {
     /// Image view of the loaded texture.
@@ -257,22 +291,17 @@ where
 }
 
 impl Asset for Texture {
-    type DecodeError = rapid_qoi::DecodeError;
-    type BuildError = OutOfMemory;
+    type DecodeError = QoiDecodeError;
+    type BuildError = UploadError;
     type Decoded = QoiImage;
-    type Fut = Ready<Result<QoiImage, rapid_qoi::DecodeError>>;
+    type Fut = Ready<Result<QoiImage, QoiDecodeError>>;
 
     fn name() -> &'static str {
         "qoi"
     }
 
     fn decode(bytes: Box<[u8]>, _loader: &Loader) -> Self::Fut {
-        ready(
-            rapid_qoi::Qoi::decode_alloc(&bytes).map(|(qoi, pixels)| QoiImage {
-                qoi,
-                pixels: pixels.into(),
-            }),
-        )
+        ready(decode_qoi(&bytes))
     }
 }
 
@@ -280,7 +309,7 @@ impl<B> AssetBuild<B> for Texture
 where
     B: BorrowMut<Graphics>,
 {
-    fn build(image: QoiImage, builder: &mut B) -> Result<Self, OutOfMemory> {
+    fn build(image: QoiImage, builder: &mut B) -> Result<Self, UploadError> {
         let graphics = builder.borrow_mut();
         let image = texture_view_from_qoi_image(&image.qoi, &image.pixels, graphics)?;
 
@@ -291,3 +320,42 @@ where
         })
     }
 }
+
+/// Texture with GPU-compressed mip chain imported from KTX2.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CompressedTexture {
+    pub texture: Texture,
+}
+
+impl Asset for CompressedTexture {
+    type DecodeError = CompressedImageDecodeError;
+    type BuildError = UploadError;
+    type Decoded = CompressedImage;
+    type Fut = Ready<Result<CompressedImage, CompressedImageDecodeError>>;
+
+    fn name() -> &'static str {
+        "arcana.compressed-image"
+    }
+
+    fn decode(bytes: Box<[u8]>, _loader: &Loader) -> Self::Fut {
+        ready(decode_compressed_image(&bytes))
+    }
+}
+
+impl<B> AssetBuild<B> for CompressedTexture
+where
+    B: BorrowMut<Graphics>,
+{
+    fn build(image: CompressedImage, builder: &mut B) -> Result<Self, UploadError> {
+        let graphics = builder.borrow_mut();
+        let image = texture_view_from_compressed_image(&image, graphics)?;
+
+        Ok(CompressedTexture {
+            texture: Texture {
+                image,
+                sampler: graphics.create_sampler(Default::default())?,
+                target: None,
+            },
+        })
+    }
+}
@@ -0,0 +1,222 @@
+use parking_lot::Mutex;
+use sierra::{
+    ivec2, Access, AspectFlags, Buffer, ComputePipeline, ComputePipelineInfo, ComputeShader,
+    Descriptors, DescriptorsAllocationError, Device, Encoder, Extent3, Format, Image, ImageCopy,
+    ImageInfo, ImageMemoryBarrier, ImageUsage, Layout, Offset3, OutOfMemory, PipelineInput,
+    PipelineStages, Samples::Samples1, ShaderModuleInfo, ShaderRepr, Subresource,
+};
+
+#[derive(ShaderRepr)]
+#[sierra(std140)]
+struct Constants {
+    offset: ivec2,
+    stride: u32,
+    channels: u32,
+}
+
+#[derive(Descriptors)]
+#[sierra(capacity = 32)]
+struct ExpandDescriptors {
+    #[sierra(buffer(storage), compute)]
+    pixels: Buffer,
+
+    #[sierra(image(storage, layout = General), compute)]
+    image: Image,
+}
+
+#[allow(unused)]
+#[derive(PipelineInput)]
+struct ExpandPipeline {
+    #[sierra(set)]
+    set: ExpandDescriptors,
+
+    #[sierra(push, compute)]
+    constants: Constants,
+}
+
+/// Size of channels of pixels expanded to RGBA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ChannelSize {
+    U8,
+    U16,
+}
+
+/// Expands pixels with one to three channels to RGBA.
+///
+/// One channel is luminance, two channels are luminance and alpha.
+/// Missing alpha is opaque.
+pub(super) struct ExpandUploader {
+    layout: ExpandPipelineLayout,
+    descriptors: Mutex<ExpandDescriptorsInstance>,
+    pipeline_u8: ComputePipeline,
+    pipeline_u16: ComputePipeline,
+}
+
+impl Drop for ExpandUploader {
+    fn drop(&mut self) {
+        self.descriptors.get_mut().clear();
+    }
+}
+
+impl ExpandUploader {
+    pub fn new(device: &Device) -> Result<Self, OutOfMemory> {
+        let module_u8 = device
+            .create_shader_module(ShaderModuleInfo::wgsl(
+                include_bytes!("expand8.wgsl").to_owned(),
+            ))
+            .unwrap();
+
+        let module_u16 = device
+            .create_shader_module(ShaderModuleInfo::wgsl(
+                include_bytes!("expand16.wgsl").to_owned(),
+            ))
+            .unwrap();
+
+        let layout = ExpandPipelineLayout::new(device)?;
+
+        let pipeline_u8 = device.create_compute_pipeline(ComputePipelineInfo {
+            shader: ComputeShader::new(module_u8, "main"),
+            layout: layout.raw().clone(),
+        })?;
+
+        let pipeline_u16 = device.create_compute_pipeline(ComputePipelineInfo {
+            shader: ComputeShader::new(module_u16, "main"),
+            layout: layout.raw().clone(),
+        })?;
+
+        let descriptors = layout.set.instance();
+
+        Ok(ExpandUploader {
+            layout,
+            descriptors: Mutex::new(descriptors),
+            pipeline_u8,
+            pipeline_u16,
+        })
+    }
+
+    /// Records expansion of pixels from `buffer` into `image` region.
+    ///
+    /// `buffer` must have `STORAGE` usage and size multiple of 4.
+    /// `image` must be in `TransferDstOptimal` layout.
+    #[allow(clippy::too_many_arguments)]
+    pub fn upload_synchronized<'a>(
+        &self,
+        device: &Device,
+        image: &Image,
+        offset: Offset3,
+        extent: Extent3,
+        buffer: Buffer,
+        row_length: u32,
+        channels: u32,
+        channel_size: ChannelSize,
+        encoder: &mut Encoder<'a>,
+    ) -> Result<(), OutOfMemory> {
+        assert_eq!(extent.depth, 1, "3D images unsupported yet");
+        assert_eq!(offset.z, 0);
+        debug_assert!((1..=3).contains(&channels));
+
+        let (format, pipeline) = match channel_size {
+            ChannelSize::U8 => (Format::RGBA8Uint, &self.pipeline_u8),
+            ChannelSize::U16 => (Format::RGBA16Uint, &self.pipeline_u16),
+        };
+
+        // Staging image of integer format is copied bit-exactly to any format of the same size.
+        let staging_image = device.create_image(ImageInfo {
+            extent: image.info().extent,
+            format,
+            levels: 1,
+            layers: 1,
+            samples: Samples1,
+            usage: ImageUsage::STORAGE | ImageUsage::TRANSFER_SRC,
+        })?;
+
+        let subresource = Subresource {
+            aspect: AspectFlags::COLOR,
+            level: 0,
+            layer: 0,
+        };
+
+        encoder.image_barriers(
+            PipelineStages::TOP_OF_PIPE,
+            PipelineStages::COMPUTE_SHADER,
+            &[ImageMemoryBarrier {
+                image: &staging_image,
+                old_layout: None,
+                new_layout: Layout::General,
+                old_access: Access::empty(),
+                new_access: Access::SHADER_STORAGE_WRITE,
+                family_transfer: None,
+                range: subresource.into(),
+            }],
+        );
+
+        encoder.bind_compute_pipeline(pipeline);
+
+        {
+            let mut descriptors = self.descriptors.lock();
+            let updated = descriptors
+                .update(
+                    &ExpandDescriptors {
+                        pixels: buffer,
+                        image: staging_image.clone(),
+                    },
+                    device,
+                    encoder,
+                )
+                .map_err(|err| match err {
+                    DescriptorsAllocationError::OutOfMemory { source } => source,
+                    _ => {
+                        tracing::error!("Unexpected error: {}", err);
+                        OutOfMemory
+                    }
+                })?;
+
+            encoder.bind_compute_descriptors(&self.layout, updated);
+        }
+
+        let stride = match row_length {
+            0 => extent.width,
+            row_length => row_length,
+        };
+
+        encoder.push_constants(
+            &self.layout,
+            &Constants {
+                offset: ivec2::from([offset.x, offset.y]),
+                stride,
+                channels,
+            },
+        );
+        encoder.dispatch(extent.width, extent.height, extent.depth);
+
+        encoder.image_barriers(
+            PipelineStages::COMPUTE_SHADER,
+            PipelineStages::TRANSFER,
+            &[ImageMemoryBarrier {
+                image: &staging_image,
+                old_layout: Some(Layout::General),
+                new_layout: Layout::TransferSrcOptimal,
+                old_access: Access::SHADER_STORAGE_WRITE,
+                new_access: Access::TRANSFER_READ,
+                family_transfer: None,
+                range: subresource.into(),
+            }],
+        );
+
+        encoder.copy_image(
+            &staging_image,
+            Layout::TransferSrcOptimal,
+            image,
+            Layout::TransferDstOptimal,
+            &[ImageCopy {
+                src_subresource: subresource.into(),
+                src_offset: offset,
+                dst_subresource: subresource.into(),
+                dst_offset: offset,
+                extent,
+            }],
+        );
+
+        Ok(())
+    }
+}
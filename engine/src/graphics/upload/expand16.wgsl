@@ -0,0 +1,39 @@
+struct Pixels {
+    data: array<u32>;
+};
+
+struct Constants {
+    offset: vec2<i32>;
+    stride: u32;
+    channels: u32;
+};
+
+[[group(0), binding(0)]]
+var<storage, read> pixels: Pixels;
+
+[[group(0), binding(1)]]
+var image: texture_storage_2d<rgba16uint, write>;
+
+var<push_constant> constants: Constants;
+
+fn channel(index: u32) -> u32 {
+    return (pixels.data[index / 2u] >> ((index % 2u) * 16u)) & 0xffffu;
+}
+
+[[stage(compute), workgroup_size(1)]]
+fn main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    let index = (id.x + id.y * constants.stride) * constants.channels;
+
+    var color: vec4<u32>;
+    if (constants.channels == 1u) {
+        let l = channel(index);
+        color = vec4<u32>(l, l, l, 65535u);
+    } else if (constants.channels == 2u) {
+        let l = channel(index);
+        color = vec4<u32>(l, l, l, channel(index + 1u));
+    } else {
+        color = vec4<u32>(channel(index), channel(index + 1u), channel(index + 2u), 65535u);
+    }
+
+    textureStore(image, vec2<i32>(id.xy) + constants.offset, color);
+}
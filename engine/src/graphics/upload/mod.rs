@@ -4,19 +4,127 @@ use bytemuck::Pod;
 use scoped_arena::Scope;
 use sierra::{
     Access, Buffer, BufferCopy, BufferImageCopy, BufferInfo, BufferUsage, Device, Encoder, Extent3,
-    Format, Image, ImageMemoryBarrier, Layout, Offset3, OutOfMemory, PipelineStages, Queue,
-    SubresourceLayers,
+    Fence, Filter, Format, FormatFeatures, Image, ImageBlit, ImageMemoryBarrier, Layout, Offset3,
+    OutOfMemory, PipelineStages, Queue, SubresourceLayers,
 };
 
-use super::UploadImage;
+use self::expand::ChannelSize;
+use super::{GraphicsError, UploadImage};
 
+mod expand;
 mod rgb2rgba;
 
+/// Error that may occur when image is uploaded.
+#[derive(Debug, thiserror::Error)]
+pub enum UploadError {
+    #[error(transparent)]
+    OutOfMemory(#[from] OutOfMemory),
+
+    /// Mip levels are generated with linear blits
+    /// that are not guaranteed to be supported for this format.
+    #[error("Mip levels generation is unsupported for format '{0:?}'")]
+    MipsUnsupported(Format),
+
+    /// Mip levels are generated from whole level,
+    /// so upload must cover it entirely.
+    #[error("Mip levels generation requires upload of whole level")]
+    MipsPartialUpload,
+
+    /// Pixels can't be converted to image format.
+    #[error("Uploading from '{from:?}' to '{to:?}' is unsupported")]
+    UnsupportedConversion { from: Format, to: Format },
+
+    /// Device lacks feature required for image format.
+    #[error("Format '{0:?}' is unsupported by device")]
+    UnsupportedFormat(Format),
+}
+
+/// Data of deferred uploads packed to be copied from single staging buffer.
+///
+/// Each chunk is placed at offset aligned to [`StagingArena::ALIGN`].
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{bytemuck, graphics::StagingArena};
+/// let mut arena = StagingArena::new();
+///
+/// let chunks: Vec<Vec<u32>> = (0..100u32).map(|i| vec![i; 1 + i as usize % 7]).collect();
+/// let offsets: Vec<u64> = chunks.iter().map(|c| arena.push(c).unwrap()).collect();
+///
+/// let mut end = 0;
+/// for (chunk, &offset) in chunks.iter().zip(&offsets) {
+///     let offset = offset as usize;
+///     assert_eq!(offset % StagingArena::ALIGN, 0);
+///     assert!(offset >= end);
+///
+///     end = offset + chunk.len() * 4;
+///     assert_eq!(&arena.as_bytes()[offset..end], bytemuck::cast_slice(chunk));
+/// }
+/// assert_eq!(arena.len(), end);
+///
+/// arena.clear();
+/// assert!(arena.is_empty());
+/// ```
+#[derive(Default)]
+pub struct StagingArena {
+    data: Vec<u8>,
+}
+
+impl StagingArena {
+    /// Alignment of data packed into shared staging buffer.
+    /// Multiple of 4 and of every texel block size up to 16 bytes
+    /// as required for buffer to image copies.
+    pub const ALIGN: usize = 48;
+
+    pub fn new() -> Self {
+        StagingArena { data: Vec::new() }
+    }
+
+    /// Appends data to the arena and returns its offset.
+    pub fn push<T>(&mut self, data: &[T]) -> Result<u64, OutOfMemory>
+    where
+        T: Pod,
+    {
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+
+        let offset = (self.data.len() + Self::ALIGN - 1) / Self::ALIGN * Self::ALIGN;
+        self.data.resize(offset, 0);
+        self.data.extend_from_slice(bytes);
+
+        u64::try_from(offset).map_err(|_| OutOfMemory)
+    }
+
+    /// Returns size of packed data in bytes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Removes all data, keeping allocated memory for reuse.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns packed data.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
+
 pub struct Uploader {
     buffer_uploads: Vec<BufferUpload>,
     image_uploads: Vec<ImageUpload>,
 
+    /// Data of deferred uploads that is copied from single staging buffer.
+    /// Reused between flushes.
+    staging: StagingArena,
+
     rgb2rgba: rgb2rgba::Rgb2RgbaUploader,
+    expand: expand::ExpandUploader,
 }
 
 impl Uploader {
@@ -25,13 +133,16 @@ impl Uploader {
             buffer_uploads: Vec::new(),
             image_uploads: Vec::new(),
 
+            staging: StagingArena::new(),
+
             rgb2rgba: rgb2rgba::Rgb2RgbaUploader::new(device)?,
+            expand: expand::ExpandUploader::new(device)?,
         })
     }
 
     pub fn upload_buffer<T>(
         &mut self,
-        device: &Device,
+        _device: &Device,
         buffer: &Buffer,
         offset: u64,
         data: &[T],
@@ -43,17 +154,11 @@ impl Uploader {
             return Ok(());
         }
 
-        let staging = device.create_buffer_static(
-            BufferInfo {
-                align: 15,
-                size: size_of_val(data) as u64,
-                usage: BufferUsage::TRANSFER_SRC,
-            },
-            data,
-        )?;
+        let staging_offset = self.staging.push(data)?;
 
         self.buffer_uploads.push(BufferUpload {
-            staging,
+            staging_offset,
+            size: size_of_val(data) as u64,
             buffer: buffer.clone(),
             offset,
             old_access: Access::all(),
@@ -117,24 +222,20 @@ impl Uploader {
         device: &Device,
         upload: UploadImage,
         data: &[T],
-    ) -> Result<(), OutOfMemory>
+    ) -> Result<(), UploadError>
     where
         T: Pod,
     {
-        let staging_usage = if upload.format == upload.image.info().format {
-            BufferUsage::TRANSFER_SRC
-        } else {
-            BufferUsage::UNIFORM_TEXEL
-        };
+        let conversion = Conversion::new(upload.format, upload.image.info().format)?;
+        let generate_mips = check_generate_mips(device, &upload)?;
 
-        let staging = device.create_buffer_static(
-            BufferInfo {
-                align: 15,
-                size: u64::try_from(size_of_val(data)).map_err(|_| OutOfMemory)?,
-                usage: staging_usage,
+        // Converted pixels are bound to descriptors as whole buffer.
+        let staging = match conversion {
+            Conversion::Copy => Staging::Packed {
+                offset: self.staging.push(data)?,
             },
-            data,
-        )?;
+            _ => Staging::Buffer(create_staging(device, data, conversion)?),
+        };
 
         self.image_uploads.push(ImageUpload {
             image: upload.image.clone(),
@@ -146,9 +247,10 @@ impl Uploader {
             old_access: upload.old_access,
             new_access: upload.new_access,
             staging,
-            format: upload.format,
+            conversion,
             row_length: upload.row_length,
             image_height: upload.image_height,
+            generate_mips,
         });
 
         Ok(())
@@ -160,24 +262,13 @@ impl Uploader {
         upload: UploadImage,
         data: &[T],
         encoder: &mut Encoder<'a>,
-    ) -> Result<(), OutOfMemory>
+    ) -> Result<(), UploadError>
     where
         T: Pod,
     {
-        let staging_usage = if upload.format == upload.image.info().format {
-            BufferUsage::TRANSFER_SRC
-        } else {
-            BufferUsage::UNIFORM_TEXEL
-        };
-
-        let staging = device.create_buffer_static(
-            BufferInfo {
-                align: 15,
-                size: u64::try_from(size_of_val(data)).map_err(|_| OutOfMemory)?,
-                usage: staging_usage,
-            },
-            data,
-        )?;
+        let conversion = Conversion::new(upload.format, upload.image.info().format)?;
+        let generate_mips = check_generate_mips(device, &upload)?;
+        let staging = create_staging(device, data, conversion)?;
 
         encoder.image_barriers(
             PipelineStages::TOP_OF_PIPE,
@@ -193,79 +284,151 @@ impl Uploader {
             }],
         );
 
-        match (upload.format, upload.image.info().format) {
-            (from, to) if from == to => encoder.copy_buffer_to_image(
-                &staging,
+        self.record_upload(
+            device,
+            conversion,
+            upload.image,
+            upload.offset,
+            upload.extent,
+            upload.layers,
+            staging,
+            0,
+            upload.row_length,
+            upload.image_height,
+            encoder,
+        )?;
+
+        if generate_mips {
+            generate_mips_synchronized(
                 upload.image,
+                upload.layers,
+                upload.new_layout,
+                upload.new_access,
+                encoder,
+            );
+        } else {
+            encoder.image_barriers(
+                PipelineStages::TRANSFER,
+                PipelineStages::ALL_COMMANDS,
+                &[ImageMemoryBarrier {
+                    image: upload.image,
+                    old_layout: Some(Layout::TransferDstOptimal),
+                    new_layout: upload.new_layout,
+                    old_access: Access::TRANSFER_WRITE,
+                    new_access: upload.new_access,
+                    family_transfer: None,
+                    range: upload.layers.into(),
+                }],
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Records commands to fill image region from staging buffer.
+    /// Image must be in `TransferDstOptimal` layout.
+    /// Staging offset must be zero unless formats match.
+    #[allow(clippy::too_many_arguments)]
+    fn record_upload(
+        &self,
+        device: &Device,
+        conversion: Conversion,
+        image: &Image,
+        offset: Offset3,
+        extent: Extent3,
+        layers: SubresourceLayers,
+        staging: Buffer,
+        staging_offset: u64,
+        row_length: u32,
+        image_height: u32,
+        encoder: &mut Encoder<'_>,
+    ) -> Result<(), OutOfMemory> {
+        debug_assert!(matches!(conversion, Conversion::Copy) || staging_offset == 0);
+
+        match conversion {
+            Conversion::Copy => encoder.copy_buffer_to_image(
+                &staging,
+                image,
                 Layout::TransferDstOptimal,
                 &[BufferImageCopy {
-                    buffer_offset: 0,
-                    buffer_row_length: upload.row_length,
-                    buffer_image_height: upload.image_height,
-                    image_subresource: upload.layers,
-                    image_offset: upload.offset,
-                    image_extent: upload.extent,
+                    buffer_offset: staging_offset,
+                    buffer_row_length: row_length,
+                    buffer_image_height: image_height,
+                    image_subresource: layers,
+                    image_offset: offset,
+                    image_extent: extent,
                 }],
             ),
-            (Format::RGB8Unorm, Format::RGBA8Unorm) => {
+            Conversion::Rgb2Rgba => {
                 self.rgb2rgba.upload_synchronized(
                     device,
-                    upload.image,
-                    upload.offset,
-                    upload.extent,
-                    staging.clone(),
-                    upload.row_length,
-                    upload.image_height,
+                    image,
+                    offset,
+                    extent,
+                    staging,
+                    row_length,
+                    image_height,
                     encoder,
                 )?;
             }
-            (Format::RGB8Srgb, Format::RGBA8Srgb) => {
-                self.rgb2rgba.upload_synchronized(
+            Conversion::Expand {
+                channels,
+                channel_size,
+            } => {
+                self.expand.upload_synchronized(
                     device,
-                    upload.image,
-                    upload.offset,
-                    upload.extent,
-                    staging.clone(),
-                    upload.row_length,
-                    upload.image_height,
+                    image,
+                    offset,
+                    extent,
+                    staging,
+                    row_length,
+                    channels,
+                    channel_size,
                     encoder,
                 )?;
             }
-            (from, to) => {
-                panic!("Uploading from '{:?}' to '{:?}' is unimplemented", from, to)
-            }
         }
 
-        encoder.image_barriers(
-            PipelineStages::TRANSFER,
-            PipelineStages::ALL_COMMANDS,
-            &[ImageMemoryBarrier {
-                image: upload.image,
-                old_layout: Some(Layout::TransferDstOptimal),
-                new_layout: upload.new_layout,
-                old_access: Access::TRANSFER_WRITE,
-                new_access: upload.new_access,
-                family_transfer: None,
-                range: upload.layers.into(),
-            }],
-        );
-
         Ok(())
     }
 
+    /// Submits all deferred uploads.
+    /// Returns staging buffers read by submitted commands.
+    ///
+    /// If `fence` is provided it is signaled when uploads are complete.
     pub fn flush_uploads(
         &mut self,
         device: &Device,
         queue: &mut Queue,
+        fence: Option<&mut Fence>,
         scope: &Scope<'_>,
-    ) -> Result<(), OutOfMemory> {
+    ) -> Result<Vec<Buffer>, GraphicsError> {
         if self.buffer_uploads.is_empty() && self.image_uploads.is_empty() {
-            return Ok(());
+            if fence.is_some() {
+                queue.try_submit(&mut [], None, &mut [], fence, scope)?;
+            }
+            return Ok(Vec::new());
         }
 
+        // Single staging buffer for all uploads without conversion.
+        let packed = if self.staging.is_empty() {
+            None
+        } else {
+            Some(device.create_buffer_static(
+                BufferInfo {
+                    align: 15,
+                    size: u64::try_from(self.staging.len()).map_err(|_| OutOfMemory)?,
+                    usage: BufferUsage::TRANSFER_SRC,
+                },
+                self.staging.as_bytes(),
+            )?)
+        };
+
         let mut encoder = queue.create_encoder(scope)?;
 
         if !self.buffer_uploads.is_empty() {
+            let packed = packed.as_ref().expect("Buffer uploads are packed");
+
             tracing::debug!("Uploading buffers");
 
             let mut old_access = Access::empty();
@@ -285,12 +448,12 @@ impl Uploader {
 
             for upload in &self.buffer_uploads {
                 encoder.copy_buffer(
-                    &upload.staging,
+                    packed,
                     &upload.buffer,
                     &[BufferCopy {
-                        src_offset: 0,
+                        src_offset: upload.staging_offset,
                         dst_offset: upload.offset,
-                        size: upload.staging.info().size,
+                        size: upload.size,
                     }],
                 );
             }
@@ -329,53 +492,45 @@ impl Uploader {
             );
 
             for upload in &self.image_uploads {
-                match (upload.format, upload.image.info().format) {
-                    (from, to) if from == to => encoder.copy_buffer_to_image(
-                        &upload.staging,
-                        &upload.image,
-                        Layout::TransferDstOptimal,
-                        &[BufferImageCopy {
-                            buffer_offset: 0,
-                            buffer_row_length: upload.row_length,
-                            buffer_image_height: upload.image_height,
-                            image_subresource: upload.layers,
-                            image_offset: upload.offset,
-                            image_extent: upload.extent,
-                        }],
-                    ),
-                    (Format::RGB8Unorm, Format::RGBA8Unorm) => {
-                        self.rgb2rgba.upload_synchronized(
-                            device,
-                            &upload.image,
-                            Offset3::zeros(),
-                            upload.image.info().extent.into_3d(),
-                            upload.staging.clone(),
-                            upload.row_length,
-                            upload.image_height,
-                            &mut encoder,
-                        )?;
-                    }
-                    (Format::RGB8Srgb, Format::RGBA8Srgb) => {
-                        self.rgb2rgba.upload_synchronized(
-                            device,
-                            &upload.image,
-                            Offset3::zeros(),
-                            upload.image.info().extent.into_3d(),
-                            upload.staging.clone(),
-                            upload.row_length,
-                            upload.image_height,
-                            &mut encoder,
-                        )?;
-                    }
-                    (from, to) => {
-                        panic!("Uploading from '{:?}' to '{:?}' is unimplemented", from, to)
+                let (staging, staging_offset) = match &upload.staging {
+                    Staging::Packed { offset } => {
+                        (packed.clone().expect("Image upload is packed"), *offset)
                     }
+                    Staging::Buffer(buffer) => (buffer.clone(), 0),
+                };
+
+                self.record_upload(
+                    device,
+                    upload.conversion,
+                    &upload.image,
+                    upload.offset,
+                    upload.extent,
+                    upload.layers,
+                    staging,
+                    staging_offset,
+                    upload.row_length,
+                    upload.image_height,
+                    &mut encoder,
+                )?;
+
+                if upload.generate_mips {
+                    generate_mips_synchronized(
+                        &upload.image,
+                        upload.layers,
+                        upload.new_layout,
+                        upload.new_access,
+                        &mut encoder,
+                    );
                 }
             }
 
             let mut images = Vec::with_capacity_in(images_len, scope);
 
-            for upload in &self.image_uploads {
+            for upload in self
+                .image_uploads
+                .iter()
+                .filter(|upload| !upload.generate_mips)
+            {
                 images.push(ImageMemoryBarrier {
                     image: &upload.image,
                     old_layout: Some(Layout::TransferDstOptimal),
@@ -394,16 +549,97 @@ impl Uploader {
             );
         }
 
-        queue.submit(&mut [], Some(encoder.finish()), &mut [], None, scope);
+        queue.try_submit(&mut [], Some(encoder.finish()), &mut [], fence, scope)?;
+
+        let staging = packed
+            .into_iter()
+            .chain(
+                self.image_uploads
+                    .iter()
+                    .filter_map(|upload| match &upload.staging {
+                        Staging::Packed { .. } => None,
+                        Staging::Buffer(buffer) => Some(buffer.clone()),
+                    }),
+            )
+            .collect();
 
         self.buffer_uploads.clear();
         self.image_uploads.clear();
+        self.staging.clear();
+        Ok(staging)
+    }
+}
+
+/// Signal of completion of uploads submitted with
+/// [`Graphics::flush_uploads_with_fence`](super::Graphics::flush_uploads_with_fence).
+///
+/// Owns staging buffers of the uploads until they are complete.
+/// Source data is copied into staging buffers when upload is requested,
+/// so caller may reuse it right away,
+/// while staging memory is recycled only after this fence is waited or dropped.
+/// Dropping unwaited fence blocks until uploads are complete.
+pub struct UploadFence {
+    device: Device,
+    fence: Fence,
+    staging: Vec<Buffer>,
+    complete: bool,
+}
+
+impl UploadFence {
+    pub(super) fn new(device: Device, fence: Fence, staging: Vec<Buffer>) -> Self {
+        UploadFence {
+            device,
+            fence,
+            staging,
+            complete: false,
+        }
+    }
+
+    /// Blocks until uploads are complete and releases staging buffers.
+    ///
+    /// Fails with [`GraphicsError::DeviceLost`] if device was lost.
+    /// Uploads never complete then, and staging buffers are released right away.
+    pub fn wait(&mut self) -> Result<(), GraphicsError> {
+        if self.complete {
+            return Ok(());
+        }
+
+        if let Err(err) = self.device.try_wait_fences(&mut [&mut self.fence], true) {
+            let err = GraphicsError::from(err);
+            if let GraphicsError::DeviceLost = err {
+                self.staging.clear();
+                self.complete = true;
+            }
+            return Err(err);
+        }
+
+        self.staging.clear();
+        self.complete = true;
         Ok(())
     }
+
+    /// Returns `true` if uploads were waited for.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Returns number of staging buffers held until uploads are complete.
+    pub fn staging_buffers(&self) -> usize {
+        self.staging.len()
+    }
+}
+
+impl Drop for UploadFence {
+    fn drop(&mut self) {
+        // Errors can't be reported from drop.
+        // Staging buffers are released on device loss anyway.
+        let _ = self.wait();
+    }
 }
 
 struct BufferUpload {
-    staging: Buffer,
+    staging_offset: u64,
+    size: u64,
     buffer: Buffer,
     offset: u64,
     old_access: Access,
@@ -419,8 +655,349 @@ struct ImageUpload {
     new_layout: Layout,
     old_access: Access,
     new_access: Access,
-    staging: Buffer,
-    format: Format,
+    staging: Staging,
+    conversion: Conversion,
     row_length: u32,
     image_height: u32,
+    generate_mips: bool,
+}
+
+/// Source of deferred image upload.
+enum Staging {
+    /// Data at offset in shared staging buffer.
+    Packed { offset: u64 },
+
+    /// Dedicated staging buffer.
+    Buffer(Buffer),
+}
+
+/// Conversion of uploaded pixels to image format.
+#[derive(Clone, Copy, Debug)]
+enum Conversion {
+    /// Formats match.
+    Copy,
+
+    /// RGB is padded with opaque alpha.
+    Rgb2Rgba,
+
+    /// Luminance or luminance with alpha are expanded to RGBA.
+    Expand {
+        channels: u32,
+        channel_size: ChannelSize,
+    },
+}
+
+impl Conversion {
+    fn new(from: Format, to: Format) -> Result<Self, UploadError> {
+        use Format::*;
+
+        let expand = |channels, channel_size| Conversion::Expand {
+            channels,
+            channel_size,
+        };
+
+        let conversion = match (from, to) {
+            (from, to) if from == to => Conversion::Copy,
+            (RGB8Unorm, RGBA8Unorm) | (RGB8Srgb, RGBA8Srgb) => Conversion::Rgb2Rgba,
+            (R8Unorm, RGBA8Unorm) | (R8Srgb, RGBA8Srgb) => expand(1, ChannelSize::U8),
+            (RG8Unorm, RGBA8Unorm) | (RG8Srgb, RGBA8Srgb) => expand(2, ChannelSize::U8),
+            (R16Unorm, RGBA16Unorm) => expand(1, ChannelSize::U16),
+            (RG16Unorm, RGBA16Unorm) => expand(2, ChannelSize::U16),
+            (RGB16Unorm, RGBA16Unorm) => expand(3, ChannelSize::U16),
+            (from, to) => return Err(UploadError::UnsupportedConversion { from, to }),
+        };
+
+        Ok(conversion)
+    }
+}
+
+fn create_staging<T>(
+    device: &Device,
+    data: &[T],
+    conversion: Conversion,
+) -> Result<Buffer, OutOfMemory>
+where
+    T: Pod,
+{
+    let usage = match conversion {
+        Conversion::Copy => BufferUsage::TRANSFER_SRC,
+        Conversion::Rgb2Rgba => BufferUsage::UNIFORM_TEXEL,
+        Conversion::Expand { .. } => BufferUsage::STORAGE,
+    };
+
+    let create = |bytes: &[u8]| {
+        device.create_buffer_static(
+            BufferInfo {
+                align: 15,
+                size: u64::try_from(bytes.len()).map_err(|_| OutOfMemory)?,
+                usage,
+            },
+            bytes,
+        )
+    };
+
+    let bytes: &[u8] = bytemuck::cast_slice(data);
+
+    match conversion {
+        // Storage buffer is read by 4 bytes.
+        Conversion::Expand { .. } if bytes.len() % 4 != 0 => {
+            let mut padded = bytes.to_vec();
+            padded.resize((bytes.len() + 3) & !3, 0);
+            create(&padded)
+        }
+        _ => create(bytes),
+    }
+}
+
+/// Returns `true` if mip levels should be generated for the upload.
+/// Returns error if they can't be.
+fn check_generate_mips(device: &Device, upload: &UploadImage) -> Result<bool, UploadError> {
+    let info = upload.image.info();
+
+    if !upload.generate_mips || upload.layers.level + 1 >= info.levels {
+        return Ok(false);
+    }
+
+    // Levels are downsampled whole, partially uploaded level would leave them stale.
+    let extent = level_extent(info.extent.into_3d(), upload.layers.level);
+    if upload.offset != Offset3::zeros() || upload.extent != extent {
+        return Err(UploadError::MipsPartialUpload);
+    }
+
+    let properties = device.format_properties(info.format);
+    check_mips_format(info.format, properties.optimal_tiling_features)?;
+    Ok(true)
+}
+
+/// Checks that mip levels of image with specified format can be generated on upload
+/// given format features reported by device for optimal tiling.
+///
+/// Mip levels are generated with linear blits,
+/// so format must support `BLIT_SRC`, `BLIT_DST` and `SAMPLED_IMAGE_FILTER_LINEAR`.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{graphics::{check_mips_format, UploadError}, sierra::{Format, FormatFeatures}};
+/// let blit = FormatFeatures::BLIT_SRC
+///     | FormatFeatures::BLIT_DST
+///     | FormatFeatures::SAMPLED_IMAGE_FILTER_LINEAR;
+/// assert!(check_mips_format(Format::RGBA8Srgb, blit).is_ok());
+///
+/// let err = check_mips_format(Format::BC7Unorm, FormatFeatures::SAMPLED_IMAGE).unwrap_err();
+/// assert!(matches!(err, UploadError::MipsUnsupported(Format::BC7Unorm)));
+/// ```
+pub fn check_mips_format(format: Format, features: FormatFeatures) -> Result<(), UploadError> {
+    let required = FormatFeatures::BLIT_SRC
+        | FormatFeatures::BLIT_DST
+        | FormatFeatures::SAMPLED_IMAGE_FILTER_LINEAR;
+
+    if !features.contains(required) {
+        return Err(UploadError::MipsUnsupported(format));
+    }
+
+    Ok(())
+}
+
+/// Step recorded when mip levels are generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MipStep {
+    /// Mip level is transitioned from `old_layout` into `new_layout`.
+    Transition {
+        level: u32,
+        old_layout: Option<Layout>,
+        new_layout: Layout,
+    },
+
+    /// Mip level is filled by linear blit from previous level.
+    Blit {
+        level: u32,
+        src_end: Offset3,
+        dst_end: Offset3,
+    },
+}
+
+/// Returns steps that fill mip levels after `base` by downsampling each level into the next one
+/// and transition all filled levels into `new_layout`.
+///
+/// Level `base` must be in `TransferDstOptimal` layout.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{graphics::{mip_steps, MipStep}, sierra::{Extent3, Layout}};
+/// let extent = Extent3 {
+///     width: 256,
+///     height: 256,
+///     depth: 1,
+/// };
+/// let steps = mip_steps(extent, 0, 9, Layout::ShaderReadOnlyOptimal);
+///
+/// for level in 0..9 {
+///     let layouts: Vec<_> = steps
+///         .iter()
+///         .filter_map(|step| match *step {
+///             MipStep::Transition { level: l, old_layout, new_layout } if l == level => {
+///                 Some((old_layout, new_layout))
+///             }
+///             _ => None,
+///         })
+///         .collect();
+///
+///     let mut expected = vec![];
+///     if level > 0 {
+///         expected.push((None, Layout::TransferDstOptimal));
+///     }
+///     if level < 8 {
+///         expected.push((Some(Layout::TransferDstOptimal), Layout::TransferSrcOptimal));
+///         expected.push((Some(Layout::TransferSrcOptimal), Layout::ShaderReadOnlyOptimal));
+///     } else {
+///         expected.push((Some(Layout::TransferDstOptimal), Layout::ShaderReadOnlyOptimal));
+///     }
+///     assert_eq!(layouts, expected, "level {}", level);
+/// }
+///
+/// let blits: Vec<_> = steps
+///     .iter()
+///     .filter_map(|step| match *step {
+///         MipStep::Blit { level, dst_end, .. } => Some((level, dst_end.x, dst_end.y)),
+///         _ => None,
+///     })
+///     .collect();
+///
+/// let expected: Vec<_> = (1..9).map(|level| (level, 256 >> level, 256 >> level)).collect();
+/// assert_eq!(blits, expected);
+/// ```
+pub fn mip_steps(extent: Extent3, base: u32, levels: u32, new_layout: Layout) -> Vec<MipStep> {
+    let last = levels - 1;
+
+    let level_end = |level: u32| {
+        let extent = level_extent(extent, level);
+        Offset3::new(
+            extent.width as i32,
+            extent.height as i32,
+            extent.depth as i32,
+        )
+    };
+
+    let mut steps = Vec::with_capacity((last - base) as usize * 4 + 1);
+
+    for level in base + 1..=last {
+        steps.push(MipStep::Transition {
+            level: level - 1,
+            old_layout: Some(Layout::TransferDstOptimal),
+            new_layout: Layout::TransferSrcOptimal,
+        });
+        steps.push(MipStep::Transition {
+            level,
+            old_layout: None,
+            new_layout: Layout::TransferDstOptimal,
+        });
+        steps.push(MipStep::Blit {
+            level,
+            src_end: level_end(level - 1),
+            dst_end: level_end(level),
+        });
+    }
+
+    // All levels but the last one were blit sources.
+    for level in base..last {
+        steps.push(MipStep::Transition {
+            level,
+            old_layout: Some(Layout::TransferSrcOptimal),
+            new_layout,
+        });
+    }
+
+    steps.push(MipStep::Transition {
+        level: last,
+        old_layout: Some(Layout::TransferDstOptimal),
+        new_layout,
+    });
+
+    steps
+}
+
+fn level_extent(extent: Extent3, level: u32) -> Extent3 {
+    Extent3 {
+        width: (extent.width >> level).max(1),
+        height: (extent.height >> level).max(1),
+        depth: (extent.depth >> level).max(1),
+    }
+}
+
+/// Records [`mip_steps`] for the uploaded level.
+///
+/// Level `layers.level` must be in `TransferDstOptimal` layout after transfer write.
+fn generate_mips_synchronized(
+    image: &Image,
+    layers: SubresourceLayers,
+    new_layout: Layout,
+    new_access: Access,
+    encoder: &mut Encoder<'_>,
+) {
+    let info = image.info();
+
+    let access = |layout: Option<Layout>| match layout {
+        None => Access::empty(),
+        Some(Layout::TransferDstOptimal) => Access::TRANSFER_WRITE,
+        Some(Layout::TransferSrcOptimal) => Access::TRANSFER_READ,
+        Some(_) => new_access,
+    };
+
+    let mut barriers = Vec::new();
+
+    let steps = mip_steps(info.extent.into_3d(), layers.level, info.levels, new_layout);
+    for step in steps {
+        match step {
+            MipStep::Transition {
+                level,
+                old_layout,
+                new_layout,
+            } => barriers.push(ImageMemoryBarrier {
+                image,
+                old_layout,
+                new_layout,
+                old_access: access(old_layout),
+                new_access: access(Some(new_layout)),
+                family_transfer: None,
+                range: SubresourceLayers { level, ..layers }.into(),
+            }),
+            MipStep::Blit {
+                level,
+                src_end,
+                dst_end,
+            } => {
+                encoder.image_barriers(
+                    PipelineStages::TRANSFER,
+                    PipelineStages::TRANSFER,
+                    &barriers,
+                );
+                barriers.clear();
+
+                encoder.blit_image(
+                    image,
+                    Layout::TransferSrcOptimal,
+                    image,
+                    Layout::TransferDstOptimal,
+                    &[ImageBlit {
+                        src_subresource: SubresourceLayers {
+                            level: level - 1,
+                            ..layers
+                        },
+                        src_offsets: [Offset3::zeros(), src_end],
+                        dst_subresource: SubresourceLayers { level, ..layers },
+                        dst_offsets: [Offset3::zeros(), dst_end],
+                    }],
+                    Filter::Linear,
+                );
+            }
+        }
+    }
+
+    encoder.image_barriers(
+        PipelineStages::TRANSFER,
+        PipelineStages::ALL_COMMANDS,
+        &barriers,
+    );
 }
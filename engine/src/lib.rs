@@ -13,7 +13,9 @@
 
 extern crate self as arcana;
 
+pub mod anim;
 pub mod assets;
+pub mod blueprint;
 pub mod camera;
 pub mod cfg;
 pub mod clocks;
@@ -24,15 +26,21 @@ pub mod fps;
 pub mod game;
 pub mod lifespan;
 mod noophash;
+pub mod predict;
+pub mod prefab;
 pub mod prelude;
 pub mod rect;
+pub mod rng;
 pub mod scoped_allocator;
+pub mod snapshot;
 pub mod system;
 pub mod task;
+pub mod text;
+pub mod tween;
 // pub mod unfold;
 
 // Reexport crates used in public API.
-pub use {bincode, bytemuck, edict, eyre, na, palette, scoped_arena, tracing};
+pub use {bincode, bytemuck, edict, eyre, na, palette, rand, scoped_arena, tracing};
 
 cfg_if::cfg_if! {
     if #[cfg(all(feature = "with-egui", feature = "graphics"))] {
@@ -56,6 +64,12 @@ cfg_if::cfg_if! {
     }
 }
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "gamepad")] {
+        pub mod gamepad;
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "graphics")] {
         pub use sierra;
@@ -69,6 +83,12 @@ cfg_if::cfg_if! {
     }
 }
 
+cfg_if::cfg_if! {
+    if #[cfg(all(feature = "graphics", any(feature = "2d", feature = "3d")))] {
+        pub mod pick;
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "sigils")] {
         pub use sigils;
@@ -81,6 +101,12 @@ cfg_if::cfg_if! {
     }
 }
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "net-stats")] {
+        pub mod net_stats;
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "evoke")] {
         pub use evoke;
@@ -88,7 +114,7 @@ cfg_if::cfg_if! {
 }
 
 pub use arcana_proc::timespan;
-pub use arcana_time::{TimeSpan, TimeSpanParseErr, TimeStamp};
+pub use arcana_time::{SignedTimeSpan, TimeSpan, TimeSpanParseErr, TimeStamp};
 
 /// Installs default eyre handler.
 pub fn install_eyre_handler() {
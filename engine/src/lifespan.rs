@@ -1,4 +1,6 @@
-use edict::{prelude::ActionEncoder, query::Entities, system::Res, world::QueryRef};
+use edict::{
+    component::Component, prelude::ActionEncoder, query::Entities, system::Res, world::QueryRef,
+};
 
 use crate::{
     clocks::{ClockIndex, TimeSpan},
@@ -6,22 +8,119 @@ use crate::{
 };
 
 /// Component for entities with limited lifespan.
-#[repr(transparent)]
+///
+/// Entity is despawned by [`lifetime_system`] when lifespan runs out.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{game::Game, lifespan::{lifetime_system, LifeSpan}, TimeSpan};
+/// let mut game = Game::deterministic(0);
+/// game.scheduler.add_system(lifetime_system);
+///
+/// let mut lifespan = LifeSpan::new(TimeSpan::SECOND);
+/// lifespan.extend(TimeSpan::SECOND);
+/// assert_eq!(lifespan.remaining(), TimeSpan::SECOND * 2);
+///
+/// let e = game.world.spawn((lifespan,));
+///
+/// game.step(TimeSpan::SECOND);
+/// let lifespan = game.world.query_one_mut::<&mut LifeSpan>(&e).unwrap();
+/// assert_eq!(lifespan.remaining(), TimeSpan::SECOND);
+///
+/// // Paused lifespan doesn't decrease.
+/// lifespan.pause();
+/// game.step(TimeSpan::SECOND * 5);
+/// let lifespan = game.world.query_one_mut::<&mut LifeSpan>(&e).unwrap();
+/// assert_eq!(lifespan.remaining(), TimeSpan::SECOND);
+///
+/// lifespan.resume();
+/// game.step(TimeSpan::SECOND);
+/// assert!(game.world.query_one_mut::<&LifeSpan>(&e).is_err());
+/// ```
+#[derive(Component)]
 pub struct LifeSpan {
     pub left: TimeSpan,
+    paused: bool,
+    mark_expired: bool,
+    expired: bool,
 }
 
 impl LifeSpan {
     pub fn new(span: TimeSpan) -> Self {
-        LifeSpan { left: span }
+        LifeSpan {
+            left: span,
+            paused: false,
+            mark_expired: false,
+            expired: false,
+        }
+    }
+
+    /// Makes entity receive [`LifeSpanExpired`] marker when lifespan runs out.
+    /// Entity is despawned on the next run of [`lifetime_system`].
+    pub fn with_expired_marker(mut self) -> Self {
+        self.mark_expired = true;
+        self
     }
 
     // Shortens lifetime to specified span.
     pub fn truncate(&mut self, span: TimeSpan) {
         self.left = std::cmp::min(self.left, span);
     }
+
+    /// Prolongs lifetime by specified span.
+    pub fn extend(&mut self, span: TimeSpan) {
+        self.left += span;
+    }
+
+    /// Returns time left before entity is despawned.
+    pub fn remaining(&self) -> TimeSpan {
+        self.left
+    }
+
+    /// Stops lifespan from decreasing.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Lets paused lifespan decrease again.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` if lifespan is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
 }
 
+/// Marker added to entities with [`LifeSpan::with_expired_marker`]
+/// when lifespan runs out.
+///
+/// Entity with the marker is kept for one more step and then despawned,
+/// so other systems may react, e.g. spawn an explosion.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{game::Game, lifespan::{lifetime_system, LifeSpan, LifeSpanExpired}, TimeSpan};
+/// let mut game = Game::deterministic(0);
+/// game.scheduler.add_system(lifetime_system);
+///
+/// let e = game
+///     .world
+///     .spawn((LifeSpan::new(TimeSpan::SECOND).with_expired_marker(),));
+///
+/// game.step(TimeSpan::SECOND);
+/// assert!(game.world.query_one_mut::<&LifeSpanExpired>(&e).is_ok());
+///
+/// game.step(TimeSpan::SECOND);
+/// assert!(game.world.query_one_mut::<&LifeSpanExpired>(&e).is_err());
+/// assert!(game.world.query_one_mut::<&LifeSpan>(&e).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Component)]
+pub struct LifeSpanExpired;
+
 pub fn lifetime_system(
     clock: Res<ClockIndex>,
     mut query: QueryRef<(Entities, &mut LifeSpan)>,
@@ -29,15 +128,28 @@ pub fn lifetime_system(
     scope: &mut ScopedAllocator,
 ) {
     let mut despawn = Vec::new_in(&**scope);
+    let mut expired = Vec::new_in(&**scope);
 
     for (e, ls) in query.iter_mut() {
-        if ls.left > clock.delta {
+        if ls.expired {
+            despawn.push(e);
+        } else if ls.paused {
+            continue;
+        } else if ls.left > clock.delta {
             ls.left -= clock.delta;
+        } else if ls.mark_expired {
+            ls.left = TimeSpan::ZERO;
+            ls.expired = true;
+            expired.push(e);
         } else {
             despawn.push(e);
         }
     }
 
+    for e in expired {
+        encoder.insert(e, LifeSpanExpired);
+    }
+
     for e in despawn {
         encoder.despawn(e);
     }
@@ -1,4 +1,11 @@
-use std::{borrow::BorrowMut, sync::Arc};
+use std::{
+    borrow::BorrowMut,
+    io::Cursor,
+    ops::{Add, Mul},
+    sync::Arc,
+};
+
+use edict::{entity::EntityId, world::World, Component};
 
 use futures::future::BoxFuture;
 use goods::{Asset, AssetBuild, AssetField, AssetFieldBuild, Container, Loader};
@@ -12,7 +19,7 @@ use skelly::Skelly;
 #[cfg(feature = "graphics")]
 use crate::graphics::{
     BindingFileHeader, Graphics, IndicesFileHeader, Material, MaterialBuildError,
-    MaterialDecodeError, MaterialDecoded, MaterialInfo, Mesh,
+    MaterialDecodeError, MaterialDecoded, MaterialInfo, Mesh, MeshBounds, MorphTargetFileHeader,
 };
 
 #[cfg(feature = "graphics")]
@@ -23,6 +30,11 @@ pub struct PrimitiveInfo {
     pub indices: Option<IndicesFileHeader>,
     pub topology: PrimitiveTopology,
     pub material: Option<usize>,
+    pub morph_targets: Vec<MorphTargetFileHeader>,
+
+    /// Bounds of vertex positions.
+    #[serde(default)]
+    pub bounds: Option<MeshBounds>,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -37,6 +49,9 @@ pub enum Collider {
         vertices: Vec<na::Point3<f32>>,
         indices: Vec<[u32; 3]>,
     },
+    Compound {
+        shapes: Vec<Collider>,
+    },
 }
 
 #[cfg(feature = "graphics")]
@@ -46,20 +61,236 @@ pub struct Skin {
     pub skelly: Skelly<f32, String>,
 }
 
+/// Node of the model affected by animation channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum AnimationTarget {
+    /// Node with model's meshes.
+    Root,
+
+    /// Joint of model's skin with specified index.
+    Joint(usize),
+}
+
+/// Interpolation between animation keyframes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Interpolation {
+    Linear,
+    Step,
+
+    /// Cubic Hermite spline.
+    /// Each keyframe has in-tangent, value and out-tangent, in that order.
+    CubicSpline,
+}
+
+/// Keyframe values of animated node property.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ChannelValues {
+    Translation(Vec<na::Vector3<f32>>),
+    Rotation(Vec<na::Quaternion<f32>>),
+    Scale(Vec<na::Vector3<f32>>),
+}
+
+/// Value of animated node property at some time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChannelValue {
+    Translation(na::Vector3<f32>),
+    Rotation(na::UnitQuaternion<f32>),
+    Scale(na::Vector3<f32>),
+}
+
+/// Curve of a single node property.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AnimationChannel {
+    pub target: AnimationTarget,
+    pub interpolation: Interpolation,
+
+    /// Ascending keyframe times in seconds.
+    pub times: Vec<f32>,
+    pub values: ChannelValues,
+}
+
+impl AnimationChannel {
+    /// Samples property value at `time` in seconds.
+    ///
+    /// Values before first and after last keyframe are clamped.
+    /// Returns `None` if channel has no keyframes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+    /// # use arcana::{na, model::*};
+    /// let quarter = na::UnitQuaternion::from_euler_angles(0.0, 0.0, FRAC_PI_2);
+    ///
+    /// let animation = AnimationFileHeader {
+    ///     name: Some("spin".to_owned()),
+    ///     channels: vec![AnimationChannel {
+    ///         target: AnimationTarget::Root,
+    ///         interpolation: Interpolation::Linear,
+    ///         times: vec![0.0, 1.0],
+    ///         values: ChannelValues::Rotation(vec![
+    ///             *na::UnitQuaternion::identity(),
+    ///             *quarter,
+    ///         ]),
+    ///     }],
+    /// };
+    ///
+    /// // Animations survive model file round trip.
+    /// let bytes = arcana::bincode::serialize(&animation).unwrap();
+    /// let decoded: AnimationFileHeader = arcana::bincode::deserialize(&bytes).unwrap();
+    /// assert_eq!(decoded.channels, animation.channels);
+    ///
+    /// let animation = Animation::from(decoded);
+    /// assert_eq!(animation.duration(), 1.0);
+    ///
+    /// let channel = &animation.channels[0];
+    /// let angle = |time| match channel.sample(time) {
+    ///     Some(ChannelValue::Rotation(rotation)) => rotation.angle(),
+    ///     _ => unreachable!(),
+    /// };
+    ///
+    /// assert!((angle(0.5) - FRAC_PI_4).abs() < 1e-5);
+    /// assert!((angle(2.0) - FRAC_PI_2).abs() < 1e-5);
+    /// ```
+    pub fn sample(&self, time: f32) -> Option<ChannelValue> {
+        let interpolation = self.interpolation;
+        match &self.values {
+            ChannelValues::Translation(values) => {
+                sample_keys(&self.times, values, interpolation, time, |a, b, t| {
+                    a.lerp(&b, t)
+                })
+                .map(ChannelValue::Translation)
+            }
+            ChannelValues::Rotation(values) => {
+                sample_keys(&self.times, values, interpolation, time, |a, b, t| {
+                    let a = na::UnitQuaternion::new_normalize(a);
+                    let b = na::UnitQuaternion::new_normalize(b);
+                    // Slerp is undefined for opposite rotations.
+                    *a.try_slerp(&b, t, f32::EPSILON)
+                        .unwrap_or_else(|| a.nlerp(&b, t))
+                })
+                .map(|value| ChannelValue::Rotation(na::UnitQuaternion::new_normalize(value)))
+            }
+            ChannelValues::Scale(values) => {
+                sample_keys(&self.times, values, interpolation, time, |a, b, t| {
+                    a.lerp(&b, t)
+                })
+                .map(ChannelValue::Scale)
+            }
+        }
+    }
+}
+
+/// Samples keyframes at `time`.
+fn sample_keys<T>(
+    times: &[f32],
+    values: &[T],
+    interpolation: Interpolation,
+    time: f32,
+    lerp: impl FnOnce(T, T, f32) -> T,
+) -> Option<T>
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    // Index of value of keyframe `i`.
+    let value = |i: usize| match interpolation {
+        Interpolation::CubicSpline => values.get(i * 3 + 1).copied(),
+        _ => values.get(i).copied(),
+    };
+
+    let next = times.partition_point(|&t| t <= time);
+    if next == 0 {
+        return value(0);
+    }
+    if next == times.len() {
+        return value(next - 1);
+    }
+
+    let prev = next - 1;
+    let span = times[next] - times[prev];
+    let t = (time - times[prev]) / span;
+
+    match interpolation {
+        Interpolation::Step => value(prev),
+        Interpolation::Linear => Some(lerp(value(prev)?, value(next)?, t)),
+        Interpolation::CubicSpline => {
+            let out_tangent = *values.get(prev * 3 + 2)?;
+            let in_tangent = *values.get(next * 3)?;
+
+            let t2 = t * t;
+            let t3 = t2 * t;
+
+            Some(
+                value(prev)? * (2.0 * t3 - 3.0 * t2 + 1.0)
+                    + out_tangent * ((t3 - 2.0 * t2 + t) * span)
+                    + value(next)? * (-2.0 * t3 + 3.0 * t2)
+                    + in_tangent * ((t3 - t2) * span),
+            )
+        }
+    }
+}
+
+/// Animation as stored in model file.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AnimationFileHeader {
+    pub name: Option<String>,
+    pub channels: Vec<AnimationChannel>,
+}
+
+/// Animation of model's nodes.
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub name: Option<String>,
+    pub channels: Arc<[AnimationChannel]>,
+}
+
+impl From<AnimationFileHeader> for Animation {
+    fn from(header: AnimationFileHeader) -> Self {
+        Animation {
+            name: header.name,
+            channels: header.channels.into(),
+        }
+    }
+}
+
+impl Animation {
+    /// Returns time of the last keyframe in seconds.
+    pub fn duration(&self) -> f32 {
+        self.channels
+            .iter()
+            .filter_map(|channel| channel.times.last().copied())
+            .fold(0.0, f32::max)
+    }
+
+    /// Returns channels that animate specified node.
+    pub fn node_channels(
+        &self,
+        target: AnimationTarget,
+    ) -> impl Iterator<Item = &AnimationChannel> + '_ {
+        self.channels
+            .iter()
+            .filter(move |channel| channel.target == target)
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct ModelFileHeader {
     pub magic: u32,
     pub colliders: Vec<Collider>,
+    pub animations: Vec<AnimationFileHeader>,
     #[cfg(feature = "graphics")]
     pub primitives: Vec<PrimitiveInfo>,
     #[cfg(feature = "graphics")]
     pub skin: Option<Skin>,
     #[cfg(feature = "graphics")]
     pub materials: Vec<MaterialInfo>,
+    #[cfg(feature = "graphics")]
+    pub morph_weights: Vec<f32>,
 }
 
 pub struct ModelFileDecoded {
     colliders: Vec<Collider>,
+    animations: Vec<AnimationFileHeader>,
     #[cfg(feature = "graphics")]
     primitives: Vec<PrimitiveInfo>,
     #[cfg(feature = "graphics")]
@@ -67,6 +298,8 @@ pub struct ModelFileDecoded {
     #[cfg(feature = "graphics")]
     materials: Vec<MaterialDecoded>,
     #[cfg(feature = "graphics")]
+    morph_weights: Vec<f32>,
+    #[cfg(feature = "graphics")]
     bytes: Box<[u8]>,
 }
 
@@ -74,18 +307,107 @@ impl ModelFileHeader {
     pub const MAGIC: u32 = u32::from_le_bytes(*b"arcm");
 }
 
+/// Node of the imported model scene.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SceneNode {
+    pub name: Option<String>,
+
+    /// Index of the parent node.
+    /// Parent nodes always precede their children.
+    pub parent: Option<usize>,
+
+    /// Transform relative to the parent node.
+    pub iso: na::Isometry3<f32>,
+    pub scale: na::Vector3<f32>,
+
+    /// Index of the model attached to this node.
+    pub model: Option<usize>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ModelSceneFileHeader {
+    pub magic: u32,
+    pub nodes: Vec<SceneNode>,
+
+    /// Sizes of model files stored consecutively after the header.
+    pub models: Vec<usize>,
+}
+
+impl ModelSceneFileHeader {
+    pub const MAGIC: u32 = u32::from_le_bytes(*b"arcs");
+}
+
 #[derive(Clone, Debug)]
 pub struct Model {
     pub primitives: Arc<[Primitive]>,
     pub colliders: Arc<[Collider]>,
+    pub animations: Arc<[Animation]>,
     pub skin: Option<Skin>,
     pub materials: Arc<[Material]>,
+
+    /// Default weights of morph targets of the primitives.
+    pub morph_weights: Arc<[f32]>,
 }
 
 #[derive(Clone, Debug)]
 pub struct Primitive {
     pub mesh: Mesh,
     pub material: Option<usize>,
+    pub bounds: Option<MeshBounds>,
+}
+
+/// Current weights of morph targets of entity's mesh.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{model::MorphWeights, na};
+/// let mut weights = MorphWeights::new(&[0.0, 0.0]);
+/// weights.weights[0] = 0.5;
+/// weights.weights[1] = 1.0;
+///
+/// let base = na::Vector3::new(1.0, 0.0, 0.0);
+/// let deltas = [na::Vector3::new(0.0, 2.0, 0.0), na::Vector3::new(0.0, 0.0, -1.0)];
+///
+/// assert_eq!(weights.active().count(), 2);
+/// assert_eq!(weights.morph(base, deltas), na::Vector3::new(1.0, 1.0, -1.0));
+/// ```
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct MorphWeights {
+    /// Weight of each morph target.
+    pub weights: Vec<f32>,
+}
+
+impl MorphWeights {
+    /// Returns weights initialized with defaults,
+    /// e.g. with [`Model::morph_weights`].
+    pub fn new(defaults: &[f32]) -> Self {
+        MorphWeights {
+            weights: defaults.to_vec(),
+        }
+    }
+
+    /// Returns indices and weights of morph targets with non-zero weight.
+    pub fn active(&self) -> impl Iterator<Item = (usize, f32)> + '_ {
+        self.weights
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, weight)| weight != 0.0)
+    }
+
+    /// Applies deltas of morph targets to base value of vertex attribute.
+    /// Deltas past number of weights are ignored.
+    pub fn morph(
+        &self,
+        base: na::Vector3<f32>,
+        deltas: impl IntoIterator<Item = na::Vector3<f32>>,
+    ) -> na::Vector3<f32> {
+        self.weights
+            .iter()
+            .zip(deltas)
+            .fold(base, |value, (&weight, delta)| value + delta * weight)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -96,6 +418,15 @@ pub enum ModelDecodeError {
     #[error("Failed to deserialize model file header")]
     HeaderError { source: bincode::Error },
 
+    #[error("Scene blob is too small to contain all models")]
+    BlobTooSmall,
+
+    #[error("Scene node {node} refers to parent {parent} that does not precede it")]
+    InvalidParent { node: usize, parent: usize },
+
+    #[error("Scene node {node} refers to missing model {model}")]
+    InvalidModel { node: usize, model: usize },
+
     #[error("Failed to build material")]
     Material {
         #[from]
@@ -108,6 +439,9 @@ pub enum ModelBuildError {
     #[error("Failed to build mesh")]
     Mesh { source: OutOfMemory },
 
+    #[error("Primitive refers to missing material {material}")]
+    InvalidMaterial { material: usize },
+
     #[error("Failed to build material")]
     Material {
         #[from]
@@ -164,6 +498,7 @@ impl Asset for Model {
 
                     Ok(ModelFileDecoded {
                         colliders: header.colliders,
+                        animations: header.animations,
 
                         #[cfg(feature = "graphics")]
                         primitives: header.primitives,
@@ -174,6 +509,9 @@ impl Asset for Model {
                         #[cfg(feature = "graphics")]
                         materials,
 
+                        #[cfg(feature = "graphics")]
+                        morph_weights: header.morph_weights,
+
                         #[cfg(feature = "graphics")]
                         bytes,
                     })
@@ -200,6 +538,7 @@ where
                     &primitive.bindings,
                     primitive.indices.as_ref(),
                     primitive.topology,
+                    &primitive.morph_targets,
                     &decoded.bytes,
                     builder.borrow_mut(),
                 );
@@ -209,6 +548,7 @@ where
                         primitives.push(Primitive {
                             mesh,
                             material: primitive.material,
+                            bounds: primitive.bounds,
                         });
                     }
 
@@ -231,16 +571,178 @@ where
                     <Material as AssetFieldBuild<Container, B>>::build(material, builder)?;
                 materials.push(material);
             }
+
+            for primitive in &primitives {
+                if let Some(material) = primitive.material {
+                    if material >= materials.len() {
+                        return Err(ModelBuildError::InvalidMaterial { material });
+                    }
+                }
+            }
         }
 
         Ok(Model {
             colliders: decoded.colliders.into(),
+            animations: decoded
+                .animations
+                .into_iter()
+                .map(Animation::from)
+                .collect(),
             #[cfg(feature = "graphics")]
             primitives: primitives.into(),
             #[cfg(feature = "graphics")]
             skin: decoded.skin,
             #[cfg(feature = "graphics")]
             materials: materials.into(),
+            #[cfg(feature = "graphics")]
+            morph_weights: decoded.morph_weights.into(),
+        })
+    }
+}
+
+/// Scene with hierarchy of nodes referencing multiple models.
+#[derive(Clone, Debug)]
+pub struct ModelScene {
+    pub nodes: Arc<[SceneNode]>,
+    pub models: Arc<[Model]>,
+}
+
+pub struct ModelSceneDecoded {
+    nodes: Vec<SceneNode>,
+    models: Vec<ModelFileDecoded>,
+}
+
+impl Asset for ModelScene {
+    type Decoded = ModelSceneDecoded;
+    type DecodeError = ModelDecodeError;
+    type BuildError = ModelBuildError;
+    type Fut = BoxFuture<'static, Result<ModelSceneDecoded, ModelDecodeError>>;
+
+    fn name() -> &'static str {
+        "arcana.model-scene"
+    }
+
+    fn decode(bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+        let mut cursor = Cursor::new(&*bytes);
+
+        let header = match bincode::deserialize_from::<_, ModelSceneFileHeader>(&mut cursor) {
+            Ok(header) => header,
+            Err(err) => {
+                return Box::pin(async { Err(ModelDecodeError::HeaderError { source: err }) })
+            }
+        };
+
+        if header.magic != ModelSceneFileHeader::MAGIC {
+            tracing::error!(
+                "Scene blob contains wrong magic number '{:X}'. Expected '{:X}'",
+                header.magic,
+                ModelSceneFileHeader::MAGIC
+            );
+            return Box::pin(async { Err(ModelDecodeError::MagicError) });
+        }
+
+        for (node, scene_node) in header.nodes.iter().enumerate() {
+            if let Some(parent) = scene_node.parent {
+                if parent >= node {
+                    return Box::pin(async move {
+                        Err(ModelDecodeError::InvalidParent { node, parent })
+                    });
+                }
+            }
+            if let Some(model) = scene_node.model {
+                if model >= header.models.len() {
+                    return Box::pin(
+                        async move { Err(ModelDecodeError::InvalidModel { node, model }) },
+                    );
+                }
+            }
+        }
+
+        let mut offset = cursor.position() as usize;
+        let mut blobs = Vec::with_capacity(header.models.len());
+
+        for &size in &header.models {
+            let blob = offset
+                .checked_add(size)
+                .and_then(|end| bytes.get(offset..end));
+
+            match blob {
+                None => return Box::pin(async { Err(ModelDecodeError::BlobTooSmall) }),
+                Some(blob) => blobs.push(Box::<[u8]>::from(blob)),
+            }
+            offset += size;
+        }
+
+        let loader = loader.clone();
+        let nodes = header.nodes;
+
+        Box::pin(async move {
+            let mut models = Vec::with_capacity(blobs.len());
+            for blob in blobs {
+                models.push(Model::decode(blob, &loader).await?);
+            }
+            Ok(ModelSceneDecoded { nodes, models })
+        })
+    }
+}
+
+impl<B> AssetBuild<B> for ModelScene
+where
+    B: BorrowMut<Graphics>,
+{
+    fn build(decoded: ModelSceneDecoded, builder: &mut B) -> Result<Self, ModelBuildError> {
+        let models = decoded
+            .models
+            .into_iter()
+            .map(|model| <Model as AssetBuild<B>>::build(model, builder))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ModelScene {
+            nodes: decoded.nodes.into(),
+            models: models.into(),
         })
     }
 }
+
+impl ModelScene {
+    /// Spawns entity for every primitive of every model node in the scene.
+    /// Node transforms are accumulated down the hierarchy starting from `root`.
+    ///
+    /// Node scale is not representable by `Global3` and is ignored.
+    /// Primitives without material are spawned with default one.
+    ///
+    /// Node and material indices are validated when scene is loaded.
+    pub fn spawn(&self, world: &mut World, root: &na::Isometry3<f32>) -> Vec<EntityId> {
+        let mut globals = Vec::with_capacity(self.nodes.len());
+        let mut entities = Vec::new();
+
+        for node in self.nodes.iter() {
+            let iso = match node.parent {
+                None => root * node.iso,
+                Some(parent) => globals[parent] * node.iso,
+            };
+            globals.push(iso);
+
+            if let Some(model) = node.model {
+                let model = &self.models[model];
+                for primitive in model.primitives.iter() {
+                    let material = match primitive.material {
+                        None => Material::default(),
+                        Some(material) => model.materials[material].clone(),
+                    };
+                    let entity = world.spawn((
+                        primitive.mesh.clone(),
+                        material,
+                        crate::scene::Global3::new(iso),
+                    ));
+                    if let Some(bounds) = primitive.bounds {
+                        let _ = world.insert(entity, bounds);
+                    }
+                    entities.push(entity);
+                }
+            }
+        }
+
+        entities
+    }
+}
@@ -0,0 +1,161 @@
+//! Bandwidth metering for replicated components.
+//!
+//! Replication code reports number of bytes each component type takes
+//! in sent and received packets, and [`NetStats`] keeps totals
+//! for a rolling window of ticks.
+//! This helps to spot which component dominates traffic.
+//!
+//! Engine doesn't feed these stats, since `evoke` packs replicated components internally
+//! and does not report their sizes.
+//! Game should insert [`NetStats`] resource, call [`NetStats::record_sent`]
+//! and [`NetStats::record_received`] where it packs and unpacks component data,
+//! and call [`NetStats::end_tick`] once per network tick.
+//!
+//! Available with `net-stats` feature, so that there is no overhead without it.
+
+use std::{
+    any::type_name,
+    collections::{HashMap, VecDeque},
+};
+
+/// Default number of ticks in [`NetStats`] window.
+pub const DEFAULT_NET_STATS_WINDOW: usize = 60;
+
+#[derive(Clone, Debug, Default)]
+struct TickStats {
+    sent: HashMap<&'static str, u64>,
+    received: HashMap<&'static str, u64>,
+}
+
+/// Resource with bytes sent and received per component type.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::net_stats::NetStats;
+/// struct Position;
+/// struct Health;
+///
+/// let mut stats = NetStats::new(2);
+///
+/// stats.record_sent::<Position>(12);
+/// stats.record_sent::<Health>(4);
+/// stats.end_tick();
+///
+/// stats.record_sent::<Position>(12);
+/// stats.record_received::<Health>(4);
+/// stats.end_tick();
+///
+/// assert_eq!(stats.sent::<Position>(), 24);
+/// assert_eq!(stats.sent::<Health>(), 4);
+/// assert_eq!(stats.received::<Health>(), 4);
+/// assert_eq!(stats.total_sent(), 28);
+/// assert_eq!(stats.top_sent()[0].1, 24);
+///
+/// // First tick leaves the window.
+/// stats.end_tick();
+/// assert_eq!(stats.sent::<Position>(), 12);
+/// assert_eq!(stats.sent::<Health>(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NetStats {
+    window: usize,
+    ticks: VecDeque<TickStats>,
+    current: TickStats,
+}
+
+impl Default for NetStats {
+    fn default() -> Self {
+        NetStats::new(DEFAULT_NET_STATS_WINDOW)
+    }
+}
+
+impl NetStats {
+    /// Returns new stats that keep totals over `window` ticks.
+    pub fn new(window: usize) -> Self {
+        NetStats {
+            window: window.max(1),
+            ticks: VecDeque::new(),
+            current: TickStats::default(),
+        }
+    }
+
+    /// Records bytes of component `T` written into outgoing packet.
+    pub fn record_sent<T: 'static>(&mut self, bytes: usize) {
+        *self.current.sent.entry(type_name::<T>()).or_default() += bytes as u64;
+    }
+
+    /// Records bytes of component `T` read from incoming packet.
+    pub fn record_received<T: 'static>(&mut self, bytes: usize) {
+        *self.current.received.entry(type_name::<T>()).or_default() += bytes as u64;
+    }
+
+    /// Finishes current tick.
+    /// Records made after this call are attributed to the next tick.
+    /// The oldest tick leaves the window when it is full.
+    pub fn end_tick(&mut self) {
+        let tick = std::mem::take(&mut self.current);
+        if self.ticks.len() >= self.window {
+            self.ticks.pop_front();
+        }
+        self.ticks.push_back(tick);
+    }
+
+    /// Returns bytes sent for component `T` within the window.
+    pub fn sent<T: 'static>(&self) -> u64 {
+        let name = type_name::<T>();
+        self.ticks
+            .iter()
+            .filter_map(|tick| tick.sent.get(name))
+            .sum()
+    }
+
+    /// Returns bytes received for component `T` within the window.
+    pub fn received<T: 'static>(&self) -> u64 {
+        let name = type_name::<T>();
+        self.ticks
+            .iter()
+            .filter_map(|tick| tick.received.get(name))
+            .sum()
+    }
+
+    /// Returns bytes sent for all components within the window.
+    pub fn total_sent(&self) -> u64 {
+        self.ticks.iter().flat_map(|tick| tick.sent.values()).sum()
+    }
+
+    /// Returns bytes received for all components within the window.
+    pub fn total_received(&self) -> u64 {
+        self.ticks
+            .iter()
+            .flat_map(|tick| tick.received.values())
+            .sum()
+    }
+
+    /// Returns component type names with bytes sent within the window,
+    /// largest first.
+    pub fn top_sent(&self) -> Vec<(&'static str, u64)> {
+        top(self.ticks.iter().map(|tick| &tick.sent))
+    }
+
+    /// Returns component type names with bytes received within the window,
+    /// largest first.
+    pub fn top_received(&self) -> Vec<(&'static str, u64)> {
+        top(self.ticks.iter().map(|tick| &tick.received))
+    }
+}
+
+fn top<'a>(
+    ticks: impl Iterator<Item = &'a HashMap<&'static str, u64>>,
+) -> Vec<(&'static str, u64)> {
+    let mut totals = HashMap::<&'static str, u64>::new();
+    for tick in ticks {
+        for (name, bytes) in tick {
+            *totals.entry(*name).or_default() += *bytes;
+        }
+    }
+
+    let mut totals: Vec<_> = totals.into_iter().collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    totals
+}
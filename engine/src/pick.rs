@@ -0,0 +1,222 @@
+//! Finds entity under the cursor.
+//!
+//! Picking is performed on the CPU against the same data renderers use,
+//! so it works without reading back GPU buffers.
+
+use edict::{entity::EntityId, world::World, Entities};
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "2d")] {
+        use crate::{
+            camera::Camera2,
+            scene::{Global2, RenderGlobal2},
+            sprite::Sprite,
+        };
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "3d")] {
+        use crate::{
+            camera::Camera3,
+            graphics::{MeshBounds, Scale},
+            scene::{Global3, RenderGlobal3},
+        };
+    }
+}
+
+/// Returns entity under the cursor as seen by the camera.
+///
+/// `cursor` is in screen space that spans from `-1` to `1` on both axes
+/// with `y` pointing up, `aspect` is the aspect ratio of the viewport.
+///
+/// Dispatches to [`pick2`] or [`pick3`] depending on camera kind.
+/// Returns `None` if camera entity has neither camera component.
+pub fn pick(
+    world: &mut World,
+    camera: EntityId,
+    cursor: na::Point2<f32>,
+    aspect: f32,
+) -> Option<EntityId> {
+    #[cfg(feature = "2d")]
+    if world.query_one_mut::<&Camera2>(camera).is_ok() {
+        return pick2(world, camera, cursor, aspect);
+    }
+
+    #[cfg(feature = "3d")]
+    if world.query_one_mut::<&Camera3>(camera).is_ok() {
+        return pick3(world, camera, cursor, aspect);
+    }
+
+    let _ = (world, camera, cursor, aspect);
+    None
+}
+
+/// Returns topmost entity with [`Sprite`] which `world` rect contains the cursor.
+///
+/// Sprites on higher layers win, ties are broken in favor of the sprite found first.
+/// Rotated sprites are tested precisely in their local space.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{camera::Camera2, edict::world::World, na, pick::pick2, rect::Rect, scene::Global2, sprite::Sprite};
+/// let mut world = World::new();
+///
+/// // Shows area from -1 to 1 on both axes.
+/// let camera = world.spawn((Camera2::new(1.0), Global2::identity()));
+///
+/// let square = |layer| Sprite {
+///     world: Rect { left: -0.5, right: 0.5, bottom: -0.5, top: 0.5 },
+///     layer,
+///     ..Sprite::default()
+/// };
+///
+/// let lower = world.spawn((square(0), Global2::identity()));
+/// let upper = world.spawn((square(1), Global2::new(na::Translation2::new(0.4, 0.0).into())));
+///
+/// // Both sprites are under the cursor, topmost layer wins.
+/// assert_eq!(pick2(&mut world, camera, na::Point2::new(0.2, 0.0), 1.0), Some(upper));
+///
+/// assert_eq!(pick2(&mut world, camera, na::Point2::new(-0.3, 0.0), 1.0), Some(lower));
+/// assert_eq!(pick2(&mut world, camera, na::Point2::new(0.95, 0.95), 1.0), None);
+/// ```
+#[cfg(feature = "2d")]
+pub fn pick2(
+    world: &mut World,
+    camera: EntityId,
+    cursor: na::Point2<f32>,
+    aspect: f32,
+) -> Option<EntityId> {
+    let (global, camera) = world.query_one_mut::<(&Global2, &Camera2)>(camera).ok()?;
+
+    let point = camera.screen_to_world(&global.iso, &cursor, aspect);
+
+    let mut picked: Option<(EntityId, u32)> = None;
+
+    for (entity, sprite, global, render_global) in world
+        .query_mut::<(Entities, &Sprite, &Global2, Option<&RenderGlobal2>)>()
+        .iter()
+    {
+        if matches!(picked, Some((_, layer)) if layer >= sprite.layer) {
+            continue;
+        }
+
+        let iso = render_global.map_or(global.iso, |render_global| render_global.iso);
+        let local = iso.inverse_transform_point(&point);
+
+        if sprite.world.contains(&local) {
+            picked = Some((entity, sprite.layer));
+        }
+    }
+
+    picked.map(|(entity, _)| entity)
+}
+
+/// Returns nearest entity with [`MeshBounds`] hit by the ray cast from the cursor.
+///
+/// Ray is tested against mesh bounds only,
+/// so entity may be picked when cursor is over empty corner of its bounds.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{camera::Camera3, edict::world::World, graphics::MeshBounds, na, pick::pick3, scene::Global3};
+/// let mut world = World::new();
+///
+/// // Looks along negative Z axis.
+/// let camera = world.spawn((
+///     Camera3::perspective(2.0, 0.1, 100.0),
+///     Global3::new(na::Isometry3::translation(0.0, 0.0, 10.0)),
+/// ));
+///
+/// let cube = MeshBounds {
+///     min: na::Point3::new(-0.25, -0.25, -0.25),
+///     max: na::Point3::new(0.25, 0.25, 0.25),
+/// };
+///
+/// let far = world.spawn((cube, Global3::new(na::Isometry3::translation(0.4, 0.0, -5.0))));
+/// let near = world.spawn((cube, Global3::new(na::Isometry3::identity())));
+///
+/// assert_eq!(pick3(&mut world, camera, na::Point2::origin(), 1.0), Some(near));
+/// assert_eq!(pick3(&mut world, camera, na::Point2::new(0.9, 0.0), 1.0), Some(far));
+///
+/// // Both cubes are on the ray, nearest one is hit.
+/// assert_eq!(pick3(&mut world, camera, na::Point2::new(0.4, 0.0), 1.0), Some(near));
+///
+/// // Ray passes beside the cubes.
+/// assert_eq!(pick3(&mut world, camera, na::Point2::new(0.0, 0.9), 1.0), None);
+/// ```
+#[cfg(feature = "3d")]
+pub fn pick3(
+    world: &mut World,
+    camera: EntityId,
+    cursor: na::Point2<f32>,
+    aspect: f32,
+) -> Option<EntityId> {
+    let (global, camera) = world.query_one_mut::<(&Global3, &Camera3)>(camera).ok()?;
+
+    // Ray spans whole clip volume from near to far plane.
+    let view = na::Affine3::from_matrix_unchecked(global.iso.to_homogeneous());
+    let origin = camera.screen_to_world(&view, &na::Point3::new(cursor.x, cursor.y, -1.0), aspect);
+    let target = camera.screen_to_world(&view, &na::Point3::new(cursor.x, cursor.y, 1.0), aspect);
+    let ray_dir = target - origin;
+
+    let mut picked: Option<(EntityId, f32)> = None;
+
+    for (entity, bounds, global, render_global, scale) in world
+        .query_mut::<(
+            Entities,
+            &MeshBounds,
+            &Global3,
+            Option<&RenderGlobal3>,
+            Option<&Scale>,
+        )>()
+        .iter()
+    {
+        let iso = render_global.map_or(global.iso, |render_global| render_global.iso);
+
+        // Affine transformation keeps ray parameter,
+        // so hits in local space are comparable.
+        let mut origin = iso.inverse_transform_point(&origin);
+        let mut dir = iso.inverse_transform_vector(&ray_dir);
+
+        if let Some(scale) = scale {
+            origin.coords.component_div_assign(&scale.0);
+            dir.component_div_assign(&scale.0);
+        }
+
+        if let Some(toi) = ray_aabb(&origin, &dir, bounds) {
+            if picked.map_or(true, |(_, nearest)| toi < nearest) {
+                picked = Some((entity, toi));
+            }
+        }
+    }
+
+    picked.map(|(entity, _)| entity)
+}
+
+/// Returns ray parameter of the first intersection with the box
+/// using slab test. Ray starting inside the box hits at `0`.
+#[cfg(feature = "3d")]
+fn ray_aabb(origin: &na::Point3<f32>, dir: &na::Vector3<f32>, bounds: &MeshBounds) -> Option<f32> {
+    let mut tmin = 0.0f32;
+    let mut tmax = f32::INFINITY;
+
+    for axis in 0..3 {
+        let inv = 1.0 / dir[axis];
+        let t0 = (bounds.min[axis] - origin[axis]) * inv;
+        let t1 = (bounds.max[axis] - origin[axis]) * inv;
+
+        // `min` and `max` skip NaN that appears when ray is parallel to the slab
+        // and starts on its plane.
+        tmin = tmin.max(t0.min(t1));
+        tmax = tmax.min(t0.max(t1));
+    }
+
+    if tmin <= tmax {
+        Some(tmin)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,148 @@
+//! Client-side prediction.
+//!
+//! Waiting for server to respond to player input makes controls feel laggy.
+//! Instead client applies input to its own copy of the state right away
+//! and sends input to the server tagged with a sequence number.
+//! When authoritative state arrives, [`Prediction`] takes it and re-applies
+//! inputs server has not processed yet, so mispredictions are corrected
+//! without discarding recent input.
+
+use std::collections::VecDeque;
+
+use edict::component::Component;
+
+/// Default number of unacknowledged inputs kept by [`Prediction`].
+pub const DEFAULT_PREDICTION_CAPACITY: usize = 128;
+
+/// State that can be simulated on client ahead of the server.
+///
+/// `apply_input` should perform the same simulation step server does,
+/// otherwise every authoritative state will correct the prediction.
+pub trait Predict {
+    /// Input that drives the state, e.g. player command.
+    type Input;
+
+    /// Advances state by applying the input.
+    fn apply_input(&mut self, input: &Self::Input);
+}
+
+/// Predicted state with ring of inputs not yet acknowledged by server.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::predict::{Predict, Prediction};
+/// #[derive(Debug, PartialEq)]
+/// struct Position(f32);
+///
+/// impl Predict for Position {
+///     type Input = f32;
+///
+///     fn apply_input(&mut self, velocity: &f32) {
+///         self.0 += velocity;
+///     }
+/// }
+///
+/// let mut prediction = Prediction::new(Position(0.0));
+/// let first = prediction.apply(1.0);
+/// let second = prediction.apply(1.0);
+/// let third = prediction.apply(1.0);
+/// assert_eq!(*prediction.state(), Position(3.0));
+///
+/// // Server hit an obstacle on the first input.
+/// // Remaining inputs are re-applied to corrected state.
+/// prediction.reconcile(Position(0.5), Some(first));
+/// assert_eq!(*prediction.state(), Position(2.5));
+/// assert_eq!(prediction.pending().count(), 2);
+///
+/// prediction.reconcile(Position(1.5), Some(second));
+/// prediction.reconcile(Position(2.5), Some(third));
+/// assert_eq!(*prediction.state(), Position(2.5));
+/// assert_eq!(prediction.pending().count(), 0);
+/// ```
+#[derive(Component)]
+#[edict(where S: 'static, S::Input: 'static)]
+pub struct Prediction<S: Predict> {
+    state: S,
+    pending: VecDeque<(u64, S::Input)>,
+    next_seq: u64,
+    capacity: usize,
+}
+
+impl<S> Prediction<S>
+where
+    S: Predict,
+{
+    /// Returns new prediction starting from the state.
+    pub fn new(state: S) -> Self {
+        Prediction {
+            state,
+            pending: VecDeque::new(),
+            next_seq: 0,
+            capacity: DEFAULT_PREDICTION_CAPACITY,
+        }
+    }
+
+    /// Sets maximum number of unacknowledged inputs.
+    ///
+    /// When exceeded, oldest inputs are forgotten and
+    /// won't be re-applied on reconciliation.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Returns predicted state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Applies input to predicted state and records it.
+    ///
+    /// Returns sequence number of the input.
+    /// It should be sent to server with the input
+    /// and returned back with authoritative state.
+    pub fn apply(&mut self, input: S::Input) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        self.state.apply_input(&input);
+
+        if self.pending.len() >= self.capacity {
+            tracing::warn!(
+                "Prediction input ring is full. Input {} is dropped",
+                self.pending[0].0
+            );
+            self.pending.pop_front();
+        }
+        self.pending.push_back((seq, input));
+        seq
+    }
+
+    /// Replaces predicted state with authoritative one and
+    /// re-applies inputs that came after `ack`.
+    ///
+    /// `ack` is sequence number of the last input applied by server to produce the state,
+    /// or `None` if server hasn't applied any.
+    pub fn reconcile(&mut self, authoritative: S, ack: Option<u64>) {
+        if let Some(ack) = ack {
+            while let Some((seq, _)) = self.pending.front() {
+                if *seq > ack {
+                    break;
+                }
+                self.pending.pop_front();
+            }
+        }
+
+        self.state = authoritative;
+        for (_, input) in &self.pending {
+            self.state.apply_input(input);
+        }
+    }
+
+    /// Returns an iterator over inputs not yet acknowledged by server
+    /// with their sequence numbers.
+    pub fn pending(&self) -> impl Iterator<Item = (u64, &S::Input)> + '_ {
+        self.pending.iter().map(|(seq, input)| (*seq, input))
+    }
+}
@@ -0,0 +1,145 @@
+//! Imperative prefabs that spawn components after their dependencies are loaded.
+//!
+//! Prefab starts loading all dependencies at once and receives them together
+//! when they are ready.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use edict::{component::Component, entity::EntityId, query::Entities, world::World};
+use futures::task::noop_waker_ref;
+use goods::Loader;
+
+use crate::{assets::Assets, scoped_allocator::ScopedAllocator};
+
+/// Prefab spawns components when all its dependencies are loaded.
+///
+/// `Loaded` may be any type, typically a tuple or struct of loaded assets.
+/// Dependencies are loaded concurrently when `load` joins their futures,
+/// e.g. with `futures::join!`.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Mutex;
+/// # use arcana::{assets::{Assets, Loader}, eyre, game::Game, prefab::{prefab_system, spawn_prefab, Prefab}, TimeSpan};
+/// # use edict::{component::Component, entity::EntityId, world::World};
+/// # use futures::{channel::oneshot, future::BoxFuture};
+/// #[derive(Component)]
+/// struct Health(u32);
+///
+/// /// Tank which health arrives later, e.g. from server.
+/// struct Tank {
+///     health: Mutex<Option<oneshot::Receiver<u32>>>,
+/// }
+///
+/// impl Prefab for Tank {
+///     type Loaded = u32;
+///     type Fut = BoxFuture<'static, eyre::Result<u32>>;
+///
+///     fn load(&self, _loader: &Loader) -> Self::Fut {
+///         let health = self.health.lock().unwrap().take().unwrap();
+///         Box::pin(async move { Ok(health.await?) })
+///     }
+///
+///     fn spawn(self, health: u32, world: &mut World, entity: EntityId) -> eyre::Result<()> {
+///         let _ = world.insert(entity, Health(health));
+///         Ok(())
+///     }
+/// }
+///
+/// let mut game = Game::deterministic(0);
+/// game.world.insert_resource(Assets::new(Loader::builder().build()));
+/// game.scheduler.add_system(prefab_system::<Tank>);
+///
+/// let (send, recv) = oneshot::channel();
+/// let tank = spawn_prefab(&mut game.world, Tank { health: Mutex::new(Some(recv)) });
+///
+/// // Components are not spawned until dependencies are loaded.
+/// game.step(TimeSpan::SECOND);
+/// assert!(game.world.query_one_mut::<&Health>(tank).is_err());
+///
+/// send.send(100).unwrap();
+/// game.step(TimeSpan::SECOND);
+/// assert_eq!(game.world.query_one_mut::<&Health>(tank).unwrap().0, 100);
+/// ```
+pub trait Prefab: Send + Sync + Sized + 'static {
+    /// Loaded dependencies.
+    type Loaded: Send + 'static;
+
+    /// Future that resolves when all dependencies are loaded.
+    type Fut: Future<Output = eyre::Result<Self::Loaded>> + Send + 'static;
+
+    /// Starts loading of all dependencies.
+    fn load(&self, loader: &Loader) -> Self::Fut;
+
+    /// Spawns prefab components on the entity using loaded dependencies.
+    fn spawn(self, loaded: Self::Loaded, world: &mut World, entity: EntityId) -> eyre::Result<()>;
+}
+
+/// Component of entity which prefab waits for dependencies.
+#[derive(Component)]
+#[edict(where P: Prefab)]
+pub struct PendingPrefab<P: Prefab> {
+    prefab: Option<P>,
+    fut: Pin<Box<P::Fut>>,
+}
+
+/// Spawns new entity and starts loading prefab dependencies.
+///
+/// Prefab components are spawned by [`prefab_system`] when loading completes.
+///
+/// # Panics
+///
+/// This function panics if [`Assets`] resource is missing.
+pub fn spawn_prefab<P>(world: &mut World, prefab: P) -> EntityId
+where
+    P: Prefab,
+{
+    let fut = prefab.load(&world.expect_resource::<Assets>().loader);
+
+    world.spawn((PendingPrefab {
+        prefab: Some(prefab),
+        fut: Box::pin(fut),
+    },))
+}
+
+/// Spawns components of prefabs of type `P` which dependencies are loaded.
+///
+/// Prefab whose dependencies fail to load is dropped with error logged.
+/// Its entity is left as is.
+pub fn prefab_system<P>(allocator: &mut ScopedAllocator, world: &mut World)
+where
+    P: Prefab,
+{
+    let mut cx = Context::from_waker(noop_waker_ref());
+    let mut ready = Vec::new_in(&**allocator);
+
+    for (entity, pending) in world
+        .query_mut::<(Entities, &mut PendingPrefab<P>)>()
+        .iter_mut()
+    {
+        if let Poll::Ready(result) = pending.fut.as_mut().poll(&mut cx) {
+            ready.push((entity, pending.prefab.take(), result));
+        }
+    }
+
+    for (entity, prefab, result) in ready {
+        let _ = world.remove::<PendingPrefab<P>>(entity);
+
+        let prefab = prefab.expect("Prefab is taken only once");
+        let result = result.and_then(|loaded| prefab.spawn(loaded, world, entity));
+
+        if let Err(err) = result {
+            tracing::error!(
+                "Failed to spawn prefab '{}' on entity '{}'. {:#}",
+                std::any::type_name::<P>(),
+                entity,
+                err
+            );
+        }
+    }
+}
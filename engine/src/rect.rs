@@ -13,6 +13,56 @@ use sierra::Format;
 #[cfg(feature = "graphics")]
 use crate::graphics::{Semantics, VertexAttribute};
 
+/// Axis-aligned rectangle defined by its edges.
+///
+/// Y axis points up, so `bottom` is the lower edge and `top` is the upper one,
+/// and well-formed rect has `left <= right` and `bottom <= top`.
+/// Rects with swapped edges are used to flip texture coordinates
+/// (see [`Rect::flip_vertical`] and [`Rect::flip_horizontal`]).
+/// Geometric predicates and set operations treat such rects
+/// as their [`normalized`](Rect::normalized) counterparts,
+/// while [`Rect::width`] and [`Rect::height`] are negative for them.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{na, rect::Rect};
+/// let a = Rect { left: 0.0, right: 2.0, bottom: 0.0, top: 2.0 };
+/// let b = Rect { left: 1.0, right: 3.0, bottom: 1.0, top: 3.0 };
+/// let far = Rect { left: 5.0, right: 6.0, bottom: 5.0, top: 6.0 };
+///
+/// // Overlapping.
+/// assert!(a.intersects(&b));
+/// assert_eq!(
+///     a.intersection(&b),
+///     Some(Rect { left: 1.0, right: 2.0, bottom: 1.0, top: 2.0 })
+/// );
+/// assert_eq!(a.union(&b), Rect { left: 0.0, right: 3.0, bottom: 0.0, top: 3.0 });
+///
+/// // Disjoint.
+/// assert!(!a.intersects(&far));
+/// assert_eq!(a.intersection(&far), None);
+///
+/// // Containment.
+/// let inner = Rect::from_center_size(na::Point2::new(1.0, 1.0), na::Vector2::new(1.0, 1.0));
+/// assert_eq!(inner, Rect { left: 0.5, right: 1.5, bottom: 0.5, top: 1.5 });
+/// assert!(a.contains(&na::Point2::new(1.0, 1.0)));
+/// assert!(!a.contains(&na::Point2::new(1.0, 2.5)));
+/// assert_eq!(a.intersection(&inner), Some(inner));
+/// assert_eq!(a.union(&inner), a);
+///
+/// // Inverted top and bottom.
+/// let flipped = a.flip_vertical();
+/// assert_eq!(flipped.height(), -2.0);
+/// assert_eq!(flipped.normalized(), a);
+/// assert!(flipped.contains(&na::Point2::new(1.0, 1.0)));
+/// assert!(flipped.intersects(&b));
+/// assert_eq!(flipped.intersection(&b), a.intersection(&b));
+/// assert_eq!(flipped.union(&far), a.union(&far));
+///
+/// // Scaling keeps the center.
+/// assert_eq!(a.scaled(0.5), Rect { left: 0.5, right: 1.5, bottom: 0.5, top: 1.5 });
+/// ```
 #[derive(Clone, Copy, PartialEq)]
 #[repr(C)]
 pub struct Rect<T = f32> {
@@ -212,17 +262,81 @@ where
     }
 }
 
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
 impl<T> Rect<T>
 where
     T: Scalar + PartialOrd,
 {
+    /// Returns rect with `left <= right` and `bottom <= top`
+    /// covering the same area.
+    pub fn normalized(&self) -> Rect<T> {
+        Rect {
+            left: min(self.left.clone(), self.right.clone()),
+            right: max(self.left.clone(), self.right.clone()),
+            bottom: min(self.bottom.clone(), self.top.clone()),
+            top: max(self.bottom.clone(), self.top.clone()),
+        }
+    }
+
+    /// Returns `true` if point lies inside the rect or on its edge.
     pub fn contains(&self, point: &na::Point2<T>) -> bool {
-        self.left <= point.x
-            && self.right >= point.x
-            && self.bottom <= point.y
-            && self.top >= point.y
+        let rect = self.normalized();
+        rect.left <= point.x
+            && rect.right >= point.x
+            && rect.bottom <= point.y
+            && rect.top >= point.y
+    }
+
+    /// Returns `true` if rects share at least one point.
+    pub fn intersects(&self, other: &Rect<T>) -> bool {
+        let (a, b) = (self.normalized(), other.normalized());
+        a.left <= b.right && a.right >= b.left && a.bottom <= b.top && a.top >= b.bottom
     }
 
+    /// Returns normalized rect shared by both rects
+    /// or `None` if they don't intersect.
+    pub fn intersection(&self, other: &Rect<T>) -> Option<Rect<T>> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        let (a, b) = (self.normalized(), other.normalized());
+        Some(Rect {
+            left: max(a.left, b.left),
+            right: min(a.right, b.right),
+            bottom: max(a.bottom, b.bottom),
+            top: min(a.top, b.top),
+        })
+    }
+
+    /// Returns smallest normalized rect containing both rects.
+    pub fn union(&self, other: &Rect<T>) -> Rect<T> {
+        let (a, b) = (self.normalized(), other.normalized());
+        Rect {
+            left: min(a.left, b.left),
+            right: max(a.right, b.right),
+            bottom: min(a.bottom, b.bottom),
+            top: max(a.top, b.top),
+        }
+    }
+
+    /// Returns smallest rect containing both rects.
+    /// Unlike [`Rect::union`] expects both rects to be normalized.
     pub fn overlap(&self, other: &Rect<T>) -> Rect<T> {
         Rect {
             left: if self.left < other.left {
@@ -274,14 +388,60 @@ impl<T> Rect<T>
 where
     T: Num + Copy,
 {
+    /// Returns rect of specified size centered at the point.
+    pub fn from_center_size(center: na::Point2<T>, size: na::Vector2<T>) -> Rect<T>
+    where
+        T: Scalar,
+    {
+        let two = T::one() + T::one();
+        let half_width = size.x / two;
+        let half_height = size.y / two;
+
+        Rect {
+            left: center.x - half_width,
+            right: center.x + half_width,
+            bottom: center.y - half_height,
+            top: center.y + half_height,
+        }
+    }
+
+    /// Returns width of the rect.
+    /// Negative if `left` is greater than `right`.
     pub fn width(&self) -> T {
         self.right - self.left
     }
 
+    /// Returns height of the rect.
+    /// Negative if `bottom` is greater than `top`.
     pub fn height(&self) -> T {
         self.top - self.bottom
     }
 
+    /// Returns center point of the rect.
+    pub fn center(&self) -> na::Point2<T>
+    where
+        T: Scalar,
+    {
+        let two = T::one() + T::one();
+        na::Point2::new(
+            self.left + self.width() / two,
+            self.bottom + self.height() / two,
+        )
+    }
+
+    /// Returns rect with size multiplied by `factor` and the same center.
+    /// Orientation of edges is preserved.
+    pub fn scaled(&self, factor: T) -> Rect<T>
+    where
+        T: Scalar,
+    {
+        let center = self.center();
+        Rect::from_center_size(
+            center,
+            na::Vector2::new(self.width() * factor, self.height() * factor),
+        )
+    }
+
     pub fn relative_to(&self, rhs: &Rect<T>) -> Rect<T> {
         let x = |x| (x - rhs.left) / (rhs.right - rhs.left);
         let y = |y| (y - rhs.bottom) / (rhs.top - rhs.bottom);
@@ -1,14 +1,38 @@
 use crate::noophash::NoopHasherBuilder;
 
 use {
-    hashbrown::hash_map::{Entry, HashMap},
+    hashbrown::{
+        hash_map::{Entry, HashMap},
+        HashSet,
+    },
     std::any::{Any, TypeId},
 };
 
 /// Resources map.
 /// Can contain up to one instance of a type.
+///
+/// Map tracks which resources were accessed mutably,
+/// so that systems may skip work when resource is unchanged.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::resources::Res;
+/// let mut res = Res::new();
+/// res.insert(1u32);
+/// assert!(res.reset_modified::<u32>());
+/// assert!(!res.modified::<u32>());
+///
+/// *res.get_mut::<u32>().unwrap() += 1;
+/// assert!(res.modified::<u32>());
+/// assert!(res.reset_modified::<u32>());
+///
+/// let _ = res.get::<u32>();
+/// assert!(!res.modified::<u32>());
+/// ```
 pub struct Res {
     map: HashMap<TypeId, Box<dyn Any + Send + Sync>, NoopHasherBuilder>,
+    modified: HashSet<TypeId, NoopHasherBuilder>,
 }
 
 impl Default for Res {
@@ -22,12 +46,16 @@ impl Res {
     pub fn new() -> Self {
         Res {
             map: HashMap::with_hasher(NoopHasherBuilder),
+            modified: HashSet::with_hasher(NoopHasherBuilder),
         }
     }
 
     /// Inserts value into the map.
     /// Returns old value of the same type if one was added into map before.
+    ///
+    /// Resource is marked as modified.
     pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.modified.insert(TypeId::of::<T>());
         match self.map.entry(TypeId::of::<T>()) {
             Entry::Occupied(mut entry) => {
                 let old = entry.get_mut().downcast_mut().unwrap();
@@ -48,18 +76,40 @@ impl Res {
             .map(|b| b.downcast_ref().unwrap())
     }
 
+    /// Returns `true` if value of requested type was added into map.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
     /// Returns mutable reference to value in the map.
     /// Returns `None` if value of requested type was not added into map before.
+    ///
+    /// Resource is marked as modified.
     pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
-        self.map
-            .get_mut(&TypeId::of::<T>())
-            .map(|b| b.downcast_mut().unwrap())
+        let value = self.map.get_mut(&TypeId::of::<T>())?;
+        self.modified.insert(TypeId::of::<T>());
+        Some(value.downcast_mut().unwrap())
     }
 
     /// Returns mutable reference to value in the map.
-    /// Executes provided closure and adds one into map if vale of requested
+    /// Executes provided closure and adds one into map if value of requested
     /// type was not added into map before.
-    pub fn with<T: Send + Sync + 'static>(&mut self, f: impl FnOnce() -> T) -> &mut T {
+    ///
+    /// Resource is marked as modified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::resources::Res;
+    /// let mut res = Res::new();
+    /// *res.get_or_insert_with(|| 1u32) += 1;
+    /// assert_eq!(*res.get_or_insert_with(|| 10u32), 2);
+    /// ```
+    pub fn get_or_insert_with<T: Send + Sync + 'static>(
+        &mut self,
+        f: impl FnOnce() -> T,
+    ) -> &mut T {
+        self.modified.insert(TypeId::of::<T>());
         self.map
             .entry(TypeId::of::<T>())
             .or_insert_with(|| Box::new(f()))
@@ -71,31 +121,73 @@ impl Res {
     /// Executes provided closure and adds one into map if vale of requested
     /// type was not added into map before.
     ///
-    /// Unlike [`Resources::with`] closure may fail returning error
+    /// Same as [`Res::get_or_insert_with`].
+    pub fn with<T: Send + Sync + 'static>(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        self.get_or_insert_with(f)
+    }
+
+    /// Returns mutable reference to value in the map.
+    /// Executes provided closure and adds one into map if vale of requested
+    /// type was not added into map before.
+    ///
+    /// Unlike [`Res::with`] closure may fail returning error
     /// which will be propagated back to caller.
     pub fn try_with<T: Send + Sync + 'static>(
         &mut self,
         f: impl FnOnce() -> eyre::Result<T>,
     ) -> eyre::Result<&mut T> {
-        match self.map.entry(TypeId::of::<T>()) {
-            Entry::Occupied(entry) => Ok(entry.into_mut().downcast_mut().unwrap()),
-            Entry::Vacant(entry) => {
-                let value = f()?;
-                Ok(entry.insert(Box::new(value)).downcast_mut().unwrap())
-            }
-        }
+        let value = match self.map.entry(TypeId::of::<T>()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Box::new(f()?)),
+        };
+        self.modified.insert(TypeId::of::<T>());
+        Ok(value.downcast_mut().unwrap())
     }
 
     /// Removes resource and returns it.
     pub fn remove<T: 'static>(&mut self) -> Option<Box<T>> {
+        self.modified.remove(&TypeId::of::<T>());
         self.map
             .remove(&TypeId::of::<T>())
             .map(|b| b.downcast().unwrap())
     }
 
+    /// Removes resource and returns it by value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::resources::Res;
+    /// let mut res = Res::new();
+    /// res.insert(String::from("level-1"));
+    ///
+    /// assert_eq!(res.take::<String>(), Some(String::from("level-1")));
+    /// assert!(!res.contains::<String>());
+    /// assert_eq!(res.take::<String>(), None);
+    /// ```
+    pub fn take<T: 'static>(&mut self) -> Option<T> {
+        self.remove().map(|b| *b)
+    }
+
+    /// Returns `true` if resource was accessed mutably
+    /// since it was inserted or since last [`Res::reset_modified`] call.
+    ///
+    /// Returns `false` if resource is absent.
+    pub fn modified<T: 'static>(&self) -> bool {
+        self.modified.contains(&TypeId::of::<T>())
+    }
+
+    /// Clears modified flag of the resource.
+    /// Returns previous value of the flag.
+    pub fn reset_modified<T: 'static>(&mut self) -> bool {
+        self.modified.remove(&TypeId::of::<T>())
+    }
+
     /// Query multiple resources at once.
     /// Items queried are expected in the `Resources`.
     /// To query optionally, wrap reference in `Option`.
+    ///
+    /// Resources queried by mutable reference are marked as modified.
     pub fn query<'a, Q>(&'a mut self) -> Q::Item
     where
         Q: Query<'a>,
@@ -239,6 +331,11 @@ mod sealed {
 
             fn get(res: &'a mut Res) -> ($($a,)+) {
                 assert!(<Self as QueryValid>::is_valid());
+                $(
+                    if $a::mutable() && res.map.contains_key(&$a::ty()) {
+                        res.modified.insert($a::ty());
+                    }
+                )+
                 unsafe { ($(
                     $a::get(res.map.get_mut(&$a::ty()).map(|b| NonNull::from(&mut **b))),
                 )+) }
@@ -1,28 +1,37 @@
-use std::fmt::{self, Display};
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+};
 
 use edict::{
     component::Component,
-    entity::EntityId,
     query::{Alt, Entities, Modified, With},
-    relation::{ChildOf, FilterNotRelates, Related, RelatesExclusive, Relation},
-    world::QueryRef,
+    relation::{FilterNotRelates, Related, RelatesExclusive, Relation},
+    world::{QueryRef, World},
 };
 use hashbrown::{HashMap, HashSet};
 
-use crate::scoped_allocator::ScopedAllocator;
-
+use crate::{scoped_allocator::ScopedAllocator, system::FixAlpha};
+
+/// Relation from child entity to its parent in transform hierarchy.
+///
+/// Entity has at most one parent.
+/// Children of an entity are queried with `Related<Parent>`.
+/// Children are despawned when their parent is despawned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Relation)]
+#[edict(exclusive, owned)]
+pub struct Parent;
+
+/// Transform of the entity relative to its [`Parent`].
+///
+/// [`scene_system2`] composes it with parent's [`Global2`]
+/// to produce entity's [`Global2`].
 #[cfg(feature = "2d")]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
 pub struct Local2 {
     pub iso: na::Isometry2<f32>,
 }
 
-#[cfg(feature = "2d")]
-impl Relation for Local2 {
-    const EXCLUSIVE: bool = true;
-    const OWNED: bool = true;
-}
-
 #[cfg(feature = "2d")]
 impl Display for Local2 {
     #[inline]
@@ -133,6 +142,50 @@ impl Global2 {
     pub fn to_affine(&self) -> na::Affine2<f32> {
         na::Affine2::from_matrix_unchecked(self.iso.to_homogeneous())
     }
+
+    /// Transforms point from local space to world space.
+    #[inline]
+    pub fn transform_point(&self, point: &na::Point2<f32>) -> na::Point2<f32> {
+        self.iso.transform_point(point)
+    }
+
+    /// Transforms vector from local space to world space.
+    /// Translation is not applied.
+    #[inline]
+    pub fn transform_vector(&self, vector: &na::Vector2<f32>) -> na::Vector2<f32> {
+        self.iso.transform_vector(vector)
+    }
+
+    /// Transforms point from world space to local space.
+    #[inline]
+    pub fn inverse_transform_point(&self, point: &na::Point2<f32>) -> na::Point2<f32> {
+        self.iso.inverse_transform_point(point)
+    }
+
+    /// Forward direction in world space.
+    /// Local Y axis, same as [`Cardinal::North`](crate::direction::Cardinal::North).
+    #[inline]
+    pub fn forward(&self) -> na::Vector2<f32> {
+        self.iso.rotation * na::Vector2::y()
+    }
+
+    /// Right direction in world space.
+    /// Local X axis.
+    #[inline]
+    pub fn right(&self) -> na::Vector2<f32> {
+        self.iso.rotation * na::Vector2::x()
+    }
+
+    /// Rotates to make [`Global2::forward`] face the target point.
+    /// Rotation is unchanged if target is at the position.
+    #[inline]
+    pub fn look_at(&mut self, target: &na::Point2<f32>) -> &mut Self {
+        let dir = target - na::Point2::from(self.iso.translation.vector);
+        if dir.norm_squared() > f32::EPSILON {
+            self.iso.rotation = na::UnitComplex::rotation_between(&na::Vector2::y(), &dir);
+        }
+        self
+    }
 }
 
 #[cfg(feature = "2d")]
@@ -153,18 +206,16 @@ impl From<na::Isometry2<f32>> for Global2 {
     }
 }
 
+/// Transform of the entity relative to its [`Parent`].
+///
+/// [`scene_system3`] composes it with parent's [`Global3`]
+/// to produce entity's [`Global3`].
 #[cfg(feature = "3d")]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
 pub struct Local3 {
     pub iso: na::Isometry3<f32>,
 }
 
-#[cfg(feature = "3d")]
-impl Relation for Local3 {
-    const EXCLUSIVE: bool = true;
-    const OWNED: bool = true;
-}
-
 #[cfg(feature = "3d")]
 impl Display for Local3 {
     #[inline]
@@ -262,6 +313,51 @@ impl Global3 {
     pub fn to_affine(&self) -> na::Affine3<f32> {
         na::Affine3::from_matrix_unchecked(self.iso.to_homogeneous())
     }
+
+    /// Transforms point from local space to world space.
+    pub fn transform_point(&self, point: &na::Point3<f32>) -> na::Point3<f32> {
+        self.iso.transform_point(point)
+    }
+
+    /// Transforms vector from local space to world space.
+    /// Translation is not applied.
+    pub fn transform_vector(&self, vector: &na::Vector3<f32>) -> na::Vector3<f32> {
+        self.iso.transform_vector(vector)
+    }
+
+    /// Transforms point from world space to local space.
+    pub fn inverse_transform_point(&self, point: &na::Point3<f32>) -> na::Point3<f32> {
+        self.iso.inverse_transform_point(point)
+    }
+
+    /// Forward direction in world space.
+    /// Negative local Z axis, same as camera view direction.
+    pub fn forward(&self) -> na::Vector3<f32> {
+        self.iso.rotation * -na::Vector3::z()
+    }
+
+    /// Right direction in world space.
+    /// Local X axis.
+    pub fn right(&self) -> na::Vector3<f32> {
+        self.iso.rotation * na::Vector3::x()
+    }
+
+    /// Up direction in world space.
+    /// Local Y axis.
+    pub fn up(&self) -> na::Vector3<f32> {
+        self.iso.rotation * na::Vector3::y()
+    }
+
+    /// Rotates to make [`Global3::forward`] face the target point.
+    /// `up` is the world-space direction local Y axis is aligned to.
+    /// Rotation is unchanged if target is at the position.
+    pub fn look_at(&mut self, target: &na::Point3<f32>, up: &na::Vector3<f32>) -> &mut Self {
+        let dir = target - na::Point3::from(self.iso.translation.vector);
+        if dir.norm_squared() > f32::EPSILON {
+            self.iso.rotation = na::UnitQuaternion::face_towards(&-dir, up);
+        }
+        self
+    }
 }
 
 #[cfg(feature = "3d")]
@@ -280,200 +376,413 @@ impl From<na::Isometry3<f32>> for Global3 {
     }
 }
 
+/// Transform of the entity at the previous fixed step.
+///
+/// Entities opt into transform interpolation by having this component.
+/// Fixed step systems that move entities should call [`store_prev_global2`]
+/// before they update [`Global2`].
+#[cfg(feature = "2d")]
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct PrevGlobal2 {
+    pub iso: na::Isometry2<f32>,
+}
+
+#[cfg(feature = "2d")]
+impl From<Global2> for PrevGlobal2 {
+    #[inline]
+    fn from(global: Global2) -> Self {
+        PrevGlobal2 { iso: global.iso }
+    }
+}
+
+/// Transform to render entity with.
+///
+/// Blend of [`PrevGlobal2`] and [`Global2`] computed by [`interpolate_transforms`].
+#[cfg(feature = "2d")]
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct RenderGlobal2 {
+    pub iso: na::Isometry2<f32>,
+}
+
+/// Transform of the entity at the previous fixed step.
+///
+/// Entities opt into transform interpolation by having this component.
+/// Fixed step systems that move entities should call [`store_prev_global3`]
+/// before they update [`Global3`].
+#[cfg(feature = "3d")]
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct PrevGlobal3 {
+    pub iso: na::Isometry3<f32>,
+}
+
+#[cfg(feature = "3d")]
+impl From<Global3> for PrevGlobal3 {
+    #[inline]
+    fn from(global: Global3) -> Self {
+        PrevGlobal3 { iso: global.iso }
+    }
+}
+
+/// Transform to render entity with.
+///
+/// Blend of [`PrevGlobal3`] and [`Global3`] computed by [`interpolate_transforms`].
+#[cfg(feature = "3d")]
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct RenderGlobal3 {
+    pub iso: na::Isometry3<f32>,
+}
+
+/// Copies [`Global2`] into [`PrevGlobal2`] for all entities that have both.
+#[cfg(feature = "2d")]
+pub fn store_prev_global2(world: &mut World) {
+    for (global, prev) in world
+        .query_mut::<(&Global2, &mut PrevGlobal2)>()
+        .iter_mut()
+    {
+        prev.iso = global.iso;
+    }
+}
+
+/// Copies [`Global3`] into [`PrevGlobal3`] for all entities that have both.
+#[cfg(feature = "3d")]
+pub fn store_prev_global3(world: &mut World) {
+    for (global, prev) in world
+        .query_mut::<(&Global3, &mut PrevGlobal3)>()
+        .iter_mut()
+    {
+        prev.iso = global.iso;
+    }
+}
+
+#[cfg(feature = "2d")]
+fn interpolate_iso2(
+    prev: &na::Isometry2<f32>,
+    next: &na::Isometry2<f32>,
+    alpha: f32,
+) -> na::Isometry2<f32> {
+    na::Isometry2::from_parts(
+        prev.translation
+            .vector
+            .lerp(&next.translation.vector, alpha)
+            .into(),
+        prev.rotation.slerp(&next.rotation, alpha),
+    )
+}
+
+#[cfg(feature = "3d")]
+fn interpolate_iso3(
+    prev: &na::Isometry3<f32>,
+    next: &na::Isometry3<f32>,
+    alpha: f32,
+) -> na::Isometry3<f32> {
+    // Slerp is ambiguous for opposite rotations. Snap to the latest one then.
+    let rotation = prev
+        .rotation
+        .try_slerp(&next.rotation, alpha, f32::EPSILON)
+        .unwrap_or(next.rotation);
+
+    na::Isometry3::from_parts(
+        prev.translation
+            .vector
+            .lerp(&next.translation.vector, alpha)
+            .into(),
+        rotation,
+    )
+}
+
+/// Computes render transforms blending previous and current fixed step transforms
+/// with [`FixAlpha`] factor.
+///
+/// Add this system once, after fixed systems.
+/// Renderers use [`RenderGlobal2`] and [`RenderGlobal3`] when present instead of globals.
+pub fn interpolate_transforms(allocator: &mut ScopedAllocator, world: &mut World) {
+    let alpha = world.get_resource::<FixAlpha>().map_or(1.0, |alpha| alpha.0);
+
+    #[cfg(feature = "2d")]
+    {
+        let mut new_entities = Vec::new_in(&**allocator);
+        for e in world
+            .query_mut::<Entities>()
+            .with::<PrevGlobal2>()
+            .with::<Global2>()
+            .without::<RenderGlobal2>()
+            .iter()
+        {
+            new_entities.push(e);
+        }
+
+        for e in new_entities {
+            let iso = na::Isometry2::identity();
+            world.insert(e, RenderGlobal2 { iso }).unwrap();
+        }
+
+        for (prev, global, render) in world
+            .query_mut::<(&PrevGlobal2, &Global2, &mut RenderGlobal2)>()
+            .iter_mut()
+        {
+            render.iso = interpolate_iso2(&prev.iso, &global.iso, alpha);
+        }
+    }
+
+    #[cfg(feature = "3d")]
+    {
+        let mut new_entities = Vec::new_in(&**allocator);
+        for e in world
+            .query_mut::<Entities>()
+            .with::<PrevGlobal3>()
+            .with::<Global3>()
+            .without::<RenderGlobal3>()
+            .iter()
+        {
+            new_entities.push(e);
+        }
+
+        for e in new_entities {
+            let iso = na::Isometry3::identity();
+            world.insert(e, RenderGlobal3 { iso }).unwrap();
+        }
+
+        for (prev, global, render) in world
+            .query_mut::<(&PrevGlobal3, &Global3, &mut RenderGlobal3)>()
+            .iter_mut()
+        {
+            render.iso = interpolate_iso3(&prev.iso, &global.iso, alpha);
+        }
+    }
+}
+
+/// Propagates transforms down the hierarchy built with [`Parent`] relation.
+///
+/// Sets [`Global2`] of each child to parent's [`Global2`] composed with child's [`Local2`].
+/// Only subtrees of children with modified [`Local2`] and of roots with modified [`Global2`]
+/// are updated. Parents are always updated before their children.
+/// Entities that are their own ancestors are skipped.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{game::Game, na, scene::{scene_system2, Global2, Local2, Parent}, TimeSpan};
+/// let (root_iso, parent_local, child_local) = (
+///     na::Isometry2::new(na::Vector2::new(1.0, 2.0), 0.5),
+///     na::Isometry2::new(na::Vector2::new(3.0, 0.0), 1.0),
+///     na::Isometry2::new(na::Vector2::new(0.0, -1.0), -0.25),
+/// );
+/// let mut game = Game::deterministic(0);
+/// game.scheduler.add_system(scene_system2);
+///
+/// let root = game.world.spawn((Global2::new(root_iso),));
+/// let parent = game.world.spawn((Local2::new(parent_local), Global2::identity()));
+/// let child = game.world.spawn((Local2::new(child_local), Global2::identity()));
+/// game.world.add_relation(parent, Parent, root).unwrap();
+/// game.world.add_relation(child, Parent, parent).unwrap();
+///
+/// game.step(TimeSpan::ZERO);
+///
+/// let parent_global = *game.world.query_one_mut::<&Global2>(&parent).unwrap();
+/// let child_global = *game.world.query_one_mut::<&Global2>(&child).unwrap();
+/// assert_eq!(parent_global.iso, root_iso * parent_local);
+/// assert_eq!(child_global.iso, parent_global.iso * child_local);
+/// ```
 #[cfg(feature = "2d")]
 pub fn scene_system2(
-    mut roots_modified: QueryRef<
-        (Entities, Modified<&Global2>, Related<ChildOf>),
-        FilterNotRelates<ChildOf>,
-    >,
-    mut modified: QueryRef<(
-        Entities,
-        RelatesExclusive<&ChildOf>,
-        Modified<&Local2>,
-        &Global2,
-    )>,
-    mut children: QueryRef<Related<ChildOf>, (With<Local2>, With<Global2>)>,
+    mut roots_modified: QueryRef<(Modified<&Global2>, Related<Parent>), FilterNotRelates<Parent>>,
+    mut modified: QueryRef<(Entities, Modified<&Local2>, RelatesExclusive<&Parent>), With<Global2>>,
+    mut parents: QueryRef<RelatesExclusive<&Parent>>,
+    mut children: QueryRef<Related<Parent>, (With<Local2>, With<Global2>)>,
     mut global: QueryRef<&Global2>,
     mut update: QueryRef<(&Local2, Alt<Global2>)>,
     scope: &mut ScopedAllocator,
 ) {
-    use std::collections::VecDeque;
-
-    let mut subtrees =
-        HashMap::<EntityId, (EntityId, Vec<(EntityId, EntityId), _>), _, _>::new_in(&**scope);
-    let mut visited = HashSet::new_in(&**scope);
-    let mut to_visit = Vec::new_in(&**scope);
-    let mut globals = HashMap::new_in(&**scope);
+    let mut dirty = HashSet::new_in(&**scope);
 
-    roots_modified.for_each(|(entity, global, children)| {
-        globals.insert(entity, *global);
-        for &child in children {
-            to_visit.push((entity, child));
-        }
+    roots_modified.for_each(|(_global, children)| {
+        dirty.extend(children.iter().copied());
     });
     drop(roots_modified);
 
-    modified.for_each(|(entity, (ChildOf, parent), _local, global)| {
-        globals.insert(entity, *global);
-        to_visit.push((parent, entity));
+    modified.for_each(|(entity, _local, _parent)| {
+        dirty.insert(entity);
     });
     drop(modified);
 
-    for (parent, entity) in to_visit.drain(..) {
-        if !visited.insert(entity) {
-            continue;
+    // Subtrees of dirty entities without dirty ancestors cover all dirty entities.
+    let mut tops = Vec::new_in(&**scope);
+    let mut ancestors = HashSet::new_in(&**scope);
+
+    'dirty: for &entity in dirty.iter() {
+        let (Parent, parent) = match parents.get_one(entity) {
+            Ok(relation) => relation,
+            Err(_) => continue,
+        };
+
+        ancestors.clear();
+        ancestors.insert(entity);
+
+        let mut ancestor = parent;
+        loop {
+            if !ancestors.insert(ancestor) {
+                tracing::warn!(
+                    "Entity {} is in hierarchy cycle. Transform is not updated",
+                    entity
+                );
+                continue 'dirty;
+            }
+            if dirty.contains(&ancestor) {
+                continue 'dirty;
+            }
+            match parents.get_one(ancestor) {
+                Ok((Parent, parent)) => ancestor = parent,
+                Err(_) => break,
+            }
         }
 
-        let mut subtree = Vec::new_in(&**scope);
-
-        let mut to_visit = VecDeque::new_in(&**scope);
+        tops.push((parent, entity));
+    }
+    drop(parents);
 
-        if let Ok(children) = children.get_one(entity) {
-            for child in children {
-                to_visit.push_back((entity, *child));
-            }
-        }
+    // Breadth-first order puts parents before their children.
+    let mut order = Vec::new_in(&**scope);
+    let mut queue = VecDeque::new_in(&**scope);
+    queue.extend(tops.iter().copied());
 
-        while let Some((parent, entity)) = to_visit.pop_front() {
-            subtree.push((parent, entity));
-
-            match subtrees.get(&entity) {
-                None => {
-                    let old = visited.insert(entity);
-                    debug_assert!(old, "Entity visited twice");
-                    if let Ok(children) = children.get_one(entity) {
-                        for child in children {
-                            to_visit.push_back((entity, *child));
-                        }
-                    }
-                }
-                Some((e, child_subtree)) => {
-                    debug_assert!(visited.contains(&entity));
-                    debug_assert_eq!(*e, entity);
-                    subtree.extend_from_slice(child_subtree);
-                }
+    while let Some((parent, entity)) = queue.pop_front() {
+        order.push((parent, entity));
+        if let Ok(entity_children) = children.get_one(entity) {
+            for &child in entity_children {
+                queue.push_back((entity, child));
             }
         }
-
-        subtrees.insert(entity, (parent, subtree));
     }
-    drop(to_visit);
-    drop(visited);
+    drop(children);
 
-    for (_, (parent, _)) in &subtrees {
-        globals
-            .entry(*parent)
-            .or_insert_with(|| global.get_one_copied(*parent).unwrap());
+    let mut globals = HashMap::new_in(&**scope);
+    for &(parent, _) in tops.iter() {
+        let parent_global = global.get_one_copied(parent).unwrap_or_default();
+        globals.insert(parent, parent_global);
     }
     drop(global);
 
-    for (entity, (parent, subtree)) in subtrees {
-        let (local, mut global) = update.get_one(entity).unwrap();
-        global.iso = globals[&parent].iso * local.iso;
-        globals.insert(entity, *global);
-
-        for (parent, entity) in subtree {
-            let (local, mut global) = update.get_one(entity).unwrap();
+    for (parent, entity) in order {
+        if let Ok((local, mut global)) = update.get_one(entity) {
             global.iso = globals[&parent].iso * local.iso;
             globals.insert(entity, *global);
         }
     }
 }
 
+/// Propagates transforms down the hierarchy built with [`Parent`] relation.
+///
+/// Sets [`Global3`] of each child to parent's [`Global3`] composed with child's [`Local3`].
+/// Only subtrees of children with modified [`Local3`] and of roots with modified [`Global3`]
+/// are updated. Parents are always updated before their children.
+/// Entities that are their own ancestors are skipped.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{game::Game, na, scene::{scene_system3, Global3, Local3, Parent}, TimeSpan};
+/// let (root_iso, parent_local, child_local) = (
+///     na::Isometry3::translation(1.0, 2.0, 3.0),
+///     na::Isometry3::rotation(na::Vector3::y() * 0.5),
+///     na::Isometry3::translation(0.0, -1.0, 0.0),
+/// );
+/// let mut game = Game::deterministic(0);
+/// game.scheduler.add_system(scene_system3);
+///
+/// let root = game.world.spawn((Global3::new(root_iso),));
+/// let parent = game.world.spawn((Local3::new(parent_local), Global3::identity()));
+/// let child = game.world.spawn((Local3::new(child_local), Global3::identity()));
+/// game.world.add_relation(parent, Parent, root).unwrap();
+/// game.world.add_relation(child, Parent, parent).unwrap();
+///
+/// game.step(TimeSpan::ZERO);
+///
+/// let parent_global = *game.world.query_one_mut::<&Global3>(&parent).unwrap();
+/// let child_global = *game.world.query_one_mut::<&Global3>(&child).unwrap();
+/// assert_eq!(parent_global.iso, root_iso * parent_local);
+/// assert_eq!(child_global.iso, parent_global.iso * child_local);
+/// ```
 #[cfg(feature = "3d")]
 pub fn scene_system3(
-    mut roots_modified: QueryRef<
-        (Entities, Modified<&Global3>, Related<ChildOf>),
-        FilterNotRelates<ChildOf>,
-    >,
-    mut modified: QueryRef<(
-        Entities,
-        RelatesExclusive<&ChildOf>,
-        Modified<&Local3>,
-        &Global3,
-    )>,
-    mut children: QueryRef<Related<ChildOf>, (With<Local3>, With<Global3>)>,
+    mut roots_modified: QueryRef<(Modified<&Global3>, Related<Parent>), FilterNotRelates<Parent>>,
+    mut modified: QueryRef<(Entities, Modified<&Local3>, RelatesExclusive<&Parent>), With<Global3>>,
+    mut parents: QueryRef<RelatesExclusive<&Parent>>,
+    mut children: QueryRef<Related<Parent>, (With<Local3>, With<Global3>)>,
     mut global: QueryRef<&Global3>,
     mut update: QueryRef<(&Local3, Alt<Global3>)>,
     scope: &mut ScopedAllocator,
 ) {
-    use std::collections::VecDeque;
-
-    let mut subtrees =
-        HashMap::<EntityId, (EntityId, Vec<(EntityId, EntityId), _>), _, _>::new_in(&**scope);
-    let mut visited = HashSet::new_in(&**scope);
-    let mut to_visit = Vec::new_in(&**scope);
-    let mut globals = HashMap::new_in(&**scope);
+    let mut dirty = HashSet::new_in(&**scope);
 
-    roots_modified.for_each(|(entity, global, children)| {
-        globals.insert(entity, *global);
-        for &child in children {
-            to_visit.push((entity, child));
-        }
+    roots_modified.for_each(|(_global, children)| {
+        dirty.extend(children.iter().copied());
     });
     drop(roots_modified);
 
-    modified.for_each(|(entity, (ChildOf, parent), _local, global)| {
-        globals.insert(entity, *global);
-        to_visit.push((parent, entity));
+    modified.for_each(|(entity, _local, _parent)| {
+        dirty.insert(entity);
     });
     drop(modified);
 
-    for (parent, entity) in to_visit.drain(..) {
-        if !visited.insert(entity) {
-            continue;
+    // Subtrees of dirty entities without dirty ancestors cover all dirty entities.
+    let mut tops = Vec::new_in(&**scope);
+    let mut ancestors = HashSet::new_in(&**scope);
+
+    'dirty: for &entity in dirty.iter() {
+        let (Parent, parent) = match parents.get_one(entity) {
+            Ok(relation) => relation,
+            Err(_) => continue,
+        };
+
+        ancestors.clear();
+        ancestors.insert(entity);
+
+        let mut ancestor = parent;
+        loop {
+            if !ancestors.insert(ancestor) {
+                tracing::warn!(
+                    "Entity {} is in hierarchy cycle. Transform is not updated",
+                    entity
+                );
+                continue 'dirty;
+            }
+            if dirty.contains(&ancestor) {
+                continue 'dirty;
+            }
+            match parents.get_one(ancestor) {
+                Ok((Parent, parent)) => ancestor = parent,
+                Err(_) => break,
+            }
         }
 
-        let mut subtree = Vec::new_in(&**scope);
-
-        let mut to_visit = VecDeque::new_in(&**scope);
+        tops.push((parent, entity));
+    }
+    drop(parents);
 
-        if let Ok(children) = children.get_one(entity) {
-            for child in children {
-                to_visit.push_back((entity, *child));
-            }
-        }
+    // Breadth-first order puts parents before their children.
+    let mut order = Vec::new_in(&**scope);
+    let mut queue = VecDeque::new_in(&**scope);
+    queue.extend(tops.iter().copied());
 
-        while let Some((parent, entity)) = to_visit.pop_front() {
-            subtree.push((parent, entity));
-
-            match subtrees.get(&entity) {
-                None => {
-                    let old = visited.insert(entity);
-                    debug_assert!(old, "Entity visited twice");
-                    if let Ok(children) = children.get_one(entity) {
-                        for child in children {
-                            to_visit.push_back((entity, *child));
-                        }
-                    }
-                }
-                Some((e, child_subtree)) => {
-                    debug_assert!(visited.contains(&entity));
-                    debug_assert_eq!(*e, entity);
-                    subtree.extend_from_slice(child_subtree);
-                }
+    while let Some((parent, entity)) = queue.pop_front() {
+        order.push((parent, entity));
+        if let Ok(entity_children) = children.get_one(entity) {
+            for &child in entity_children {
+                queue.push_back((entity, child));
             }
         }
-
-        subtrees.insert(entity, (parent, subtree));
     }
-    drop(to_visit);
-    drop(visited);
+    drop(children);
 
-    for (_, (parent, _)) in &subtrees {
-        globals
-            .entry(*parent)
-            .or_insert_with(|| global.get_one_copied(*parent).unwrap());
+    let mut globals = HashMap::new_in(&**scope);
+    for &(parent, _) in tops.iter() {
+        let parent_global = global.get_one_copied(parent).unwrap_or_default();
+        globals.insert(parent, parent_global);
     }
     drop(global);
 
-    for (entity, (parent, subtree)) in subtrees {
-        let (local, mut global) = update.get_one(entity).unwrap();
-        global.iso = globals[&parent].iso * local.iso;
-        globals.insert(entity, *global);
-
-        for (parent, entity) in subtree {
-            let (local, mut global) = update.get_one(entity).unwrap();
+    for (parent, entity) in order {
+        if let Ok((local, mut global)) = update.get_one(entity) {
             global.iso = globals[&parent].iso * local.iso;
             globals.insert(entity, *global);
         }
@@ -0,0 +1,241 @@
+//! Interpolation of values received from network.
+//!
+//! Replicated values arrive irregularly and rendering them as they come
+//! makes objects jitter. [`SnapshotBuffer`] keeps few last timestamped snapshots
+//! and yields value interpolated at a moment slightly in the past,
+//! so that there is usually a pair of snapshots around it.
+
+use std::collections::VecDeque;
+
+use edict::component::Component;
+
+use crate::{clocks::TimeStamp, tween::Lerp, TimeSpan};
+
+#[cfg(any(feature = "2d", feature = "3d"))]
+use edict::{query::Entities, world::World};
+
+#[cfg(any(feature = "2d", feature = "3d"))]
+use crate::{clocks::ClockIndex, scoped_allocator::ScopedAllocator};
+
+#[cfg(feature = "2d")]
+use crate::scene::{Global2, RenderGlobal2};
+
+#[cfg(feature = "3d")]
+use crate::scene::{Global3, RenderGlobal3};
+
+/// Default number of snapshots kept by [`SnapshotBuffer`].
+pub const DEFAULT_SNAPSHOT_CAPACITY: usize = 8;
+
+/// Buffer of the last few timestamped snapshots of a value.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{snapshot::SnapshotBuffer, TimeSpan, TimeStamp};
+/// let ms = |ms: u64| TimeStamp::ORIGIN + TimeSpan::MILLISECOND * ms;
+///
+/// let mut buffer = SnapshotBuffer::new(TimeSpan::MILLISECOND * 100);
+/// buffer.push(ms(0), 0.0f32);
+/// buffer.push(ms(200), 30.0);
+/// buffer.push(ms(100), 10.0); // Arrived out of order.
+///
+/// // Sampled 100ms in the past, between second and third snapshots.
+/// assert_eq!(buffer.sample(ms(250)), Some(20.0));
+///
+/// // No snapshot after sampled moment. Clamped to the latest one.
+/// assert_eq!(buffer.sample(ms(400)), Some(30.0));
+///
+/// // Before the earliest snapshot.
+/// assert_eq!(buffer.sample(ms(50)), Some(0.0));
+/// ```
+#[derive(Clone, Debug, Component)]
+#[edict(where T: 'static)]
+pub struct SnapshotBuffer<T> {
+    snapshots: VecDeque<(TimeStamp, T)>,
+    capacity: usize,
+    delay: TimeSpan,
+}
+
+impl<T> SnapshotBuffer<T>
+where
+    T: Lerp,
+{
+    /// Returns new empty buffer that samples values `delay` in the past.
+    ///
+    /// Delay should be somewhat larger than interval between snapshots,
+    /// otherwise sampled value is often clamped to the latest snapshot.
+    pub fn new(delay: TimeSpan) -> Self {
+        SnapshotBuffer {
+            snapshots: VecDeque::with_capacity(DEFAULT_SNAPSHOT_CAPACITY),
+            capacity: DEFAULT_SNAPSHOT_CAPACITY,
+            delay,
+        }
+    }
+
+    /// Sets number of snapshots kept in the buffer.
+    /// At least two snapshots are kept.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(2);
+        while self.snapshots.len() > self.capacity {
+            self.snapshots.pop_front();
+        }
+        self
+    }
+
+    /// Returns interpolation delay.
+    pub fn delay(&self) -> TimeSpan {
+        self.delay
+    }
+
+    /// Sets interpolation delay.
+    pub fn set_delay(&mut self, delay: TimeSpan) {
+        self.delay = delay;
+    }
+
+    /// Returns the latest snapshot.
+    pub fn latest(&self) -> Option<&T> {
+        self.snapshots.back().map(|(_, value)| value)
+    }
+
+    /// Adds snapshot taken at `time`.
+    ///
+    /// Snapshots that arrive out of order are put in place.
+    /// Snapshot with the same time replaces previous one.
+    /// Snapshot older than all buffered ones is dropped when buffer is full.
+    pub fn push(&mut self, time: TimeStamp, value: T) {
+        let idx = self.snapshots.partition_point(|(t, _)| *t <= time);
+
+        if idx > 0 && self.snapshots[idx - 1].0 == time {
+            self.snapshots[idx - 1].1 = value;
+            return;
+        }
+
+        if idx == 0 && self.snapshots.len() >= self.capacity {
+            return;
+        }
+
+        self.snapshots.insert(idx, (time, value));
+        while self.snapshots.len() > self.capacity {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Removes all snapshots.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Returns value at `now` minus delay,
+    /// interpolated between the pair of snapshots around that moment.
+    ///
+    /// Value is clamped to the latest snapshot when there is none after that moment,
+    /// e.g. when packets are dropped or late,
+    /// and to the earliest one when there is none before.
+    pub fn sample(&self, now: TimeStamp) -> Option<T> {
+        let target = TimeStamp::ORIGIN + now.elapsed().saturating_sub(self.delay);
+        let idx = self.snapshots.partition_point(|(t, _)| *t <= target);
+
+        if idx == 0 {
+            return self.snapshots.front().map(|(_, value)| value.clone());
+        }
+
+        if idx == self.snapshots.len() {
+            return self.snapshots.back().map(|(_, value)| value.clone());
+        }
+
+        let (from_time, from) = &self.snapshots[idx - 1];
+        let (to_time, to) = &self.snapshots[idx];
+
+        let span = to_time.duration_since(*from_time);
+        let elapsed = target.duration_since(*from_time);
+        let t = elapsed.as_nanos() as f64 / span.as_nanos() as f64;
+
+        Some(from.lerp(to, t as f32))
+    }
+}
+
+/// Buffers replicated [`Global2`] of entities with `SnapshotBuffer<Isometry2>`
+/// and writes interpolated transform into [`RenderGlobal2`].
+///
+/// Snapshot is taken whenever `Global2` differs from the latest buffered one.
+/// Snapshots are timestamped with real time, so they are not affected by game pause.
+#[cfg(feature = "2d")]
+pub fn snapshot_system2(allocator: &mut ScopedAllocator, world: &mut World) {
+    let now = world.expect_resource::<ClockIndex>().real_now;
+
+    let mut new_entities = Vec::new_in(&**allocator);
+    for e in world
+        .query_mut::<Entities>()
+        .with::<SnapshotBuffer<na::Isometry2<f32>>>()
+        .with::<Global2>()
+        .without::<RenderGlobal2>()
+        .iter()
+    {
+        new_entities.push(e);
+    }
+
+    for e in new_entities {
+        let iso = na::Isometry2::identity();
+        world.insert(e, RenderGlobal2 { iso }).unwrap();
+    }
+
+    for (global, buffer, render) in world
+        .query_mut::<(
+            &Global2,
+            &mut SnapshotBuffer<na::Isometry2<f32>>,
+            &mut RenderGlobal2,
+        )>()
+        .iter_mut()
+    {
+        if buffer.latest() != Some(&global.iso) {
+            buffer.push(now, global.iso);
+        }
+
+        if let Some(iso) = buffer.sample(now) {
+            render.iso = iso;
+        }
+    }
+}
+
+/// Buffers replicated [`Global3`] of entities with `SnapshotBuffer<Isometry3>`
+/// and writes interpolated transform into [`RenderGlobal3`].
+///
+/// Snapshot is taken whenever `Global3` differs from the latest buffered one.
+/// Snapshots are timestamped with real time, so they are not affected by game pause.
+#[cfg(feature = "3d")]
+pub fn snapshot_system3(allocator: &mut ScopedAllocator, world: &mut World) {
+    let now = world.expect_resource::<ClockIndex>().real_now;
+
+    let mut new_entities = Vec::new_in(&**allocator);
+    for e in world
+        .query_mut::<Entities>()
+        .with::<SnapshotBuffer<na::Isometry3<f32>>>()
+        .with::<Global3>()
+        .without::<RenderGlobal3>()
+        .iter()
+    {
+        new_entities.push(e);
+    }
+
+    for e in new_entities {
+        let iso = na::Isometry3::identity();
+        world.insert(e, RenderGlobal3 { iso }).unwrap();
+    }
+
+    for (global, buffer, render) in world
+        .query_mut::<(
+            &Global3,
+            &mut SnapshotBuffer<na::Isometry3<f32>>,
+            &mut RenderGlobal3,
+        )>()
+        .iter_mut()
+    {
+        if buffer.latest() != Some(&global.iso) {
+            buffer.push(now, global.iso);
+        }
+
+        if let Some(iso) = buffer.sample(now) {
+            render.iso = iso;
+        }
+    }
+}
@@ -1,14 +1,14 @@
-use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+use std::{borrow::Cow, collections::VecDeque, marker::PhantomData, ops::Range, sync::Arc};
 
-use edict::{system::Res, world::QueryRef};
+use edict::{component::Component, system::Res, world::QueryRef};
 
-use crate::{clocks::ClockIndex, rect::Rect};
-
-use super::{
-    graph::{AnimGraph, AnimGraphState, AnimNode, AnimTransitionRule, Transition},
-    Sprite, SpriteFrame, SpriteSheet, SpriteSize,
+use crate::{
+    anim::{AnimGraph, AnimNode, AnimTransitionRule, GraphAnimation, PlaybackMode, Transition},
+    clocks::{ClockIndex, TimeSpan},
 };
 
+use super::{Sprite, SpriteFrame, SpriteSheet, SpriteSize};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum FaceDirection {
     Left,
@@ -21,18 +21,30 @@ pub struct FrameSpan {
     to: usize,
 }
 
+/// Sprite sheet animation driven by [`AnimGraph`].
+///
+/// Thin wrapper over [`GraphAnimation`] that maps frame spans to sprite frames.
+///
+/// Frames may be assigned events of type `E`
+/// that are pushed into entity's [`AnimEventQueue`] when frames are entered.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct SpriteGraphAnimation<R> {
+pub struct SpriteGraphAnimation<R, E = ()> {
     frames: Arc<[SpriteFrame]>,
     tex_size: SpriteSize,
-    graph: Arc<AnimGraph<FrameSpan, R>>,
-    state: AnimGraphState,
+    animation: GraphAnimation<FrameSpan, R>,
+
+    /// Events of frames for each animation, sorted by frame index.
+    #[serde(default = "Vec::new")]
+    frame_events: Vec<Vec<(usize, E)>>,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum SpriteAnimationError<'a> {
     #[error("Failed to find animation by name")]
     AnimationNotFound(Cow<'a, str>),
+
+    #[error("Frame {frame} is out of animation bounds")]
+    FrameOutOfBounds { frame: usize },
 }
 
 impl<'a> SpriteAnimationError<'a> {
@@ -41,11 +53,54 @@ impl<'a> SpriteAnimationError<'a> {
             SpriteAnimationError::AnimationNotFound(name) => {
                 SpriteAnimationError::AnimationNotFound(Cow::Owned(name.into_owned()))
             }
+            SpriteAnimationError::FrameOutOfBounds { frame } => {
+                SpriteAnimationError::FrameOutOfBounds { frame }
+            }
+        }
+    }
+}
+
+/// Queue of animation events of an entity.
+///
+/// Filled by [`sprite_graph_animation_system`] with events of entered frames
+/// in the order frames were entered.
+/// Events are kept until drained.
+#[derive(Clone, Debug, Component)]
+#[edict(where E: 'static)]
+pub struct AnimEventQueue<E> {
+    events: VecDeque<E>,
+}
+
+impl<E> Default for AnimEventQueue<E> {
+    fn default() -> Self {
+        AnimEventQueue::new()
+    }
+}
+
+impl<E> AnimEventQueue<E> {
+    pub const fn new() -> Self {
+        AnimEventQueue {
+            events: VecDeque::new(),
         }
     }
+
+    /// Returns `true` if there are no events in the queue.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Removes oldest event from the queue.
+    pub fn pop(&mut self) -> Option<E> {
+        self.events.pop_front()
+    }
+
+    /// Removes all events from the queue, oldest first.
+    pub fn drain(&mut self) -> impl Iterator<Item = E> + '_ {
+        self.events.drain(..)
+    }
 }
 
-impl<R> SpriteGraphAnimation<R> {
+impl<R, E> SpriteGraphAnimation<R, E> {
     pub fn new<'a>(
         entry_animation: &'a str,
         sheet: &SpriteSheet,
@@ -83,6 +138,7 @@ impl<R> SpriteGraphAnimation<R> {
                             }
                         })
                         .collect(),
+                    playback: a.playback,
                 })
                 .collect(),
             transitions: transitions
@@ -104,23 +160,66 @@ impl<R> SpriteGraphAnimation<R> {
         Ok(SpriteGraphAnimation {
             frames: sheet.frames.clone(),
             tex_size: sheet.tex_size,
-            graph,
-            state: AnimGraphState::new(entry_animation),
+            animation: GraphAnimation::new(graph, entry_animation),
+            frame_events: Vec::new(),
         })
     }
+
+    /// Assigns events to frames of the animation.
+    ///
+    /// Frame indices are relative to the first frame of the animation.
+    /// Event is emitted every time its frame is entered,
+    /// including frames skipped over within single update.
+    pub fn with_frame_events<'a>(
+        mut self,
+        sheet: &SpriteSheet,
+        animation: &'a str,
+        events: impl IntoIterator<Item = (usize, E)>,
+    ) -> Result<Self, SpriteAnimationError<'a>> {
+        let (index, a) = sheet
+            .animations
+            .iter()
+            .enumerate()
+            .find(|(_, a)| *a.name == *animation)
+            .ok_or(SpriteAnimationError::AnimationNotFound(animation.into()))?;
+
+        let count = a.to - a.from + 1;
+
+        if self.frame_events.len() <= index {
+            self.frame_events.resize_with(index + 1, Vec::new);
+        }
+
+        let frame_events = &mut self.frame_events[index];
+        for (frame, event) in events {
+            if frame >= count {
+                return Err(SpriteAnimationError::FrameOutOfBounds { frame });
+            }
+            frame_events.push((frame, event));
+        }
+
+        // Stable sort keeps order of events of the same frame.
+        frame_events.sort_by_key(|(frame, _)| *frame);
+
+        Ok(self)
+    }
+
+    /// Returns underlying graph animation.
+    pub fn animation(&self) -> &GraphAnimation<FrameSpan, R> {
+        &self.animation
+    }
 }
 
-pub struct SpriteGraphAnimationSystem<S, R> {
-    marker: PhantomData<fn() -> (S, R)>,
+pub struct SpriteGraphAnimationSystem<S, R, E = ()> {
+    marker: PhantomData<fn() -> (S, R, E)>,
 }
 
-impl<S, R> Default for SpriteGraphAnimationSystem<S, R> {
+impl<S, R, E> Default for SpriteGraphAnimationSystem<S, R, E> {
     fn default() -> Self {
         SpriteGraphAnimationSystem::new()
     }
 }
 
-impl<S, R> SpriteGraphAnimationSystem<S, R> {
+impl<S, R, E> SpriteGraphAnimationSystem<S, R, E> {
     pub const fn new() -> Self {
         SpriteGraphAnimationSystem {
             marker: PhantomData,
@@ -128,19 +227,39 @@ impl<S, R> SpriteGraphAnimationSystem<S, R> {
     }
 }
 
-pub fn sprite_graph_animation_system<S, R>(
-    query: QueryRef<(&S, &mut SpriteGraphAnimation<R>, &mut Sprite)>,
+/// Advances sprite animations and updates sprites with current frames.
+///
+/// Events of entered frames are pushed into [`AnimEventQueue`] if entity has one.
+pub fn sprite_graph_animation_system<S, R, E>(
+    query: QueryRef<(
+        &S,
+        &mut SpriteGraphAnimation<R, E>,
+        &mut Sprite,
+        Option<&mut AnimEventQueue<E>>,
+    )>,
     clock: Res<ClockIndex>,
 ) where
     S: Send + Sync + 'static,
     R: AnimTransitionRule<S> + Send + Sync + 'static,
+    E: Clone + Send + Sync + 'static,
 {
     let delta = clock.delta;
-    query.for_each(|(state, anim, sprite)| {
-        let result = anim.state.animate(state, &anim.graph, delta);
+    query.for_each(|(state, anim, sprite, mut queue)| {
+        let all_frames = &anim.frames;
+        let frame_events = &anim.frame_events;
+
+        let result = anim
+            .animation
+            .animate_with(state, delta, |index, node, played| {
+                if let (Some(queue), Some(events)) = (&mut queue, frame_events.get(index)) {
+                    let frames = &all_frames[node.animation.from..=node.animation.to];
+                    push_frame_events(frames, events, node, played, queue);
+                }
+            });
+
         let frames = &anim.frames[result.animation.from..=result.animation.to];
 
-        let mut left = result.elapsed;
+        let mut left = result.position;
 
         let frame = frames
             .iter()
@@ -155,18 +274,58 @@ pub fn sprite_graph_animation_system<S, R>(
             .or_else(|| frames.last())
             .unwrap();
 
-        sprite.src = Rect {
-            left: (frame.src.x as f32) / frame.src_size.w as f32,
-            right: (frame.src.x as f32 + frame.src.w as f32) / frame.src_size.w as f32,
-            bottom: 1.0 - (frame.src.y as f32 + frame.src.h as f32) / frame.src_size.h as f32,
-            top: 1.0 - (frame.src.y as f32) / frame.src_size.h as f32,
-        };
-
-        sprite.tex = Rect {
-            left: (frame.tex.x as f32) / anim.tex_size.w as f32,
-            right: (frame.tex.x as f32 + frame.tex.w as f32) / anim.tex_size.w as f32,
-            bottom: (frame.tex.y as f32) / anim.tex_size.h as f32,
-            top: (frame.tex.y as f32 + frame.tex.h as f32) / anim.tex_size.h as f32,
-        };
+        sprite.src = frame.src_rect();
+        sprite.tex = frame.tex_rect(anim.tex_size);
     })
 }
+
+/// Pushes events of frames entered within `played` range of node's cycle.
+///
+/// Playing forward frame is entered at its start.
+/// Playing backward in [`PlaybackMode::PingPong`] frame is entered at its end,
+/// except the last frame that is already shown when direction changes.
+fn push_frame_events<E: Clone>(
+    frames: &[SpriteFrame],
+    events: &[(usize, E)],
+    node: &AnimNode<FrameSpan>,
+    played: Range<TimeSpan>,
+    queue: &mut AnimEventQueue<E>,
+) {
+    let mut push = |index: usize| {
+        queue.events.extend(
+            events
+                .iter()
+                .filter(|(event_frame, _)| *event_frame == index)
+                .map(|(_, event)| event.clone()),
+        );
+    };
+
+    let mut start = TimeSpan::ZERO;
+    for (index, frame) in frames.iter().enumerate() {
+        if start >= played.end {
+            return;
+        }
+        if start >= played.start {
+            push(index);
+        }
+        start += frame.span;
+    }
+
+    if node.playback != PlaybackMode::PingPong {
+        return;
+    }
+
+    // Frames are entered in reverse order at cycle time mirrored from their ends.
+    let mut end = node.span;
+    for index in (0..frames.len().saturating_sub(1)).rev() {
+        end -= frames[index + 1].span;
+        let entered = node.cycle() - end;
+
+        if entered >= played.end {
+            return;
+        }
+        if entered >= played.start {
+            push(index);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::ops::Range;
+
+/// Range of sprite instances that share batch key
+/// and can be drawn with single instanced draw call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpriteBatch<K> {
+    /// Key shared by all sprites in the batch.
+    pub key: K,
+
+    /// Range of instances in the output of [`SpriteBatcher::finish`].
+    pub range: Range<u32>,
+}
+
+/// Groups sprites into draw batches.
+///
+/// Sprites are ordered by layer, so that lower layers are drawn first.
+/// Within a layer sprites are grouped by batch key
+/// (e.g. material or texture set) and groups are ordered so that
+/// the first group continues the last batch of the previous layer
+/// and the last group matches the next layer, when possible.
+/// Sprites with equal layer and key keep submission order.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::sprite::{SpriteBatch, SpriteBatcher};
+/// let mut batcher = SpriteBatcher::new();
+///
+/// // Two materials scattered over three layers.
+/// batcher.push(2, 'a', 5);
+/// batcher.push(0, 'b', 1);
+/// batcher.push(1, 'a', 4);
+/// batcher.push(0, 'a', 0);
+/// batcher.push(2, 'b', 6);
+/// batcher.push(1, 'b', 3);
+/// batcher.push(0, 'b', 2);
+///
+/// let mut instances = Vec::new();
+/// let mut batches = Vec::new();
+/// batcher.finish(&mut instances, &mut batches);
+///
+/// // Layers are drawn back to front.
+/// assert_eq!(instances, [0, 1, 2, 3, 4, 5, 6]);
+///
+/// // Six layer-material pairs are merged into four draws.
+/// assert_eq!(
+///     batches,
+///     [
+///         SpriteBatch { key: 'a', range: 0..1 },
+///         SpriteBatch { key: 'b', range: 1..4 },
+///         SpriteBatch { key: 'a', range: 4..6 },
+///         SpriteBatch { key: 'b', range: 6..7 },
+///     ]
+/// );
+/// ```
+///
+/// Sprites pushed at the same layer are drawn with one batch per key,
+/// no matter how many of them there are.
+///
+/// ```
+/// # use arcana::sprite::SpriteBatcher;
+/// let mut batcher = SpriteBatcher::new();
+/// let mut instances = Vec::new();
+/// let mut batches = Vec::new();
+///
+/// for _ in 0..3 {
+///     for i in 0..100_000u32 {
+///         batcher.push(0, i % 3, i);
+///     }
+///
+///     instances.clear();
+///     batches.clear();
+///     batcher.finish(&mut instances, &mut batches);
+///
+///     assert_eq!(instances.len(), 100_000);
+///     assert_eq!(batches.len(), 3);
+/// }
+/// ```
+pub struct SpriteBatcher<K, T> {
+    sprites: Vec<(u32, K, T)>,
+    runs: Vec<Range<usize>>,
+}
+
+impl<K, T> Default for SpriteBatcher<K, T> {
+    fn default() -> Self {
+        SpriteBatcher::new()
+    }
+}
+
+impl<K, T> SpriteBatcher<K, T> {
+    pub fn new() -> Self {
+        SpriteBatcher {
+            sprites: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+
+    /// Returns number of pushed sprites.
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// Returns `true` if no sprites were pushed.
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Adds sprite instance at specified layer.
+    pub fn push(&mut self, layer: u32, key: K, instance: T) {
+        self.sprites.push((layer, key, instance));
+    }
+
+    /// Writes pushed instances in drawing order and batches covering them.
+    /// Batcher is empty afterwards and can be reused.
+    pub fn finish(
+        &mut self,
+        instances: &mut impl Extend<T>,
+        batches: &mut impl Extend<SpriteBatch<K>>,
+    ) where
+        K: Copy + Ord,
+        T: Copy,
+    {
+        let sprites = &mut self.sprites;
+        let runs = &mut self.runs;
+
+        sprites.sort_by(|(la, ka, _), (lb, kb, _)| la.cmp(lb).then(ka.cmp(kb)));
+
+        // Runs of sprites with equal layer and key.
+        runs.clear();
+        let mut start = 0;
+        for idx in 1..=sprites.len() {
+            if idx == sprites.len()
+                || sprites[idx].0 != sprites[start].0
+                || sprites[idx].1 != sprites[start].1
+            {
+                runs.push(start..idx);
+                start = idx;
+            }
+        }
+
+        let layer = |run: &Range<usize>| sprites[run.start].0;
+        let key = |run: &Range<usize>| sprites[run.start].1;
+
+        let layer_end = |runs: &[Range<usize>], start: usize| {
+            let mut end = start;
+            while end < runs.len() && layer(&runs[end]) == layer(&runs[start]) {
+                end += 1;
+            }
+            end
+        };
+
+        // Reorder runs within each layer to merge them with neighbour layers.
+        let mut prev_key = None;
+        let mut layer_start = 0;
+        while layer_start < runs.len() {
+            let end = layer_end(runs, layer_start);
+            let next_end = layer_end(runs, end);
+
+            let (current, next) = runs[layer_start..next_end].split_at_mut(end - layer_start);
+
+            if let Some(prev_key) = prev_key {
+                if let Some(idx) = current.iter().position(|run| key(run) == prev_key) {
+                    current[..=idx].rotate_right(1);
+                }
+            }
+
+            if let Some(idx) = current[1..]
+                .iter()
+                .position(|run| next.iter().any(|next| key(next) == key(run)))
+            {
+                current[1 + idx..].rotate_left(1);
+            }
+
+            prev_key = Some(key(&runs[end - 1]));
+            layer_start = end;
+        }
+
+        // Emit instances and merge consecutive runs with equal keys.
+        let mut offset = 0;
+        let mut current: Option<SpriteBatch<K>> = None;
+
+        for run in runs.iter() {
+            let run_key = key(run);
+            let count = (run.end - run.start) as u32;
+
+            instances.extend(
+                sprites[run.clone()]
+                    .iter()
+                    .map(|(_, _, instance)| *instance),
+            );
+
+            match &mut current {
+                Some(batch) if batch.key == run_key => batch.range.end += count,
+                _ => {
+                    batches.extend(current.take());
+                    current = Some(SpriteBatch {
+                        key: run_key,
+                        range: offset..offset + count,
+                    });
+                }
+            }
+
+            offset += count;
+        }
+
+        batches.extend(current);
+
+        runs.clear();
+        sprites.clear();
+    }
+}
@@ -1,9 +1,6 @@
-use crate::sprite::graph::AnimNode;
+use crate::anim::{AnimNode, AnimTransitionRule, CurrentAnimInfo};
 
-use super::{
-    anim::FaceDirection,
-    graph::{AnimTransitionRule, CurrentAnimInfo},
-};
+use super::anim::FaceDirection;
 
 #[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 
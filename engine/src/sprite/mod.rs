@@ -1,16 +1,17 @@
 mod anim;
+mod batch;
 // mod character;
-mod graph;
 
 use std::sync::Arc;
 
 // #[cfg(feature = "graphics")]
 // pub use crate::graphics::renderer::sprite::*;
 
-pub use self::{anim::*, graph::*};
+pub use {self::anim::*, self::batch::*, crate::anim::*};
 
 use arcana_time::TimeSpan;
 use bytemuck::{Pod, Zeroable};
+use edict::component::Component;
 use goods::Asset;
 
 use crate::{graphics::Texture, rect::Rect};
@@ -24,7 +25,10 @@ use crate::{graphics::Texture, rect::Rect};
 /// |  |        | |
 /// |  |--------| |
 /// |-------------|
-#[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
+///
+/// By default sprite is not flipped and has white tint,
+/// so it is drawn as is.
+#[derive(Clone, Copy, Debug, Zeroable, Pod, Component)]
 #[repr(C)]
 pub struct Sprite {
     /// Target rect to render this sprite into.
@@ -40,6 +44,31 @@ pub struct Sprite {
     /// The higher level sprites are rendered over
     /// lower layer sprites.
     pub layer: u32,
+
+    /// Color multiplied with material color.
+    pub tint: [f32; 4],
+
+    /// Mirrors sprite horizontally when non-zero.
+    /// Integer to keep sprite `Pod`.
+    pub flip_x: u32,
+
+    /// Mirrors sprite vertically when non-zero.
+    /// Integer to keep sprite `Pod`.
+    pub flip_y: u32,
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Sprite {
+            world: Rect::default(),
+            src: Rect::default(),
+            tex: Rect::default(),
+            layer: 0,
+            tint: [1.0; 4],
+            flip_x: 0,
+            flip_y: 0,
+        }
+    }
 }
 
 // struct Animation<F> {
@@ -249,6 +278,239 @@ pub struct SpriteFrame {
     pub span: TimeSpan,
 }
 
+impl Rect {
+    /// Returns rect normalized to `size` from rect in pixels.
+    /// Both are in texture space with Y axis pointing down.
+    pub fn from_pixels(rect: SpriteRect, size: SpriteSize) -> Rect {
+        Rect {
+            left: rect.x as f32 / size.w as f32,
+            right: (rect.x as f32 + rect.w as f32) / size.w as f32,
+            bottom: rect.y as f32 / size.h as f32,
+            top: (rect.y as f32 + rect.h as f32) / size.h as f32,
+        }
+    }
+}
+
+impl SpriteFrame {
+    /// Returns normalized rect of the frame in sprite sheet texture.
+    /// Suitable for [`Sprite::tex`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::{rect::Rect, sprite::{SpriteFrame, SpriteRect, SpriteSize}, TimeSpan};
+    /// let frame = SpriteFrame {
+    ///     tex: SpriteRect { x: 32, y: 16, w: 32, h: 16 },
+    ///     src: SpriteRect { x: 0, y: 0, w: 32, h: 16 },
+    ///     src_size: SpriteSize { w: 32, h: 16 },
+    ///     span: TimeSpan::MILLISECOND * 100,
+    /// };
+    ///
+    /// let tex = frame.tex_rect(SpriteSize { w: 128, h: 64 });
+    /// assert_eq!((tex.left, tex.right), (0.25, 0.5));
+    /// assert_eq!((tex.bottom, tex.top), (0.25, 0.5));
+    /// ```
+    pub fn tex_rect(&self, tex_size: SpriteSize) -> Rect {
+        Rect::from_pixels(self.tex, tex_size)
+    }
+
+    /// Returns normalized rect occupied by cropped frame in the original image.
+    /// Suitable for [`Sprite::src`].
+    pub fn src_rect(&self) -> Rect {
+        let rect = Rect::from_pixels(self.src, self.src_size);
+
+        // `src` has Y axis pointing up.
+        Rect {
+            left: rect.left,
+            right: rect.right,
+            bottom: 1.0 - rect.top,
+            top: 1.0 - rect.bottom,
+        }
+    }
+}
+
+impl Sprite {
+    /// Returns sprite showing the frame of the sprite sheet.
+    ///
+    /// `world` rect is centered, has unit height and aspect ratio of the original image.
+    /// Replace it to change sprite size.
+    pub fn from_frame(frame: &SpriteFrame, sheet: &SpriteSheet) -> Self {
+        let half_width = 0.5 * frame.src_size.w as f32 / frame.src_size.h as f32;
+
+        Sprite {
+            world: Rect {
+                left: -half_width,
+                right: half_width,
+                bottom: -0.5,
+                top: 0.5,
+            },
+            src: frame.src_rect(),
+            tex: frame.tex_rect(sheet.tex_size),
+            ..Sprite::default()
+        }
+    }
+
+    /// Returns sprite with specified flips.
+    pub fn with_flip(mut self, x: bool, y: bool) -> Self {
+        self.flip_x = x.into();
+        self.flip_y = y.into();
+        self
+    }
+
+    /// Returns `src` and `tex` rects with flips applied.
+    ///
+    /// Flipped `src` is mirrored within `world` rect
+    /// and flipped `tex` has swapped edges.
+    pub fn flipped_rects(&self) -> (Rect, Rect) {
+        let mut src = self.src;
+        let mut tex = self.tex;
+
+        if self.flip_x != 0 {
+            src = Rect {
+                left: 1.0 - src.right,
+                right: 1.0 - src.left,
+                ..src
+            };
+            std::mem::swap(&mut tex.left, &mut tex.right);
+        }
+
+        if self.flip_y != 0 {
+            src = Rect {
+                bottom: 1.0 - src.top,
+                top: 1.0 - src.bottom,
+                ..src
+            };
+            std::mem::swap(&mut tex.bottom, &mut tex.top);
+        }
+
+        (src, tex)
+    }
+
+    /// Returns axis-aligned bounding box of the sprite's `world` rect
+    /// placed by `iso`.
+    pub fn aabb(&self, iso: &na::Isometry2<f32>) -> Rect {
+        let corners = [
+            iso * self.world.top_left(),
+            iso * self.world.bottom_left(),
+            iso * self.world.top_right(),
+            iso * self.world.bottom_right(),
+        ];
+
+        let xs = corners.map(|p| p.x);
+        let ys = corners.map(|p| p.y);
+
+        Rect {
+            left: xs.into_iter().reduce(f32::min).unwrap(),
+            right: xs.into_iter().reduce(f32::max).unwrap(),
+            bottom: ys.into_iter().reduce(f32::min).unwrap(),
+            top: ys.into_iter().reduce(f32::max).unwrap(),
+        }
+    }
+}
+
+/// Makes [`Sprite`] drawn as nine quads, so that borders don't stretch
+/// when sprite is resized.
+///
+/// Corners keep their size, edges are stretched along one axis
+/// and center is stretched along both.
+/// Used for UI panels and health bars.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{rect::Rect, sprite::NineSlice};
+/// let pos = Rect { left: -2.0, right: 2.0, bottom: -1.0, top: 1.0 };
+/// let uv = Rect::ONE_QUAD;
+///
+/// let slice = NineSlice {
+///     border: Rect { left: 0.5, right: 0.5, bottom: 0.5, top: 0.5 },
+///     tex_border: Rect { left: 0.25, right: 0.25, bottom: 0.25, top: 0.25 },
+/// };
+///
+/// let quads: Vec<_> = slice.quads(pos, uv).collect();
+/// assert_eq!(quads.len(), 9);
+///
+/// // Bottom-left corner.
+/// let (pos, uv) = quads[0];
+/// assert_eq!((pos.left, pos.right, pos.bottom, pos.top), (-2.0, -1.5, -1.0, -0.5));
+/// assert_eq!((uv.left, uv.right, uv.bottom, uv.top), (0.0, 0.25, 0.0, 0.25));
+///
+/// // Zero borders degrade to a normal sprite.
+/// assert_eq!(NineSlice::default().quads(pos, uv).count(), 1);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Component)]
+pub struct NineSlice {
+    /// Border insets in world units.
+    pub border: Rect,
+
+    /// Border insets in texture as fractions of [`Sprite::tex`] rect.
+    pub tex_border: Rect,
+}
+
+impl NineSlice {
+    /// Returns quads with texture coordinates for sprite
+    /// occupying `pos` rect with `uv` texture rect.
+    ///
+    /// Borders are shrunk proportionally if they don't fit into `pos` rect.
+    pub fn quads(&self, pos: Rect, uv: Rect) -> impl Iterator<Item = (Rect, Rect)> {
+        let plain = self.border == Rect::default() && self.tex_border == Rect::default();
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+        let fit = |near: f32, far: f32, span: f32| {
+            let sum = near + far;
+            if sum > span.abs() && sum > 0.0 {
+                let scale = span.abs() / sum;
+                (near * scale, far * scale)
+            } else {
+                (near, far)
+            }
+        };
+
+        let (left, right) = fit(self.border.left, self.border.right, pos.right - pos.left);
+        let (bottom, top) = fit(self.border.bottom, self.border.top, pos.top - pos.bottom);
+
+        let xs = [pos.left, pos.left + left, pos.right - right, pos.right];
+        let ys = [pos.bottom, pos.bottom + bottom, pos.top - top, pos.top];
+
+        let us = [
+            uv.left,
+            lerp(uv.left, uv.right, self.tex_border.left),
+            lerp(uv.left, uv.right, 1.0 - self.tex_border.right),
+            uv.right,
+        ];
+        let vs = [
+            uv.bottom,
+            lerp(uv.bottom, uv.top, self.tex_border.bottom),
+            lerp(uv.bottom, uv.top, 1.0 - self.tex_border.top),
+            uv.top,
+        ];
+
+        let count = if plain { 1 } else { 9 };
+
+        (0..count).map(move |idx| {
+            if plain {
+                return (pos, uv);
+            }
+
+            let (col, row) = (idx % 3, idx / 3);
+            let pos = Rect {
+                left: xs[col],
+                right: xs[col + 1],
+                bottom: ys[row],
+                top: ys[row + 1],
+            };
+            let uv = Rect {
+                left: us[col],
+                right: us[col + 1],
+                bottom: vs[row],
+                top: vs[row + 1],
+            };
+            (pos, uv)
+        })
+    }
+}
+
 #[derive(Clone, Debug, Asset)]
 #[asset(name = "arcana.spritesheet")]
 pub struct SpriteSheet {
@@ -267,6 +529,38 @@ pub struct SpriteSheet {
     pub texture: Texture,
 }
 
+impl SpriteSheet {
+    /// Returns frame with specified index.
+    pub fn frame(&self, index: usize) -> Option<&SpriteFrame> {
+        self.frames.get(index)
+    }
+
+    /// Returns animation with specified name.
+    pub fn animation_by_name(&self, name: &str) -> Option<&SpriteAnimation> {
+        self.animations
+            .iter()
+            .find(|animation| &*animation.name == name)
+    }
+
+    /// Returns sprite showing frame with specified index.
+    ///
+    /// `world` rect is centered and has specified size.
+    /// Returns `None` if there is no such frame.
+    pub fn sprite(&self, index: usize, size: na::Vector2<f32>) -> Option<Sprite> {
+        let frame = self.frame(index)?;
+
+        Some(Sprite {
+            world: Rect {
+                left: -0.5 * size.x,
+                right: 0.5 * size.x,
+                bottom: -0.5 * size.y,
+                top: 0.5 * size.y,
+            },
+            ..Sprite::from_frame(frame, self)
+        })
+    }
+}
+
 fn default_distances() -> Arc<[f32]> {
     Arc::new([])
 }
@@ -287,6 +581,10 @@ pub struct SpriteAnimation {
     pub from: usize,
     pub to: usize,
 
+    /// Playback of the animation when it reaches the end.
+    #[serde(default)]
+    pub playback: PlaybackMode,
+
     #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
     pub features: serde_json::Value,
 }
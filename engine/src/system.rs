@@ -1,6 +1,12 @@
 use std::{any::TypeId, ptr::NonNull};
 
+#[cfg(feature = "profile-systems")]
+use std::time::Instant;
+
 use arcana_time::{TimeSpan, TimeStamp};
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
 use edict::{
     archetype::Archetype,
     query::Access,
@@ -10,6 +16,10 @@ use edict::{
 
 use crate::clocks::ClockIndex;
 
+mod order;
+
+pub use self::order::*;
+
 /// Default value for fixed systems tick_span
 pub const DEFAULT_TICK_SPAN: TimeSpan = TimeSpan::from_micros(20_000);
 
@@ -17,6 +27,40 @@ pub struct FixSystem<S> {
     system: S,
     step: TimeSpan,
     next: Option<TimeStamp>,
+    interpolated: bool,
+    realtime: bool,
+}
+
+/// Fraction of the fixed step elapsed since the last tick.
+///
+/// Written by fixed systems created with [`FixSystem::interpolated`]
+/// after they run, if the resource is present in the world.
+/// Value is in `[0, 1]` range and is used to blend
+/// previous and current fixed step state for rendering.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FixAlpha(pub f32);
+
+impl FixAlpha {
+    /// Returns fraction of `step` elapsed from `last` tick to `now`.
+    /// Clamped to `1.0` if next tick is overdue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::{system::FixAlpha, TimeSpan, TimeStamp};
+    /// let step = TimeSpan::from_millis(20);
+    /// let last = TimeStamp::ORIGIN + step;
+    ///
+    /// assert_eq!(FixAlpha::new(last, last, step), FixAlpha(0.0));
+    /// assert_eq!(FixAlpha::new(last, last + step / 2, step), FixAlpha(0.5));
+    /// assert_eq!(FixAlpha::new(last, last + step * 3, step), FixAlpha(1.0));
+    /// ```
+    #[inline]
+    pub fn new(last: TimeStamp, now: TimeStamp, step: TimeSpan) -> Self {
+        let alpha = now.saturating_duration_since(last).as_nanos() as f32
+            / step.as_nanos().max(1) as f32;
+        FixAlpha(alpha.min(1.0))
+    }
 }
 
 pub trait ToFixSystem<M>: IntoSystem<M> {
@@ -33,6 +77,8 @@ where
             system: self.into_system(),
             step,
             next: None,
+            interpolated: false,
+            realtime: false,
         }
     }
 }
@@ -45,8 +91,31 @@ impl<S> FixSystem<S> {
             system: system.into_system(),
             step,
             next: None,
+            interpolated: false,
+            realtime: false,
         }
     }
+
+    /// Makes this system tick in real time, ignoring pause and time scale.
+    /// Useful for networking heartbeats.
+    ///
+    /// During the run `delta` and `real_delta` of the [`ClockIndex`] are set to the step
+    /// and `real_now` to the tick time.
+    #[inline]
+    pub fn realtime(mut self) -> Self {
+        self.realtime = true;
+        self
+    }
+
+    /// Makes this system write [`FixAlpha`] resource after each run.
+    ///
+    /// Only one fixed system should be interpolated,
+    /// usually the one that runs physics.
+    #[inline]
+    pub fn interpolated(mut self) -> Self {
+        self.interpolated = true;
+        self
+    }
 }
 
 unsafe impl<S> System for FixSystem<S>
@@ -86,6 +155,10 @@ where
             return Some(Access::Write);
         }
 
+        if self.interpolated && TypeId::of::<FixAlpha>() == id {
+            return Some(Access::Write);
+        }
+
         self.system.access_resource(id)
     }
 
@@ -93,15 +166,26 @@ where
     unsafe fn run_unchecked(&mut self, world: NonNull<World>, queue: &mut dyn ActionQueue) {
         let clock = *world.as_ref().expect_resource::<ClockIndex>();
 
-        let next = self.next.get_or_insert(clock.now);
+        let now = if self.realtime {
+            clock.real_now
+        } else {
+            clock.now
+        };
+
+        let next = self.next.get_or_insert(now);
 
         // Run systems for game ticks.
-        while *next <= clock.now {
+        while *next <= now {
             {
                 // Tweak clocks.
                 let mut clock = world.as_ref().expect_resource_mut::<ClockIndex>();
                 clock.delta = self.step;
-                clock.now = *next;
+                if self.realtime {
+                    clock.real_delta = self.step;
+                    clock.real_now = *next;
+                } else {
+                    clock.now = *next;
+                }
                 *next += self.step;
             }
 
@@ -110,5 +194,314 @@ where
 
         // Restore clocks.
         *world.as_ref().expect_resource_mut() = clock;
+
+        if self.interpolated {
+            let alpha = FixAlpha::new(*next - self.step, now, self.step);
+
+            if let Some(mut fix_alpha) = world.as_ref().get_resource_mut::<FixAlpha>() {
+                *fix_alpha = alpha;
+            }
+        }
+    }
+}
+
+/// Timing statistics of a single system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemTiming {
+    /// Duration of the last run.
+    pub last: TimeSpan,
+
+    /// Longest run.
+    pub max: TimeSpan,
+
+    /// Total time spent in all runs.
+    pub total: TimeSpan,
+
+    /// Number of runs.
+    pub runs: u64,
+}
+
+impl SystemTiming {
+    /// Average duration of a run.
+    pub fn avg(&self) -> TimeSpan {
+        match self.runs {
+            0 => TimeSpan::ZERO,
+            runs => self.total / runs,
+        }
+    }
+
+    fn record(&mut self, span: TimeSpan) {
+        self.last = span;
+        self.max = self.max.max(span);
+        self.total += span;
+        self.runs += 1;
+    }
+}
+
+/// Resource with timings of profiled systems keyed by system name.
+///
+/// Timings are collected only with `profile-systems` feature enabled
+/// and only for systems wrapped with [`ToProfiledSystem::profiled`].
+#[derive(Default)]
+pub struct SystemProfile {
+    timings: Mutex<HashMap<String, SystemTiming>>,
+}
+
+impl SystemProfile {
+    pub fn new() -> Self {
+        SystemProfile::default()
+    }
+
+    /// Returns timing of the system with specified name.
+    pub fn get(&self, name: &str) -> Option<SystemTiming> {
+        self.timings.lock().get(name).copied()
+    }
+
+    /// Returns timings of all profiled systems sorted by name.
+    pub fn timings(&self) -> Vec<(String, SystemTiming)> {
+        let mut timings: Vec<_> = self
+            .timings
+            .lock()
+            .iter()
+            .map(|(name, timing)| (name.clone(), *timing))
+            .collect();
+
+        timings.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+        timings
+    }
+
+    /// Clears all collected timings.
+    pub fn reset(&self) {
+        self.timings.lock().clear();
+    }
+
+    #[cfg_attr(not(feature = "profile-systems"), allow(unused))]
+    fn record(&self, name: &str, span: TimeSpan) {
+        let mut timings = self.timings.lock();
+        match timings.get_mut(name) {
+            Some(timing) => timing.record(span),
+            None => {
+                let mut timing = SystemTiming::default();
+                timing.record(span);
+                timings.insert(name.to_owned(), timing);
+            }
+        }
+    }
+}
+
+/// System wrapper that measures time of each run.
+pub struct ProfiledSystem<S> {
+    system: S,
+    #[cfg_attr(not(feature = "profile-systems"), allow(unused))]
+    name: String,
+}
+
+pub trait ToProfiledSystem<M>: IntoSystem<M> {
+    /// Wraps system to collect its timings into [`SystemProfile`] resource.
+    ///
+    /// Without `profile-systems` feature wrapper only forwards calls.
+    fn profiled(self) -> ProfiledSystem<Self::System>;
+}
+
+impl<M, S> ToProfiledSystem<M> for S
+where
+    S: IntoSystem<M>,
+{
+    #[inline]
+    fn profiled(self) -> ProfiledSystem<Self::System> {
+        let system = self.into_system();
+        ProfiledSystem {
+            name: system.name().to_string(),
+            system,
+        }
+    }
+}
+
+unsafe impl<S> System for ProfiledSystem<S>
+where
+    S: System,
+{
+    #[inline]
+    fn is_local(&self) -> bool {
+        self.system.is_local()
+    }
+
+    #[inline]
+    fn world_access(&self) -> Option<Access> {
+        self.system.world_access()
+    }
+
+    #[inline]
+    fn skips_archetype(&self, archetype: &Archetype) -> bool {
+        self.system.skips_archetype(archetype)
+    }
+
+    #[inline]
+    fn access_component(&self, id: TypeId) -> Option<Access> {
+        self.system.access_component(id)
+    }
+
+    #[inline]
+    fn access_resource(&self, id: TypeId) -> Option<Access> {
+        #[cfg(feature = "profile-systems")]
+        if TypeId::of::<SystemProfile>() == id {
+            // Timings are behind a mutex, so shared access is enough.
+            return match self.system.access_resource(id) {
+                Some(Access::Write) => Some(Access::Write),
+                _ => Some(Access::Read),
+            };
+        }
+
+        self.system.access_resource(id)
+    }
+
+    #[cfg(not(feature = "profile-systems"))]
+    #[inline]
+    unsafe fn run_unchecked(&mut self, world: NonNull<World>, queue: &mut dyn ActionQueue) {
+        self.system.run_unchecked(world, queue);
+    }
+
+    #[cfg(feature = "profile-systems")]
+    #[inline]
+    unsafe fn run_unchecked(&mut self, world: NonNull<World>, queue: &mut dyn ActionQueue) {
+        let start = Instant::now();
+        self.system.run_unchecked(world, queue);
+        let span = TimeSpan::from(start.elapsed());
+
+        if let Some(profile) = world.as_ref().get_resource::<SystemProfile>() {
+            profile.record(&self.name, span);
+        }
+    }
+}
+
+/// System wrapper that runs the system only when condition holds.
+pub struct ConditionalSystem<S, C> {
+    system: S,
+    condition: C,
+    resource: Option<TypeId>,
+}
+
+pub trait ToConditionalSystem<M>: IntoSystem<M> {
+    /// Wraps system to be skipped entirely when `condition` returns `false`.
+    ///
+    /// Condition is checked right before each run of the system.
+    /// It should read only resources and components the system itself accesses,
+    /// scheduler doesn't know about accesses made by the condition.
+    fn run_if<C>(self, condition: C) -> ConditionalSystem<Self::System, C>
+    where
+        C: FnMut(&World) -> bool + Send + 'static;
+
+    /// Wraps system to be skipped entirely while resource `T` is absent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::system::ToConditionalSystem;
+    /// # use edict::{scheduler::Scheduler, system::{Res, ResMut}, world::World};
+    /// struct Login(&'static str);
+    /// struct Greetings(u32);
+    ///
+    /// fn greet(_login: Res<Login>, mut greetings: ResMut<Greetings>) {
+    ///     greetings.0 += 1;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.insert_resource(Greetings(0));
+    ///
+    /// let mut scheduler = Scheduler::new();
+    /// scheduler.add_system(greet.run_if_resource_exists::<Login>());
+    ///
+    /// // Would panic on missing `Login` if not skipped.
+    /// scheduler.run_sequential(&mut world);
+    /// assert_eq!(world.expect_resource::<Greetings>().0, 0);
+    ///
+    /// world.insert_resource(Login("player"));
+    /// scheduler.run_sequential(&mut world);
+    /// assert_eq!(world.expect_resource::<Greetings>().0, 1);
+    /// ```
+    fn run_if_resource_exists<T>(self) -> ConditionalSystem<Self::System, fn(&World) -> bool>
+    where
+        T: 'static;
+}
+
+impl<M, S> ToConditionalSystem<M> for S
+where
+    S: IntoSystem<M>,
+{
+    #[inline]
+    fn run_if<C>(self, condition: C) -> ConditionalSystem<Self::System, C>
+    where
+        C: FnMut(&World) -> bool + Send + 'static,
+    {
+        ConditionalSystem {
+            system: self.into_system(),
+            condition,
+            resource: None,
+        }
+    }
+
+    #[inline]
+    fn run_if_resource_exists<T>(self) -> ConditionalSystem<Self::System, fn(&World) -> bool>
+    where
+        T: 'static,
+    {
+        ConditionalSystem {
+            system: self.into_system(),
+            condition: resource_exists::<T>,
+            resource: Some(TypeId::of::<T>()),
+        }
+    }
+}
+
+fn resource_exists<T: 'static>(world: &World) -> bool {
+    world.get_resource::<T>().is_some()
+}
+
+unsafe impl<S, C> System for ConditionalSystem<S, C>
+where
+    S: System,
+    C: FnMut(&World) -> bool + Send + 'static,
+{
+    #[inline]
+    fn is_local(&self) -> bool {
+        self.system.is_local()
+    }
+
+    #[inline]
+    fn world_access(&self) -> Option<Access> {
+        match self.system.world_access() {
+            Some(Access::Write) => Some(Access::Write),
+            _ => Some(Access::Read),
+        }
+    }
+
+    #[inline]
+    fn skips_archetype(&self, archetype: &Archetype) -> bool {
+        self.system.skips_archetype(archetype)
+    }
+
+    #[inline]
+    fn access_component(&self, id: TypeId) -> Option<Access> {
+        self.system.access_component(id)
+    }
+
+    #[inline]
+    fn access_resource(&self, id: TypeId) -> Option<Access> {
+        if self.resource == Some(id) {
+            // Condition checks the resource.
+            return match self.system.access_resource(id) {
+                Some(Access::Write) => Some(Access::Write),
+                _ => Some(Access::Read),
+            };
+        }
+
+        self.system.access_resource(id)
+    }
+
+    #[inline]
+    unsafe fn run_unchecked(&mut self, world: NonNull<World>, queue: &mut dyn ActionQueue) {
+        if (self.condition)(world.as_ref()) {
+            self.system.run_unchecked(world, queue);
+        }
     }
 }
@@ -0,0 +1,193 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use edict::{scheduler::Scheduler, system::IntoSystem};
+
+/// Error that may occur when resolving order of systems.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SystemOrderError {
+    /// Two systems were added with the same label.
+    #[error("System label '{label}' is used more than once")]
+    DuplicateLabel { label: &'static str },
+
+    /// Constraint refers to label no system was added with.
+    #[error("System '{system}' is ordered relative to unknown label '{label}'")]
+    UnknownLabel {
+        system: &'static str,
+        label: &'static str,
+    },
+
+    /// Constraints can't be satisfied.
+    #[error("Systems {labels:?} have cyclic ordering constraints")]
+    Cycle { labels: Vec<&'static str> },
+}
+
+struct OrderedSystem {
+    label: &'static str,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+    add: Box<dyn FnOnce(&mut Scheduler)>,
+}
+
+/// Collection of labelled systems with ordering constraints.
+///
+/// Systems are added to the [`Scheduler`] in an order that satisfies
+/// `before` and `after` constraints, so that systems with conflicting access
+/// run in that order.
+/// Systems not constrained relative to each other keep the order they were added in.
+///
+/// Any system can be ordered, including fixed systems made with
+/// [`ToFixSystem::to_fix_system`](super::ToFixSystem::to_fix_system).
+///
+/// # Example
+///
+/// ```
+/// # use arcana::system::{SystemOrder, SystemOrderError};
+/// # use edict::system::Res;
+/// fn noop(_: Res<u32>) {}
+///
+/// let mut order = SystemOrder::new();
+/// order.add("animation", noop).after("state");
+/// order.add("render", noop).after("animation");
+/// order.add("state", noop).after("input");
+/// order.add("input", noop);
+///
+/// assert_eq!(order.resolve().unwrap(), ["input", "state", "animation", "render"]);
+///
+/// order.add("physics", noop).after("render").before("state");
+/// assert!(matches!(order.resolve(), Err(SystemOrderError::Cycle { .. })));
+/// ```
+#[derive(Default)]
+pub struct SystemOrder {
+    systems: Vec<OrderedSystem>,
+}
+
+/// Handle to set constraints of the system added to [`SystemOrder`].
+pub struct OrderedSystemEntry<'a> {
+    system: &'a mut OrderedSystem,
+}
+
+impl OrderedSystemEntry<'_> {
+    /// Makes system run before system with specified label.
+    pub fn before(self, label: &'static str) -> Self {
+        self.system.before.push(label);
+        self
+    }
+
+    /// Makes system run after system with specified label.
+    pub fn after(self, label: &'static str) -> Self {
+        self.system.after.push(label);
+        self
+    }
+}
+
+impl SystemOrder {
+    pub fn new() -> Self {
+        SystemOrder::default()
+    }
+
+    /// Adds system with a label.
+    /// Returns entry to set constraints of the system.
+    pub fn add<M, S>(&mut self, label: &'static str, system: S) -> OrderedSystemEntry<'_>
+    where
+        S: IntoSystem<M> + 'static,
+    {
+        self.systems.push(OrderedSystem {
+            label,
+            before: Vec::new(),
+            after: Vec::new(),
+            add: Box::new(move |scheduler| {
+                scheduler.add_system(system);
+            }),
+        });
+
+        OrderedSystemEntry {
+            system: self.systems.last_mut().unwrap(),
+        }
+    }
+
+    /// Returns labels of systems in the order they will be scheduled.
+    pub fn resolve(&self) -> Result<Vec<&'static str>, SystemOrderError> {
+        let order = self.resolve_indices()?;
+        Ok(order
+            .into_iter()
+            .map(|idx| self.systems[idx].label)
+            .collect())
+    }
+
+    /// Adds systems to the scheduler in resolved order.
+    ///
+    /// Nothing is added if constraints can't be satisfied.
+    pub fn schedule(self, scheduler: &mut Scheduler) -> Result<(), SystemOrderError> {
+        let order = self.resolve_indices()?;
+
+        let mut systems: Vec<_> = self.systems.into_iter().map(Some).collect();
+        for idx in order {
+            let system = systems[idx].take().unwrap();
+            (system.add)(scheduler);
+        }
+        Ok(())
+    }
+
+    fn index(&self, system: &'static str, label: &'static str) -> Result<usize, SystemOrderError> {
+        self.systems
+            .iter()
+            .position(|s| s.label == label)
+            .ok_or(SystemOrderError::UnknownLabel { system, label })
+    }
+
+    fn resolve_indices(&self) -> Result<Vec<usize>, SystemOrderError> {
+        let count = self.systems.len();
+
+        for (idx, system) in self.systems.iter().enumerate() {
+            if self.systems[..idx].iter().any(|s| s.label == system.label) {
+                return Err(SystemOrderError::DuplicateLabel {
+                    label: system.label,
+                });
+            }
+        }
+
+        // Edges from each system to systems that must run after it.
+        let mut successors = vec![Vec::new(); count];
+        let mut predecessors = vec![0usize; count];
+
+        for (idx, system) in self.systems.iter().enumerate() {
+            for &label in &system.before {
+                let other = self.index(system.label, label)?;
+                successors[idx].push(other);
+                predecessors[other] += 1;
+            }
+            for &label in &system.after {
+                let other = self.index(system.label, label)?;
+                successors[other].push(idx);
+                predecessors[idx] += 1;
+            }
+        }
+
+        // Kahn's algorithm picking earliest added system among ready ones.
+        let mut ready: BinaryHeap<_> = (0..count)
+            .filter(|&idx| predecessors[idx] == 0)
+            .map(Reverse)
+            .collect();
+
+        let mut order = Vec::with_capacity(count);
+        while let Some(Reverse(idx)) = ready.pop() {
+            order.push(idx);
+            for &next in &successors[idx] {
+                predecessors[next] -= 1;
+                if predecessors[next] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+
+        if order.len() < count {
+            let labels = (0..count)
+                .filter(|&idx| predecessors[idx] > 0)
+                .map(|idx| self.systems[idx].label)
+                .collect();
+            return Err(SystemOrderError::Cycle { labels });
+        }
+
+        Ok(order)
+    }
+}
@@ -0,0 +1,519 @@
+//! Text layout.
+//!
+//! Turns a string into positioned glyph quads using font metrics.
+//! Layout is independent from rendering and can be consumed by any text drawing node or UI.
+
+use fontdue::Font;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::rect::Rect;
+
+/// Horizontal alignment of lines within layout box.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// What to do with a single word that does not fit into wrap width.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WordOverflow {
+    /// Break the word between grapheme clusters.
+    #[default]
+    Break,
+
+    /// Keep the word on its own line, overflowing the wrap width.
+    Overflow,
+}
+
+/// Constraints for text layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextConstraints {
+    /// Maximum line width. Lines are wrapped at word boundaries to fit.
+    /// `None` disables wrapping.
+    pub max_width: Option<f32>,
+
+    /// Horizontal alignment of the lines.
+    pub align: TextAlign,
+
+    /// Handling of words longer than `max_width`.
+    pub overflow: WordOverflow,
+
+    /// Multiplier for line height derived from font metrics.
+    pub line_spacing: f32,
+}
+
+impl Default for TextConstraints {
+    fn default() -> Self {
+        TextConstraints {
+            max_width: None,
+            align: TextAlign::Left,
+            overflow: WordOverflow::Break,
+            line_spacing: 1.0,
+        }
+    }
+}
+
+/// Single glyph quad produced by layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlyphQuad {
+    /// Glyph index in the font.
+    pub glyph: u16,
+
+    /// Character this glyph was produced from.
+    pub ch: char,
+
+    /// Byte offset of the character in the source string.
+    pub offset: usize,
+
+    /// Index of the line this glyph belongs to.
+    pub line: usize,
+
+    /// Pen position on the baseline from which the glyph is drawn.
+    pub origin: na::Point2<f32>,
+
+    /// Glyph bounds in layout space.
+    pub rect: Rect,
+}
+
+/// Line of laid out text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextLine {
+    /// Byte range of the line in the source string.
+    pub start: usize,
+    pub end: usize,
+
+    /// Baseline of the line in layout space.
+    pub baseline: f32,
+
+    /// Horizontal offset of the line start.
+    pub x: f32,
+
+    /// Width of the line, excluding trailing whitespace.
+    pub width: f32,
+}
+
+/// Cursor stop at grapheme cluster boundary.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Caret {
+    /// Byte offset of the boundary in the source string.
+    pub offset: usize,
+
+    /// Line on which the caret is located.
+    pub line: usize,
+
+    /// Horizontal position of the caret.
+    pub x: f32,
+}
+
+/// Laid out paragraph of text.
+///
+/// Layout space has origin at top-left corner of the layout box,
+/// X axis pointing right and Y axis pointing up,
+/// so all lines are located below the origin.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::text::{TextConstraints, TextLayout};
+/// # use fontdue::Font;
+/// let bytes = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/assets/4077TH.TTF"));
+/// let font = Font::from_bytes(&bytes[..], Default::default()).unwrap();
+///
+/// let layout = TextLayout::new("AVA\nA", &font, 32.0, &TextConstraints::default());
+///
+/// // Glyphs on the first line advance by glyph width and kerning.
+/// let mut pen = 0.0;
+/// let mut prev = None;
+/// for (ch, glyph) in "AVA".chars().zip(layout.glyphs()) {
+///     if let Some(prev) = prev {
+///         pen += font.horizontal_kern(prev, ch, 32.0).unwrap_or(0.0);
+///     }
+///     assert_eq!(glyph.ch, ch);
+///     assert_eq!(glyph.line, 0);
+///     assert_eq!(glyph.origin.x, pen);
+///     pen += font.metrics(ch, 32.0).advance_width;
+///     prev = Some(ch);
+/// }
+///
+/// // Newline starts the next line from the left edge.
+/// let last = layout.glyphs()[3];
+/// assert_eq!(last.line, 1);
+/// assert_eq!(last.origin.x, 0.0);
+/// assert!(last.origin.y < layout.glyphs()[0].origin.y);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextLayout {
+    glyphs: Vec<GlyphQuad>,
+    lines: Vec<TextLine>,
+    carets: Vec<Caret>,
+    width: f32,
+    height: f32,
+    ascent: f32,
+    descent: f32,
+}
+
+struct Cluster {
+    start: usize,
+    end: usize,
+    advance: f32,
+    kern: f32,
+    whitespace: bool,
+    newline: bool,
+}
+
+impl TextLayout {
+    /// Lays out text using specified font, font size in pixels and constraints.
+    pub fn new(text: &str, font: &Font, size: f32, constraints: &TextConstraints) -> Self {
+        let (ascent, descent, line_height) = match font.horizontal_line_metrics(size) {
+            Some(metrics) => (metrics.ascent, metrics.descent, metrics.new_line_size),
+            None => (size, 0.0, size),
+        };
+        let line_height = line_height * constraints.line_spacing;
+
+        let clusters = measure(text, font, size);
+        let ranges = break_lines(&clusters, constraints);
+
+        let box_width = match constraints.max_width {
+            Some(max_width) => max_width,
+            None => ranges
+                .iter()
+                .map(|range| visible_width(&clusters[range.clone()]))
+                .fold(0.0, f32::max),
+        };
+
+        let mut glyphs = Vec::new();
+        let mut lines = Vec::with_capacity(ranges.len());
+        let mut carets = Vec::with_capacity(clusters.len() + 1);
+        let mut end_pen = 0.0;
+
+        for (line_idx, range) in ranges.iter().enumerate() {
+            let line_clusters = &clusters[range.clone()];
+            let width = visible_width(line_clusters);
+            let baseline = -ascent - line_idx as f32 * line_height;
+
+            let x = match constraints.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (box_width - width) * 0.5,
+                TextAlign::Right => box_width - width,
+            };
+
+            let (start, end) = match (line_clusters.first(), line_clusters.last()) {
+                (Some(first), Some(last)) => (first.start, last.end),
+                _ => {
+                    let offset = clusters.get(range.start).map_or(text.len(), |c| c.start);
+                    (offset, offset)
+                }
+            };
+
+            let mut pen = x;
+            for (idx, cluster) in line_clusters.iter().enumerate() {
+                if idx > 0 {
+                    pen += cluster.kern;
+                }
+
+                carets.push(Caret {
+                    offset: cluster.start,
+                    line: line_idx,
+                    x: pen,
+                });
+
+                let mut glyph_pen = pen;
+                for (offset, ch) in text[cluster.start..cluster.end].char_indices() {
+                    let glyph = font.lookup_glyph_index(ch);
+                    let metrics = font.metrics_indexed(glyph, size);
+
+                    if metrics.width > 0 && metrics.height > 0 {
+                        let left = glyph_pen + metrics.bounds.xmin;
+                        let bottom = baseline + metrics.bounds.ymin;
+
+                        glyphs.push(GlyphQuad {
+                            glyph,
+                            ch,
+                            offset: cluster.start + offset,
+                            line: line_idx,
+                            origin: na::Point2::new(glyph_pen, baseline),
+                            rect: Rect {
+                                left,
+                                right: left + metrics.bounds.width,
+                                bottom,
+                                top: bottom + metrics.bounds.height,
+                            },
+                        });
+                    }
+
+                    glyph_pen += metrics.advance_width;
+                }
+
+                pen += cluster.advance;
+            }
+
+            match clusters.get(range.end) {
+                Some(newline) if newline.newline => carets.push(Caret {
+                    offset: newline.start,
+                    line: line_idx,
+                    x: pen,
+                }),
+                _ => end_pen = pen,
+            }
+
+            lines.push(TextLine {
+                start,
+                end,
+                baseline,
+                x,
+                width,
+            });
+        }
+
+        carets.push(Caret {
+            offset: text.len(),
+            line: lines.len() - 1,
+            x: end_pen,
+        });
+
+        let height = match lines.len() {
+            0 => 0.0,
+            n => ascent - descent + (n - 1) as f32 * line_height,
+        };
+
+        TextLayout {
+            glyphs,
+            lines,
+            carets,
+            width: box_width,
+            height,
+            ascent,
+            descent,
+        }
+    }
+
+    /// Returns positioned glyph quads.
+    pub fn glyphs(&self) -> &[GlyphQuad] {
+        &self.glyphs
+    }
+
+    /// Returns laid out lines.
+    pub fn lines(&self) -> &[TextLine] {
+        &self.lines
+    }
+
+    /// Returns caret stops at every grapheme cluster boundary, including end of text.
+    pub fn carets(&self) -> &[Caret] {
+        &self.carets
+    }
+
+    /// Width of the layout box.
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Height of the layout box.
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// Bounds of the layout box in layout space.
+    pub fn bounds(&self) -> Rect {
+        Rect {
+            left: 0.0,
+            right: self.width,
+            top: 0.0,
+            bottom: -self.height,
+        }
+    }
+
+    /// Returns caret position for the byte offset.
+    /// Offset is rounded down to the nearest grapheme cluster boundary.
+    ///
+    /// Position is located on the baseline of the line.
+    pub fn caret_position(&self, offset: usize) -> Option<na::Point2<f32>> {
+        let idx = match self.carets.binary_search_by_key(&offset, |caret| caret.offset) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+        let caret = &self.carets[idx];
+        let line = &self.lines[caret.line];
+        Some(na::Point2::new(caret.x, line.baseline))
+    }
+
+    /// Returns byte offset of the caret closest to the point in layout space.
+    pub fn hit_test(&self, point: na::Point2<f32>) -> usize {
+        let line = self
+            .lines
+            .iter()
+            .position(|line| point.y >= line.baseline + self.descent)
+            .unwrap_or(self.lines.len().saturating_sub(1));
+
+        self.carets
+            .iter()
+            .filter(|caret| caret.line == line)
+            .min_by(|a, b| {
+                let a = (a.x - point.x).abs();
+                let b = (b.x - point.x).abs();
+                a.total_cmp(&b)
+            })
+            .map_or(0, |caret| caret.offset)
+    }
+
+    /// Distance from baseline to the top of the line.
+    pub fn ascent(&self) -> f32 {
+        self.ascent
+    }
+
+    /// Distance from baseline to the bottom of the line. Typically negative.
+    pub fn descent(&self) -> f32 {
+        self.descent
+    }
+}
+
+fn measure(text: &str, font: &Font, size: f32) -> Vec<Cluster> {
+    let mut clusters = Vec::new();
+    let mut prev: Option<char> = None;
+
+    for (start, grapheme) in text.grapheme_indices(true) {
+        let newline = grapheme == "\n" || grapheme == "\r\n";
+        let whitespace = grapheme.chars().all(char::is_whitespace);
+
+        if newline {
+            clusters.push(Cluster {
+                start,
+                end: start + grapheme.len(),
+                advance: 0.0,
+                kern: 0.0,
+                whitespace,
+                newline,
+            });
+            prev = None;
+            continue;
+        }
+
+        let first = grapheme.chars().next().unwrap();
+        let kern = match prev {
+            None => 0.0,
+            Some(prev) => font.horizontal_kern(prev, first, size).unwrap_or(0.0),
+        };
+
+        let advance = grapheme
+            .chars()
+            .map(|ch| font.metrics(ch, size).advance_width)
+            .sum();
+
+        clusters.push(Cluster {
+            start,
+            end: start + grapheme.len(),
+            advance,
+            kern,
+            whitespace,
+            newline,
+        });
+
+        prev = grapheme.chars().last();
+    }
+
+    clusters
+}
+
+/// Width of clusters placed on single line.
+fn line_width(clusters: &[Cluster]) -> f32 {
+    clusters
+        .iter()
+        .enumerate()
+        .map(|(idx, c)| if idx > 0 { c.kern + c.advance } else { c.advance })
+        .sum()
+}
+
+/// Width of clusters placed on single line, excluding trailing whitespace.
+fn visible_width(clusters: &[Cluster]) -> f32 {
+    match clusters.iter().rposition(|c| !c.whitespace) {
+        None => 0.0,
+        Some(last) => line_width(&clusters[..=last]),
+    }
+}
+
+/// Splits clusters into lines.
+/// Whitespace never starts a new line and hangs past the wrap width instead.
+fn break_lines(clusters: &[Cluster], constraints: &TextConstraints) -> Vec<std::ops::Range<usize>> {
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    let mut has_word = false;
+    let mut pen = 0.0;
+    let mut idx = 0;
+
+    while idx < clusters.len() {
+        if clusters[idx].newline {
+            lines.push(line_start..idx);
+            idx += 1;
+            line_start = idx;
+            has_word = false;
+            pen = 0.0;
+            continue;
+        }
+
+        let whitespace = clusters[idx].whitespace;
+        let mut end = idx;
+        while end < clusters.len()
+            && !clusters[end].newline
+            && clusters[end].whitespace == whitespace
+        {
+            end += 1;
+        }
+
+        let run = &clusters[idx..end];
+        let mut run_width = line_width(run);
+        if idx > line_start {
+            run_width += run[0].kern;
+        }
+
+        if whitespace {
+            pen += run_width;
+            idx = end;
+            continue;
+        }
+
+        if let Some(max_width) = constraints.max_width {
+            if pen + run_width > max_width {
+                if has_word {
+                    lines.push(line_start..idx);
+                    line_start = idx;
+                    pen = 0.0;
+                    run_width = line_width(run);
+                }
+
+                if constraints.overflow == WordOverflow::Break && pen + run_width > max_width {
+                    // Break the word between clusters, keeping at least one cluster per line.
+                    for i in idx..end {
+                        let c = &clusters[i];
+                        let w = if i > line_start {
+                            c.kern + c.advance
+                        } else {
+                            c.advance
+                        };
+
+                        if i > line_start && pen + w > max_width {
+                            lines.push(line_start..i);
+                            line_start = i;
+                            pen = c.advance;
+                        } else {
+                            pen += w;
+                        }
+                    }
+
+                    has_word = true;
+                    idx = end;
+                    continue;
+                }
+            }
+        }
+
+        pen += run_width;
+        has_word = true;
+        idx = end;
+    }
+
+    lines.push(line_start..clusters.len());
+    lines
+}
@@ -0,0 +1,336 @@
+//! Generic value animation.
+//!
+//! [`Tween`] component animates a value of type implementing [`Lerp`]
+//! over a [`TimeSpan`] with an [`Ease`] curve and writes it into
+//! another component of the same entity.
+//! Add [`tween_system`] for each pair of component and value types that are animated.
+
+use std::{any::TypeId, f32::consts::PI};
+
+use edict::{component::Component, entity::EntityId, query::Entities, world::World};
+
+use crate::{clocks::ClockIndex, scoped_allocator::ScopedAllocator, TimeSpan};
+
+/// Values that can be linearly interpolated.
+pub trait Lerp: Clone {
+    /// Returns value between `self` and `other`.
+    /// `t` of `0` gives `self` and `1` gives `other`.
+    /// Easing curves may overshoot, so `t` may be outside of `[0, 1]` range.
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    #[inline]
+    fn lerp(&self, other: &f32, t: f32) -> f32 {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for na::Vector2<f32> {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        na::Vector2::lerp(self, other, t)
+    }
+}
+
+impl Lerp for na::Vector3<f32> {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        na::Vector3::lerp(self, other, t)
+    }
+}
+
+impl Lerp for na::Point2<f32> {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        na::Point2::from(self.coords.lerp(&other.coords, t))
+    }
+}
+
+impl Lerp for na::Point3<f32> {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        na::Point3::from(self.coords.lerp(&other.coords, t))
+    }
+}
+
+impl Lerp for na::Isometry2<f32> {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self.lerp_slerp(other, t)
+    }
+}
+
+impl Lerp for na::Isometry3<f32> {
+    /// Snaps to `other` when rotations are opposite and slerp is ambiguous.
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self.try_lerp_slerp(other, t, f32::EPSILON)
+            .unwrap_or(*other)
+    }
+}
+
+impl Lerp for palette::LinSrgba<f32> {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        palette::LinSrgba::new(
+            self.red.lerp(&other.red, t),
+            self.green.lerp(&other.green, t),
+            self.blue.lerp(&other.blue, t),
+            self.alpha.lerp(&other.alpha, t),
+        )
+    }
+}
+
+impl Lerp for palette::Srgba<f32> {
+    /// Interpolates in linear space.
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        palette::Srgba::from_linear(self.into_linear().lerp(&other.into_linear(), t))
+    }
+}
+
+impl Lerp for TimeSpan {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let from = self.as_nanos() as f64;
+        let to = other.as_nanos() as f64;
+        let nanos = from + (to - from) * t as f64;
+        TimeSpan::from_nanos(nanos.max(0.0) as u64)
+    }
+}
+
+/// Easing curves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Ease {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    ElasticIn,
+    ElasticOut,
+    BounceIn,
+    BounceOut,
+}
+
+impl Ease {
+    /// Maps linear progress in `[0, 1]` to eased progress.
+    ///
+    /// Result is `0` at `0` and `1` at `1`.
+    /// Elastic curves overshoot in between.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Ease::Linear => t,
+            Ease::QuadIn => t * t,
+            Ease::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Ease::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Ease::CubicIn => t * t * t,
+            Ease::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Ease::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Ease::ElasticIn => 1.0 - Ease::ElasticOut.apply(1.0 - t),
+            Ease::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                }
+            }
+            Ease::BounceIn => 1.0 - Ease::BounceOut.apply(1.0 - t),
+            Ease::BounceOut => {
+                const N: f32 = 7.5625;
+                const D: f32 = 2.75;
+
+                if t < 1.0 / D {
+                    N * t * t
+                } else if t < 2.0 / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+/// Animates value of type `T` and writes it into component `C` of the same entity.
+///
+/// Tween is removed from the entity when complete
+/// and [`TweenCompleted`] event is recorded.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{game::Game, tween::{tween_system, Ease, Tween, TweenEvents}, TimeSpan};
+/// # use edict::component::Component;
+/// #[derive(Component)]
+/// struct Zoom(f32);
+///
+/// let mut game = Game::deterministic(0);
+/// game.scheduler.add_system(tween_system::<Zoom, f32>);
+///
+/// let camera = game.world.spawn((
+///     Zoom(1.0),
+///     Tween::new(1.0, 2.0, TimeSpan::SECOND, |zoom: &mut Zoom, value: f32| zoom.0 = value)
+///         .with_ease(Ease::CubicInOut),
+/// ));
+///
+/// game.step(TimeSpan::MILLISECOND * 500);
+/// assert_eq!(game.world.query_one_mut::<&Zoom>(camera).unwrap().0, 1.5);
+///
+/// // Tween stops at its end and is removed.
+/// game.step(TimeSpan::SECOND);
+/// assert_eq!(game.world.query_one_mut::<&Zoom>(camera).unwrap().0, 2.0);
+/// assert!(game.world.query_one_mut::<&Tween<Zoom, f32>>(camera).is_err());
+///
+/// let completed: Vec<_> = game.world.expect_resource_mut::<TweenEvents>().drain().collect();
+/// assert_eq!(completed.len(), 1);
+/// assert_eq!(completed[0].entity, camera);
+/// ```
+#[derive(Component)]
+#[edict(where C: 'static, T: 'static)]
+pub struct Tween<C, T> {
+    from: T,
+    to: T,
+    span: TimeSpan,
+    elapsed: TimeSpan,
+    ease: Ease,
+    apply: fn(&mut C, T),
+}
+
+impl<C, T> Tween<C, T>
+where
+    T: Lerp,
+{
+    /// Returns new tween from `from` to `to` that takes `span` time.
+    /// `apply` writes current value into target component.
+    pub fn new(from: T, to: T, span: TimeSpan, apply: fn(&mut C, T)) -> Self {
+        Tween {
+            from,
+            to,
+            span,
+            elapsed: TimeSpan::ZERO,
+            ease: Ease::Linear,
+            apply,
+        }
+    }
+
+    /// Sets easing curve.
+    pub fn with_ease(mut self, ease: Ease) -> Self {
+        self.ease = ease;
+        self
+    }
+
+    /// Returns current value.
+    pub fn value(&self) -> T {
+        if self.span.is_zero() {
+            return self.to.clone();
+        }
+
+        let t = self.elapsed.as_nanos() as f64 / self.span.as_nanos() as f64;
+        self.from.lerp(&self.to, self.ease.apply(t as f32))
+    }
+
+    /// Returns `true` if tween reached its end.
+    pub fn is_complete(&self) -> bool {
+        self.elapsed >= self.span
+    }
+
+    /// Advances tween by `delta`.
+    pub fn advance(&mut self, delta: TimeSpan) {
+        self.elapsed = std::cmp::min(self.elapsed + delta, self.span);
+    }
+}
+
+/// Event recorded when tween completes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TweenCompleted {
+    /// Entity tween was attached to.
+    pub entity: EntityId,
+
+    /// Type id of the component tween was writing to.
+    pub component: TypeId,
+}
+
+/// Resource with completed tweens.
+/// Should be drained by a system that reacts on completion.
+#[derive(Default)]
+pub struct TweenEvents {
+    completed: Vec<TweenCompleted>,
+}
+
+impl TweenEvents {
+    pub fn new() -> Self {
+        TweenEvents::default()
+    }
+
+    /// Drains events recorded since previous drain.
+    pub fn drain(&mut self) -> impl Iterator<Item = TweenCompleted> + '_ {
+        self.completed.drain(..)
+    }
+}
+
+/// Advances all tweens from `T` values into `C` components.
+pub fn tween_system<C, T>(allocator: &mut ScopedAllocator, world: &mut World)
+where
+    C: Component,
+    T: Lerp + Send + Sync + 'static,
+{
+    let delta = world.expect_resource::<ClockIndex>().delta;
+
+    let mut completed = Vec::new_in(&**allocator);
+
+    for (entity, tween, target) in world
+        .query_mut::<(Entities, &mut Tween<C, T>, &mut C)>()
+        .iter_mut()
+    {
+        tween.advance(delta);
+        (tween.apply)(target, tween.value());
+
+        if tween.is_complete() {
+            completed.push(entity);
+        }
+    }
+
+    if completed.is_empty() {
+        return;
+    }
+
+    for &entity in &completed {
+        let _ = world.remove::<Tween<C, T>>(entity);
+    }
+
+    if world.get_resource::<TweenEvents>().is_none() {
+        world.insert_resource(TweenEvents::new());
+    }
+
+    let mut events = world.expect_resource_mut::<TweenEvents>();
+    events
+        .completed
+        .extend(completed.into_iter().map(|entity| TweenCompleted {
+            entity,
+            component: TypeId::of::<C>(),
+        }));
+}
@@ -25,6 +25,10 @@ use edict::{
 /// `#[unfold(asset: AssetType)] over field with type `AssetId` will cause unfold system
 /// to load specified asset and put it as a component to the same entity, and then keep it sync in case of id changes, or unfold component is removed
 ///
+/// Asset field may be optional, i.e. `Option<TypedAssetId<A>>` or `Option<AssetId>`.
+/// Asset component is removed when such field is `None`,
+/// and unfold function receives `Option<&WithId<A>>` that is `None` without waiting for anything.
+///
 /// Warning: unfold system will not have any chance to see if asset component changes yet.
 ///
 /// If added component is a unfold itself with registered unfold system, this will create cascade effect.
@@ -38,6 +42,10 @@ use edict::{
 ///
 /// `#[unfold(funcname)]` on type suppresses default behavior attributes on individual fields. That is, components won't be added for fields with `#[unfold(asset)]` attribute.
 ///
+/// Enums are supported as well. Each variant unfolds its own fields,
+/// and `#[unfold(fn funcname)]` is placed over variants instead of the type.
+/// When value switches to another variant, components unfolded from previous variant are removed.
+///
 pub trait Unfold {
     /// System that operates on unfold.
     ///
@@ -0,0 +1,120 @@
+//! Tests that require graphics device.
+//!
+//! Ignored by default, run with `cargo test -- --ignored` on machine with GPU.
+
+#![cfg(feature = "graphics")]
+
+use arcana::{
+    graphics::Graphics,
+    scoped_arena::Scope,
+    sierra::{
+        BufferInfo, BufferUsage, Extent3, Format, ImageExtent, ImageInfo, ImageUsage, Layout,
+        MemoryUsage, Samples::Samples1, SubresourceLayers,
+    },
+};
+
+#[test]
+#[ignore = "requires GPU"]
+fn buffer_uploads_share_staging_buffer() {
+    let mut graphics = Graphics::new().unwrap();
+
+    let buffers: Vec<_> = (0..100)
+        .map(|_| {
+            graphics
+                .create_buffer(BufferInfo {
+                    align: 15,
+                    size: 16,
+                    usage: BufferUsage::TRANSFER_DST,
+                })
+                .unwrap()
+        })
+        .collect();
+
+    for (i, buffer) in buffers.iter().enumerate() {
+        graphics.upload_buffer(buffer, 0, &[i as u32; 4]).unwrap();
+    }
+
+    let scope = Scope::new();
+    let mut fence = graphics.flush_uploads_with_fence(&scope).unwrap();
+
+    // All uploads are copied from single staging buffer.
+    assert_eq!(fence.staging_buffers(), 1);
+
+    fence.wait().unwrap();
+    assert_eq!(fence.staging_buffers(), 0);
+}
+
+#[test]
+#[ignore = "requires GPU"]
+fn upload_fence_signals_written_data() {
+    let mut graphics = Graphics::new().unwrap();
+
+    let data: Vec<u32> = (0..1024).collect();
+    let len = data.len() * 4;
+
+    let mut buffer = graphics
+        .create_mappable_buffer(
+            BufferInfo {
+                align: 15,
+                size: len as u64,
+                usage: BufferUsage::TRANSFER_DST,
+            },
+            MemoryUsage::DOWNLOAD,
+        )
+        .unwrap();
+
+    graphics.upload_buffer(&buffer, 0, &data).unwrap();
+
+    let scope = Scope::new();
+    let mut fence = graphics.flush_uploads_with_fence(&scope).unwrap();
+    fence.wait().unwrap();
+    assert!(fence.is_complete());
+
+    let mapped = graphics.map_memory(&mut buffer, 0, len).unwrap();
+
+    // Safety: buffer is fully written by the upload.
+    let bytes = unsafe { std::slice::from_raw_parts(mapped.as_ptr().cast::<u8>(), len) };
+    assert_eq!(bytes, arcana::bytemuck::cast_slice::<u32, u8>(&data));
+
+    graphics.unmap_memory(&mut buffer);
+}
+
+#[test]
+#[ignore = "requires GPU"]
+fn download_image_returns_uploaded_texels() {
+    let mut graphics = Graphics::new().unwrap();
+
+    let (width, height) = (16, 8);
+    let texels: Vec<u8> = (0..width * height * 4)
+        .map(|i| (i * 7 % 251) as u8)
+        .collect();
+
+    let info = ImageInfo {
+        extent: ImageExtent::D2 { width, height },
+        format: Format::RGBA8Unorm,
+        levels: 1,
+        layers: 1,
+        samples: Samples1,
+        usage: ImageUsage::SAMPLED | ImageUsage::TRANSFER_SRC,
+    };
+
+    let layout = Layout::ShaderReadOnlyOptimal;
+    let image = graphics
+        .create_image_static(info, layout, &texels, Format::RGBA8Unorm, 0, 0)
+        .unwrap();
+
+    let downloaded = graphics
+        .download_image(
+            &image,
+            SubresourceLayers::all_layers(&info, 0),
+            Extent3 {
+                width,
+                height,
+                depth: 1,
+            },
+            layout,
+        )
+        .unwrap();
+
+    assert_eq!(downloaded, texels);
+}
@@ -1,6 +1,6 @@
 use arcana::{
-    game::game2, graphics, na, rect::Rect, scene::Global2, sprite::Sprite, system::SystemContext,
-    TimeSpan,
+    game::game2, graphics, na, rect::Rect, rng::SeededRng, scene::Global2, sprite::Sprite,
+    system::SystemContext, TimeSpan,
 };
 use arcana_physics::physics2::{
     dynamics::{RigidBodyBuilder, RigidBodyType},
@@ -28,15 +28,21 @@ impl Block {
             .cuboid
             .clone();
 
+        let (linvel, angvel, translation) = {
+            let mut rng = cx.world.expect_resource_mut::<SeededRng>();
+            (
+                na::Vector2::new(rng.range(-0.5..0.5), rng.range(-0.5..0.5)),
+                rng.range(-0.25..0.25),
+                na::Translation2::new(rng.range(-0.75..0.75), rng.range(-0.75..0.75)),
+            )
+        };
+
         let physical_data = cx.res.with(PhysicsData2::new);
 
         let body = physical_data.bodies.insert(
             RigidBodyBuilder::new(RigidBodyType::Dynamic)
-                .linvel(na::Vector2::new(
-                    rand::random::<f32>() - 0.5,
-                    rand::random::<f32>() - 0.5,
-                ))
-                .angvel(rand::random::<f32>() * 0.5 - 0.25)
+                .linvel(linvel)
+                .angvel(angvel)
                 .build(),
         );
 
@@ -59,13 +65,7 @@ impl Block {
                 albedo_factor: [0.3, 0.4, 0.5, 1.0],
                 ..Default::default()
             },
-            Global2::new(
-                na::Translation2::new(
-                    rand::random::<f32>() * 1.5 - 0.75,
-                    rand::random::<f32>() * 1.5 - 0.75,
-                )
-                .into(),
-            ),
+            Global2::new(translation.into()),
             body,
         ));
     }
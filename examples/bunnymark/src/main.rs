@@ -4,6 +4,7 @@ use arcana::{
     graphics::{Material, Texture},
     na,
     rect::Rect,
+    rng::SeededRng,
     scene::Global2,
     sprite::Sprite,
     system::SystemContext,
@@ -18,6 +19,11 @@ impl Bunny {
     fn spawn(self, cx: TaskContext<'_>) -> EntityId {
         let cat = cx.assets.load::<Texture, _>("bunny.png");
 
+        let translation = {
+            let mut rng = cx.world.expect_resource_mut::<SeededRng>();
+            na::Translation2::new(rng.range(-0.75..0.75), rng.range(-0.75..0.75))
+        };
+
         let entity = cx.world.spawn((
             self,
             Sprite {
@@ -29,13 +35,7 @@ impl Bunny {
                 },
                 ..Sprite::default()
             },
-            Global2::new(
-                na::Translation2::new(
-                    rand::random::<f32>() * 1.5 - 0.75,
-                    rand::random::<f32>() * 1.5 - 0.75,
-                )
-                .into(),
-            ),
+            Global2::new(translation.into()),
         ));
 
         cx.spawner.spawn(async move {
@@ -1,12 +1,9 @@
 mod raster;
 
-use {
-    arcana::*,
-    std::collections::hash_map::{Entry, HashMap},
-};
+use arcana::*;
 
 struct RaysRenderer {
-    blases: HashMap<graphics::Mesh, graphics::AccelerationStructure>,
+    blases: graphics::BlasCache,
 }
 
 impl graphics::Renderer for RaysRenderer {
@@ -15,7 +12,7 @@ impl graphics::Renderer for RaysRenderer {
         Self: Sized,
     {
         Ok(RaysRenderer {
-            blases: HashMap::new(),
+            blases: graphics::BlasCache::new(),
         })
     }
 
@@ -32,18 +29,14 @@ impl graphics::Renderer for RaysRenderer {
             .world
             .query_mut::<&graphics::Mesh>()
             .with::<graphics::Material>()
-            .without::<graphics::AccelerationStructure>()
+            .without::<graphics::Blas>()
         {
-            let blas = match self.blases.entry(mesh.clone()) {
-                Entry::Occupied(entry) => entry.get().clone(),
-                Entry::Vacant(entry) => {
-                    let blas = mesh.build_triangles_blas(&mut encoder, cx.graphics, cx.scope)?;
-                    entry.insert(blas).clone()
-                }
-            };
+            let blas = self.blases.get_or_build(mesh, &mut encoder, cx.graphics)?;
             insert_blasses.push((e, blas));
         }
 
+        self.blases.cleanup();
+
         for (e, blas) in insert_blasses {
             cx.world.insert_one(e, blas).unwrap();
         }
@@ -67,8 +67,8 @@ impl System for TankClientSystem {
             .with::<Tank>()
         {
             if tank.alive && tank.fire {
-                let pos = global.iso.transform_point(&na::Point2::new(0.0, -0.6));
-                let dir = global.iso.transform_vector(&na::Vector2::new(0.0, -10.0));
+                let pos = global.transform_point(&na::Point2::new(0.0, -0.6));
+                let dir = global.transform_vector(&na::Vector2::new(0.0, -10.0));
                 bullets.push((pos, dir));
                 tank.fire = false;
             }
@@ -100,6 +100,7 @@ impl System for TankClientSystem {
                         src: Rect::ONE_QUAD,
                         tex: Rect::ONE_QUAD,
                         layer: 0,
+                        ..Sprite::default()
                     },
                     Material {
                         albedo_factor: [1.0, 0.8, 0.2, 1.0],
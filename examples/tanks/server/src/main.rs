@@ -15,6 +15,7 @@ use arcana::{
         ContactQueue2, Physics2, PhysicsData2,
     },
     prelude::*,
+    rng::SeededRng,
     tiles::{TileMap, TileSet},
 };
 use eyre::WrapErr;
@@ -101,7 +102,6 @@ pub struct TankStateInternal {
     reload: TimeSpan,
     pending_fire_threshold: TimeSpan,
     last_fire: TimeStamp,
-    pending_fire: bool,
 }
 
 impl Default for TankStateInternal {
@@ -116,7 +116,6 @@ impl TankStateInternal {
             reload: timespan!(1 s),
             pending_fire_threshold: timespan!(200 ms),
             last_fire: TimeStamp::ORIGIN,
-            pending_fire: false,
         }
     }
 }
@@ -170,23 +169,25 @@ impl System for TankSystem {
                 // tank.drive = 0;
                 // tank.rotate = 0;
 
-                for cmd in commands.drain() {
+                let reloaded_at = internal.last_fire + internal.reload;
+                let reloaded = reloaded_at <= cx.clock.now;
+                let reloading_soon =
+                    reloaded_at <= cx.clock.now + internal.pending_fire_threshold;
+
+                // `Fire` issued shortly before reload completes stays queued until it does.
+                let ready = |cmd: &mut TankCommand| {
+                    !matches!(cmd, TankCommand::Fire) || reloaded || !reloading_soon
+                };
+
+                for cmd in commands.drain_filter(ready) {
                     match cmd {
                         TankCommand::Drive(i) => tank.drive += i,
                         TankCommand::Rotate(i) => tank.rotate += i,
-                        TankCommand::Fire => {
-                            if internal.last_fire + internal.reload
-                                <= cx.clock.now + internal.pending_fire_threshold
-                            {
-                                internal.pending_fire = true;
-                            }
-                        }
+                        TankCommand::Fire => tank.fire |= reloaded,
                     }
                 }
 
-                if internal.pending_fire && internal.last_fire + internal.reload <= cx.clock.now {
-                    tank.fire = true;
-                    internal.pending_fire = false;
+                if tank.fire {
                     internal.last_fire = cx.clock.now;
                 }
 
@@ -199,8 +200,8 @@ impl System for TankSystem {
                 }
 
                 if tank.fire {
-                    let pos = global.iso.transform_point(&na::Point2::new(0.0, -0.6));
-                    let dir = global.iso.transform_vector(&na::Vector2::new(0.0, -10.0));
+                    let pos = global.transform_point(&na::Point2::new(0.0, -0.6));
+                    let dir = global.transform_vector(&na::Vector2::new(0.0, -10.0));
                     bullets.push((pos, dir));
                 }
             }
@@ -286,7 +287,10 @@ fn main() {
 
         for i in -1..=1 {
             for j in -1..=1 {
-                let index = rand::random::<usize>() % maps.len();
+                let index = game
+                    .world
+                    .expect_resource_mut::<SeededRng>()
+                    .range(0..maps.len());
                 let map = &maps[index];
 
                 let offset = na::Vector2::new(i as f32, j as f32).component_mul(&map.size());
@@ -340,7 +344,9 @@ fn random_spawn_location(world: &mut World) -> Global2 {
         .into_iter()
         .count();
 
-    let map_index = rand::random::<usize>() % maps_count;
+    let map_index = world
+        .expect_resource_mut::<SeededRng>()
+        .range(0..maps_count);
 
     let (_, (map, set, global)) = world
         .query_mut::<(&TileMap, &TileSet, &Global2)>()
@@ -350,14 +356,16 @@ fn random_spawn_location(world: &mut World) -> Global2 {
 
     let dim = map.dimensions();
 
-    loop {
-        let x = rand::random::<usize>() % dim.x;
-        let y = rand::random::<usize>() % dim.y;
+    // Collect free cells, so that choice takes fixed amount of random values.
+    let free_cells: Vec<_> = (0..dim.y)
+        .flat_map(|y| (0..dim.x).map(move |x| (x, y)))
+        .filter(|&(x, y)| set.tiles[map.cell_at(x, y)].collider.is_none())
+        .map(|(x, y)| global.iso * na::Translation2::from(map.cell_center(x, y)))
+        .collect();
 
-        let cell = map.cell_at(x, y);
-        let tile = &set.tiles[cell];
-        if tile.collider.is_none() {
-            return Global2::new(global.iso * na::Translation2::from(map.cell_center(x, y)));
-        }
-    }
+    let cell_index = world
+        .expect_resource_mut::<SeededRng>()
+        .range(0..free_cells.len());
+
+    Global2::new(free_cells[cell_index])
 }
@@ -138,6 +138,7 @@ impl System for BulletSystem {
                         src: Rect::ONE_QUAD,
                         tex: Rect::ONE_QUAD,
                         layer: 0,
+                        ..Sprite::default()
                     },
                     Material {
                         albedo_factor: [1.0, 0.3, 0.1, 1.0],
@@ -203,6 +204,7 @@ fn unfold_tank(
             src: Rect::ONE_QUAD,
             tex: Rect::ONE_QUAD,
             layer: 1,
+            ..Sprite::default()
         },
         #[cfg(feature = "graphics")]
         Material {
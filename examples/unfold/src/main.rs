@@ -17,6 +17,8 @@ fn main() {
     game2(|mut game| async move {
         Foo::schedule_unfold_system(&mut game.scheduler);
         Bar::schedule_unfold_system(&mut game.scheduler);
+        Pickup::schedule_unfold_system(&mut game.scheduler);
+        Decal::schedule_unfold_system(&mut game.scheduler);
 
         game.world.spawn((Foo {
             a: AssetId::new(0x5321e2914afca30d).unwrap(),
@@ -28,8 +30,20 @@ fn main() {
             b: TypedAssetId::new(0x61cd051a6c24030d).unwrap(),
         },));
 
+        game.world
+            .spawn((Pickup::Weapon(AssetId::new(0x5321e2914afca30d).unwrap()),));
+
+        // Decal without texture is unfolded immediately,
+        // decal with texture is unfolded when texture is loaded.
+        game.world.spawn((Decal { texture: None },));
+        game.world.spawn((Decal {
+            texture: Some(TypedAssetId::new(0x5321e2914afca30d).unwrap()),
+        },));
+
         let mut foo_loaded = false;
         let mut bar_loaded = false;
+        let mut pickup_loaded = false;
+        let mut decals_loaded = false;
 
         game.scheduler.add_system(move |cx: SystemContext<'_>| {
             if !foo_loaded {
@@ -62,7 +76,29 @@ fn main() {
                 }
             }
 
-            if foo_loaded && bar_loaded {
+            if !pickup_loaded {
+                let q = cx.world.query::<&PickupUnfoldSpawned>();
+                for (e, spawned) in q.iter() {
+                    if let PickupUnfoldSpawned::Weapon(Some(_)) = spawned {
+                        assert!(matches!(cx.world.has_component::<Texture>(&e), Ok(true)));
+                        tracing::error!("PICKUP LOADED");
+                        pickup_loaded = true;
+                    }
+                }
+            }
+
+            if !decals_loaded {
+                let q = cx.world.query::<&DecalUnfolded>();
+                let textured = q.iter().filter(|(_, decal)| decal.textured).count();
+                let plain = q.iter().filter(|(_, decal)| !decal.textured).count();
+
+                if textured == 1 && plain == 1 {
+                    tracing::error!("DECALS LOADED");
+                    decals_loaded = true;
+                }
+            }
+
+            if foo_loaded && bar_loaded && pickup_loaded && decals_loaded {
                 cx.res.insert(Exit);
             }
         });
@@ -102,3 +138,37 @@ fn unfold_bar(
         spawn: Default::default(),
     }
 }
+
+/// Each variant unfolds its own fields.
+#[derive(Clone, Unfold)]
+pub enum Pickup {
+    Health,
+    Ammo(u32),
+    Weapon(#[unfold(asset: Texture)] AssetId),
+
+    #[unfold(fn unfold_bar)]
+    Decal {
+        #[unfold(asset: Texture)]
+        texture: AssetId,
+
+        #[unfold(asset)]
+        sprite_sheet: TypedAssetId<SpriteSheet>,
+    },
+}
+
+#[derive(Clone, Unfold)]
+#[unfold(fn unfold_decal)]
+pub struct Decal {
+    #[unfold(asset)]
+    texture: Option<TypedAssetId<Texture>>,
+}
+
+pub struct DecalUnfolded {
+    textured: bool,
+}
+
+fn unfold_decal(texture: Option<&WithId<Texture>>, _: &mut Res) -> UnfoldResult<impl Bundle> {
+    UnfoldResult::with_bundle((DecalUnfolded {
+        textured: texture.is_some(),
+    },))
+}
@@ -0,0 +1,284 @@
+//! Debug drawing of 2D colliders.
+
+use std::{f32::consts::TAU, mem::size_of, ops::Range};
+
+use arcana::{
+    camera::Camera2,
+    edict::entity::EntityId,
+    graphics::{
+        renderer::{DrawNode, RenderContext},
+        vertex_layouts_for_pipeline, Graphics, Position2, VertexType as _,
+    },
+    na,
+    palette::LinSrgba,
+    scene::{Global2, RenderGlobal2},
+    sierra::{
+        graphics_pipeline_desc, mat3, vec4, Access, Buffer, BufferInfo, BufferUsage, Descriptors,
+        DynamicGraphicsPipeline, Encoder, Extent2, FragmentShader, PipelineInput, PipelineStages,
+        PrimitiveTopology, RenderPassEncoder, ShaderModuleInfo, ShaderRepr, VertexShader,
+    },
+};
+use parry2d::shape::Shape;
+
+use crate::physics2::PhysicsData2;
+
+/// Number of segments in ball outline.
+const BALL_SEGMENTS: usize = 32;
+
+/// Half length of drawn halfspace boundary.
+const HALFSPACE_EXTENT: f32 = 1000.0;
+
+/// Draws outlines of colliders from [`PhysicsData2`] resource.
+///
+/// Colliders attached to bodies of entities are drawn with entity's [`Global2`],
+/// or [`RenderGlobal2`] if present, so outlines match sprites.
+/// Other colliders are drawn at their position in physics world.
+///
+/// Cuboids, balls and halfspaces are drawn precisely,
+/// other shapes are drawn as their bounding boxes.
+/// Nothing is drawn if there is no [`PhysicsData2`] resource.
+pub struct PhysicsDebugDraw2 {
+    /// Draws nothing when `false`.
+    pub enabled: bool,
+
+    /// Color of outlines.
+    pub color: LinSrgba<f32>,
+
+    pipeline: DynamicGraphicsPipeline,
+    pipeline_layout: <DebugPipeline as PipelineInput>::Layout,
+    set: <DebugDescriptors as Descriptors>::Instance,
+    vertices: Buffer,
+}
+
+#[derive(Clone, Copy, Default, ShaderRepr)]
+#[sierra(std140)]
+struct Uniforms {
+    camera: mat3,
+    color: vec4,
+}
+
+#[derive(Descriptors)]
+struct DebugDescriptors {
+    #[sierra(uniform, stages(vertex, fragment))]
+    uniforms: Uniforms,
+}
+
+#[allow(unused)]
+#[derive(PipelineInput)]
+struct DebugPipeline {
+    #[sierra(set)]
+    set: DebugDescriptors,
+}
+
+impl PhysicsDebugDraw2 {
+    pub fn new(color: LinSrgba<f32>, graphics: &Graphics) -> arcana::eyre::Result<Self> {
+        let shader_module = graphics.create_shader_module(ShaderModuleInfo::wgsl(
+            std::include_bytes!("debug2.wgsl")
+                .to_vec()
+                .into_boxed_slice(),
+        ))?;
+
+        let pipeline_layout = DebugPipeline::layout(graphics)?;
+
+        let (vertex_bindings, vertex_attributes) =
+            vertex_layouts_for_pipeline(&[Position2::layout()]);
+
+        let vertices = graphics.create_buffer(BufferInfo {
+            align: 255,
+            size: size_of::<Position2>() as u64 * 1024,
+            usage: BufferUsage::VERTEX | BufferUsage::TRANSFER_DST,
+        })?;
+
+        Ok(PhysicsDebugDraw2 {
+            enabled: true,
+            color,
+            pipeline: DynamicGraphicsPipeline::new(graphics_pipeline_desc! {
+                vertex_bindings,
+                vertex_attributes,
+                primitive_topology: PrimitiveTopology::LineList,
+                vertex_shader: VertexShader::new(shader_module.clone(), "vs_main"),
+                fragment_shader: Some(FragmentShader::new(shader_module, "fs_main")),
+                layout: pipeline_layout.raw().clone(),
+            }),
+            set: pipeline_layout.set.instance(),
+            pipeline_layout,
+            vertices,
+        })
+    }
+}
+
+impl DrawNode for PhysicsDebugDraw2 {
+    fn draw<'a, 'b: 'a>(
+        &'b mut self,
+        cx: RenderContext<'a, 'b>,
+        encoder: &mut Encoder<'a>,
+        render_pass: &mut RenderPassEncoder<'_, 'b>,
+        camera: EntityId,
+        viewport: Extent2,
+    ) -> arcana::eyre::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let (global, camera) = cx.world.query_one_mut::<(&Global2, &Camera2)>(camera)?;
+
+        let (camera_iso, affine) = camera.view_proj(&global.iso, viewport.width, viewport.height);
+        let view = camera_iso.inverse().to_homogeneous();
+        let camera: [[f32; 3]; 3] = (affine.to_homogeneous() * view).into();
+
+        // Outlines in collider space.
+        let mut outlines = Vec::new_in(&*cx.scope);
+        let mut colliders = Vec::new_in(&*cx.scope);
+
+        match cx.world.get_resource::<PhysicsData2>() {
+            None => return Ok(()),
+            Some(data) => {
+                for (_, collider) in data.colliders.iter() {
+                    let entity = collider
+                        .parent()
+                        .and_then(|body| data.body_user_data(body))
+                        .map(|body| body.entity);
+
+                    let start = outlines.len();
+                    push_outline(collider.shape(), &mut outlines);
+
+                    colliders.push(ColliderOutline {
+                        entity,
+                        position_wrt_parent: collider
+                            .position_wrt_parent()
+                            .copied()
+                            .unwrap_or_else(na::Isometry2::identity),
+                        position: *collider.position(),
+                        range: start..outlines.len(),
+                    });
+                }
+            }
+        }
+
+        if outlines.is_empty() {
+            return Ok(());
+        }
+
+        let mut vertices = Vec::with_capacity_in(outlines.len(), &*cx.scope);
+
+        for collider in colliders {
+            let global = collider.entity.and_then(|entity| {
+                let (global, render_global) = cx
+                    .world
+                    .query_one_mut::<(&Global2, Option<&RenderGlobal2>)>(entity)
+                    .ok()?;
+                Some(render_global.map_or(global.iso, |render_global| render_global.iso))
+            });
+
+            let iso = match global {
+                Some(global) => global * collider.position_wrt_parent,
+                None => collider.position,
+            };
+
+            vertices.extend(outlines[collider.range].iter().map(|point| {
+                let point = iso * point;
+                Position2([point.x, point.y])
+            }));
+        }
+
+        render_pass.bind_dynamic_graphics_pipeline(
+            &mut self.pipeline,
+            &mut cx.world.expect_resource_mut::<Graphics>(),
+        )?;
+
+        let graphics = cx.world.expect_resource::<Graphics>();
+
+        let color = self.color;
+        let updated = self.set.update(
+            &DebugDescriptors {
+                uniforms: Uniforms {
+                    camera: mat3::from(camera),
+                    color: vec4::from([color.red, color.green, color.blue, color.alpha]),
+                },
+            },
+            &graphics,
+            &mut *encoder,
+        )?;
+
+        render_pass.bind_graphics_descriptors(&self.pipeline_layout, updated);
+
+        let vertex_count = vertices.len() as u32;
+
+        if self.vertices.info().size < vertex_count as u64 * size_of::<Position2>() as u64 {
+            self.vertices = graphics.create_buffer(BufferInfo {
+                align: 255,
+                size: size_of::<Position2>() as u64 * (vertex_count as u64).next_power_of_two(),
+                usage: BufferUsage::VERTEX | BufferUsage::TRANSFER_DST,
+            })?;
+        }
+
+        graphics.upload_buffer_with(&self.vertices, 0, vertices.leak(), encoder)?;
+
+        encoder.memory_barrier(
+            PipelineStages::TRANSFER,
+            Access::TRANSFER_WRITE,
+            PipelineStages::VERTEX_INPUT,
+            Access::VERTEX_ATTRIBUTE_READ,
+        );
+
+        render_pass.bind_vertex_buffers(0, &[(&self.vertices, 0)]);
+        render_pass.draw(0..vertex_count, 0..1);
+
+        Ok(())
+    }
+}
+
+struct ColliderOutline {
+    entity: Option<EntityId>,
+    position_wrt_parent: na::Isometry2<f32>,
+    position: na::Isometry2<f32>,
+    range: Range<usize>,
+}
+
+/// Pushes line list of shape outline in shape space.
+fn push_outline<A: std::alloc::Allocator>(
+    shape: &dyn Shape,
+    lines: &mut Vec<na::Point2<f32>, A>,
+) {
+    if let Some(cuboid) = shape.as_cuboid() {
+        push_rect(-cuboid.half_extents, cuboid.half_extents, lines);
+    } else if let Some(ball) = shape.as_ball() {
+        let point = |i: usize| {
+            let (sin, cos) = (i as f32 * TAU / BALL_SEGMENTS as f32).sin_cos();
+            na::Point2::new(cos * ball.radius, sin * ball.radius)
+        };
+
+        for i in 0..BALL_SEGMENTS {
+            lines.extend([point(i), point(i + 1)]);
+        }
+
+        // Show rotation.
+        lines.extend([na::Point2::origin(), point(0)]);
+    } else if let Some(halfspace) = shape.as_halfspace() {
+        let normal = halfspace.normal.into_inner();
+        let tangent = na::Vector2::new(-normal.y, normal.x) * HALFSPACE_EXTENT;
+
+        lines.extend([na::Point2::from(-tangent), na::Point2::from(tangent)]);
+        lines.extend([na::Point2::origin(), na::Point2::from(normal)]);
+    } else {
+        let aabb = shape.compute_local_aabb();
+        push_rect(aabb.mins.coords, aabb.maxs.coords, lines);
+    }
+}
+
+fn push_rect<A: std::alloc::Allocator>(
+    mins: na::Vector2<f32>,
+    maxs: na::Vector2<f32>,
+    lines: &mut Vec<na::Point2<f32>, A>,
+) {
+    let corners = [
+        na::Point2::new(mins.x, mins.y),
+        na::Point2::new(maxs.x, mins.y),
+        na::Point2::new(maxs.x, maxs.y),
+        na::Point2::new(mins.x, maxs.y),
+    ];
+
+    for i in 0..4 {
+        lines.extend([corners[i], corners[(i + 1) % 4]]);
+    }
+}
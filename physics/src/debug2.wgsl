@@ -0,0 +1,32 @@
+struct VertexInput {
+    [[location(0)]] pos: vec2<f32>;
+};
+
+struct VertexOutput {
+    [[builtin(position)]] pos: vec4<f32>;
+};
+
+struct Uniforms {
+    camera: mat3x3<f32>;
+    color: vec4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> uniforms: Uniforms;
+
+[[stage(vertex)]]
+fn vs_main(
+    in: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let pos = uniforms.camera * vec3<f32>(in.pos, 1.0);
+    out.pos = vec4<f32>(pos.xy, 0.0, 1.0);
+
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return uniforms.color;
+}
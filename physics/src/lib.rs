@@ -1,7 +1,13 @@
 #![feature(allocator_api)]
 
+#[cfg(any(feature = "2d", feature = "3d"))]
+mod user_data;
+
 #[cfg(feature = "2d")]
 pub mod physics2;
 
+#[cfg(all(feature = "2d", feature = "graphics"))]
+pub mod debug2;
+
 #[cfg(feature = "3d")]
 pub mod physics3;
@@ -1,17 +1,21 @@
 use approx::relative_ne;
 use arcana::{
-    edict::entity::EntityId,
-    scene::Global2,
+    edict::{entity::EntityId, world::World},
+    scene::{store_prev_global2, Global2, PrevGlobal2},
     system::{System, SystemContext, DEFAULT_TICK_SPAN},
     TimeSpan,
 };
 use flume::{unbounded, Sender};
 use rapier2d::{
     dynamics::{
-        CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
-        RigidBodyHandle, RigidBodySet,
+        CCDSolver, GenericJoint, ImpulseJointHandle, ImpulseJointSet, IntegrationParameters,
+        IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
+    },
+    geometry::{
+        ActiveCollisionTypes, ActiveEvents, BroadPhase, ColliderBuilder, ColliderHandle,
+        ColliderSet, CollisionEvent, CollisionEventFlags, ContactPair, InteractionGroups,
+        NarrowPhase, Ray, Shape, SharedShape, TOI,
     },
-    geometry::{BroadPhase, ColliderHandle, ColliderSet, CollisionEvent, ContactPair, NarrowPhase},
     na,
     pipeline::{EventHandler, PhysicsPipeline, QueryPipeline},
     prelude::{Collider, RigidBody},
@@ -19,9 +23,13 @@ use rapier2d::{
 
 pub use {parry2d::*, rapier2d::*};
 
+use crate::user_data;
+
 pub struct ContactQueue2 {
     contacts_started: Vec<ColliderHandle>,
     contacts_stopped: Vec<ColliderHandle>,
+    contacts_started_info: Vec<ContactInfo2>,
+    info: bool,
 }
 
 impl ContactQueue2 {
@@ -29,6 +37,22 @@ impl ContactQueue2 {
         ContactQueue2 {
             contacts_started: Vec::new(),
             contacts_stopped: Vec::new(),
+            contacts_started_info: Vec::new(),
+            info: false,
+        }
+    }
+
+    /// Returns queue that reports started contacts with [`ContactInfo2`].
+    ///
+    /// Started contacts are drained with [`ContactQueue2::drain_contacts_started_info`]
+    /// instead of [`ContactQueue2::drain_contacts_started`].
+    /// The latter still yields contacts which info is not available.
+    pub const fn with_info() -> Self {
+        ContactQueue2 {
+            contacts_started: Vec::new(),
+            contacts_stopped: Vec::new(),
+            contacts_started_info: Vec::new(),
+            info: true,
         }
     }
 
@@ -39,8 +63,131 @@ impl ContactQueue2 {
     pub fn drain_contacts_stopped(&mut self) -> std::vec::Drain<'_, ColliderHandle> {
         self.contacts_stopped.drain(..)
     }
+
+    /// Drains started contacts of queue created with [`ContactQueue2::with_info`].
+    pub fn drain_contacts_started_info(&mut self) -> std::vec::Drain<'_, ContactInfo2> {
+        self.contacts_started_info.drain(..)
+    }
+}
+
+/// Contact data reported by [`ContactQueue2::with_info`].
+///
+/// Data is taken from the contact at the end of the physics step where contact started.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{edict::world::World, scene::Global2, TimeSpan};
+/// # use arcana_physics::physics2::{*, na};
+/// # let mut world = World::new();
+/// # let mut events = PhysicsEvents2::new();
+/// let mut physics = Physics2::new();
+/// let mut data = PhysicsData2::new();
+///
+/// // Two balls fly towards each other along X axis.
+/// let mut ball = |x: f32, vx: f32| {
+///     let body = RigidBodyBuilder::dynamic()
+///         .translation(na::Vector2::new(x, 0.0))
+///         .linvel(na::Vector2::new(vx, 0.0));
+///     let collider = ColliderBuilder::ball(0.5).active_events(ActiveEvents::COLLISION_EVENTS);
+///     let body = data.bodies.insert(body);
+///     let collider = data.colliders.insert_with_parent(collider, body, &mut data.bodies);
+///     let global = Global2::new(*data.bodies[body].position());
+///     (world.spawn((global, body, ContactQueue2::with_info())), collider)
+/// };
+///
+/// let (lhs, lhs_collider) = ball(-2.0, 5.0);
+/// let (rhs, rhs_collider) = ball(2.0, -5.0);
+///
+/// let mut hit = None;
+/// for _ in 0..60 {
+///     physics.step(&mut world, &mut data, &mut events, TimeSpan::from_millis(16));
+///
+///     let queue = world.query_one_mut::<&mut ContactQueue2>(&lhs).unwrap();
+///     hit = queue.drain_contacts_started_info().next();
+///     if hit.is_some() {
+///         break;
+///     }
+/// }
+///
+/// let hit = hit.expect("Balls must collide");
+/// assert_eq!(hit.other, rhs_collider);
+///
+/// // Normal points from `lhs` towards `rhs` along collision axis.
+/// assert!(hit.normal.x > 0.99);
+/// assert!(hit.point.x.abs() < 0.1 && hit.point.y.abs() < 1e-3);
+///
+/// // Other side sees opposite normal.
+/// let queue = world.query_one_mut::<&mut ContactQueue2>(&rhs).unwrap();
+/// let info = queue.drain_contacts_started_info().next().unwrap();
+/// assert_eq!(info.other, lhs_collider);
+/// assert!(info.normal.x < -0.99);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactInfo2 {
+    /// Collider of the other entity.
+    pub other: ColliderHandle,
+
+    /// World-space contact normal pointing towards the other collider.
+    pub normal: na::Vector2<f32>,
+
+    /// World-space point of the deepest contact.
+    pub point: na::Point2<f32>,
+
+    /// Sum of normal impulses applied by the solver at all contact points.
+    pub total_impulse: f32,
 }
 
+impl ContactInfo2 {
+    /// Returns contact info as seen from `collider` which is one of the pair.
+    /// Returns `None` if pair has no contact points.
+    pub fn from_contact_pair(
+        pair: &ContactPair,
+        colliders: &ColliderSet,
+        collider: ColliderHandle,
+    ) -> Option<Self> {
+        let flip = pair.collider2 == collider;
+        let other = if flip { pair.collider1 } else { pair.collider2 };
+
+        let mut total_impulse = 0.0;
+        let mut deepest = None;
+        let mut min_dist = f32::INFINITY;
+
+        for manifold in &pair.manifolds {
+            for contact in &manifold.points {
+                total_impulse += contact.data.impulse;
+
+                if contact.dist < min_dist {
+                    min_dist = contact.dist;
+                    deepest = Some((manifold, contact));
+                }
+            }
+        }
+
+        let (manifold, contact) = deepest?;
+
+        // Contact point is stored in the frame of the first collider's sub-shape.
+        let position = colliders.get(pair.collider1)?.position();
+        let position = match manifold.subshape_pos1 {
+            None => *position,
+            Some(subshape) => position * subshape,
+        };
+
+        let normal = manifold.data.normal;
+
+        Some(ContactInfo2 {
+            other,
+            normal: if flip { -normal } else { normal },
+            point: position * contact.local_p1,
+            total_impulse,
+        })
+    }
+}
+
+/// Queue of intersections with sensor colliders.
+///
+/// Filled for both the sensor entity and the entity which collider intersects the sensor.
+/// Sensor colliders are created with [`ColliderBuilderExt2::sensor2`].
 pub struct IntersectionQueue2 {
     intersecting_started: Vec<ColliderHandle>,
     intersecting_stopped: Vec<ColliderHandle>,
@@ -63,12 +210,599 @@ impl IntersectionQueue2 {
     }
 }
 
+/// Trigger volume that tracks entities overlapping its sensor collider.
+///
+/// Created with [`Trigger2Builder`] and filled by [`Physics2`].
+/// Overlapping colliders are resolved to entities they are attached to,
+/// so entity is reported once even if several of its colliders overlap.
+///
+/// # Example
+///
+/// ```
+/// # use arcana::{edict::world::World, scene::Global2, TimeSpan};
+/// # use arcana_physics::physics2::{*, na};
+/// # let mut world = World::new();
+/// # let mut events = PhysicsEvents2::new();
+/// let mut physics = Physics2::new();
+/// let mut data = PhysicsData2::new();
+///
+/// let (body, trigger) = Trigger2Builder::new(SharedShape::ball(1.0)).build(&mut data);
+/// let trigger = world.spawn((Global2::identity(), body, trigger));
+///
+/// // Ball flies through the trigger along X axis.
+/// let ball = RigidBodyBuilder::dynamic()
+///     .translation(na::Vector2::new(-3.0, 0.0))
+///     .linvel(na::Vector2::new(10.0, 0.0));
+/// let handle = data.bodies.insert(ball);
+/// data.colliders.insert_with_parent(ColliderBuilder::ball(0.25), handle, &mut data.bodies);
+/// let ball = world.spawn((Global2::new(*data.bodies[handle].position()), handle));
+///
+/// let mut log = Vec::new();
+/// for step in 0..60 {
+///     physics.step(&mut world, &mut data, &mut events, TimeSpan::from_millis(16));
+///
+///     let trigger = world.query_one_mut::<&mut Trigger2>(&trigger).unwrap();
+///     if step == 17 {
+///         // Ball is in the middle of the trigger.
+///         assert_eq!(trigger.overlapping(), [ball]);
+///     }
+///
+///     log.extend(trigger.drain_entered().map(|entity| ("enter", entity)));
+///     log.extend(trigger.drain_exited().map(|entity| ("exit", entity)));
+/// }
+///
+/// assert_eq!(log, [("enter", ball), ("exit", ball)]);
+/// ```
+pub struct Trigger2 {
+    collider: ColliderHandle,
+    colliders: Vec<(ColliderHandle, EntityId)>,
+    overlapping: Vec<EntityId>,
+    entered: Vec<EntityId>,
+    exited: Vec<EntityId>,
+}
+
+impl Trigger2 {
+    /// Returns trigger that tracks overlaps of specified sensor collider.
+    pub const fn new(collider: ColliderHandle) -> Self {
+        Trigger2 {
+            collider,
+            colliders: Vec::new(),
+            overlapping: Vec::new(),
+            entered: Vec::new(),
+            exited: Vec::new(),
+        }
+    }
+
+    /// Returns sensor collider of the trigger.
+    pub fn collider(&self) -> ColliderHandle {
+        self.collider
+    }
+
+    /// Returns entities currently overlapping the trigger.
+    pub fn overlapping(&self) -> &[EntityId] {
+        &self.overlapping
+    }
+
+    /// Drains entities which started to overlap the trigger.
+    pub fn drain_entered(&mut self) -> std::vec::Drain<'_, EntityId> {
+        self.entered.drain(..)
+    }
+
+    /// Drains entities which stopped to overlap the trigger.
+    pub fn drain_exited(&mut self) -> std::vec::Drain<'_, EntityId> {
+        self.exited.drain(..)
+    }
+
+    /// Updates overlapping entities with collision event.
+    ///
+    /// Events that do not involve trigger's collider are ignored,
+    /// as well as colliders without entity in user data.
+    /// [`Physics2`] calls this for each sensor event of entity with the trigger.
+    pub fn handle_event(&mut self, data: &PhysicsData2, event: &CollisionEvent) {
+        let (lhs, rhs, started) = match *event {
+            CollisionEvent::Started(lhs, rhs, _) => (lhs, rhs, true),
+            CollisionEvent::Stopped(lhs, rhs, _) => (lhs, rhs, false),
+        };
+
+        let other = if lhs == self.collider {
+            rhs
+        } else if rhs == self.collider {
+            lhs
+        } else {
+            return;
+        };
+
+        if started {
+            let entity = match data.collider_user_data(other) {
+                Some(user_data) => user_data.entity,
+                None => return,
+            };
+
+            if !self.overlapping.contains(&entity) {
+                self.overlapping.push(entity);
+                self.entered.push(entity);
+            }
+            self.colliders.push((other, entity));
+        } else {
+            // Stopped collider may be already removed, so entity is looked up here.
+            let idx = match self.colliders.iter().position(|(c, _)| *c == other) {
+                Some(idx) => idx,
+                None => return,
+            };
+
+            let (_, entity) = self.colliders.swap_remove(idx);
+
+            if !self.colliders.iter().any(|(_, e)| *e == entity) {
+                self.overlapping.retain(|e| *e != entity);
+                self.exited.push(entity);
+            }
+        }
+    }
+}
+
+/// Builder for trigger volumes.
+///
+/// Trigger is a fixed body with single sensor collider.
+/// It detects dynamic and kinematic bodies without affecting their motion.
+/// Trigger entity may be moved by changing its [`Global2`].
+pub struct Trigger2Builder {
+    shape: SharedShape,
+    position: na::Isometry2<f32>,
+    groups: InteractionGroups,
+}
+
+impl Trigger2Builder {
+    pub fn new(shape: SharedShape) -> Self {
+        Trigger2Builder {
+            shape,
+            position: na::Isometry2::identity(),
+            groups: InteractionGroups::all(),
+        }
+    }
+
+    /// Sets initial position of the trigger.
+    pub fn position(mut self, position: na::Isometry2<f32>) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Sets collision groups of the sensor.
+    /// See [`ColliderBuilderExt2::with_groups`].
+    pub fn groups(mut self, membership: u32, filter: u32) -> Self {
+        self.groups = InteractionGroups::new(membership, filter);
+        self
+    }
+
+    /// Inserts trigger body and sensor collider.
+    /// Returned components should be inserted into trigger entity.
+    pub fn build(&self, data: &mut PhysicsData2) -> (RigidBodyHandle, Trigger2) {
+        let body = data
+            .bodies
+            .insert(RigidBodyBuilder::fixed().position(self.position).build());
+
+        let collider = ColliderBuilder::new(self.shape.clone())
+            .sensor2()
+            .active_collision_types(ActiveCollisionTypes::all())
+            .collision_groups(self.groups)
+            .build();
+
+        let collider = data
+            .colliders
+            .insert_with_parent(collider, body, &mut data.bodies);
+
+        (body, Trigger2::new(collider))
+    }
+}
+
+/// Extension methods for [`ColliderBuilder`].
+pub trait ColliderBuilderExt2 {
+    /// Makes collider a sensor.
+    ///
+    /// Sensor does not generate contact forces and does not block other bodies.
+    /// Intersections are reported to [`IntersectionQueue2`] of both entities.
+    fn sensor2(self) -> Self;
+
+    /// Sets collision groups.
+    ///
+    /// Two colliders interact only if membership of each intersects filter of the other.
+    /// Bits are built with [`CollisionGroups2`] constants and [`CollisionGroups2::group`].
+    fn with_groups(self, membership: u32, filter: u32) -> Self;
+}
+
+impl ColliderBuilderExt2 for ColliderBuilder {
+    #[inline]
+    fn sensor2(self) -> Self {
+        self.sensor(true)
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+    }
+
+    #[inline]
+    fn with_groups(self, membership: u32, filter: u32) -> Self {
+        self.collision_groups(InteractionGroups::new(membership, filter))
+    }
+}
+
+/// Collision group bits for [`ColliderBuilderExt2::with_groups`].
+///
+/// Games define their own named groups on top of these.
+///
+/// ```ignore
+/// const WALLS: u32 = CollisionGroups2::group(1);
+/// const TANKS: u32 = CollisionGroups2::group(2);
+/// const BULLETS: u32 = CollisionGroups2::group(3);
+///
+/// // Bullets hit walls and tanks but not other bullets.
+/// ColliderBuilder::ball(0.1).with_groups(BULLETS, WALLS | TANKS);
+/// ```
+pub struct CollisionGroups2;
+
+impl CollisionGroups2 {
+    /// No groups.
+    pub const NONE: u32 = 0;
+
+    /// All groups.
+    pub const ALL: u32 = u32::MAX;
+
+    /// Returns bit of the group with specified index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than 32.
+    #[inline]
+    pub const fn group(index: u32) -> u32 {
+        assert!(index < 32, "Only 32 collision groups are supported");
+        1 << index
+    }
+}
+
+/// Impulse applied to entity's rigid body before next physics step.
+///
+/// Component is removed once applied.
+/// Unlike setting velocity directly it adds to the current motion
+/// and does not fight the solver.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ApplyImpulse2 {
+    /// Linear impulse.
+    pub linear: na::Vector2<f32>,
+
+    /// Angular impulse.
+    pub angular: f32,
+
+    /// World-space point where linear impulse is applied.
+    /// Center of mass is used if `None`.
+    pub at_point: Option<na::Point2<f32>>,
+}
+
+impl ApplyImpulse2 {
+    /// Returns impulse pushing body at `position` away from `center`,
+    /// e.g. explosion knockback.
+    /// Magnitude decreases linearly from `strength` at the center to zero at `radius`.
+    pub fn radial(
+        center: na::Point2<f32>,
+        position: na::Point2<f32>,
+        strength: f32,
+        radius: f32,
+    ) -> Self {
+        let offset = position - center;
+        let distance = offset.norm();
+
+        let linear = if distance < radius && distance > f32::EPSILON {
+            offset * (strength * (1.0 - distance / radius) / distance)
+        } else {
+            na::Vector2::zeros()
+        };
+
+        ApplyImpulse2 {
+            linear,
+            angular: 0.0,
+            at_point: None,
+        }
+    }
+}
+
+/// Force applied to entity's rigid body during next physics step.
+///
+/// Component is removed once applied.
+/// Insert it again each tick to apply continuous force.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ApplyForce2 {
+    /// Linear force.
+    pub linear: na::Vector2<f32>,
+
+    /// Torque.
+    pub angular: f32,
+
+    /// World-space point where linear force is applied.
+    /// Center of mass is used if `None`.
+    pub at_point: Option<na::Point2<f32>>,
+}
+
+/// Movement of kinematic body corrected by [`KinematicController2::move_and_slide`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CorrectedMovement2 {
+    /// Translation applied to the body.
+    pub translation: na::Vector2<f32>,
+
+    /// Body touches walkable ground.
+    pub grounded: bool,
+
+    /// Walkable ground under the body is not flat.
+    pub on_slope: bool,
+}
+
+/// Moves kinematic position-based bodies resolving collisions against other colliders.
+///
+/// Body slides along obstacles instead of stopping.
+/// Surfaces with slope not steeper than `max_slope_angle` are ground,
+/// steeper ones are walls.
+///
+/// # Example
+///
+/// ```
+/// # use arcana_physics::physics2::{*, na};
+/// let mut data = PhysicsData2::new();
+///
+/// let wall = ColliderBuilder::cuboid(0.25, 10.0)
+///     .translation(na::Vector2::new(1.0, 0.0))
+///     .build();
+/// data.colliders.insert(wall);
+///
+/// let floor = ColliderBuilder::cuboid(10.0, 0.5)
+///     .translation(na::Vector2::new(0.0, -1.1))
+///     .build();
+/// data.colliders.insert(floor);
+///
+/// let body = data.bodies.insert(RigidBodyBuilder::kinematic_position_based().build());
+/// let collider = ColliderBuilder::cuboid(0.5, 0.5).build();
+/// data.colliders
+///     .insert_with_parent(collider, body, &mut data.bodies);
+///
+/// data.query_pipeline
+///     .update(&data.islands, &data.bodies, &data.colliders);
+///
+/// let controller = KinematicController2::new();
+///
+/// // Walking into the wall is blocked on X but free on Y.
+/// let movement = controller.move_and_slide(&mut data, body, na::Vector2::new(1.0, 1.0));
+/// assert!(movement.translation.x > 0.2 && movement.translation.x < 0.25);
+/// assert!((movement.translation.y - 1.0).abs() < 1e-3);
+/// assert!(!movement.grounded);
+///
+/// // Falling stops on the floor.
+/// let movement = controller.move_and_slide(&mut data, body, na::Vector2::new(0.0, -1.0));
+/// assert!(movement.translation.y > -0.1 && movement.translation.y < -0.08);
+/// assert!(movement.grounded);
+/// assert!(!movement.on_slope);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct KinematicController2 {
+    /// Gap kept between body and obstacles.
+    pub offset: f32,
+
+    /// Up direction.
+    pub up: na::UnitVector2<f32>,
+
+    /// Maximum angle in radians between ground normal and `up`.
+    pub max_slope_angle: f32,
+
+    /// Maximum number of slides along obstacles in single movement.
+    pub max_slides: usize,
+
+    /// Colliders which groups do not interact with these are ignored.
+    pub groups: InteractionGroups,
+}
+
+impl Default for KinematicController2 {
+    #[inline]
+    fn default() -> Self {
+        KinematicController2::new()
+    }
+}
+
+impl KinematicController2 {
+    #[inline]
+    pub fn new() -> Self {
+        KinematicController2 {
+            offset: 0.01,
+            up: na::Vector2::y_axis(),
+            max_slope_angle: std::f32::consts::FRAC_PI_4,
+            max_slides: 4,
+            groups: InteractionGroups::all(),
+        }
+    }
+
+    /// Computes movement of the body by `desired` translation corrected by obstacles
+    /// and sets body's next kinematic position.
+    ///
+    /// The shape of body's first collider is used.
+    /// Body without colliders moves freely.
+    /// Uses query pipeline updated by the last [`Physics2`] step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if body is missing.
+    ///
+    /// # Example
+    ///
+    /// Position set by the controller is applied by the next [`Physics2`] step.
+    ///
+    /// ```
+    /// # use arcana::{edict::world::World, scene::Global2, TimeSpan};
+    /// # use arcana_physics::physics2::{*, na};
+    /// # let mut events = PhysicsEvents2::new();
+    /// let mut world = World::new();
+    /// let mut physics = Physics2::new();
+    /// let mut data = PhysicsData2::new();
+    ///
+    /// let wall = ColliderBuilder::cuboid(0.25, 10.0).translation(na::Vector2::new(1.0, 0.0));
+    /// data.colliders.insert(wall);
+    ///
+    /// let body = data.bodies.insert(RigidBodyBuilder::kinematic_position_based());
+    /// let collider = ColliderBuilder::cuboid(0.5, 0.5);
+    /// data.colliders.insert_with_parent(collider, body, &mut data.bodies);
+    /// let entity = world.spawn((Global2::identity(), body));
+    ///
+    /// // First step updates query pipeline.
+    /// let step = TimeSpan::from_millis(16);
+    /// physics.step(&mut world, &mut data, &mut events, step);
+    ///
+    /// let controller = KinematicController2::new();
+    /// for _ in 0..3 {
+    ///     controller.move_and_slide(&mut data, body, na::Vector2::new(0.2, 0.0));
+    ///     physics.step(&mut world, &mut data, &mut events, step);
+    /// }
+    ///
+    /// // Body stopped at the wall and entity followed it.
+    /// let global = world.query_one_mut::<&Global2>(&entity).unwrap();
+    /// assert!(global.iso.translation.x > 0.2 && global.iso.translation.x < 0.25);
+    /// assert_eq!(global.iso, *data.bodies[body].position());
+    /// ```
+    pub fn move_and_slide(
+        &self,
+        data: &mut PhysicsData2,
+        handle: RigidBodyHandle,
+        desired: na::Vector2<f32>,
+    ) -> CorrectedMovement2 {
+        let movement = self.correct_movement(data, handle, desired);
+
+        let body = data.bodies.get_mut(handle).expect("Body is missing");
+        let translation = body.translation() + movement.translation;
+        body.set_next_kinematic_translation(translation);
+
+        movement
+    }
+
+    fn correct_movement(
+        &self,
+        data: &PhysicsData2,
+        handle: RigidBodyHandle,
+        desired: na::Vector2<f32>,
+    ) -> CorrectedMovement2 {
+        let mut movement = CorrectedMovement2 {
+            translation: na::Vector2::zeros(),
+            grounded: false,
+            on_slope: false,
+        };
+
+        let body = data.bodies.get(handle).expect("Body is missing");
+        let collider = match body.colliders().first() {
+            None => {
+                movement.translation = desired;
+                return movement;
+            }
+            Some(&collider) => &data.colliders[collider],
+        };
+
+        let shape = collider.shape();
+        let mut position = *collider.position();
+
+        // Ignore own colliders.
+        let filter = |other: ColliderHandle| data.colliders[other].parent() != Some(handle);
+
+        let cast = |position: &na::Isometry2<f32>, dir: &na::Vector2<f32>, max_toi: f32| {
+            data.query_pipeline.cast_shape(
+                &data.colliders,
+                position,
+                dir,
+                shape,
+                max_toi,
+                self.groups,
+                Some(&filter),
+            )
+        };
+
+        let mut remaining = desired;
+
+        for _ in 0..self.max_slides {
+            let distance = remaining.norm();
+            if distance <= f32::EPSILON {
+                break;
+            }
+            let dir = remaining / distance;
+
+            match cast(&position, &dir, distance + self.offset) {
+                None => {
+                    movement.translation += remaining;
+                    position.translation.vector += remaining;
+                    break;
+                }
+                Some((_, toi)) => {
+                    let allowed = (toi.toi - self.offset).clamp(0.0, distance);
+                    movement.translation += dir * allowed;
+                    position.translation.vector += dir * allowed;
+
+                    // Normal of the obstacle in world space.
+                    let normal = toi.normal1.into_inner();
+                    self.check_ground(&normal, &mut movement);
+
+                    // Slide along the obstacle.
+                    remaining = dir * (distance - allowed);
+                    let into = remaining.dot(&normal);
+                    if into < 0.0 {
+                        remaining -= normal * into;
+                    }
+                }
+            }
+        }
+
+        if !movement.grounded {
+            // Probe for ground right below the body.
+            let down = -self.up.into_inner();
+            if let Some((_, toi)) = cast(&position, &down, self.offset * 2.0) {
+                self.check_ground(&toi.normal1.into_inner(), &mut movement);
+            }
+        }
+
+        movement
+    }
+
+    fn check_ground(&self, normal: &na::Vector2<f32>, movement: &mut CorrectedMovement2) {
+        let angle = normal.dot(&self.up).clamp(-1.0, 1.0).acos();
+        if angle <= self.max_slope_angle {
+            movement.grounded = true;
+            movement.on_slope |= angle > 1e-3;
+        }
+    }
+}
+
+/// Resource with collision events of all entities.
+///
+/// Filled by [`Physics2`] created with [`Physics2::with_global_events`].
+/// Should be drained by a system that reacts on collisions.
+#[derive(Default)]
+pub struct PhysicsEvents2 {
+    collision_started: Vec<(EntityId, EntityId)>,
+    collision_stopped: Vec<(EntityId, EntityId)>,
+}
+
+impl PhysicsEvents2 {
+    pub const fn new() -> Self {
+        PhysicsEvents2 {
+            collision_started: Vec::new(),
+            collision_stopped: Vec::new(),
+        }
+    }
+
+    /// Drains pairs of entities which colliders started to touch or intersect.
+    pub fn drain_collision_started(&mut self) -> std::vec::Drain<'_, (EntityId, EntityId)> {
+        self.collision_started.drain(..)
+    }
+
+    /// Drains pairs of entities which colliders stopped to touch or intersect.
+    pub fn drain_collision_stopped(&mut self) -> std::vec::Drain<'_, (EntityId, EntityId)> {
+        self.collision_stopped.drain(..)
+    }
+}
+
 pub struct Physics2 {
     pipeline: PhysicsPipeline,
     integration_parameters: IntegrationParameters,
     broad_phase: BroadPhase,
     narrow_phase: NarrowPhase,
     ccd_solver: CCDSolver,
+    global_events: bool,
+    interpolation: bool,
+    gravity: Option<na::Vector2<f32>>,
 }
 
 pub struct PhysicsData2 {
@@ -111,6 +845,274 @@ impl PhysicsData2 {
         let collider = self.colliders.get(handle)?;
         ColliderUserData2::get(collider)
     }
+
+    /// Stores entity in user data of the body and its colliders,
+    /// so that events and queries resolve them to the entity.
+    ///
+    /// [`Physics2`] calls this for all entities with [`RigidBodyHandle`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if body is missing.
+    pub fn attach_body(&mut self, entity: EntityId, handle: RigidBodyHandle) {
+        let body = self.bodies.get_mut(handle).expect("Body is missing");
+        BodyUserData2 { entity }.set_to(body);
+
+        for (index, &collider) in body.colliders().iter().enumerate() {
+            let collider = self.colliders.get_mut(collider).unwrap();
+            ColliderUserData2 {
+                entity,
+                body_index: index,
+            }
+            .set_to(collider);
+        }
+    }
+
+    /// Removes body together with attached colliders and joints.
+    /// Returns `false` if body was already removed.
+    ///
+    /// [`Physics2`] calls this for bodies which entity was despawned
+    /// or lost [`RigidBodyHandle`] component.
+    /// Such bodies are found by checking all bodies on each step,
+    /// there is no hook on component removal.
+    /// If entity still has handle of removed body,
+    /// [`RigidBodyHandle`] component is removed from it on the next step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::{edict::world::World, scene::Global2, TimeSpan};
+    /// # use arcana_physics::physics2::*;
+    /// # let mut events = PhysicsEvents2::new();
+    /// let mut world = World::new();
+    /// let mut physics = Physics2::new();
+    /// let mut data = PhysicsData2::new();
+    ///
+    /// let spawned: Vec<_> = (0..1000)
+    ///     .map(|_| {
+    ///         let body = data.bodies.insert(RigidBodyBuilder::dynamic());
+    ///         data.colliders
+    ///             .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut data.bodies);
+    ///         (world.spawn((Global2::identity(), body)), body)
+    ///     })
+    ///     .collect();
+    ///
+    /// // Bodies are attached to entities on the first step.
+    /// let step = TimeSpan::from_millis(16);
+    /// physics.step(&mut world, &mut data, &mut events, step);
+    ///
+    /// // Bodies of despawned entities are removed on the next step.
+    /// for &(entity, _) in &spawned[..500] {
+    ///     world.despawn(&entity).unwrap();
+    /// }
+    /// physics.step(&mut world, &mut data, &mut events, step);
+    /// assert_eq!(data.bodies.len(), 500);
+    /// assert_eq!(data.colliders.len(), 500);
+    ///
+    /// for &(_, handle) in &spawned[500..] {
+    ///     assert!(data.remove_body(handle));
+    ///     assert!(!data.remove_body(handle));
+    /// }
+    ///
+    /// assert_eq!(data.bodies.len(), 0);
+    /// assert_eq!(data.colliders.len(), 0);
+    ///
+    /// // Entities that still hold handles of removed bodies are detached from them.
+    /// physics.step(&mut world, &mut data, &mut events, step);
+    /// for &(entity, _) in &spawned[500..] {
+    ///     assert!(world.query_one_mut::<&RigidBodyHandle>(&entity).is_err());
+    /// }
+    /// ```
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) -> bool {
+        self.bodies
+            .remove(
+                handle,
+                &mut self.islands,
+                &mut self.colliders,
+                &mut self.impulse_joints,
+                &mut self.multibody_joints,
+                true,
+            )
+            .is_some()
+    }
+
+    /// Inserts impulse joint between two bodies.
+    ///
+    /// Joint is removed automatically when either body is removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::{edict::world::World, TimeSpan};
+    /// # use arcana_physics::physics2::{*, na};
+    /// # let mut world = World::new();
+    /// # let mut events = PhysicsEvents2::new();
+    /// let mut physics = Physics2::new().with_gravity(na::Vector2::new(0.0, -9.81));
+    /// let mut data = PhysicsData2::new();
+    ///
+    /// // Bodies without entities are simulated as well.
+    /// let anchor = data.bodies.insert(RigidBodyBuilder::dynamic());
+    /// data.colliders
+    ///     .insert_with_parent(ColliderBuilder::ball(0.25), anchor, &mut data.bodies);
+    ///
+    /// let wheel = RigidBodyBuilder::dynamic().translation(na::Vector2::new(1.0, 0.0));
+    /// let wheel = data.bodies.insert(wheel);
+    /// data.colliders
+    ///     .insert_with_parent(ColliderBuilder::ball(0.25), wheel, &mut data.bodies);
+    ///
+    /// let joint = RevoluteJointBuilder::new().local_anchor2(na::Point2::new(-1.0, 0.0));
+    /// let joint = data.insert_joint(anchor, wheel, joint);
+    ///
+    /// for _ in 0..50 {
+    ///     physics.step(&mut world, &mut data, &mut events, TimeSpan::from_millis(16));
+    /// }
+    ///
+    /// // Bodies fell together and stayed connected.
+    /// let anchor_pos = data.bodies[anchor].translation();
+    /// let wheel_pos = data.bodies[wheel].translation();
+    /// assert!(anchor_pos.y < -1.0);
+    /// assert!(((wheel_pos - anchor_pos).norm() - 1.0).abs() < 0.01);
+    ///
+    /// assert!(data.remove_joint(joint));
+    /// ```
+    pub fn insert_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        joint: impl Into<GenericJoint>,
+    ) -> ImpulseJointHandle {
+        self.impulse_joints.insert(body1, body2, joint, true)
+    }
+
+    /// Removes impulse joint.
+    /// Returns `false` if joint was already removed.
+    pub fn remove_joint(&mut self, handle: ImpulseJointHandle) -> bool {
+        self.impulse_joints.remove(handle, true).is_some()
+    }
+
+    /// Casts ray and returns closest collider hit and time of impact.
+    ///
+    /// Hit point is `origin + dir * toi`.
+    /// Only colliders which groups interact with `groups` are tested.
+    /// Uses query pipeline updated by the last [`Physics2`] step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_physics::physics2::{*, na};
+    /// let mut data = PhysicsData2::new();
+    ///
+    /// let near = ColliderBuilder::cuboid(1.0, 1.0).translation(na::Vector2::new(5.0, 0.0));
+    /// let near = data.colliders.insert(near);
+    ///
+    /// let far = ColliderBuilder::cuboid(1.0, 1.0).translation(na::Vector2::new(10.0, 0.0));
+    /// data.colliders.insert(far);
+    ///
+    /// data.query_pipeline
+    ///     .update(&data.islands, &data.bodies, &data.colliders);
+    ///
+    /// let origin = na::Point2::origin();
+    /// let all = InteractionGroups::all();
+    ///
+    /// let (hit, toi) = data.cast_ray(origin, na::Vector2::x(), 100.0, all).unwrap();
+    /// assert_eq!(hit, near);
+    /// assert!((toi - 4.0).abs() < 1e-5);
+    ///
+    /// assert!(data.cast_ray(origin, -na::Vector2::x(), 100.0, all).is_none());
+    /// assert!(data.cast_ray(origin, na::Vector2::x(), 3.0, all).is_none());
+    /// ```
+    pub fn cast_ray(
+        &self,
+        origin: na::Point2<f32>,
+        dir: na::Vector2<f32>,
+        max_toi: f32,
+        groups: InteractionGroups,
+    ) -> Option<(ColliderHandle, f32)> {
+        self.query_pipeline.cast_ray(
+            &self.colliders,
+            &Ray::new(origin, dir),
+            max_toi,
+            true,
+            groups,
+            None,
+        )
+    }
+
+    /// Casts ray and returns entity of the closest collider hit and time of impact.
+    ///
+    /// Colliders not attached to entities are skipped.
+    pub fn cast_ray_entity(
+        &self,
+        origin: na::Point2<f32>,
+        dir: na::Vector2<f32>,
+        max_toi: f32,
+        groups: InteractionGroups,
+    ) -> Option<(EntityId, f32)> {
+        user_data::query_entity(
+            |handle| Some(self.colliders.get(handle)?.user_data),
+            |filter| {
+                self.query_pipeline.cast_ray(
+                    &self.colliders,
+                    &Ray::new(origin, dir),
+                    max_toi,
+                    true,
+                    groups,
+                    Some(filter),
+                )
+            },
+        )
+    }
+
+    /// Casts shape moving from `position` with `velocity`
+    /// and returns closest collider hit and time of impact.
+    ///
+    /// Hit position is `position` translated by `velocity * toi.toi`.
+    pub fn cast_shape(
+        &self,
+        shape: &dyn Shape,
+        position: &na::Isometry2<f32>,
+        velocity: &na::Vector2<f32>,
+        max_toi: f32,
+        groups: InteractionGroups,
+    ) -> Option<(ColliderHandle, TOI)> {
+        self.query_pipeline.cast_shape(
+            &self.colliders,
+            position,
+            velocity,
+            shape,
+            max_toi,
+            groups,
+            None,
+        )
+    }
+
+    /// Casts shape moving from `position` with `velocity`
+    /// and returns entity of the closest collider hit and time of impact.
+    ///
+    /// Colliders not attached to entities are skipped.
+    pub fn cast_shape_entity(
+        &self,
+        shape: &dyn Shape,
+        position: &na::Isometry2<f32>,
+        velocity: &na::Vector2<f32>,
+        max_toi: f32,
+        groups: InteractionGroups,
+    ) -> Option<(EntityId, TOI)> {
+        user_data::query_entity(
+            |handle| Some(self.colliders.get(handle)?.user_data),
+            |filter| {
+                self.query_pipeline.cast_shape(
+                    &self.colliders,
+                    position,
+                    velocity,
+                    shape,
+                    max_toi,
+                    groups,
+                    Some(filter),
+                )
+            },
+        )
+    }
 }
 
 impl Default for Physics2 {
@@ -137,21 +1139,131 @@ impl Physics2 {
             broad_phase: BroadPhase::new(),
             narrow_phase: NarrowPhase::new(),
             ccd_solver: CCDSolver::new(),
+            global_events: false,
+            interpolation: false,
+            gravity: None,
         }
     }
-}
 
-impl System for Physics2 {
+    /// Replaces integration parameters.
+    ///
+    /// `dt` is overwritten on each run with clock delta,
+    /// which is the step when system runs at fixed rate.
+    ///
+    /// # Example
+    ///
+    /// CCD prevents fast bodies from tunneling through thin walls.
+    ///
+    /// ```
+    /// # use arcana::{edict::world::World, scene::Global2, TimeSpan};
+    /// # use arcana_physics::physics2::{*, na};
+    /// let params = IntegrationParameters {
+    ///     max_ccd_substeps: 4,
+    ///     ..IntegrationParameters::default()
+    /// };
+    /// let mut physics = Physics2::new().with_integration_parameters(params);
+    ///
+    /// let mut world = World::new();
+    /// let mut data = PhysicsData2::new();
+    /// let mut events = PhysicsEvents2::new();
+    ///
+    /// let wall = ColliderBuilder::cuboid(0.05, 10.0).translation(na::Vector2::new(5.0, 0.0));
+    /// data.colliders.insert(wall);
+    ///
+    /// let mut spawn = |position: na::Vector2<f32>, velocity: na::Vector2<f32>| {
+    ///     let body = RigidBodyBuilder::dynamic()
+    ///         .translation(position)
+    ///         .linvel(velocity)
+    ///         .ccd_enabled(true)
+    ///         .build();
+    ///     let global = Global2::new(*body.position());
+    ///     let body = data.bodies.insert(body);
+    ///     data.colliders
+    ///         .insert_with_parent(ColliderBuilder::ball(0.1), body, &mut data.bodies);
+    ///     world.spawn((global, body));
+    ///     body
+    /// };
+    ///
+    /// let bullet = spawn(na::Vector2::zeros(), na::Vector2::new(500.0, 0.0));
+    /// let walker = spawn(na::Vector2::new(0.0, 50.0), na::Vector2::new(1.0, 0.0));
+    ///
+    /// // `dt` is taken from step delta, not from integration parameters.
+    /// physics.step(&mut world, &mut data, &mut events, TimeSpan::from_millis(10));
+    /// assert!((data.bodies[walker].translation().x - 0.01).abs() < 1e-5);
+    ///
+    /// for _ in 0..10 {
+    ///     physics.step(&mut world, &mut data, &mut events, TimeSpan::from_millis(10));
+    /// }
+    ///
+    /// assert!(data.bodies[bullet].translation().x < 5.0);
+    /// ```
     #[inline]
-    fn name(&self) -> &str {
-        "Physics"
+    pub fn with_integration_parameters(mut self, params: IntegrationParameters) -> Self {
+        self.integration_parameters = params;
+        self
     }
 
-    fn run(&mut self, cx: SystemContext<'_>) {
-        let data = cx.res.with(PhysicsData2::new);
+    /// Sets gravity written into [`PhysicsData2`] on the next run.
+    #[inline]
+    pub fn with_gravity(mut self, gravity: na::Vector2<f32>) -> Self {
+        self.set_gravity(gravity);
+        self
+    }
+
+    /// Sets gravity written into [`PhysicsData2`] on the next run.
+    ///
+    /// Gravity may be changed later directly in [`PhysicsData2::gravity`].
+    #[inline]
+    pub fn set_gravity(&mut self, gravity: na::Vector2<f32>) {
+        self.gravity = Some(gravity);
+    }
+
+    /// Makes this system add [`PrevGlobal2`] to all entities with rigid bodies.
+    ///
+    /// Previous transform is stored at the start of each step,
+    /// and [`interpolate_transforms`](arcana::scene::interpolate_transforms) system
+    /// blends it with [`Global2`] into `RenderGlobal2` that sprite renderer reads.
+    /// Run this system with `to_fix_system(step).interpolated()`
+    /// so that blend factor is written.
+    #[inline]
+    pub fn with_interpolation(mut self) -> Self {
+        self.interpolation = true;
+        self
+    }
 
-        let mut remove_bodies = Vec::with_capacity_in(64, &*cx.scope);
-        let world = &mut *cx.world;
+    /// Makes this system record collision events into [`PhysicsEvents2`] resource
+    /// in addition to per-entity queues.
+    #[inline]
+    pub fn with_global_events(mut self) -> Self {
+        self.global_events = true;
+        self
+    }
+
+    /// Runs single physics step of `delta` for entities in the world.
+    ///
+    /// [`Physics2`] system calls this with clock delta
+    /// and [`PhysicsData2`] and [`PhysicsEvents2`] resources.
+    /// Collision events of all entities are recorded into `events`
+    /// only if created with [`Physics2::with_global_events`].
+    pub fn step(
+        &mut self,
+        world: &mut World,
+        data: &mut PhysicsData2,
+        events: &mut PhysicsEvents2,
+        delta: TimeSpan,
+    ) {
+        if let Some(gravity) = self.gravity.take() {
+            data.gravity = gravity;
+        }
+
+        let dt = delta.as_secs_f32();
+        if dt > 0.0 {
+            self.integration_parameters.dt = dt;
+        }
+
+        // Bodies are checked against their entities on each step
+        // instead of tracking component removals.
+        let mut remove_bodies = Vec::new();
         data.bodies.iter().for_each(|(handle, body)| {
             if let Some(body_data) = BodyUserData2::get(body) {
                 match world.query_one_mut::<&RigidBodyHandle>(&body_data.entity) {
@@ -161,37 +1273,23 @@ impl System for Physics2 {
             }
         });
         for handle in remove_bodies {
-            data.bodies.remove(
-                handle,
-                &mut data.islands,
-                &mut data.colliders,
-                &mut data.impulse_joints,
-                &mut data.multibody_joints,
-                true,
-            );
+            data.remove_body(handle);
         }
 
-        for (entity, body) in cx.world.query_mut::<&RigidBodyHandle>() {
-            let body = data.bodies.get_mut(*body).unwrap();
-
-            match BodyUserData2::get(body) {
-                Some(body_data) if body_data.entity == entity => {}
-                _ => {
-                    BodyUserData2 { entity }.set_to(body);
-
-                    for (index, &collider) in body.colliders().iter().enumerate() {
-                        let collider = data.colliders.get_mut(collider).unwrap();
-                        ColliderUserData2 {
-                            entity,
-                            body_index: index,
-                        }
-                        .set_to(collider);
-                    }
-                }
+        let mut detached = Vec::new();
+        for (entity, &body) in world.query_mut::<&RigidBodyHandle>() {
+            match data.bodies.get(body).map(BodyUserData2::get) {
+                // Body was removed with `PhysicsData2::remove_body`.
+                None => detached.push(entity),
+                Some(Some(body_data)) if body_data.entity == entity => {}
+                Some(_) => data.attach_body(entity, body),
             }
         }
+        for entity in detached {
+            let _ = world.remove::<RigidBodyHandle>(&entity);
+        }
 
-        for (_entity, (global, body)) in cx.world.query_mut::<(&Global2, &RigidBodyHandle)>() {
+        for (_entity, (global, body)) in world.query_mut::<(&Global2, &RigidBodyHandle)>() {
             let body = data.bodies.get_mut(*body).unwrap();
 
             if relative_ne!(*body.position(), global.iso) {
@@ -226,6 +1324,54 @@ impl System for Physics2 {
             }
         }
 
+        let mut applied_impulses = Vec::new();
+        for (entity, (body, impulse)) in world.query_mut::<(&RigidBodyHandle, &ApplyImpulse2)>() {
+            let body = data.bodies.get_mut(*body).unwrap();
+
+            match impulse.at_point {
+                None => body.apply_impulse(impulse.linear, true),
+                Some(point) => body.apply_impulse_at_point(impulse.linear, point, true),
+            }
+            body.apply_torque_impulse(impulse.angular, true);
+
+            applied_impulses.push(entity);
+        }
+
+        for entity in applied_impulses {
+            let _ = world.remove::<ApplyImpulse2>(&entity);
+        }
+
+        let mut applied_forces = Vec::new();
+        for (entity, (&handle, force)) in world.query_mut::<(&RigidBodyHandle, &ApplyForce2)>() {
+            let body = data.bodies.get_mut(handle).unwrap();
+
+            match force.at_point {
+                None => body.add_force(force.linear, true),
+                Some(point) => body.add_force_at_point(force.linear, point, true),
+            }
+            body.add_torque(force.angular, true);
+
+            applied_forces.push((entity, handle));
+        }
+
+        if self.interpolation {
+            let mut new_entities = Vec::new();
+            for (entity, (global, _, prev)) in
+                world.query_mut::<(&Global2, &RigidBodyHandle, Option<&PrevGlobal2>)>()
+            {
+                if prev.is_none() {
+                    new_entities.push((entity, PrevGlobal2::from(*global)));
+                }
+            }
+
+            for (entity, prev) in new_entities {
+                let _ = world.insert_one(entity, prev);
+            }
+        }
+
+        // Keep transforms before the step for render interpolation.
+        store_prev_global2(world);
+
         let (tx, rx) = unbounded();
 
         self.pipeline.step(
@@ -243,53 +1389,72 @@ impl System for Physics2 {
             &SenderEventHandler { tx },
         );
 
-        for (_, (global, body)) in cx.world.query_mut::<(&mut Global2, &RigidBodyHandle)>() {
+        for (_, (global, body)) in world.query_mut::<(&mut Global2, &RigidBodyHandle)>() {
             let body = data.bodies.get_mut(*body).unwrap();
             global.iso = *body.position();
         }
 
+        // Forces persist in rapier until reset.
+        for (entity, handle) in applied_forces {
+            if let Some(body) = data.bodies.get_mut(handle) {
+                body.reset_forces(false);
+                body.reset_torques(false);
+            }
+            let _ = world.remove::<ApplyForce2>(&entity);
+        }
+
         while let Ok(event) = rx.recv() {
-            match event {
-                CollisionEvent::Started(lhs, rhs, _) => {
-                    let lhs_data =
-                        ColliderUserData2::get(data.colliders.get(lhs).unwrap()).unwrap();
-
-                    let rhs_data =
-                        ColliderUserData2::get(data.colliders.get(rhs).unwrap()).unwrap();
-
-                    if let Ok(queue) = cx
-                        .world
-                        .query_one_mut::<&mut ContactQueue2>(&lhs_data.entity)
-                    {
-                        queue.contacts_started.push(rhs);
-                    }
+            let (lhs, rhs, flags, started) = match event {
+                CollisionEvent::Started(lhs, rhs, flags) => (lhs, rhs, flags, true),
+                CollisionEvent::Stopped(lhs, rhs, flags) => (lhs, rhs, flags, false),
+            };
 
-                    if let Ok(queue) = cx
-                        .world
-                        .query_one_mut::<&mut ContactQueue2>(&rhs_data.entity)
-                    {
-                        queue.contacts_started.push(lhs);
-                    }
+            let sensor = flags.contains(CollisionEventFlags::SENSOR);
+
+            // Removed colliders may be missing.
+            let lhs_entity = data.collider_user_data(lhs).map(|data| data.entity);
+            let rhs_entity = data.collider_user_data(rhs).map(|data| data.entity);
+
+            if let (true, Some(lhs), Some(rhs)) = (self.global_events, lhs_entity, rhs_entity) {
+                if started {
+                    events.collision_started.push((lhs, rhs));
+                } else {
+                    events.collision_stopped.push((lhs, rhs));
                 }
-                CollisionEvent::Stopped(lhs, rhs, _) => {
-                    let lhs_data =
-                        ColliderUserData2::get(data.colliders.get(lhs).unwrap()).unwrap();
-
-                    let rhs_data =
-                        ColliderUserData2::get(data.colliders.get(rhs).unwrap()).unwrap();
-
-                    if let Ok(queue) = cx
-                        .world
-                        .query_one_mut::<&mut ContactQueue2>(&lhs_data.entity)
-                    {
-                        queue.contacts_stopped.push(rhs);
+            }
+
+            for (entity, collider, other) in [(lhs_entity, lhs, rhs), (rhs_entity, rhs, lhs)] {
+                let entity = match entity {
+                    Some(entity) => entity,
+                    None => continue,
+                };
+
+                if sensor {
+                    if let Ok(trigger) = world.query_one_mut::<&mut Trigger2>(&entity) {
+                        trigger.handle_event(data, &event);
+                    }
+                    if let Ok(queue) = world.query_one_mut::<&mut IntersectionQueue2>(&entity) {
+                        if started {
+                            queue.intersecting_started.push(other);
+                        } else {
+                            queue.intersecting_stopped.push(other);
+                        }
                     }
+                } else if let Ok(queue) = world.query_one_mut::<&mut ContactQueue2>(&entity) {
+                    if started {
+                        let info = match queue.info {
+                            false => None,
+                            true => self.narrow_phase.contact_pair(lhs, rhs).and_then(|pair| {
+                                ContactInfo2::from_contact_pair(pair, &data.colliders, collider)
+                            }),
+                        };
 
-                    if let Ok(queue) = cx
-                        .world
-                        .query_one_mut::<&mut ContactQueue2>(&rhs_data.entity)
-                    {
-                        queue.contacts_stopped.push(lhs);
+                        match info {
+                            Some(info) => queue.contacts_started_info.push(info),
+                            None => queue.contacts_started.push(other),
+                        }
+                    } else {
+                        queue.contacts_stopped.push(other);
                     }
                 }
             }
@@ -300,6 +1465,30 @@ impl System for Physics2 {
     }
 }
 
+impl System for Physics2 {
+    #[inline]
+    fn name(&self) -> &str {
+        "Physics"
+    }
+
+    fn run(&mut self, cx: SystemContext<'_>) {
+        let data = cx.res.with(PhysicsData2::new);
+
+        let mut events = PhysicsEvents2::new();
+        self.step(cx.world, data, &mut events, cx.clock.delta);
+
+        if self.global_events {
+            let global = cx.res.with(PhysicsEvents2::new);
+            global
+                .collision_started
+                .extend(events.drain_collision_started());
+            global
+                .collision_stopped
+                .extend(events.drain_collision_stopped());
+        }
+    }
+}
+
 pub struct BodyUserData2 {
     pub entity: EntityId,
 }
@@ -314,12 +1503,12 @@ impl BodyUserData2 {
     }
 
     fn to_user_data(&self) -> u128 {
-        self.entity.bits() as u128
+        user_data::body_to_user_data(self.entity)
     }
 
     fn from_user_data(user_data: u128) -> Option<Self> {
         Some(BodyUserData2 {
-            entity: EntityId::from_bits(user_data as u64)?,
+            entity: user_data::body_from_user_data(user_data)?,
         })
     }
 }
@@ -339,13 +1528,11 @@ impl ColliderUserData2 {
     }
 
     fn to_user_data(&self) -> u128 {
-        ((self.body_index as u128) << 64) | (self.entity.bits() as u128)
+        user_data::collider_to_user_data(self.entity, self.body_index)
     }
 
     fn from_user_data(user_data: u128) -> Option<Self> {
-        Some(ColliderUserData2 {
-            body_index: (user_data >> 64) as usize,
-            entity: EntityId::from_bits(user_data as u64)?,
-        })
+        let (entity, body_index) = user_data::collider_from_user_data(user_data)?;
+        Some(ColliderUserData2 { entity, body_index })
     }
 }
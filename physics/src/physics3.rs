@@ -1,7 +1,7 @@
 use approx::relative_ne;
 use arcana::{
     edict::entity::EntityId,
-    scene::Global3,
+    scene::{store_prev_global3, Global3},
     system::{System, SystemContext, DEFAULT_TICK_SPAN},
     TimeSpan,
 };
@@ -11,7 +11,10 @@ use rapier3d::{
         CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
         RigidBodyHandle, RigidBodySet,
     },
-    geometry::{BroadPhase, ColliderHandle, ColliderSet, CollisionEvent, ContactPair, NarrowPhase},
+    geometry::{
+        BroadPhase, ColliderHandle, ColliderSet, CollisionEvent, ContactPair, InteractionGroups,
+        NarrowPhase, Ray, Shape, TOI,
+    },
     na,
     pipeline::{EventHandler, PhysicsPipeline, QueryPipeline},
     prelude::{Collider, RigidBody},
@@ -19,6 +22,8 @@ use rapier3d::{
 
 pub use {parry3d::*, rapier3d::*};
 
+use crate::user_data;
+
 // use crate::{
 //     clocks::TimeSpan,
 //     scene::Global3,
@@ -109,6 +114,104 @@ impl PhysicsData3 {
         let collider = self.colliders.get(handle)?;
         ColliderUserData3::get(collider)
     }
+
+    /// Casts ray and returns closest collider hit and time of impact.
+    ///
+    /// Hit point is `origin + dir * toi`.
+    /// Only colliders which groups interact with `groups` are tested.
+    /// Uses query pipeline updated by the last [`Physics3`] step.
+    pub fn cast_ray(
+        &self,
+        origin: na::Point3<f32>,
+        dir: na::Vector3<f32>,
+        max_toi: f32,
+        groups: InteractionGroups,
+    ) -> Option<(ColliderHandle, f32)> {
+        self.query_pipeline.cast_ray(
+            &self.colliders,
+            &Ray::new(origin, dir),
+            max_toi,
+            true,
+            groups,
+            None,
+        )
+    }
+
+    /// Casts ray and returns entity of the closest collider hit and time of impact.
+    ///
+    /// Colliders not attached to entities are skipped.
+    pub fn cast_ray_entity(
+        &self,
+        origin: na::Point3<f32>,
+        dir: na::Vector3<f32>,
+        max_toi: f32,
+        groups: InteractionGroups,
+    ) -> Option<(EntityId, f32)> {
+        user_data::query_entity(
+            |handle| Some(self.colliders.get(handle)?.user_data),
+            |filter| {
+                self.query_pipeline.cast_ray(
+                    &self.colliders,
+                    &Ray::new(origin, dir),
+                    max_toi,
+                    true,
+                    groups,
+                    Some(filter),
+                )
+            },
+        )
+    }
+
+    /// Casts shape moving from `position` with `velocity`
+    /// and returns closest collider hit and time of impact.
+    ///
+    /// Hit position is `position` translated by `velocity * toi.toi`.
+    pub fn cast_shape(
+        &self,
+        shape: &dyn Shape,
+        position: &na::Isometry3<f32>,
+        velocity: &na::Vector3<f32>,
+        max_toi: f32,
+        groups: InteractionGroups,
+    ) -> Option<(ColliderHandle, TOI)> {
+        self.query_pipeline.cast_shape(
+            &self.colliders,
+            position,
+            velocity,
+            shape,
+            max_toi,
+            groups,
+            None,
+        )
+    }
+
+    /// Casts shape moving from `position` with `velocity`
+    /// and returns entity of the closest collider hit and time of impact.
+    ///
+    /// Colliders not attached to entities are skipped.
+    pub fn cast_shape_entity(
+        &self,
+        shape: &dyn Shape,
+        position: &na::Isometry3<f32>,
+        velocity: &na::Vector3<f32>,
+        max_toi: f32,
+        groups: InteractionGroups,
+    ) -> Option<(EntityId, TOI)> {
+        user_data::query_entity(
+            |handle| Some(self.colliders.get(handle)?.user_data),
+            |filter| {
+                self.query_pipeline.cast_shape(
+                    &self.colliders,
+                    position,
+                    velocity,
+                    shape,
+                    max_toi,
+                    groups,
+                    Some(filter),
+                )
+            },
+        )
+    }
 }
 
 impl Default for PhysicsData3 {
@@ -224,6 +327,9 @@ impl System for Physics3 {
             }
         }
 
+        // Keep transforms before the step for render interpolation.
+        store_prev_global3(cx.world);
+
         let (tx, rx) = unbounded();
 
         self.pipeline.step(
@@ -310,12 +416,12 @@ impl BodyUserData3 {
     }
 
     fn to_user_data(&self) -> u128 {
-        self.entity.bits() as u128
+        user_data::body_to_user_data(self.entity)
     }
 
     fn from_user_data(user_data: u128) -> Option<Self> {
         Some(BodyUserData3 {
-            entity: EntityId::from_bits(user_data as u64)?,
+            entity: user_data::body_from_user_data(user_data)?,
         })
     }
 }
@@ -335,13 +441,11 @@ impl ColliderUserData3 {
     }
 
     fn to_user_data(&self) -> u128 {
-        ((self.body_index as u128) << 64) | (self.entity.bits() as u128)
+        user_data::collider_to_user_data(self.entity, self.body_index)
     }
 
     fn from_user_data(user_data: u128) -> Option<Self> {
-        Some(ColliderUserData3 {
-            body_index: (user_data >> 64) as usize,
-            entity: EntityId::from_bits(user_data as u64)?,
-        })
+        let (entity, body_index) = user_data::collider_from_user_data(user_data)?;
+        Some(ColliderUserData3 { entity, body_index })
     }
 }
@@ -0,0 +1,45 @@
+//! Encoding of entities in rapier user data shared by 2D and 3D physics.
+
+use arcana::edict::entity::EntityId;
+
+/// Returns user data of the body attached to `entity`.
+#[inline]
+pub(crate) fn body_to_user_data(entity: EntityId) -> u128 {
+    entity.bits() as u128
+}
+
+/// Returns entity encoded with [`body_to_user_data`].
+#[inline]
+pub(crate) fn body_from_user_data(user_data: u128) -> Option<EntityId> {
+    EntityId::from_bits(user_data as u64)
+}
+
+/// Returns user data of the collider with `body_index` in the body attached to `entity`.
+#[inline]
+pub(crate) fn collider_to_user_data(entity: EntityId, body_index: usize) -> u128 {
+    ((body_index as u128) << 64) | (entity.bits() as u128)
+}
+
+/// Returns entity and body index encoded with [`collider_to_user_data`].
+#[inline]
+pub(crate) fn collider_from_user_data(user_data: u128) -> Option<(EntityId, usize)> {
+    let entity = EntityId::from_bits(user_data as u64)?;
+    Some((entity, (user_data >> 64) as usize))
+}
+
+/// Runs collider query and resolves hit collider to entity.
+///
+/// `query` receives filter that skips colliders not attached to entities.
+/// `user_data` returns user data of collider or `None` if collider is missing.
+pub(crate) fn query_entity<H, T>(
+    user_data: impl Fn(H) -> Option<u128>,
+    query: impl FnOnce(&dyn Fn(H) -> bool) -> Option<(H, T)>,
+) -> Option<(EntityId, T)> {
+    let entity = |handle| {
+        let (entity, _) = collider_from_user_data(user_data(handle)?)?;
+        Some(entity)
+    };
+
+    let (handle, hit) = query(&|handle| entity(handle).is_some())?;
+    Some((entity(handle)?, hit))
+}
@@ -13,12 +13,21 @@ struct FieldAttributes {
     asset: Option<Asset>,
 }
 
-struct Parsed {
-    input: syn::DeriveInput,
+struct ParsedFields {
     attrs: TypeAttributes,
     fields: Vec<FieldAttributes>,
 }
 
+enum ParsedData {
+    Struct(ParsedFields),
+    Enum(Vec<ParsedFields>),
+}
+
+struct Parsed {
+    input: syn::DeriveInput,
+    data: ParsedData,
+}
+
 fn parse_field_attributes(attrs: &[syn::Attribute]) -> syn::Result<FieldAttributes> {
     let mut asset = None::<Asset>;
 
@@ -85,369 +94,659 @@ fn parse_type_attributes(attrs: &[syn::Attribute]) -> syn::Result<TypeAttributes
     Ok(TypeAttributes { func })
 }
 
-pub fn derive_unfold(item: TokenStream) -> syn::Result<TokenStream> {
-    let parsed = parse(item)?;
+impl ParsedFields {
+    fn is_none(&self) -> bool {
+        self.attrs.func.is_none() && self.fields.iter().all(|f| f.asset.is_none())
+    }
+}
 
-    let ident = &parsed.input.ident;
+/// Generated code for fields of the struct or single enum variant.
+struct UnfoldFields {
+    /// Fields of the spawned marker type or variant.
+    spawned_decl: proc_macro2::TokenStream,
 
-    let none = parsed.attrs.func.is_none() && parsed.fields.iter().all(|f| f.asset.is_none());
+    /// Pattern that binds value fields required to initialize spawned marker.
+    init_pat: proc_macro2::TokenStream,
 
-    if none {
-        // With no attributes, nothing should be done for the unfold type.
-        // Assign dummy system as unfold system.
-        return Ok(quote::quote! {
-            impl ::arcana::unfold::Unfold for #ident {
-                type UnfoldSystem = ::arcana::unfold::DummyUnfoldSystem;
-            }
-        }
-        .into());
+    /// Fields of initial spawned marker.
+    init: proc_macro2::TokenStream,
+
+    /// Pattern that binds value fields.
+    value_pat: proc_macro2::TokenStream,
+
+    /// Pattern that binds spawned marker fields.
+    spawned_pat: proc_macro2::TokenStream,
+
+    /// Statements that bring spawned marker in sync with value.
+    update: proc_macro2::TokenStream,
+
+    /// Statements that remove unfolded components from `entity`.
+    cleanup: proc_macro2::TokenStream,
+
+    /// Declarations of pending inserts.
+    pending: proc_macro2::TokenStream,
+
+    /// Statements that apply pending inserts.
+    apply: proc_macro2::TokenStream,
+}
+
+fn field_member(field: &syn::Field, index: usize) -> syn::Member {
+    match &field.ident {
+        None => syn::Member::Unnamed(syn::Index {
+            span: field.span(),
+            index: index as u32,
+        }),
+        Some(field_ident) => syn::Member::Named(field_ident.clone()),
     }
+}
 
-    let data = match parsed.input.data {
-        syn::Data::Struct(s) => s,
-        _ => unreachable!(),
+/// Returns type of the asset id wrapped into `Option`.
+fn optional_asset_id(ty: &syn::Type) -> Option<&syn::Type> {
+    let path = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
     };
 
-    let system_ident = quote::format_ident!("{ident}UnfoldSystem");
-    let system_name = syn::LitStr::new(&format!("{ident} unfold system"), ident.span());
-    let system_struct = quote::quote_spanned!(ident.span() => #[derive(Clone, Copy, Debug, Default)] pub struct #system_ident;);
-    let unfolded_ident = quote::format_ident!("{ident}UnfoldSpawned");
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
 
-    let stream = match &parsed.attrs.func {
+fn field_asset_type(field: &syn::Field, asset: &Asset) -> proc_macro2::TokenStream {
+    let ty = optional_asset_id(&field.ty).unwrap_or(&field.ty);
+    match &asset.ty {
         None => {
-            let mut unfolded_fields = Vec::new();
-            let mut unfolded_fields_init = Vec::new();
+            quote::quote_spanned!(ty.span() => <#ty as ::arcana::assets::TypedAssetIdExt>::Asset)
+        }
+        Some(ty) => {
+            quote::quote_spanned!(ty.span() => #ty)
+        }
+    }
+}
 
-            let mut cleanup_statements = Vec::new();
-            let mut updates = Vec::new();
+fn delimit_fields(
+    fields: &syn::Fields,
+    decls: Vec<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    match fields {
+        syn::Fields::Named(_) => quote::quote!({ #(#decls,)* }),
+        syn::Fields::Unnamed(_) => quote::quote!(( #(#decls,)* )),
+        syn::Fields::Unit => quote::quote!(),
+    }
+}
 
-            let asset_fields = parsed.fields.iter().enumerate().filter_map(|(i, f)| {
-                let asset = f.asset.as_ref()?;
-                Some((i, asset))
-            });
+/// Generates code that inserts loaded assets as components.
+fn unfold_asset_fields(index: usize, fields: &syn::Fields, parsed: &ParsedFields) -> UnfoldFields {
+    let mut decls = Vec::new();
+    let mut inits = Vec::new();
+    let mut value_pats = Vec::new();
+    let mut spawned_pats = Vec::new();
+    let mut updates = Vec::new();
+    let mut cleanups = Vec::new();
+    let mut pending = Vec::new();
+    let mut apply = Vec::new();
+
+    let asset_fields = parsed.fields.iter().enumerate().filter_map(|(i, f)| {
+        let asset = f.asset.as_ref()?;
+        Some((i, asset))
+    });
+
+    for (spawned_idx, (value_idx, asset)) in asset_fields.enumerate() {
+        let field = fields.iter().nth(value_idx).unwrap();
+        let asset_ty = field_asset_type(field, asset);
+
+        let value_member = field_member(field, value_idx);
+        let spawned_member = field_member(field, spawned_idx);
+
+        let value_binding = quote::format_ident!("value_{}", value_idx, span = field.span());
+        let spawned_binding = quote::format_ident!("spawned_{}", value_idx, span = field.span());
+
+        match &field.ident {
+            None => decls.push(
+                quote::quote_spanned!(field.span() => ::core::option::Option<::arcana::assets::AssetId>),
+            ),
+            Some(field_ident) => decls.push(
+                quote::quote_spanned!(field.span() => #field_ident: ::core::option::Option<::arcana::assets::AssetId>),
+            ),
+        }
+
+        inits.push(quote::quote_spanned!(field.span() => #spawned_member: None));
+        value_pats.push(quote::quote_spanned!(field.span() => #value_member: #value_binding));
+        spawned_pats.push(quote::quote_spanned!(field.span() => #spawned_member: #spawned_binding));
+
+        cleanups.push(
+            quote::quote_spanned!(field.span() => let _ = world.remove::<#asset_ty>(entity);),
+        );
+
+        let load = quote::quote_spanned!(field.span() =>
+            match *#spawned_binding {
+                Some(old_id) if old_id == id => {}
+                _ => match cx.assets.build::<#asset_ty, _>(id, cx.graphics) {
+                    None => {},
+                    Some(Ok(asset)) => {
+                        *#spawned_binding = Some(id);
+                        entity_builder.add(<#asset_ty as Clone>::clone(asset));
+                    }
+                    Some(Err(err)) => {
+                        ::arcana::tracing::error!("Failed to load asset '{}({:})'. {:#}", type_name::<#asset_ty>(), id, err);
+                        *#spawned_binding = Some(id);
+                    }
+                },
+            }
+        );
+
+        if optional_asset_id(&field.ty).is_some() {
+            // Component is removed when id is reset to `None`.
+            let removals = quote::format_ident!("removals_{}_{}", index, value_idx);
 
-            for (spawned_idx, (value_idx, asset)) in asset_fields.enumerate() {
-                let field = data.fields.iter().nth(value_idx).unwrap();
-                let ty = &field.ty;
+            pending.push(quote::quote!(let mut #removals = Vec::new_in(&*cx.scope);));
+            apply.push(quote::quote! {
+                for e in #removals {
+                    let _ = cx.world.remove::<#asset_ty>(&e);
+                }
+            });
 
-                let asset_ty = match &asset.ty {
+            updates.push(quote::quote_spanned!(field.span() =>
+                match #value_binding {
                     None => {
-                        quote::quote_spanned!(ty.span() => <#ty as TypedAssetIdExt>::Asset)
+                        if #spawned_binding.take().is_some() {
+                            #removals.push(e);
+                        }
                     }
-                    Some(ty) => {
-                        quote::quote_spanned!(ty.span() => #ty)
+                    Some(id) => {
+                        let id = *Borrow::<AssetId>::borrow(id);
+                        #load
                     }
-                };
+                }
+            ));
+        } else {
+            updates.push(quote::quote_spanned!(field.span() => {
+                let id = *Borrow::<AssetId>::borrow(#value_binding);
+                #load
+            }));
+        }
+    }
+
+    let builders = quote::format_ident!("builders_{}", index);
+
+    UnfoldFields {
+        spawned_decl: delimit_fields(fields, decls),
+        init_pat: quote::quote!({ .. }),
+        init: quote::quote!({ #(#inits,)* }),
+        value_pat: quote::quote!({ #(#value_pats,)* .. }),
+        spawned_pat: quote::quote!({ #(#spawned_pats,)* .. }),
+        update: quote::quote! {
+            let mut entity_builder = EntityBuilder::new();
+
+            #(#updates)*
+
+            if !entity_builder.is_empty() {
+                #builders.push((e, entity_builder));
+            }
+        },
+        cleanup: quote::quote!(#(#cleanups)*),
+        pending: quote::quote! {
+            let mut #builders = Vec::new_in(&*cx.scope);
+            #(#pending)*
+        },
+        apply: quote::quote! {
+            #(#apply)*
+
+            for (e, entity_builder) in #builders {
+                cx.world.try_insert_bundle(&e, entity_builder).unwrap();
+            }
+        },
+    }
+}
 
-                cleanup_statements.push(
-                    quote::quote_spanned!(ty.span() => let _ = cx.world.remove::<#asset_ty>(&e);),
+/// Generates code that calls unfold function when all assets are loaded
+/// and after any field changes.
+fn unfold_fn_fields(
+    index: usize,
+    fields: &syn::Fields,
+    parsed: &ParsedFields,
+    unfold: &syn::Path,
+) -> UnfoldFields {
+    let mut decls = Vec::new();
+    let mut init_pats = Vec::new();
+    let mut inits = Vec::new();
+    let mut value_pats = Vec::new();
+    let mut spawned_pats = Vec::new();
+    let mut updates = Vec::new();
+
+    let mut unfold_fn_arg_types = Vec::new();
+    let mut unfold_fn_args = Vec::new();
+
+    for (idx, f) in parsed.fields.iter().enumerate() {
+        let field = fields.iter().nth(idx).unwrap();
+        let ty = &field.ty;
+
+        let member = field_member(field, idx);
+        let value_binding = quote::format_ident!("value_{}", idx, span = field.span());
+        let spawned_binding = quote::format_ident!("spawned_{}", idx, span = field.span());
+
+        value_pats.push(quote::quote_spanned!(field.span() => #member: #value_binding));
+        spawned_pats.push(quote::quote_spanned!(field.span() => #member: #spawned_binding));
+
+        match &f.asset {
+            None => {
+                match &field.ident {
+                    None => decls.push(quote::quote_spanned!(field.span() => #ty)),
+                    Some(field_ident) => {
+                        decls.push(quote::quote_spanned!(field.span() => #field_ident: #ty))
+                    }
+                }
+
+                init_pats.push(quote::quote_spanned!(field.span() => #member: #value_binding));
+                inits.push(
+                    quote::quote_spanned!(field.span() => #member: Clone::clone(#value_binding)),
                 );
 
-                let unfolded_field_ident = match &field.ident {
-                    None => syn::Member::Unnamed(syn::Index {
-                        span: field.span(),
-                        index: spawned_idx as u32,
-                    }),
-                    Some(field_ident) => syn::Member::Named(field_ident.clone()),
-                };
+                unfold_fn_arg_types.push(quote::quote_spanned!(field.span() => &#ty));
+                unfold_fn_args.push(quote::quote_spanned!(field.span() => &*#spawned_binding));
 
-                let value_field_ident = match &field.ident {
-                    None => syn::Member::Unnamed(syn::Index {
-                        span: field.span(),
-                        index: value_idx as u32,
-                    }),
-                    Some(field_ident) => syn::Member::Named(field_ident.clone()),
-                };
+                updates.push(quote::quote_spanned!(field.span() =>
+                    if *#value_binding != *#spawned_binding {
+                        updated = true;
+                        *#spawned_binding = Clone::clone(#value_binding);
+                    }
+                ));
+            }
+            Some(asset) => {
+                let asset_ty = field_asset_type(field, asset);
 
-                if field.ident.is_some() {
-                    unfolded_fields
-                        .push(quote::quote_spanned!(field.span() => #unfolded_field_ident: ::core::option::Option<::arcana::assets::AssetId>));
-                    unfolded_fields_init
-                        .push(quote::quote_spanned!(field.span() => #unfolded_field_ident: ::core::option::Option::None));
-                } else {
-                    unfolded_fields
-                        .push(quote::quote_spanned!(field.span() => ::core::option::Option<::arcana::assets::AssetId>));
-                    unfolded_fields_init
-                        .push(quote::quote_spanned!(field.span() => ::core::option::Option::None));
+                let spawned_ty = quote::quote_spanned!(field.span() => ::core::option::Option< ::core::result::Result< ::arcana::assets::WithId<#asset_ty>, ::arcana::assets::AssetId > >);
+
+                match &field.ident {
+                    None => decls.push(spawned_ty),
+                    Some(field_ident) => {
+                        decls.push(quote::quote_spanned!(field.span() => #field_ident: #spawned_ty))
+                    }
                 }
 
-                updates.push(quote::quote_spanned!(field.span() => 
-                    let id = *Borrow::<AssetId>::borrow(&value.#value_field_ident);
-                    match unfolded.#unfolded_field_ident {
-                        Some(old_id) if old_id == id => {}
+                inits.push(quote::quote_spanned!(field.span() => #member: None));
+
+                let load = quote::quote_spanned!(field.span() =>
+                    match &*#spawned_binding {
+                        Some(Ok(old_id)) if WithId::id(old_id) == id => {}
+                        Some(Err(old_id)) if *old_id == id => {
+                            ready = false;
+                        }
                         _ => match cx.assets.build::<#asset_ty, _>(id, cx.graphics) {
-                            None => {},
+                            None => {
+                                ready = false;
+                            },
                             Some(Ok(asset)) => {
-                                unfolded.#unfolded_field_ident = Some(id);
-                                entity_builder.add(<#asset_ty as Clone>::clone(asset));
+                                updated = true;
+                                *#spawned_binding = Some(Ok(WithId::new(Clone::clone(asset), id)));
                             }
                             Some(Err(err)) => {
-                                tracing::error!("Failed to load asset '{}({:})'. {:#}", type_name::<#asset_ty>(), id, err);
-                                unfolded.#unfolded_field_ident = Some(id);
+                                ready = false;
+
+                                ::arcana::tracing::error!("Failed to load asset '{}({:})'. {:#}", type_name::<#asset_ty>(), id, err);
+                                *#spawned_binding = Some(Err(id));
                             }
                         },
                     }
-                ));
-            }
+                );
 
-            let unfolded_struct = quote::quote_spanned!(ident.span() => struct #unfolded_ident {
-                #(#unfolded_fields,)*
-            });
+                if optional_asset_id(&field.ty).is_some() {
+                    // Absent asset is passed as `None` and does not block unfolding.
+                    unfold_fn_arg_types.push(quote::quote_spanned!(field.span() => ::core::option::Option<&::arcana::assets::WithId<#asset_ty>>));
+                    unfold_fn_args.push(quote::quote_spanned!(field.span() => #spawned_binding.as_ref().map(|asset| asset.as_ref().unwrap())));
 
-            quote::quote! {
-                impl ::arcana::unfold::Unfold for #ident {
-                    type UnfoldSystem = #system_ident;
+                    updates.push(quote::quote_spanned!(field.span() =>
+                        match #value_binding {
+                            None => {
+                                if #spawned_binding.is_some() {
+                                    updated = true;
+                                    *#spawned_binding = None;
+                                }
+                            }
+                            Some(id) => {
+                                let id = *Borrow::<AssetId>::borrow(id);
+                                #load
+                            }
+                        }
+                    ));
+                } else {
+                    unfold_fn_arg_types.push(
+                        quote::quote_spanned!(field.span() => &::arcana::assets::WithId<#asset_ty>),
+                    );
+                    unfold_fn_args.push(quote::quote_spanned!(field.span() => #spawned_binding.as_ref().unwrap().as_ref().unwrap()));
+
+                    updates.push(quote::quote_spanned!(field.span() => {
+                        let id = *Borrow::<AssetId>::borrow(#value_binding);
+                        #load
+                    }));
                 }
+            }
+        }
+    }
 
-                #system_struct
-
-                #unfolded_struct
-
-                impl ::arcana::system::System for #system_ident {
-                    fn name(&self) -> &str {
-                        #system_name
-                    }
-
-                    fn run(&mut self, cx: ::arcana::system::SystemContext<'_>) {
-                        use core::{any::type_name, borrow::Borrow, clone::Clone, option::Option::{self, None, Some}};
-                        use std::vec::Vec;
-                        use arcana::{assets::{AssetId, TypedAssetIdExt}, edict::{entity::EntityId, bundle::EntityBuilder}};
+    let inserts = quote::format_ident!("inserts_{}", index);
+    let spawns = quote::format_ident!("spawns_{}", index);
 
-                        let cleanup_query = cx.world.query_mut::<()>().with::<#unfolded_ident>().without::<#ident>();
+    UnfoldFields {
+        spawned_decl: delimit_fields(fields, decls),
+        init_pat: quote::quote!({ #(#init_pats,)* .. }),
+        init: quote::quote!({ #(#inits,)* }),
+        value_pat: quote::quote!({ #(#value_pats,)* .. }),
+        spawned_pat: quote::quote!({ #(#spawned_pats,)* .. }),
+        update: quote::quote! {
+            let mut ready = true;
 
-                        let mut cleanup = Vec::new_in(&*cx.scope);
-                        cleanup.extend(cleanup_query.into_iter().map(|(e, ())| e));
+            #(#updates)*
 
-                        for e in cleanup {
-                            let _ = cx.world.remove::<#unfolded_ident>(&e);
-                            #( #cleanup_statements )*
-                        }
+            if updated && ready {
+                let UnfoldResult { insert, spawn } = (#unfold)( #(#unfold_fn_args,)* cx.res );
+                #inserts.push((e, insert));
 
-                        let query = cx.world.query_mut::<(&#ident, Option<&mut #unfolded_ident>)>();
+                if Iterator::size_hint(&spawn).1 != Some(0) {
+                    #spawns.push((e, spawn));
+                }
+            }
+        },
+        cleanup: quote::quote! {
+            fn cleanup<T: ::arcana::edict::bundle::Bundle, I>(
+                world: &mut ::arcana::edict::world::World,
+                entity: &::arcana::edict::entity::EntityId,
+                _: fn( #(#unfold_fn_arg_types,)* &mut ::arcana::resources::Res ) -> ::arcana::unfold::UnfoldResult<T, I>,
+            ) {
+                let _ = world.remove_bundle::<T>(entity);
+            }
 
-                        let mut inserts = Vec::new_in(&*cx.scope);
+            cleanup(world, entity, #unfold);
+        },
+        pending: quote::quote! {
+            let mut #inserts = Vec::new_in(&*cx.scope);
+            let mut #spawns = Vec::new_in(&*cx.scope);
+        },
+        apply: quote::quote! {
+            for (e, insert) in #inserts {
+                cx.world.try_insert_bundle(&e, insert).unwrap();
+            }
 
-                        for (e, (value, unfolded)) in query {
-                            let mut unfolded_insert = None;
+            for (_, spawn) in #spawns {
+                for bundle in spawn {
+                    cx.world.spawn(bundle);
+                }
+            }
+        },
+    }
+}
 
-                            let unfolded: &mut #unfolded_ident = match unfolded {
-                                None => {
-                                    unfolded_insert.get_or_insert(#unfolded_ident {
-                                        #(#unfolded_fields_init,)*
-                                    })
-                                }
-                                Some(unfolded) => unfolded,
-                            };
+fn unfold_fields(index: usize, fields: &syn::Fields, parsed: &ParsedFields) -> UnfoldFields {
+    match &parsed.attrs.func {
+        None => unfold_asset_fields(index, fields, parsed),
+        Some(unfold) => unfold_fn_fields(index, fields, parsed, unfold),
+    }
+}
 
-                            let mut entity_builder = EntityBuilder::new();
+pub fn derive_unfold(item: TokenStream) -> syn::Result<TokenStream> {
+    let parsed = parse(item)?;
 
-                            #(#updates;)*
+    let ident = &parsed.input.ident;
 
-                            if let Some(unfolded_insert) = unfolded_insert {
-                                entity_builder.add(unfolded_insert);
-                            }
+    let none = match &parsed.data {
+        ParsedData::Struct(fields) => fields.is_none(),
+        ParsedData::Enum(variants) => variants.iter().all(ParsedFields::is_none),
+    };
 
-                            if !entity_builder.is_empty() {
-                                inserts.push((e, entity_builder));
-                            }
-                        }
+    if none {
+        // With no attributes, nothing should be done for the unfold type.
+        // Assign dummy system as unfold system.
+        return Ok(quote::quote! {
+            impl ::arcana::unfold::Unfold for #ident {
+                type UnfoldSystem = ::arcana::unfold::DummyUnfoldSystem;
+            }
+        }
+        .into());
+    }
 
-                        for (e, mut entity_builder) in inserts {
-                            cx.world.try_insert_bundle(&e, entity_builder).unwrap();
-                        }
-                    }
-                }
+    let system_ident = quote::format_ident!("{ident}UnfoldSystem");
+    let system_name = syn::LitStr::new(&format!("{ident} unfold system"), ident.span());
+    let system_struct = quote::quote_spanned!(ident.span() => #[derive(Clone, Copy, Debug, Default)] pub struct #system_ident;);
+    let spawned_ident = quote::format_ident!("{ident}UnfoldSpawned");
+
+    // Value and spawned marker paths with generated code for each variant.
+    // Struct is handled as single variant.
+    let mut variants = Vec::new();
+
+    let is_enum = match (&parsed.input.data, &parsed.data) {
+        (syn::Data::Struct(data), ParsedData::Struct(fields)) => {
+            variants.push((
+                quote::quote!(#ident),
+                quote::quote!(#spawned_ident),
+                unfold_fields(0, &data.fields, fields),
+            ));
+            false
+        }
+        (syn::Data::Enum(data), ParsedData::Enum(fields)) => {
+            for (index, (variant, fields)) in data.variants.iter().zip(fields).enumerate() {
+                let variant_ident = &variant.ident;
+                variants.push((
+                    quote::quote!(#ident::#variant_ident),
+                    quote::quote!(#spawned_ident::#variant_ident),
+                    unfold_fields(index, &variant.fields, fields),
+                ));
             }
+            true
         }
-        Some(unfold) => {
-            let mut unfolded_fields = Vec::new();
-            let mut unfolded_fields_init = Vec::new();
+        _ => unreachable!(),
+    };
 
-            let mut updates = Vec::new();
+    let has_fn = match &parsed.data {
+        ParsedData::Struct(fields) => fields.attrs.func.is_some(),
+        ParsedData::Enum(variants) => variants.iter().any(|v| v.attrs.func.is_some()),
+    };
 
-            let mut unfold_fn_arg_types = Vec::new();
-            let mut unfold_fn_args = Vec::new();
+    let value_paths = variants.iter().map(|(path, _, _)| path).collect::<Vec<_>>();
+    let spawned_paths = variants.iter().map(|(_, path, _)| path).collect::<Vec<_>>();
+    let variant_indices = (0..variants.len()).collect::<Vec<_>>();
+
+    let init_pats = variants.iter().map(|(_, _, f)| &f.init_pat);
+    let inits = variants.iter().map(|(_, _, f)| &f.init);
+    let value_pats = variants.iter().map(|(_, _, f)| &f.value_pat);
+    let spawned_pats = variants.iter().map(|(_, _, f)| &f.spawned_pat);
+    let updates = variants.iter().map(|(_, _, f)| &f.update);
+    let cleanups = variants.iter().map(|(_, _, f)| &f.cleanup);
+    let pendings = variants.iter().map(|(_, _, f)| &f.pending);
+    let applies = variants.iter().map(|(_, _, f)| &f.apply);
+
+    let init = quote::quote! {
+        match value {
+            #( #value_paths #init_pats => #spawned_paths #inits, )*
+        }
+    };
 
-            for (idx, f) in parsed.fields.iter().enumerate() {
-                let field = data.fields.iter().nth(idx).unwrap();
-                let ty = &field.ty;
+    let mark_updated = if has_fn {
+        quote::quote!(updated = true;)
+    } else {
+        quote::quote!()
+    };
 
-                let field_ident = match &field.ident {
-                    None => syn::Member::Unnamed(syn::Index {
-                        span: field.span(),
-                        index: idx as u32,
-                    }),
-                    Some(field_ident) => syn::Member::Named(field_ident.clone()),
-                };
+    let declare_updated = if has_fn {
+        quote::quote!(let mut updated = false;)
+    } else {
+        quote::quote!()
+    };
 
-                match &f.asset {
-                    None => {
-                        if field.ident.is_some() {
-                            unfolded_fields
-                                .push(quote::quote_spanned!(field.span() => #field_ident: #ty));
-                            unfolded_fields_init.push(
-                                quote::quote_spanned!(field_ident.span() => #field_ident: Clone::clone(&value.#field_ident)),
-                            );
-                        } else {
-                            unfolded_fields.push(quote::quote_spanned!(field.span() => #ty));
-                            unfolded_fields_init.push(
-                                quote::quote_spanned!(field_ident.span() => Clone::clone(&value.#field_ident))
-                            );
+    let (
+        spawned_type,
+        spawned_impl,
+        cleanup_collect,
+        cleanup_call,
+        switch,
+        switch_pending,
+        switch_cleanup,
+        unmatched,
+    ) = if is_enum {
+        let variant_idents = match &parsed.input.data {
+            syn::Data::Enum(data) => data.variants.iter().map(|v| &v.ident).collect::<Vec<_>>(),
+            _ => unreachable!(),
+        };
+        let decls = variants.iter().map(|(_, _, f)| &f.spawned_decl);
+
+        (
+            quote::quote_spanned!(ident.span() => enum #spawned_ident {
+                #( #variant_idents #decls, )*
+            }),
+            quote::quote! {
+                impl #spawned_ident {
+                    fn variant(&self) -> usize {
+                        match self {
+                            #( #spawned_paths { .. } => #variant_indices, )*
                         }
-
-                        unfold_fn_arg_types.push(quote::quote_spanned!(field.span() => &#ty));
-                        unfold_fn_args
-                            .push(quote::quote_spanned!(field.span() => &unfolded.#field_ident));
-
-                        updates.push(quote::quote_spanned!(field.span() => 
-                            if value.#field_ident != unfolded.#field_ident {
-                                updated = true;
-                            }
-                        ));
                     }
-                    Some(asset) => {
-                        let asset_ty = match &asset.ty {
-                            None => {
-                                quote::quote_spanned!(ty.span() => <#ty as ::arcana::assets::TypedAssetIdExt>::Asset)
-                            }
-                            Some(ty) => {
-                                quote::quote_spanned!(ty.span() => #ty)
-                            }
-                        };
 
-                        if field.ident.is_some() {
-                            unfolded_fields.push(quote::quote_spanned!(field.span() => #field_ident: ::core::option::Option< ::core::result::Result< ::arcana::assets::WithId<#asset_ty>, ::arcana::assets::AssetId > >));
-                            unfolded_fields_init.push(
-                                quote::quote_spanned!(field_ident.span() => #field_ident: None),
-                            );
-                        } else {
-                            unfolded_fields.push(
-                                quote::quote_spanned!(field.span() => ::core::option::Option<::arcana::assets::WithId<#asset_ty>>),
-                            );
-                            unfolded_fields_init
-                                .push(quote::quote_spanned!(field_ident.span() => None));
+                    fn cleanup(world: &mut ::arcana::edict::world::World, entity: &::arcana::edict::entity::EntityId, variant: usize) {
+                        match variant {
+                            #( #variant_indices => { #cleanups } )*
+                            _ => unreachable!(),
                         }
-
-                        unfold_fn_arg_types
-                            .push(quote::quote_spanned!(field.span() => &::arcana::assets::WithId<#asset_ty>));
-                        unfold_fn_args
-                            .push(quote::quote_spanned!(field.span() => unfolded.#field_ident.as_ref().unwrap().as_ref().unwrap()));
-
-                        updates.push(quote::quote_spanned!(field.span() => {
-                            let id = *Borrow::<AssetId>::borrow(&value.#field_ident);
-                            match &unfolded.#field_ident {
-                                Some(Ok(old_id)) if WithId::id(old_id) == id => {}
-                                Some(Err(old_id)) if *old_id == id => {}
-                                _ => match cx.assets.build::<#asset_ty, _>(id, cx.graphics) {
-                                    None => {
-                                        ready = false;
-                                    },
-                                    Some(Ok(asset)) => {
-                                        updated = true;
-                                        unfolded.#field_ident = Some(Ok(WithId::new(Clone::clone(asset), id)));
-                                    }
-                                    Some(Err(err)) => {
-                                        ready = false;
-
-                                        ::arcana::tracing::error!("Failed to load asset '{}({:})'. {:#}", type_name::<#asset_ty>(), id, err);
-                                        unfolded.#field_ident = Some(Err(id));
-                                    }
-                                },
-                            }
-                        }));
                     }
                 }
-            }
-
-            let unfolded_struct = quote::quote_spanned!(ident.span() => struct #unfolded_ident {
-                #(#unfolded_fields,)*
-            });
-
+            },
+            quote::quote!(cleanup.extend(cleanup_query.into_iter().map(|(e, spawned)| (e, spawned.variant())));),
             quote::quote! {
-                impl ::arcana::unfold::Unfold for #ident {
-                    type UnfoldSystem = #system_ident;
+                for (e, variant) in cleanup {
+                    let _ = cx.world.remove::<#spawned_ident>(&e);
+                    #spawned_ident::cleanup(cx.world, &e, variant);
                 }
+            },
+            quote::quote! {
+                let variant = match value {
+                    #( #value_paths { .. } => #variant_indices, )*
+                };
 
-                #system_struct
-
-                #unfolded_struct
-
-                impl ::arcana::system::System for #system_ident {
-                    fn name(&self) -> &str {
-                        #system_name
+                // Variant has changed. Components unfolded from old variant are removed after the loop.
+                if spawned.variant() != variant {
+                    switched.push((e, spawned.variant()));
+                    *spawned = #init;
+                    #mark_updated
+                }
+            },
+            quote::quote!(let mut switched = Vec::new_in(&*cx.scope);),
+            quote::quote! {
+                for (e, variant) in switched {
+                    #spawned_ident::cleanup(cx.world, &e, variant);
+                }
+            },
+            quote::quote!(_ => unreachable!(),),
+        )
+    } else {
+        let (_, _, fields) = &variants[0];
+        let decl = &fields.spawned_decl;
+        let cleanup = &fields.cleanup;
+
+        let semi = match &parsed.input.data {
+            syn::Data::Struct(syn::DataStruct {
+                fields: syn::Fields::Named(_),
+                ..
+            }) => quote::quote!(),
+            _ => quote::quote!(;),
+        };
+
+        (
+            quote::quote_spanned!(ident.span() => struct #spawned_ident #decl #semi),
+            quote::quote! {
+                impl #spawned_ident {
+                    fn cleanup(world: &mut ::arcana::edict::world::World, entity: &::arcana::edict::entity::EntityId) {
+                        #cleanup
                     }
+                }
+            },
+            quote::quote!(cleanup.extend(cleanup_query.into_iter().map(|(e, _)| e));),
+            quote::quote! {
+                for e in cleanup {
+                    let _ = cx.world.remove::<#spawned_ident>(&e);
+                    #spawned_ident::cleanup(cx.world, &e);
+                }
+            },
+            quote::quote!(),
+            quote::quote!(),
+            quote::quote!(),
+            quote::quote!(),
+        )
+    };
 
-                    fn run(&mut self, cx: ::arcana::system::SystemContext<'_>) {
-                        use core::{any::type_name, borrow::Borrow, clone::Clone, iter::Iterator, option::Option::{self, Some, None}};
-                        use std::vec::Vec;
-                        use ::arcana::{assets::WithId, edict::{bundle::Bundle, entity::EntityId, world::World}, unfold::UnfoldResult, resources::Res};
-
-                        let cleanup_query = cx.world.query_mut::<&#unfolded_ident>().without::<#ident>();
-
-                        let mut cleanup = Vec::new_in(&*cx.scope);
-                        cleanup.extend(cleanup_query.into_iter().map(|(e, _)| e));
+    let stream = quote::quote! {
+        impl ::arcana::unfold::Unfold for #ident {
+            type UnfoldSystem = #system_ident;
+        }
 
-                        for e in cleanup {
-                            let _ = cx.world.remove::<#unfolded_ident>(&e);
+        #system_struct
 
-                            fn cleanup<T: Bundle, I>(world: &mut World, entity: &EntityId, _: fn( #(#unfold_fn_arg_types,)* &mut Res ) -> UnfoldResult<T, I>) {
-                                let _ = world.remove_bundle::<T>(entity);
-                            }
+        #spawned_type
 
-                            cleanup(cx.world, &e, #unfold);
-                        }
+        #spawned_impl
 
-                        let query = cx.world.query_mut::<(&#ident, Option<&mut #unfolded_ident>)>();
+        impl ::arcana::system::System for #system_ident {
+            fn name(&self) -> &str {
+                #system_name
+            }
 
-                        let mut unfolded_inserts = Vec::new_in(&*cx.scope);
-                        let mut inserts = Vec::new_in(&*cx.scope);
-                        let mut spawns = Vec::new_in(&*cx.scope);
+            #[allow(unused_imports)]
+            fn run(&mut self, cx: ::arcana::system::SystemContext<'_>) {
+                use core::{any::type_name, borrow::Borrow, clone::Clone, iter::Iterator, option::Option::{self, None, Some}};
+                use std::vec::Vec;
+                use ::arcana::{assets::{AssetId, WithId}, edict::bundle::EntityBuilder, unfold::UnfoldResult};
 
-                        for (e, (value, unfolded)) in query {
-                            let mut unfolded_insert = None;
-                            let mut ready = true;
-                            let mut updated = false;
+                let cleanup_query = cx.world.query_mut::<&#spawned_ident>().without::<#ident>();
 
-                            let unfolded: &mut #unfolded_ident = match unfolded {
-                                None => {
-                                    updated = true;
-                                    unfolded_insert.get_or_insert(#unfolded_ident {
-                                        #(#unfolded_fields_init,)*
-                                    })
-                                }
-                                Some(unfolded) => unfolded,
-                            };
+                let mut cleanup = Vec::new_in(&*cx.scope);
+                #cleanup_collect
 
-                            #(#updates)*
+                #cleanup_call
 
+                let query = cx.world.query_mut::<(&#ident, Option<&mut #spawned_ident>)>();
 
-                            if updated && ready {
-                                let UnfoldResult { insert, spawn } = (#unfold)( #(#unfold_fn_args,)* cx.res );
-                                inserts.push((e, insert));
+                let mut spawned_inserts = Vec::new_in(&*cx.scope);
+                #switch_pending
+                #(#pendings)*
 
-                                if Iterator::size_hint(&spawn).1 != Some(0) {
-                                    spawns.push((e, spawn));
-                                }
-                            }
+                for (e, (value, spawned)) in query {
+                    let mut spawned_insert = None;
+                    #declare_updated
 
-                            if let Some(unfolded_insert) = unfolded_insert {
-                                unfolded_inserts.push((e, unfolded_insert));
-                            }
+                    let spawned: &mut #spawned_ident = match spawned {
+                        None => {
+                            #mark_updated
+                            spawned_insert.get_or_insert(#init)
                         }
-
-                        for (e, insert) in inserts {
-                            cx.world.try_insert_bundle(&e, insert).unwrap();
+                        Some(spawned) => {
+                            #switch
+                            spawned
                         }
+                    };
 
-                        for (e, unfolded) in unfolded_inserts {
-                            cx.world.try_insert(&e, unfolded).unwrap();
-                        }
+                    match (value, &mut *spawned) {
+                        #( (#value_paths #value_pats, #spawned_paths #spawned_pats) => { #updates } )*
+                        #unmatched
+                    }
 
-                        for (e, spawn) in spawns {
-                            todo!()
-                        }
+                    if let Some(spawned_insert) = spawned_insert {
+                        spawned_inserts.push((e, spawned_insert));
                     }
                 }
+
+                #switch_cleanup
+
+                #(#applies)*
+
+                for (e, spawned) in spawned_inserts {
+                    cx.world.try_insert(&e, spawned).unwrap();
+                }
             }
         }
     };
@@ -458,29 +757,42 @@ pub fn derive_unfold(item: TokenStream) -> syn::Result<TokenStream> {
 fn parse(item: TokenStream) -> syn::Result<Parsed> {
     let input = syn::parse::<syn::DeriveInput>(item)?;
 
-    match &input.data {
-        syn::Data::Enum(data) => Err(syn::Error::new_spanned(
-            data.enum_token,
-            "Enumerations are unsupported by `Unfold` derive macro",
-        )),
-        syn::Data::Union(data) => Err(syn::Error::new_spanned(
-            data.union_token,
-            "Unions are unsupported by `Unfold` derive macro",
-        )),
-        syn::Data::Struct(data) => {
-            let attrs = parse_type_attributes(&input.attrs)?;
-
-            let fields = data
-                .fields
+    let parse_fields = |attrs, fields: &syn::Fields| -> syn::Result<ParsedFields> {
+        let attrs = parse_type_attributes(attrs)?;
+
+        let fields = fields
+            .iter()
+            .map(|f| parse_field_attributes(&f.attrs))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ParsedFields { attrs, fields })
+    };
+
+    let data = match &input.data {
+        syn::Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "Unions are unsupported by `Unfold` derive macro",
+            ))
+        }
+        syn::Data::Struct(data) => ParsedData::Struct(parse_fields(&input.attrs, &data.fields)?),
+        syn::Data::Enum(data) => {
+            if parse_type_attributes(&input.attrs)?.func.is_some() {
+                return Err(syn::Error::new_spanned(
+                    data.enum_token,
+                    "`#[unfold(fn ...)]` must be placed on enum variants",
+                ));
+            }
+
+            let variants = data
+                .variants
                 .iter()
-                .map(|f| parse_field_attributes(&f.attrs))
+                .map(|v| parse_fields(&v.attrs, &v.fields))
                 .collect::<Result<Vec<_>, _>>()?;
 
-            Ok(Parsed {
-                input,
-                attrs,
-                fields,
-            })
+            ParsedData::Enum(variants)
         }
-    }
+    };
+
+    Ok(Parsed { input, data })
 }
@@ -0,0 +1,152 @@
+#![feature(allocator_api)]
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use arcana::{
+    assets::{TrivialAsset, TypedAssetId, WithId},
+    game::{headless, Exit},
+    resources::Res,
+    system::SystemContext,
+    unfold::{Unfold, UnfoldResult},
+};
+
+#[test]
+fn compile() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/unfold/enum.rs");
+    t.pass("tests/unfold/optional.rs");
+}
+
+#[derive(Clone, Unfold)]
+enum Pickup {
+    #[unfold(fn unfold_health)]
+    Health { amount: u32 },
+
+    #[unfold(fn unfold_ammo)]
+    Ammo(u32),
+}
+
+struct Healing(u32);
+
+struct Rounds(u32);
+
+fn unfold_health(amount: &u32, _: &mut Res) -> UnfoldResult<(Healing,)> {
+    UnfoldResult::with_bundle((Healing(*amount),))
+}
+
+fn unfold_ammo(count: &u32, _: &mut Res) -> UnfoldResult<(Rounds,)> {
+    UnfoldResult::with_bundle((Rounds(*count),))
+}
+
+#[test]
+fn enum_unfolds_variant_bundle() {
+    let checked = Arc::new(AtomicBool::new(false));
+    let done = checked.clone();
+
+    headless(|mut game| async move {
+        game.scheduler.add_system(PickupUnfoldSystem);
+
+        let health = game.world.spawn((Pickup::Health { amount: 5 },));
+        let ammo = game.world.spawn((Pickup::Ammo(3),));
+
+        game.scheduler.add_system(move |cx: SystemContext<'_>| {
+            assert!(matches!(
+                cx.world.query_one::<&Healing>(&health).map(|h| h.0),
+                Ok(5)
+            ));
+            assert!(matches!(
+                cx.world.has_component::<Rounds>(&health),
+                Ok(false)
+            ));
+
+            assert!(matches!(
+                cx.world.query_one::<&Rounds>(&ammo).map(|r| r.0),
+                Ok(3)
+            ));
+            assert!(matches!(
+                cx.world.has_component::<Healing>(&ammo),
+                Ok(false)
+            ));
+
+            done.store(true, Ordering::Relaxed);
+            cx.res.insert(Exit);
+        });
+
+        Ok(game)
+    });
+
+    assert!(checked.load(Ordering::Relaxed));
+}
+
+#[derive(Clone)]
+struct Sound(Box<[u8]>);
+
+impl TrivialAsset for Sound {
+    type Error = std::convert::Infallible;
+
+    fn name() -> &'static str {
+        "test.sound"
+    }
+
+    fn decode(bytes: Box<[u8]>) -> Result<Self, Self::Error> {
+        Ok(Sound(bytes))
+    }
+}
+
+#[derive(Clone, Unfold)]
+#[unfold(fn unfold_jingle)]
+struct Jingle {
+    #[unfold(asset)]
+    sound: Option<TypedAssetId<Sound>>,
+}
+
+struct Playing(bool);
+
+fn unfold_jingle(sound: Option<&WithId<Sound>>, _: &mut Res) -> UnfoldResult<(Playing,)> {
+    UnfoldResult::with_bundle((Playing(sound.is_some()),))
+}
+
+#[test]
+fn optional_asset_unfolds_without_waiting() {
+    let checked = Arc::new(AtomicBool::new(false));
+    let done = checked.clone();
+
+    headless(|mut game| async move {
+        game.scheduler.add_system(JingleUnfoldSystem);
+
+        let silent = game.world.spawn((Jingle { sound: None },));
+
+        // There is no asset with this id, it never finishes loading.
+        let loud = game.world.spawn((Jingle {
+            sound: Some(TypedAssetId::new(0x5321e2914afca30d).unwrap()),
+        },));
+
+        let mut ticks = 0;
+        game.scheduler.add_system(move |cx: SystemContext<'_>| {
+            // Absent asset doesn't block unfolding.
+            assert!(matches!(
+                cx.world.query_one::<&Playing>(&silent).map(|p| p.0),
+                Ok(false)
+            ));
+
+            // Present asset blocks unfolding until loaded.
+            assert!(matches!(
+                cx.world.has_component::<Playing>(&loud),
+                Ok(false)
+            ));
+
+            ticks += 1;
+            if ticks == 10 {
+                done.store(true, Ordering::Relaxed);
+                cx.res.insert(Exit);
+            }
+        });
+
+        Ok(game)
+    });
+
+    assert!(checked.load(Ordering::Relaxed));
+}
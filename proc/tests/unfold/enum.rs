@@ -0,0 +1,47 @@
+#![feature(allocator_api)]
+
+use arcana::{
+    assets::{AssetId, TrivialAsset, WithId},
+    resources::Res,
+    unfold::{Unfold, UnfoldResult},
+};
+
+#[derive(Clone)]
+pub struct Sound(Box<[u8]>);
+
+impl TrivialAsset for Sound {
+    type Error = std::convert::Infallible;
+
+    fn name() -> &'static str {
+        "test.sound"
+    }
+
+    fn decode(bytes: Box<[u8]>) -> Result<Self, Self::Error> {
+        Ok(Sound(bytes))
+    }
+}
+
+#[derive(Clone, Unfold)]
+pub enum Pickup {
+    #[unfold(fn unfold_health)]
+    Health {
+        amount: u32,
+    },
+
+    Weapon(#[unfold(asset: Sound)] AssetId),
+}
+
+pub struct Healing(u32);
+
+fn unfold_health(amount: &u32, _: &mut Res) -> UnfoldResult<(Healing,)> {
+    UnfoldResult::with_bundle((Healing(*amount),))
+}
+
+fn main() {
+    let _ = PickupUnfoldSystem;
+    let _ = |spawned: &PickupUnfoldSpawned| match spawned {
+        PickupUnfoldSpawned::Health { amount } => *amount,
+        PickupUnfoldSpawned::Weapon(id) => id.is_some() as u32,
+    };
+    let _ = |_: &WithId<Sound>| ();
+}
@@ -0,0 +1,45 @@
+#![feature(allocator_api)]
+
+use arcana::{
+    assets::{AssetId, TrivialAsset, TypedAssetId, WithId},
+    resources::Res,
+    unfold::{Unfold, UnfoldResult},
+};
+
+#[derive(Clone)]
+pub struct Sound(Box<[u8]>);
+
+impl TrivialAsset for Sound {
+    type Error = std::convert::Infallible;
+
+    fn name() -> &'static str {
+        "test.sound"
+    }
+
+    fn decode(bytes: Box<[u8]>) -> Result<Self, Self::Error> {
+        Ok(Sound(bytes))
+    }
+}
+
+#[derive(Clone, Unfold)]
+pub struct Emitter {
+    #[unfold(asset: Sound)]
+    sound: Option<AssetId>,
+}
+
+#[derive(Clone, Unfold)]
+#[unfold(fn unfold_jingle)]
+pub struct Jingle {
+    #[unfold(asset)]
+    sound: Option<TypedAssetId<Sound>>,
+}
+
+pub struct Playing(bool);
+
+fn unfold_jingle(sound: Option<&WithId<Sound>>, _: &mut Res) -> UnfoldResult<(Playing,)> {
+    UnfoldResult::with_bundle((Playing(sound.is_some()),))
+}
+
+fn main() {
+    let _ = (EmitterUnfoldSystem, JingleUnfoldSystem);
+}
@@ -0,0 +1,181 @@
+use arcana::na;
+use goods::AssetId;
+use hashbrown::HashMap;
+
+use super::map::{CellOutOfBounds, TileMap};
+
+/// Number of cells along each side of a chunk.
+pub const CHUNK_SIZE: usize = 32;
+
+const CHUNK_CELLS: usize = CHUNK_SIZE * CHUNK_SIZE;
+
+type ChunkLoader = Box<dyn FnMut((usize, usize)) -> Option<Box<[usize]>> + Send + Sync>;
+
+/// Tile map with cells stored in square chunks of [`CHUNK_SIZE`] cells.
+///
+/// Chunks are allocated only when cells in them are accessed,
+/// so very large maps take memory only for the areas in use.
+/// Chunks that are not loaded are requested from the loader,
+/// or filled with `empty` cell if there is no loader or it has no such chunk.
+///
+/// Loaded chunks can be dropped with [`ChunkedTileMap::retain_chunks`],
+/// e.g. when they are far off-screen.
+///
+/// # Example
+///
+/// ```
+/// # use arcana_tiles::{ChunkedTileMap, CHUNK_SIZE};
+/// let mut map = ChunkedTileMap::new(goods::AssetId::new(1).unwrap(), 1.0, 10_000, 10_000, 0)
+///     .with_loader(|(x, y)| Some(vec![x + y; CHUNK_SIZE * CHUNK_SIZE].into()));
+///
+/// assert_eq!(map.cell_at(5, 5), Some(0));
+/// assert_eq!(map.cell_at(40, 70), Some(3));
+/// assert_eq!(map.cell_at(10_000, 0), None);
+///
+/// // Only chunks with accessed cells are allocated.
+/// let mut loaded: Vec<_> = map.loaded_chunks().collect();
+/// loaded.sort();
+/// assert_eq!(loaded, [(0, 0), (1, 2)]);
+///
+/// map.retain_chunks(|chunk, _| chunk == (1, 2));
+/// assert_eq!(map.loaded_chunks().collect::<Vec<_>>(), [(1, 2)]);
+/// ```
+pub struct ChunkedTileMap {
+    pub set: AssetId,
+    pub cell_size: f32,
+    width: usize,
+    height: usize,
+    empty: usize,
+    chunks: HashMap<(usize, usize), Box<[usize]>>,
+    loader: Option<ChunkLoader>,
+}
+
+impl ChunkedTileMap {
+    /// Returns new tile map of `width` by `height` cells
+    /// with all cells set to `empty`.
+    pub fn new(set: AssetId, cell_size: f32, width: usize, height: usize, empty: usize) -> Self {
+        ChunkedTileMap {
+            set,
+            cell_size,
+            width,
+            height,
+            empty,
+            chunks: HashMap::new(),
+            loader: None,
+        }
+    }
+
+    /// Sets function to load chunks on demand.
+    ///
+    /// Loader receives chunk coordinates and returns cells of the chunk row by row,
+    /// [`CHUNK_SIZE`] cells in each row.
+    /// Chunks for which loader returns `None` are filled with `empty` cell.
+    pub fn with_loader<F>(mut self, loader: F) -> Self
+    where
+        F: FnMut((usize, usize)) -> Option<Box<[usize]>> + Send + Sync + 'static,
+    {
+        self.loader = Some(Box::new(loader));
+        self
+    }
+
+    pub fn dimensions(&self) -> na::Vector2<usize> {
+        na::Vector2::new(self.width, self.height)
+    }
+
+    /// Returns tile of the cell, loading its chunk if needed.
+    /// Returns `None` if coordinates are out of bounds.
+    pub fn cell_at(&mut self, x: usize, y: usize) -> Option<usize> {
+        let (chunk, index) = self.locate(x, y)?;
+        Some(self.load_chunk(chunk)[index])
+    }
+
+    /// Replaces tile of the cell, loading its chunk if needed.
+    pub fn set_cell(&mut self, x: usize, y: usize, cell: usize) -> Result<(), CellOutOfBounds> {
+        let (chunk, index) = self.locate(x, y).ok_or(CellOutOfBounds { x, y })?;
+        self.load_chunk(chunk)[index] = cell;
+        Ok(())
+    }
+
+    /// Returns coordinates of loaded chunks.
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Drops loaded chunks for which `keep` returns `false`.
+    ///
+    /// `keep` receives chunk coordinates and cells,
+    /// so changed chunks can be saved before they are dropped.
+    /// Dropped chunks are loaded again when accessed.
+    pub fn retain_chunks(&mut self, mut keep: impl FnMut((usize, usize), &[usize]) -> bool) {
+        self.chunks.retain(|&chunk, cells| keep(chunk, cells));
+    }
+
+    fn locate(&self, x: usize, y: usize) -> Option<((usize, usize), usize)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let chunk = (x / CHUNK_SIZE, y / CHUNK_SIZE);
+        let index = (y % CHUNK_SIZE) * CHUNK_SIZE + x % CHUNK_SIZE;
+        Some((chunk, index))
+    }
+
+    fn load_chunk(&mut self, chunk: (usize, usize)) -> &mut [usize] {
+        let empty = self.empty;
+        let loader = &mut self.loader;
+
+        self.chunks.entry(chunk).or_insert_with(|| {
+            let cells = loader.as_mut().and_then(|loader| loader(chunk));
+            match cells {
+                Some(cells) if cells.len() == CHUNK_CELLS => cells,
+                Some(cells) => {
+                    arcana::tracing::error!(
+                        "Chunk ({}, {}) has {} cells instead of {}",
+                        chunk.0,
+                        chunk.1,
+                        cells.len(),
+                        CHUNK_CELLS
+                    );
+                    vec![empty; CHUNK_CELLS].into()
+                }
+                None => vec![empty; CHUNK_CELLS].into(),
+            }
+        })
+    }
+}
+
+/// Splits cells of dense tile map into chunks.
+///
+/// Missing cells of partial last row are set to `0`.
+///
+/// # Example
+///
+/// ```
+/// # use arcana_tiles::{ChunkedTileMap, TileMap};
+/// let map = TileMap {
+///     set: goods::AssetId::new(1).unwrap(),
+///     cell_size: 1.0,
+///     width: 40,
+///     cells: (0..80).collect::<Vec<_>>().into(),
+/// };
+///
+/// let mut chunked = ChunkedTileMap::from(map);
+/// assert_eq!(chunked.cell_at(0, 0), Some(0));
+/// assert_eq!(chunked.cell_at(35, 1), Some(75));
+/// assert_eq!(chunked.cell_at(0, 2), None);
+/// ```
+impl From<TileMap> for ChunkedTileMap {
+    fn from(map: TileMap) -> Self {
+        let dimensions = map.dimensions();
+        let mut chunked =
+            ChunkedTileMap::new(map.set, map.cell_size, dimensions.x, dimensions.y, 0);
+
+        for (y, row) in map.cells.chunks(map.width.max(1)).enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                let (chunk, index) = chunked.locate(x, y).unwrap();
+                chunked.load_chunk(chunk)[index] = cell;
+            }
+        }
+
+        chunked
+    }
+}
@@ -11,6 +11,7 @@ use arcana::{
     palette::LinSrgba,
     rect::Rect,
     scene::Global2,
+    TimeSpan,
 };
 use sierra::{
     graphics_pipeline_desc, mat3, Access, Buffer, DepthTest, Descriptors, DynamicGraphicsPipeline,
@@ -18,7 +19,7 @@ use sierra::{
     RenderPassEncoder, Sampler, ShaderModuleInfo, ShaderRepr, VertexInputRate, VertexShader,
 };
 
-use crate::{TileMap, TileSet};
+use crate::{TileAnimationClock, TileMap, TileSet};
 
 pub struct SpriteDraw {
     pipeline: DynamicGraphicsPipeline,
@@ -204,12 +205,17 @@ impl DrawNode for SpriteDraw {
             sprites.push(instance);
         }
 
+        let tiles_elapsed = cx
+            .world
+            .get_resource::<TileAnimationClock>()
+            .map_or(TimeSpan::ZERO, |clock| clock.elapsed);
+
         for (_, (map, set, global)) in cx.world.query_mut::<(&TileMap, &TileSet, &Global2)>() {
             let hc = map.cell_size * 0.5;
 
             for (j, row) in map.cells.chunks(map.width).enumerate() {
                 for (i, &cell) in row.iter().enumerate() {
-                    let tile = match set.tiles.get(cell) {
+                    let tile = match set.tile_at(cell, tiles_elapsed) {
                         None => {
                             return Err(eyre::eyre!("Missing tile '{}' in the tileset", cell));
                         }
@@ -326,12 +332,16 @@ pub fn collect_sprites(
     get_texture_index: FnMut(ImageView) -> u32,
     extend: &mut impl Extend<SpriteInstance>,
 ) {
+    let tiles_elapsed = world
+        .get_resource::<TileAnimationClock>()
+        .map_or(TimeSpan::ZERO, |clock| clock.elapsed);
+
     for (_, (map, set, global)) in world.query_mut::<(&TileMap, &TileSet, &Global2)>() {
         let hc = map.cell_size * 0.5;
 
         for (j, row) in map.cells.chunks(map.width).enumerate() {
             for (i, &cell) in row.iter().enumerate() {
-                let tile = match set.tiles.get(cell) {
+                let tile = match set.tile_at(cell, tiles_elapsed) {
                     None => {
                         return Err(eyre::eyre!("Missing tile '{}' in the tileset", cell));
                     }
@@ -1,7 +1,8 @@
 #![feature(allocator_api)]
 
+mod chunked;
 mod map;
 mod set;
 mod tile;
 
-pub use self::{map::*, set::*, tile::*};
+pub use self::{chunked::*, map::*, set::*, tile::*};
@@ -9,14 +9,31 @@ use arcana::{
 };
 use goods::{Asset, AssetId};
 
-#[cfg(feature = "physics2d")]
-use crate::physics2::{
-    prelude::{ColliderBuilder, RigidBodyBuilder, RigidBodyHandle, SharedShape},
+#[cfg(feature = "physics")]
+use arcana_physics::physics2::{
+    prelude::{Collider, ColliderBuilder, RigidBodyBuilder, RigidBodyHandle, SharedShape},
     PhysicsData2,
 };
 
 use super::set::TileSet;
 
+/// Error returned when cell coordinates are outside of the [`TileMap`].
+#[derive(Clone, Copy, Debug, thiserror::Error)]
+#[error("Cell ({x}, {y}) is out of tile map bounds")]
+pub struct CellOutOfBounds {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// Rectangle of cells in the [`TileMap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
 #[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize, Asset, Unfold)]
 #[asset(name = "arcana.tilemap")]
 #[unfold(fn unfold_tile_map)]
@@ -42,6 +59,98 @@ impl TileMap {
         self.cells[y * self.width + x]
     }
 
+    /// Returns index of the cell, or `None` if coordinates are out of bounds.
+    pub fn get_cell(&self, x: usize, y: usize) -> Option<usize> {
+        let index = self.cell_index(x, y)?;
+        Some(self.cells[index])
+    }
+
+    /// Replaces tile of the cell.
+    ///
+    /// Changed cells are picked by tile map unfolding,
+    /// so colliders of the map are rebuilt on next run of unfold system.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use arcana_tiles::TileMap;
+    /// let mut map = TileMap {
+    ///     set: goods::AssetId::new(1).unwrap(),
+    ///     cell_size: 1.0,
+    ///     width: 2,
+    ///     cells: Arc::from([0, 0, 0, 0]),
+    /// };
+    ///
+    /// map.set_cell(1, 1, 3).unwrap();
+    /// assert_eq!(map.cell_at(1, 1), 3);
+    ///
+    /// assert!(map.set_cell(2, 0, 3).is_err());
+    /// assert!(map.set_cell(0, 2, 3).is_err());
+    /// ```
+    pub fn set_cell(&mut self, x: usize, y: usize, cell: usize) -> Result<(), CellOutOfBounds> {
+        let index = self.cell_index(x, y).ok_or(CellOutOfBounds { x, y })?;
+
+        match Arc::get_mut(&mut self.cells) {
+            Some(cells) => cells[index] = cell,
+            None => {
+                // Cells are shared with unfolded copy or another map.
+                let mut cells = self.cells.to_vec();
+                cells[index] = cell;
+                self.cells = cells.into();
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns coordinates of the cell that contains the point in world space.
+    ///
+    /// `iso` is the map's isometry, e.g. `Global2::iso` of the map entity.
+    /// Cells are centered at [`TileMap::cell_center`]
+    /// and span half of `cell_size` in each direction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use arcana::na;
+    /// # use arcana_tiles::TileMap;
+    /// let map = TileMap {
+    ///     set: goods::AssetId::new(1).unwrap(),
+    ///     cell_size: 2.0,
+    ///     width: 3,
+    ///     cells: Arc::from([0; 6]),
+    /// };
+    ///
+    /// let iso = na::Isometry2::translation(10.0, 0.0);
+    ///
+    /// assert_eq!(map.cell_at_world(&iso, na::Point2::new(10.0, 0.0)), Some((0, 0)));
+    /// assert_eq!(map.cell_at_world(&iso, na::Point2::new(9.0, -1.0)), Some((0, 0)));
+    /// assert_eq!(map.cell_at_world(&iso, na::Point2::new(14.9, 2.9)), Some((2, 1)));
+    ///
+    /// assert_eq!(map.cell_at_world(&iso, na::Point2::new(8.9, 0.0)), None);
+    /// assert_eq!(map.cell_at_world(&iso, na::Point2::new(15.0, 0.0)), None);
+    /// assert_eq!(map.cell_at_world(&iso, na::Point2::new(10.0, 3.0)), None);
+    /// ```
+    pub fn cell_at_world(
+        &self,
+        iso: &na::Isometry2<f32>,
+        point: na::Point2<f32>,
+    ) -> Option<(usize, usize)> {
+        let local = iso.inverse_transform_point(&point);
+
+        let x = (local.x / self.cell_size + 0.5).floor();
+        let y = (local.y / self.cell_size + 0.5).floor();
+
+        if !(x >= 0.0 && y >= 0.0) {
+            return None;
+        }
+
+        let (x, y) = (x as usize, y as usize);
+        self.cell_index(x, y)?;
+        Some((x, y))
+    }
+
     pub fn cell_center(&self, x: usize, y: usize) -> na::Point2<f32> {
         na::Point2::new(x as f32 * self.cell_size, y as f32 * self.cell_size)
     }
@@ -54,6 +163,66 @@ impl TileMap {
         let y = ((self.cells.len() - 1) / self.width) + 1;
         self.cell_size * na::Vector2::new(x as f32, y as f32)
     }
+
+    /// Covers solid cells with rectangles.
+    ///
+    /// Adjacent solid cells are merged greedily,
+    /// first along rows and then down the columns,
+    /// so filled areas are covered by few rectangles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use arcana_tiles::{CellRect, TileMap};
+    /// let map = TileMap {
+    ///     set: goods::AssetId::new(1).unwrap(),
+    ///     cell_size: 1.0,
+    ///     width: 4,
+    ///     cells: Arc::from([
+    ///         1, 1, 1, 0, //
+    ///         1, 1, 1, 0, //
+    ///         1, 1, 1, 0, //
+    ///         0, 0, 0, 1, //
+    ///     ]),
+    /// };
+    ///
+    /// let rects = map.solid_rects(|cell| cell == 1);
+    /// assert_eq!(
+    ///     rects,
+    ///     [
+    ///         CellRect { x: 0, y: 0, width: 3, height: 3 },
+    ///         CellRect { x: 3, y: 3, width: 1, height: 1 },
+    ///     ]
+    /// );
+    /// ```
+    pub fn solid_rects(&self, is_solid: impl FnMut(usize) -> bool) -> Vec<CellRect> {
+        solid_rects(self.width, &self.cells, is_solid)
+    }
+
+    /// Builds single collider for all cells with tiles that have collider.
+    ///
+    /// Solid cells are merged with [`TileMap::solid_rects`],
+    /// so collider is a compound of one cuboid per rectangle.
+    /// Collider is positioned in map space.
+    /// Returns `None` if no cell is solid.
+    ///
+    /// Unfolded tile maps rebuild their collider when cells change.
+    #[cfg(feature = "physics")]
+    pub fn build_collider(&self, set: &TileSet) -> Option<Collider> {
+        build_collider(set, self.cell_size, self.width, &self.cells)
+    }
+
+    fn cell_index(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width {
+            return None;
+        }
+        let index = y.checked_mul(self.width)?.checked_add(x)?;
+        if index >= self.cells.len() {
+            return None;
+        }
+        Some(index)
+    }
 }
 
 fn unfold_tile_map(
@@ -63,46 +232,112 @@ fn unfold_tile_map(
     cells: &Arc<[usize]>,
     res: &mut Res,
 ) -> UnfoldResult<impl Bundle> {
-    #[cfg(not(feature = "physics2d"))]
+    #[cfg(not(feature = "physics"))]
     drop((cell_size, width, cells, res));
 
-    #[cfg(feature = "physics2d")]
+    // Body is replaced when cells change,
+    // physics system removes bodies no longer referenced by entities.
+    #[cfg(feature = "physics")]
     let body: RigidBodyHandle = {
-        let mut compound = Vec::new();
-
-        for (j, row) in cells.chunks(*width).enumerate() {
-            for (i, &cell) in row.iter().enumerate() {
-                let tile = match set.tiles.get(cell) {
-                    None => {
-                        tracing::error!("Missing tile '{}' in the tileset", cell);
-                        continue;
-                    }
-                    Some(tile) => tile,
-                };
-                if let Some(collider) = tile.collider {
-                    let tr = na::Translation2::new(i as f32 * cell_size, j as f32 * cell_size);
-                    let shape = collider.shared_shape(*cell_size, res);
-                    compound.push((tr.into(), shape));
-                }
-            }
-        }
-
-        let shape = SharedShape::compound(compound);
         let physics = res.with(PhysicsData2::new);
-        let body = physics
-            .bodies
-            .insert(RigidBodyBuilder::new_static().build());
-        physics.colliders.insert_with_parent(
-            ColliderBuilder::new(shape).build(),
-            body,
-            &mut physics.bodies,
-        );
+        let body = physics.bodies.insert(RigidBodyBuilder::fixed().build());
+
+        if let Some(collider) = build_collider(set, *cell_size, *width, cells) {
+            physics
+                .colliders
+                .insert_with_parent(collider, body, &mut physics.bodies);
+        }
         body
     };
 
     UnfoldResult::with_bundle((
-        #[cfg(feature = "physics2d")]
+        #[cfg(feature = "physics")]
         body,
         TileSet::clone(set),
     ))
 }
+
+fn solid_rects(
+    width: usize,
+    cells: &[usize],
+    mut is_solid: impl FnMut(usize) -> bool,
+) -> Vec<CellRect> {
+    let mut rects = Vec::new();
+    if width == 0 {
+        return rects;
+    }
+
+    let height = (cells.len() + width - 1) / width;
+
+    // Solid cells not yet covered by a rectangle.
+    let mut solid: Vec<bool> = cells.iter().map(|&cell| is_solid(cell)).collect();
+    let at =
+        |solid: &[bool], x: usize, y: usize| solid.get(y * width + x).copied().unwrap_or(false);
+
+    for y in 0..height {
+        for x in 0..width {
+            if !at(&solid, x, y) {
+                continue;
+            }
+
+            let mut w = 1;
+            while x + w < width && at(&solid, x + w, y) {
+                w += 1;
+            }
+
+            let mut h = 1;
+            while y + h < height && (x..x + w).all(|i| at(&solid, i, y + h)) {
+                h += 1;
+            }
+
+            for j in y..y + h {
+                solid[j * width + x..j * width + x + w].fill(false);
+            }
+
+            rects.push(CellRect {
+                x,
+                y,
+                width: w,
+                height: h,
+            });
+        }
+    }
+
+    rects
+}
+
+#[cfg(feature = "physics")]
+fn build_collider(
+    set: &TileSet,
+    cell_size: f32,
+    width: usize,
+    cells: &[usize],
+) -> Option<Collider> {
+    let rects = solid_rects(width, cells, |cell| match set.tiles.get(cell) {
+        None => {
+            arcana::tracing::error!("Missing tile '{}' in the tileset", cell);
+            false
+        }
+        Some(tile) => tile.collider.is_some(),
+    });
+
+    if rects.is_empty() {
+        return None;
+    }
+
+    let compound = rects
+        .iter()
+        .map(|rect| {
+            // Cell centers are at multiples of `cell_size`.
+            let x = (rect.x as f32 + (rect.width - 1) as f32 * 0.5) * cell_size;
+            let y = (rect.y as f32 + (rect.height - 1) as f32 * 0.5) * cell_size;
+            let shape = SharedShape::cuboid(
+                rect.width as f32 * cell_size * 0.5,
+                rect.height as f32 * cell_size * 0.5,
+            );
+            (na::Isometry2::translation(x, y), shape)
+        })
+        .collect();
+
+    Some(ColliderBuilder::new(SharedShape::compound(compound)).build())
+}
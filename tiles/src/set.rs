@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use arcana::{clocks::ClockIndex, edict::world::World, TimeSpan};
 use goods::Asset;
 
 use super::tile::Tile;
@@ -10,3 +11,42 @@ pub struct TileSet {
     #[cfg_attr(feature = "graphics", asset(container))]
     pub tiles: Arc<[Tile]>,
 }
+
+impl TileSet {
+    /// Returns tile to draw for cell with tile `index`
+    /// when shared animation clock shows `elapsed`.
+    ///
+    /// Animated tiles are resolved to tile of current frame,
+    /// other tiles are returned as is.
+    pub fn tile_at(&self, index: usize, elapsed: TimeSpan) -> Option<&Tile> {
+        let tile = self.tiles.get(index)?;
+
+        match &tile.animation {
+            None => Some(tile),
+            Some(animation) => match animation.tile_at(elapsed) {
+                None => Some(tile),
+                Some(frame) => self.tiles.get(frame),
+            },
+        }
+    }
+}
+
+/// Shared clock of tile animations.
+///
+/// Resource advanced by [`tile_animation_system`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TileAnimationClock {
+    /// Time elapsed since tile animations started.
+    pub elapsed: TimeSpan,
+}
+
+/// Advances [`TileAnimationClock`] resource, inserting it if missing.
+pub fn tile_animation_system(world: &mut World) {
+    let delta = world.expect_resource::<ClockIndex>().delta;
+
+    if world.get_resource::<TileAnimationClock>().is_none() {
+        world.insert_resource(TileAnimationClock::default());
+    }
+
+    world.expect_resource_mut::<TileAnimationClock>().elapsed += delta;
+}
@@ -1,3 +1,5 @@
+use arcana::TimeSpan;
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "graphics")] {
         use arcana::{
@@ -59,4 +61,71 @@ pub struct Tile {
     #[cfg(feature = "graphics")]
     #[serde(default)]
     pub uv: Rect,
+
+    /// Makes tile animated, cycling through other tiles of the set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation: Option<TileAnimation>,
+}
+
+/// Animation of a tile.
+///
+/// Animated tile is drawn as tiles of frames in turn and repeats.
+/// All instances of animated tile are driven by shared [`TileAnimationClock`](crate::TileAnimationClock),
+/// so they stay in sync.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TileAnimation {
+    pub frames: Vec<TileAnimationFrame>,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TileAnimationFrame {
+    /// Index of the tile in the set.
+    pub tile: usize,
+
+    /// How long frame is shown.
+    pub span: TimeSpan,
+}
+
+impl TileAnimation {
+    /// Returns duration of single animation cycle.
+    pub fn span(&self) -> TimeSpan {
+        self.frames.iter().map(|frame| frame.span).sum()
+    }
+
+    /// Returns index of the tile shown at `elapsed` time since animation start.
+    /// Returns `None` if animation has no frames.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana::TimeSpan;
+    /// # use arcana_tiles::{TileAnimation, TileAnimationFrame};
+    /// let animation = TileAnimation {
+    ///     frames: vec![
+    ///         TileAnimationFrame { tile: 3, span: TimeSpan::from_millis(100) },
+    ///         TileAnimationFrame { tile: 4, span: TimeSpan::from_millis(200) },
+    ///     ],
+    /// };
+    ///
+    /// assert_eq!(animation.tile_at(TimeSpan::ZERO), Some(3));
+    /// assert_eq!(animation.tile_at(TimeSpan::from_millis(150)), Some(4));
+    /// assert_eq!(animation.tile_at(TimeSpan::from_millis(300)), Some(3));
+    /// assert_eq!(animation.tile_at(TimeSpan::from_millis(599)), Some(4));
+    /// ```
+    pub fn tile_at(&self, elapsed: TimeSpan) -> Option<usize> {
+        let span = self.span();
+        if span == TimeSpan::ZERO {
+            return self.frames.first().map(|frame| frame.tile);
+        }
+
+        let mut left = elapsed % span;
+        for frame in &self.frames {
+            if left < frame.span {
+                return Some(frame.tile);
+            }
+            left -= frame.span;
+        }
+
+        None
+    }
 }
@@ -2,7 +2,11 @@
 
 //! Contains types for time measurement and ticking.
 
+mod signed;
 mod span;
 mod stamp;
 
-pub use self::{span::*, stamp::*};
+#[cfg(feature = "serde")]
+pub mod serde;
+
+pub use self::{signed::*, span::*, stamp::*};
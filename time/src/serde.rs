@@ -0,0 +1,252 @@
+//! Modules for `#[serde(with = "...")]` attribute
+//! that encode [`TimeSpan`] fields in formats of external schemas.
+//!
+//! # Example
+//!
+//! ```
+//! # use arcana_time::TimeSpan;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "arcana_time::serde::as_seconds_f64")]
+//!     cooldown: TimeSpan,
+//!
+//!     #[serde(with = "arcana_time::serde::as_millis_u64")]
+//!     timeout: TimeSpan,
+//!
+//!     #[serde(with = "arcana_time::serde::as_string")]
+//!     round: TimeSpan,
+//! }
+//!
+//! let config = Config {
+//!     cooldown: 1500 * TimeSpan::MILLISECOND,
+//!     timeout: 30 * TimeSpan::SECOND,
+//!     round: 3 * TimeSpan::MINUTE,
+//! };
+//!
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"cooldown":1.5,"timeout":30000,"round":"03:00"}"#);
+//! assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+//! ```
+
+use core::fmt;
+
+use ::serde::{de, Deserializer, Serializer};
+
+use crate::TimeSpan;
+
+/// Encodes [`TimeSpan`] as floating point number of seconds.
+///
+/// Spans longer than about 104 days may lose nanosecond precision.
+///
+/// # Example
+///
+/// ```
+/// # use arcana_time::TimeSpan;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Delay(#[serde(with = "arcana_time::serde::as_seconds_f64")] TimeSpan);
+///
+/// for span in [
+///     TimeSpan::ZERO,
+///     TimeSpan::NANOSECOND,
+///     250 * TimeSpan::MILLISECOND,
+///     TimeSpan::from_nanos(1_234_567_891),
+///     TimeSpan::DAY + 7 * TimeSpan::MICROSECOND,
+/// ] {
+///     let json = serde_json::to_string(&Delay(span)).unwrap();
+///     assert_eq!(serde_json::from_str::<Delay>(&json).unwrap().0, span);
+/// }
+///
+/// assert_eq!(serde_json::from_str::<Delay>("0.5").unwrap().0, TimeSpan::SECOND / 2);
+/// assert_eq!(serde_json::from_str::<Delay>("2").unwrap().0, 2 * TimeSpan::SECOND);
+/// assert!(serde_json::from_str::<Delay>("-0.5").is_err());
+/// assert!(serde_json::from_str::<Delay>("1e20").is_err());
+/// ```
+pub mod as_seconds_f64 {
+    use super::*;
+
+    pub fn serialize<S>(span: &TimeSpan, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(span.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<TimeSpan, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = TimeSpan;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("non-negative number of seconds")
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<TimeSpan, E>
+            where
+                E: de::Error,
+            {
+                TimeSpan::try_from_secs_f64(v)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Float(v), &self))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<TimeSpan, E>
+            where
+                E: de::Error,
+            {
+                TimeSpan::SECOND
+                    .checked_mul(v)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<TimeSpan, E>
+            where
+                E: de::Error,
+            {
+                match u64::try_from(v) {
+                    Ok(v) => self.visit_u64(v),
+                    Err(_) => Err(E::invalid_value(de::Unexpected::Signed(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_f64(Visitor)
+    }
+}
+
+/// Encodes [`TimeSpan`] as integer number of milliseconds.
+///
+/// Sub-millisecond part is truncated on serialization.
+///
+/// # Example
+///
+/// ```
+/// # use arcana_time::TimeSpan;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Timeout(#[serde(with = "arcana_time::serde::as_millis_u64")] TimeSpan);
+///
+/// for span in [TimeSpan::ZERO, TimeSpan::MILLISECOND, 90 * TimeSpan::MINUTE] {
+///     let json = serde_json::to_string(&Timeout(span)).unwrap();
+///     assert_eq!(serde_json::from_str::<Timeout>(&json).unwrap().0, span);
+/// }
+///
+/// let json = serde_json::to_string(&Timeout(TimeSpan::from_micros(1500))).unwrap();
+/// assert_eq!(json, "1");
+///
+/// assert!(serde_json::from_str::<Timeout>("-1").is_err());
+/// assert!(serde_json::from_str::<Timeout>(&u64::MAX.to_string()).is_err());
+/// ```
+pub mod as_millis_u64 {
+    use super::*;
+
+    pub fn serialize<S>(span: &TimeSpan, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(span.as_millis())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<TimeSpan, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = TimeSpan;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("non-negative integer number of milliseconds")
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<TimeSpan, E>
+            where
+                E: de::Error,
+            {
+                TimeSpan::MILLISECOND
+                    .checked_mul(v)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<TimeSpan, E>
+            where
+                E: de::Error,
+            {
+                match u64::try_from(v) {
+                    Ok(v) => self.visit_u64(v),
+                    Err(_) => Err(E::invalid_value(de::Unexpected::Signed(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_u64(Visitor)
+    }
+}
+
+/// Encodes [`TimeSpan`] as string in its display format
+/// regardless of serializer being human-readable.
+///
+/// Sub-microsecond part is truncated on serialization.
+///
+/// # Example
+///
+/// ```
+/// # use arcana_time::TimeSpan;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Round(#[serde(with = "arcana_time::serde::as_string")] TimeSpan);
+///
+/// for span in [
+///     TimeSpan::ZERO,
+///     2500 * TimeSpan::MICROSECOND,
+///     TimeSpan::DAY + 90 * TimeSpan::MINUTE + TimeSpan::MICROSECOND,
+/// ] {
+///     let json = serde_json::to_string(&Round(span)).unwrap();
+///     assert_eq!(serde_json::from_str::<Round>(&json).unwrap().0, span);
+/// }
+///
+/// let json = serde_json::to_string(&Round(1500 * TimeSpan::MILLISECOND)).unwrap();
+/// assert_eq!(json, r#""01.5""#);
+///
+/// assert!(serde_json::from_str::<Round>("1500").is_err());
+/// assert!(serde_json::from_str::<Round>(r#""soon""#).is_err());
+/// ```
+pub mod as_string {
+    use super::*;
+
+    pub fn serialize<S>(span: &TimeSpan, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(span)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<TimeSpan, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = TimeSpan;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("string with encoded time span")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<TimeSpan, E>
+            where
+                E: de::Error,
+            {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
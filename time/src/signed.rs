@@ -0,0 +1,355 @@
+use {
+    crate::span::{TimeSpan, TimeSpanParseErr},
+    core::{
+        fmt,
+        ops::{Add, AddAssign, Neg, Sub, SubAssign},
+        str::FromStr,
+    },
+};
+
+/// Time span that may be negative, e.g. difference between two spans or timestamps.
+/// Underlying value is `i64` number of nanoseconds.
+///
+/// Produced by [`TimeSpan::signed_sub`] and [`TimeStamp::signed_duration_since`](crate::TimeStamp::signed_duration_since)
+/// which never panic, unlike subtraction of unsigned values.
+/// `TimeSpan` should still be used for storage of durations.
+///
+/// Displayed and parsed as `TimeSpan` with `-` prefix for negative values.
+/// Serialized in the same format for human-readable serializer
+/// and as signed number of nanoseconds for binary serializer.
+///
+/// # Example
+///
+/// ```
+/// # use arcana_time::{SignedTimeSpan, TimeSpan};
+/// let ahead = TimeSpan::SECOND.signed_sub(250 * TimeSpan::MILLISECOND);
+/// let behind = (250 * TimeSpan::MILLISECOND).signed_sub(TimeSpan::SECOND);
+/// let same = TimeSpan::SECOND.signed_sub(TimeSpan::SECOND);
+///
+/// assert!(ahead.is_positive());
+/// assert!(behind.is_negative());
+/// assert_eq!(same, SignedTimeSpan::ZERO);
+///
+/// assert_eq!(ahead, -behind);
+/// assert_eq!(ahead.abs(), 750 * TimeSpan::MILLISECOND);
+/// assert_eq!(behind.abs(), 750 * TimeSpan::MILLISECOND);
+/// assert_eq!(ahead + behind, same);
+///
+/// assert_eq!(ahead.to_string(), "00.75");
+/// assert_eq!(behind.to_string(), "-00.75");
+/// assert_eq!(same.to_string(), "00");
+///
+/// assert_eq!("-00.75".parse::<SignedTimeSpan>().unwrap(), behind);
+/// assert_eq!("1m".parse::<SignedTimeSpan>().unwrap(), SignedTimeSpan::from(TimeSpan::MINUTE));
+///
+/// // Differences beyond representable range saturate.
+/// let max = TimeSpan::from_nanos(u64::MAX);
+/// assert_eq!(TimeSpan::ZERO.signed_sub(max), SignedTimeSpan::MIN);
+/// assert_eq!(max.signed_sub(TimeSpan::ZERO), SignedTimeSpan::MAX);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct SignedTimeSpan {
+    nanos: i64,
+}
+
+impl Default for SignedTimeSpan {
+    #[inline]
+    fn default() -> Self {
+        SignedTimeSpan::ZERO
+    }
+}
+
+impl SignedTimeSpan {
+    /// Zero time span.
+    ///
+    /// This is also default value.
+    pub const ZERO: Self = SignedTimeSpan { nanos: 0 };
+
+    /// Smallest representable span, about 292 years in the past.
+    pub const MIN: Self = SignedTimeSpan { nanos: i64::MIN };
+
+    /// Largest representable span, about 292 years in the future.
+    pub const MAX: Self = SignedTimeSpan { nanos: i64::MAX };
+
+    /// Convert number of nanoseconds into `SignedTimeSpan`.
+    #[inline]
+    pub const fn from_nanos(nanos: i64) -> Self {
+        SignedTimeSpan { nanos }
+    }
+
+    /// Returns number of nanoseconds.
+    #[inline]
+    pub const fn as_nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    /// Returns number of seconds as floating point value.
+    #[inline]
+    pub fn as_secs_f32(&self) -> f32 {
+        self.nanos as f32 / TimeSpan::SECOND.as_nanos() as f32
+    }
+
+    /// Returns number of seconds as high precision floating point value.
+    #[inline]
+    pub fn as_secs_f64(&self) -> f64 {
+        self.nanos as f64 / TimeSpan::SECOND.as_nanos() as f64
+    }
+
+    /// Returns absolute value of the span.
+    #[inline]
+    pub const fn abs(&self) -> TimeSpan {
+        TimeSpan::from_nanos(self.nanos.unsigned_abs())
+    }
+
+    /// Returns `true` if span is zero.
+    #[inline]
+    pub const fn is_zero(&self) -> bool {
+        self.nanos == 0
+    }
+
+    /// Returns `true` if span is greater than zero.
+    #[inline]
+    pub const fn is_positive(&self) -> bool {
+        self.nanos > 0
+    }
+
+    /// Returns `true` if span is less than zero.
+    #[inline]
+    pub const fn is_negative(&self) -> bool {
+        self.nanos < 0
+    }
+
+    /// Checked addition.
+    /// Returns `None` if result overflows.
+    #[inline]
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.nanos.checked_add(rhs.nanos) {
+            Some(nanos) => Some(SignedTimeSpan { nanos }),
+            None => None,
+        }
+    }
+
+    /// Checked subtraction.
+    /// Returns `None` if result overflows.
+    #[inline]
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.nanos.checked_sub(rhs.nanos) {
+            Some(nanos) => Some(SignedTimeSpan { nanos }),
+            None => None,
+        }
+    }
+
+    /// Saturating addition.
+    /// Result is clamped to [`SignedTimeSpan::MIN`] and [`SignedTimeSpan::MAX`].
+    ///
+    /// `+` and `-` operators saturate as well, so arithmetic never panics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::SignedTimeSpan;
+    /// let one = SignedTimeSpan::from_nanos(1);
+    ///
+    /// assert_eq!(SignedTimeSpan::MAX + one, SignedTimeSpan::MAX);
+    /// assert_eq!(SignedTimeSpan::MIN - one, SignedTimeSpan::MIN);
+    /// assert_eq!(SignedTimeSpan::MIN + SignedTimeSpan::MIN, SignedTimeSpan::MIN);
+    /// assert_eq!(SignedTimeSpan::ZERO - SignedTimeSpan::MIN, SignedTimeSpan::MAX);
+    /// assert_eq!(-SignedTimeSpan::MIN, SignedTimeSpan::MAX);
+    ///
+    /// let mut span = SignedTimeSpan::MAX;
+    /// span += one;
+    /// assert_eq!(span, SignedTimeSpan::MAX);
+    /// span -= SignedTimeSpan::MAX;
+    /// assert_eq!(span, SignedTimeSpan::ZERO);
+    ///
+    /// assert_eq!(SignedTimeSpan::MAX.checked_add(one), None);
+    /// assert_eq!(SignedTimeSpan::MIN.checked_sub(one), None);
+    /// assert_eq!(SignedTimeSpan::MAX.checked_sub(one), Some(SignedTimeSpan::from_nanos(i64::MAX - 1)));
+    /// assert_eq!(SignedTimeSpan::MIN.checked_add(one), Some(SignedTimeSpan::from_nanos(i64::MIN + 1)));
+    /// ```
+    #[inline]
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        SignedTimeSpan {
+            nanos: self.nanos.saturating_add(rhs.nanos),
+        }
+    }
+
+    /// Saturating subtraction.
+    /// Result is clamped to [`SignedTimeSpan::MIN`] and [`SignedTimeSpan::MAX`].
+    #[inline]
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        SignedTimeSpan {
+            nanos: self.nanos.saturating_sub(rhs.nanos),
+        }
+    }
+
+    /// Returns span as `TimeSpan` or `None` if span is negative.
+    #[inline]
+    pub const fn to_unsigned(&self) -> Option<TimeSpan> {
+        if self.nanos < 0 {
+            None
+        } else {
+            Some(TimeSpan::from_nanos(self.nanos as u64))
+        }
+    }
+}
+
+impl From<TimeSpan> for SignedTimeSpan {
+    /// Converts unsigned span.
+    /// Spans longer than [`SignedTimeSpan::MAX`] saturate.
+    #[inline]
+    fn from(span: TimeSpan) -> Self {
+        SignedTimeSpan {
+            nanos: span.as_nanos().min(i64::MAX as u64) as i64,
+        }
+    }
+}
+
+impl Neg for SignedTimeSpan {
+    type Output = Self;
+
+    /// Negates the span.
+    /// [`SignedTimeSpan::MIN`] saturates to [`SignedTimeSpan::MAX`].
+    #[inline]
+    fn neg(self) -> Self {
+        SignedTimeSpan {
+            nanos: self.nanos.saturating_neg(),
+        }
+    }
+}
+
+impl Add for SignedTimeSpan {
+    type Output = Self;
+
+    /// Adds spans saturating at the bounds.
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self.saturating_add(rhs)
+    }
+}
+
+impl AddAssign for SignedTimeSpan {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.saturating_add(rhs);
+    }
+}
+
+impl Sub for SignedTimeSpan {
+    type Output = Self;
+
+    /// Subtracts spans saturating at the bounds.
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self.saturating_sub(rhs)
+    }
+}
+
+impl SubAssign for SignedTimeSpan {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.saturating_sub(rhs);
+    }
+}
+
+impl fmt::Debug for SignedTimeSpan {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for SignedTimeSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+        fmt::Display::fmt(&self.abs(), f)
+    }
+}
+
+impl FromStr for SignedTimeSpan {
+    type Err = TimeSpanParseErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+
+        let nanos = s.parse::<TimeSpan>()?.as_nanos();
+
+        let nanos = if negative {
+            0i64.checked_sub_unsigned(nanos)
+        } else {
+            i64::try_from(nanos).ok()
+        };
+
+        match nanos {
+            Some(nanos) => Ok(SignedTimeSpan { nanos }),
+            None => Err(TimeSpanParseErr::Overflow),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SignedTimeSpan {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Serialize in pretty format for human readable serializer
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_i64(self.nanos)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SignedTimeSpan {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = SignedTimeSpan;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("String with signed time span or integer number of nanoseconds")
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(SignedTimeSpan { nanos: v })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match i64::try_from(v) {
+                    Ok(nanos) => Ok(SignedTimeSpan { nanos }),
+                    Err(_) => Err(E::custom("Integer is too large for SignedTimeSpan")),
+                }
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                v.parse().map_err(|err| E::custom(err))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(Visitor)
+        } else {
+            deserializer.deserialize_i64(Visitor)
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use crate::signed::SignedTimeSpan;
+
 use core::{
     fmt,
-    iter::Sum,
+    iter::{FusedIterator, Sum},
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Range, Rem, RemAssign, Sub, SubAssign},
     str::FromStr,
     time::Duration,
@@ -98,7 +100,6 @@ impl TimeSpan {
 
     /// One Gregorian year.
     /// Defined as 365.24219 days.
-
     pub const GREGORIAN_YEAR: Self = TimeSpan {
         nanos: 31_556_925_216_000_000,
     };
@@ -199,6 +200,312 @@ impl TimeSpan {
         self.nanos as f64 / Self::SECOND.nanos as f64
     }
 
+    /// Multiplies span by a factor, e.g. to slow down or speed up timers.
+    ///
+    /// Computed in `f64` and rounded to the nearest nanosecond.
+    /// Result saturates at zero for negative and NaN factors
+    /// and at largest representable span on overflow.
+    ///
+    /// Spans longer than about 104 days are not representable by `f64` exactly
+    /// and may lose nanosecond precision.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::TimeSpan;
+    /// assert_eq!(TimeSpan::SECOND.mul_f32(0.0), TimeSpan::ZERO);
+    /// assert_eq!(TimeSpan::SECOND.mul_f32(1.5), 1500 * TimeSpan::MILLISECOND);
+    /// assert_eq!(TimeSpan::SECOND.mul_f32(-1.0), TimeSpan::ZERO);
+    /// assert_eq!(TimeSpan::YEAR.mul_f32(1e10), TimeSpan::from_nanos(u64::MAX));
+    /// assert_eq!(TimeSpan::SECOND.div_f32(0.5), 2 * TimeSpan::SECOND);
+    /// assert_eq!(TimeSpan::SECOND.div_f32(0.0), TimeSpan::from_nanos(u64::MAX));
+    /// ```
+    #[inline]
+    pub fn mul_f32(self, factor: f32) -> Self {
+        Self::from_nanos_f64(self.nanos as f64 * factor as f64)
+    }
+
+    /// Divides span by a factor.
+    ///
+    /// Same rounding and saturation rules as [`TimeSpan::mul_f32`] apply.
+    /// Division by zero gives largest representable span for non-zero span.
+    #[inline]
+    pub fn div_f32(self, factor: f32) -> Self {
+        Self::from_nanos_f64(self.nanos as f64 / factor as f64)
+    }
+
+    /// Converts number of seconds into `TimeSpan`
+    /// rounding to the nearest nanosecond.
+    ///
+    /// Saturates at zero for negative and NaN values
+    /// and at largest representable span on overflow.
+    /// Use [`TimeSpan::try_from_secs_f64`] to detect such values.
+    ///
+    /// Spans longer than about 104 days are not representable by `f64` exactly
+    /// and may lose nanosecond precision.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::TimeSpan;
+    /// assert_eq!(TimeSpan::from_secs_f64(0.5), TimeSpan::SECOND / 2);
+    /// assert_eq!(TimeSpan::from_secs_f64(1e-9), TimeSpan::NANOSECOND);
+    /// assert_eq!(TimeSpan::from_secs_f64(1.4e-9), TimeSpan::NANOSECOND);
+    /// assert_eq!(TimeSpan::from_secs_f64(1.6e-9), 2 * TimeSpan::NANOSECOND);
+    ///
+    /// let span = TimeSpan::from_nanos(1_234_567_891);
+    /// assert_eq!(TimeSpan::from_secs_f64(span.as_secs_f64()), span);
+    ///
+    /// assert_eq!(TimeSpan::from_secs_f64(-1.0), TimeSpan::ZERO);
+    /// assert_eq!(TimeSpan::from_secs_f64(f64::NAN), TimeSpan::ZERO);
+    /// assert_eq!(TimeSpan::from_secs_f64(1e20), TimeSpan::from_nanos(u64::MAX));
+    /// assert_eq!(TimeSpan::from_secs_f32(0.25), TimeSpan::SECOND / 4);
+    /// ```
+    #[inline]
+    pub fn from_secs_f64(seconds: f64) -> Self {
+        match Self::try_from_secs_f64(seconds) {
+            Ok(span) => span,
+            Err(TimeSpanFromSecsErr::Overflow) => TimeSpan { nanos: u64::MAX },
+            Err(_) => TimeSpan::ZERO,
+        }
+    }
+
+    /// Converts number of seconds into `TimeSpan`
+    /// rounding to the nearest nanosecond.
+    ///
+    /// Same as [`TimeSpan::from_secs_f64`].
+    #[inline]
+    pub fn from_secs_f32(seconds: f32) -> Self {
+        Self::from_secs_f64(seconds as f64)
+    }
+
+    /// Converts number of seconds into `TimeSpan`
+    /// rounding to the nearest nanosecond.
+    ///
+    /// Returns error for NaN, negative values
+    /// and values that do not fit into `TimeSpan`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::{TimeSpan, TimeSpanFromSecsErr};
+    /// assert_eq!(TimeSpan::try_from_secs_f64(0.5), Ok(TimeSpan::SECOND / 2));
+    /// assert_eq!(TimeSpan::try_from_secs_f64(-0.0), Ok(TimeSpan::ZERO));
+    /// assert_eq!(TimeSpan::try_from_secs_f64(-0.5), Err(TimeSpanFromSecsErr::Negative));
+    /// assert_eq!(TimeSpan::try_from_secs_f64(f64::NAN), Err(TimeSpanFromSecsErr::NaN));
+    ///
+    /// // Far more than `u64::MAX` nanoseconds.
+    /// assert_eq!(TimeSpan::try_from_secs_f64(2e13), Err(TimeSpanFromSecsErr::Overflow));
+    /// assert_eq!(TimeSpan::try_from_secs_f64(f64::INFINITY), Err(TimeSpanFromSecsErr::Overflow));
+    ///
+    /// // Largest representable span is about 584 years.
+    /// assert!(TimeSpan::try_from_secs_f64(1.8e10).is_ok());
+    /// assert!(TimeSpan::try_from_secs_f64(1.9e10).is_err());
+    ///
+    /// assert_eq!(TimeSpan::try_from_secs_f32(0.25), Ok(TimeSpan::SECOND / 4));
+    /// assert_eq!(TimeSpan::try_from_secs_f32(f32::NAN), Err(TimeSpanFromSecsErr::NaN));
+    /// ```
+    pub fn try_from_secs_f64(seconds: f64) -> Result<Self, TimeSpanFromSecsErr> {
+        if seconds.is_nan() {
+            return Err(TimeSpanFromSecsErr::NaN);
+        }
+
+        if seconds < 0.0 {
+            return Err(TimeSpanFromSecsErr::Negative);
+        }
+
+        let nanos = seconds * Self::SECOND.nanos as f64 + 0.5;
+
+        // `u64::MAX` is rounded up to 2^64 which is not representable.
+        if nanos >= u64::MAX as f64 {
+            return Err(TimeSpanFromSecsErr::Overflow);
+        }
+
+        Ok(TimeSpan {
+            nanos: nanos as u64,
+        })
+    }
+
+    /// Converts number of seconds into `TimeSpan`
+    /// rounding to the nearest nanosecond.
+    ///
+    /// Same as [`TimeSpan::try_from_secs_f64`].
+    #[inline]
+    pub fn try_from_secs_f32(seconds: f32) -> Result<Self, TimeSpanFromSecsErr> {
+        Self::try_from_secs_f64(seconds as f64)
+    }
+
+    #[inline]
+    fn from_nanos_f64(nanos: f64) -> Self {
+        // Float to integer casts saturate and map NaN to zero.
+        TimeSpan {
+            nanos: (nanos + 0.5) as u64,
+        }
+    }
+
+    /// Checked addition.
+    /// Returns `None` if result overflows.
+    #[inline]
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.nanos.checked_add(rhs.nanos) {
+            Some(nanos) => Some(TimeSpan { nanos }),
+            None => None,
+        }
+    }
+
+    /// Checked subtraction.
+    /// Returns `None` if `rhs` is larger than `self`.
+    #[inline]
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.nanos.checked_sub(rhs.nanos) {
+            Some(nanos) => Some(TimeSpan { nanos }),
+            None => None,
+        }
+    }
+
+    /// Returns signed difference between `self` and `rhs`.
+    ///
+    /// Never panics, result saturates if difference
+    /// does not fit into [`SignedTimeSpan`].
+    #[inline]
+    pub const fn signed_sub(self, rhs: Self) -> SignedTimeSpan {
+        let nanos = self.nanos as i128 - rhs.nanos as i128;
+
+        let nanos = if nanos > i64::MAX as i128 {
+            i64::MAX
+        } else if nanos < i64::MIN as i128 {
+            i64::MIN
+        } else {
+            nanos as i64
+        };
+
+        SignedTimeSpan::from_nanos(nanos)
+    }
+
+    /// Checked multiplication by integer.
+    /// Returns `None` if result overflows.
+    #[inline]
+    pub const fn checked_mul(self, rhs: u64) -> Option<Self> {
+        match self.nanos.checked_mul(rhs) {
+            Some(nanos) => Some(TimeSpan { nanos }),
+            None => None,
+        }
+    }
+
+    /// Saturating addition.
+    /// Returns largest representable span if result overflows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::TimeSpan;
+    /// const MAX: TimeSpan = TimeSpan::from_nanos(u64::MAX);
+    /// const SUM: TimeSpan = MAX.saturating_add(TimeSpan::SECOND);
+    /// assert_eq!(SUM, MAX);
+    /// assert_eq!(TimeSpan::SECOND.saturating_sub(TimeSpan::MINUTE), TimeSpan::ZERO);
+    /// assert_eq!(TimeSpan::DAY.saturating_mul(u64::MAX), MAX);
+    /// assert_eq!(TimeSpan::SECOND.checked_sub(TimeSpan::MINUTE), None);
+    /// assert_eq!(TimeSpan::MINUTE.checked_add(TimeSpan::SECOND), Some(61 * TimeSpan::SECOND));
+    /// ```
+    #[inline]
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        TimeSpan {
+            nanos: self.nanos.saturating_add(rhs.nanos),
+        }
+    }
+
+    /// Saturating subtraction.
+    /// Returns zero span if `rhs` is larger than `self`.
+    #[inline]
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        TimeSpan {
+            nanos: self.nanos.saturating_sub(rhs.nanos),
+        }
+    }
+
+    /// Saturating multiplication by integer.
+    /// Returns largest representable span if result overflows.
+    #[inline]
+    pub const fn saturating_mul(self, rhs: u64) -> Self {
+        TimeSpan {
+            nanos: self.nanos.saturating_mul(rhs),
+        }
+    }
+
+    /// Returns displayable compact form of the span, e.g. "1h30m".
+    /// Precision is one microsecond.
+    ///
+    /// Compact form is accepted by `FromStr` implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::TimeSpan;
+    /// let span = TimeSpan::HOUR + 30 * TimeSpan::MINUTE;
+    /// assert_eq!(span.format_units().to_string(), "1h30m");
+    /// assert_eq!("1h30m".parse::<TimeSpan>().unwrap(), span);
+    /// assert_eq!("90m".parse::<TimeSpan>().unwrap(), span);
+    /// assert_eq!("1d 2h 3m 4s 5ms 6us".parse::<TimeSpan>().unwrap().format_units().to_string(), "1d2h3m4s5ms6us");
+    /// assert_eq!(TimeSpan::ZERO.format_units().to_string(), "0s");
+    /// assert!("30m1h".parse::<TimeSpan>().is_err());
+    /// assert!("1m1m".parse::<TimeSpan>().is_err());
+    /// ```
+    #[inline]
+    pub fn format_units(&self) -> UnitsDisplay {
+        UnitsDisplay { span: *self }
+    }
+
+    /// Returns iterator over spans from zero to `self` exclusive with `step` increment.
+    ///
+    /// Yields nothing if `step` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::TimeSpan;
+    /// let steps: Vec<_> = TimeSpan::SECOND.steps(300 * TimeSpan::MILLISECOND).collect();
+    /// assert_eq!(steps.len(), 4);
+    /// assert_eq!(steps[3], 900 * TimeSpan::MILLISECOND);
+    ///
+    /// assert_eq!(TimeSpan::SECOND.steps(500 * TimeSpan::MILLISECOND).len(), 2);
+    /// assert_eq!(TimeSpan::SECOND.steps_inclusive(500 * TimeSpan::MILLISECOND).len(), 3);
+    /// assert_eq!(TimeSpan::SECOND.steps(TimeSpan::ZERO).len(), 0);
+    /// assert_eq!(TimeSpan::ZERO.steps(TimeSpan::SECOND).len(), 0);
+    /// assert_eq!(TimeSpan::ZERO.steps_inclusive(TimeSpan::SECOND).len(), 1);
+    /// ```
+    #[inline]
+    pub fn steps(self, step: TimeSpan) -> TimeSpanSteps {
+        let count = if step.is_zero() {
+            0
+        } else {
+            self.nanos / step.nanos + u64::from(!self.nanos.is_multiple_of(step.nanos))
+        };
+
+        TimeSpanSteps {
+            step,
+            next: 0,
+            count,
+        }
+    }
+
+    /// Returns iterator over spans from zero to `self` inclusive with `step` increment.
+    ///
+    /// Yields nothing if `step` is zero.
+    #[inline]
+    pub fn steps_inclusive(self, step: TimeSpan) -> TimeSpanSteps {
+        let count = if step.is_zero() {
+            0
+        } else {
+            (self.nanos / step.nanos).saturating_add(1)
+        };
+
+        TimeSpanSteps {
+            step,
+            next: 0,
+            count,
+        }
+    }
+
     /// Returns `true` if this is zero span.
     /// That is, it equals `TimeSpan::ZERO`.
     /// Returns false otherwise.
@@ -208,6 +515,45 @@ impl TimeSpan {
     }
 }
 
+/// Iterator over evenly spaced spans.
+/// Returned by [`TimeSpan::steps`] and [`TimeSpan::steps_inclusive`].
+#[derive(Clone, Debug)]
+pub struct TimeSpanSteps {
+    step: TimeSpan,
+    next: u64,
+    count: u64,
+}
+
+impl Iterator for TimeSpanSteps {
+    type Item = TimeSpan;
+
+    #[inline]
+    fn next(&mut self) -> Option<TimeSpan> {
+        if self.next < self.count {
+            let span = self.step * self.next;
+            self.next += 1;
+            Some(span)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for TimeSpanSteps {
+    #[inline]
+    fn len(&self) -> usize {
+        (self.count - self.next) as usize
+    }
+}
+
+impl FusedIterator for TimeSpanSteps {}
+
 impl Add for TimeSpan {
     type Output = Self;
 
@@ -395,11 +741,18 @@ impl fmt::Display for TimeSpan {
 
 const MAX_TIME_SPAN_STRING: usize = 48;
 
+/// Error returned when parsing `TimeSpan` from string fails.
 #[derive(Debug)]
 pub enum TimeSpanParseErr {
     NonASCII,
     StringTooLarge { len: usize },
     IntParseError { source: core::num::ParseIntError },
+    /// One of the numeric fields is empty.
+    EmptyField,
+    /// Time span does not fit into `TimeSpan`.
+    Overflow,
+    /// Unit in compact form is repeated or follows smaller unit.
+    UnitOutOfOrder { pos: usize },
     UnexpectedDelimeter { delim: char, pos: usize },
     UnexpectedEndOfString,
     UnexpectedSuffix,
@@ -420,12 +773,19 @@ impl fmt::Display for TimeSpanParseErr {
                 )
             }
             Self::IntParseError { .. } => f.write_str("Failed to parse integer"),
+            Self::EmptyField => f.write_str("Numeric field is empty"),
+            Self::Overflow => f.write_str("Time span is too large"),
+            Self::UnitOutOfOrder { pos } => write!(
+                f,
+                "Unit at {} is repeated or follows smaller unit. Units must go from days to microseconds",
+                pos
+            ),
             Self::UnexpectedDelimeter { delim, pos } => {
                 write!(f, "Unexpected delimeter '{}' at {}", delim, pos)
             }
             Self::UnexpectedEndOfString => f.write_str("Unexpected end of string"),
             Self::UnexpectedSuffix => {
-                f.write_str("Unexpected suffix. Only `s`, `ms` and `us` suffixes are supported")
+                f.write_str("Unexpected suffix. Only `d`, `h`, `m`, `s`, `ms` and `us` suffixes are supported")
             }
             Self::HoursOutOfBound { hours } => {
                 write!(f, "Hours must be in range 0-23 when days are specified. Value at hours position is '{}'", hours)
@@ -454,6 +814,80 @@ impl std::error::Error for TimeSpanParseErr {
     }
 }
 
+/// Error returned when converting floating point number of seconds into `TimeSpan` fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeSpanFromSecsErr {
+    /// Number of seconds is NaN.
+    NaN,
+    /// Number of seconds is negative.
+    Negative,
+    /// Time span does not fit into `TimeSpan`.
+    Overflow,
+}
+
+impl fmt::Display for TimeSpanFromSecsErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NaN => f.write_str("Number of seconds is NaN"),
+            Self::Negative => f.write_str("Number of seconds is negative"),
+            Self::Overflow => f.write_str("Time span is too large"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TimeSpanFromSecsErr {}
+
+/// Parses time span from its display format or compact form with unit suffixes.
+///
+/// Fails instead of overflowing on spans that do not fit into `TimeSpan`.
+///
+/// # Example
+///
+/// ```
+/// # use arcana_time::{TimeSpan, TimeSpanParseErr};
+/// // Largest representable span with microsecond precision.
+/// let max: TimeSpan = "213503d23:34:33.709551".parse().unwrap();
+/// assert!(TimeSpan::from_nanos(u64::MAX) - max < TimeSpan::MICROSECOND);
+///
+/// assert!(matches!(
+///     "213503d23:34:33.709552".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::Overflow)
+/// ));
+/// assert!(matches!(
+///     "213504d00:00:00".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::Overflow)
+/// ));
+/// assert!(matches!(
+///     "100000000d00:00:00".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::Overflow)
+/// ));
+/// assert!(matches!(
+///     "18446744073709551616us".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::Overflow)
+/// ));
+/// assert!(matches!(
+///     "18446744074s".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::Overflow)
+/// ));
+///
+/// assert!(matches!(
+///     "01:".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::EmptyField)
+/// ));
+/// assert!(matches!(
+///     "1d:00:00".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::EmptyField)
+/// ));
+/// assert!(matches!(
+///     "5.".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::EmptyField)
+/// ));
+/// assert!(matches!(
+///     "ms".parse::<TimeSpan>(),
+///     Err(TimeSpanParseErr::EmptyField)
+/// ));
+/// ```
 impl FromStr for TimeSpan {
     type Err = TimeSpanParseErr;
 
@@ -466,6 +900,10 @@ impl FromStr for TimeSpan {
             return Err(TimeSpanParseErr::StringTooLarge { len: s.len() });
         }
 
+        if !s.contains([':', '.']) && s.contains(|c: char| c.is_ascii_alphabetic()) {
+            return parse_units(s);
+        }
+
         let mut seps = s.match_indices(|c: char| !c.is_ascii_digit() && !c.is_ascii_whitespace());
 
         struct Ranges {
@@ -474,62 +912,47 @@ impl FromStr for TimeSpan {
             minutes: Option<Range<usize>>,
             seconds: Range<usize>,
             fract: Option<Range<usize>>,
-            denom: u32,
         }
 
         impl Ranges {
             fn parse(self, s: &str) -> Result<TimeSpan, TimeSpanParseErr> {
-                let seconds: u64 = s[self.seconds]
-                    .trim()
-                    .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
+                let seconds = parse_field(&s[self.seconds])?;
 
                 if self.minutes.is_some() && seconds > 59 {
                     return Err(TimeSpanParseErr::SecondsOutOfBound { seconds });
                 }
 
-                let minutes: u64 = self
-                    .minutes
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
+                let minutes = self.minutes.map_or(Ok(0), |r| parse_field(&s[r]))?;
 
                 if self.hours.is_some() && minutes > 59 {
                     return Err(TimeSpanParseErr::MinutesOutOfBound { minutes });
                 }
 
-                let hours: u64 = self
-                    .hours
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
+                let hours = self.hours.map_or(Ok(0), |r| parse_field(&s[r]))?;
 
                 if self.days.is_some() && hours > 23 {
                     return Err(TimeSpanParseErr::HoursOutOfBound { hours });
                 }
 
-                let days: u64 = self
-                    .days
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-
-                let fract: u64 = self
-                    .fract
-                    .map(|r| s[r].trim().parse())
-                    .unwrap_or(Ok(0))
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                let micros = if self.denom > 6 {
-                    fract / 10u64.pow(self.denom - 6)
-                } else {
-                    fract * 10u64.pow(6 - self.denom)
+                let days = self.days.map_or(Ok(0), |r| parse_field(&s[r]))?;
+
+                let micros = match self.fract {
+                    None => 0,
+                    Some(r) => {
+                        // Digits beyond microseconds are ignored.
+                        let fract = s[r].trim();
+                        let fract = &fract[..fract.len().min(6)];
+                        parse_field(fract)? * 10u64.pow(6 - fract.len() as u32)
+                    }
                 };
 
-                Ok(days * TimeSpan::DAY
-                    + hours * TimeSpan::HOUR
-                    + minutes * TimeSpan::MINUTE
-                    + seconds * TimeSpan::SECOND
-                    + micros * TimeSpan::MICROSECOND)
+                TimeSpan::DAY
+                    .checked_mul(days)
+                    .and_then(|span| span.checked_add(TimeSpan::HOUR.checked_mul(hours)?))
+                    .and_then(|span| span.checked_add(TimeSpan::MINUTE.checked_mul(minutes)?))
+                    .and_then(|span| span.checked_add(TimeSpan::SECOND.checked_mul(seconds)?))
+                    .and_then(|span| span.checked_add(micros * TimeSpan::MICROSECOND))
+                    .ok_or(TimeSpanParseErr::Overflow)
             }
         }
 
@@ -543,7 +966,6 @@ impl FromStr for TimeSpan {
                             minutes: Some(hm + 1..ms),
                             seconds: ms + 1..s.len(),
                             fract: None,
-                            denom: 0,
                         },
                         Some((sf, ".")) => {
                             if let Some((pos, delim)) = seps.next() {
@@ -557,8 +979,7 @@ impl FromStr for TimeSpan {
                                     hours: Some(dh + 1..hm),
                                     minutes: Some(hm + 1..ms),
                                     seconds: ms + 1..sf,
-                                    fract: Some(sf + 1..s.len().min(sf + 21)),
-                                    denom: (s.len() - sf - 1) as u32,
+                                    fract: Some(sf + 1..s.len()),
                                 }
                             }
                         }
@@ -604,8 +1025,7 @@ impl FromStr for TimeSpan {
                                 hours: Some(0..hms),
                                 minutes: Some(hms + 1..ms),
                                 seconds: ms + 1..sf,
-                                fract: Some(sf + 1..s.len().min(sf + 21)),
-                                denom: (s.len() - sf - 1) as u32,
+                                fract: Some(sf + 1..s.len()),
                             }
                         }
                     }
@@ -615,7 +1035,6 @@ impl FromStr for TimeSpan {
                         minutes: Some(hms + 1..ms),
                         seconds: ms + 1..s.len(),
                         fract: None,
-                        denom: 0,
                     },
                     Some((pos, delim)) => {
                         return Err(TimeSpanParseErr::UnexpectedDelimeter {
@@ -637,7 +1056,6 @@ impl FromStr for TimeSpan {
                             minutes: Some(0..hms),
                             seconds: hms + 1..sf,
                             fract: Some(sf + 1..s.len()),
-                            denom: (s.len() - sf - 1) as u32,
                         }
                     }
                 }
@@ -647,7 +1065,6 @@ impl FromStr for TimeSpan {
                     minutes: Some(0..hms),
                     seconds: hms + 1..s.len(),
                     fract: None,
-                    denom: 0,
                 },
                 Some((pos, delim)) => {
                     return Err(TimeSpanParseErr::UnexpectedDelimeter {
@@ -670,53 +1087,15 @@ impl FromStr for TimeSpan {
                         minutes: None,
                         seconds: 0..sf,
                         fract: Some(sf + 1..s.len()),
-                        denom: (s.len() - sf - 1) as u32,
                     }
                 }
             }
 
-            Some((suffix, "s")) => {
-                if s[suffix..].trim() != "s" {
-                    return Err(TimeSpanParseErr::UnexpectedSuffix);
-                }
-
-                let seconds: u64 = s[..suffix]
-                    .trim()
-                    .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                return Ok(seconds * Self::SECOND);
-            }
-
-            Some((suffix, "m")) => {
-                if s[suffix..].trim() != "ms" {
-                    return Err(TimeSpanParseErr::UnexpectedSuffix);
-                }
-
-                let millis: u64 = s[..suffix]
-                    .trim()
-                    .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                return Ok(millis * Self::MILLISECOND);
-            }
-
-            Some((suffix, "u")) => {
-                if s[suffix..].trim() != "us" {
-                    return Err(TimeSpanParseErr::UnexpectedSuffix);
-                }
-
-                let micros: u64 = s[..suffix]
-                    .trim()
-                    .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                return Ok(micros * Self::MICROSECOND);
-            }
-
             None => {
-                let seconds: u64 = s
-                    .trim()
-                    .parse()
-                    .map_err(|source| TimeSpanParseErr::IntParseError { source })?;
-                return Ok(seconds * Self::SECOND);
+                let seconds = parse_field(s)?;
+                return Self::SECOND
+                    .checked_mul(seconds)
+                    .ok_or(TimeSpanParseErr::Overflow);
             }
 
             Some((pos, delim)) => {
@@ -730,6 +1109,102 @@ impl FromStr for TimeSpan {
     }
 }
 
+/// Parses non-empty integer field.
+fn parse_field(s: &str) -> Result<u64, TimeSpanParseErr> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(TimeSpanParseErr::EmptyField);
+    }
+
+    s.parse().map_err(|source: core::num::ParseIntError| {
+        if *source.kind() == core::num::IntErrorKind::PosOverflow {
+            TimeSpanParseErr::Overflow
+        } else {
+            TimeSpanParseErr::IntParseError { source }
+        }
+    })
+}
+
+/// Units of compact form from largest to smallest.
+const UNITS: [(&str, TimeSpan); 6] = [
+    ("d", TimeSpan::DAY),
+    ("h", TimeSpan::HOUR),
+    ("m", TimeSpan::MINUTE),
+    ("s", TimeSpan::SECOND),
+    ("ms", TimeSpan::MILLISECOND),
+    ("us", TimeSpan::MICROSECOND),
+];
+
+/// Parses compact form, a sequence of `<number><unit>` segments like "1h30m".
+/// Each unit may appear once and units must go from largest to smallest.
+fn parse_units(s: &str) -> Result<TimeSpan, TimeSpanParseErr> {
+    let mut rest = s.trim_start();
+    let mut next_unit = 0;
+    let mut total = TimeSpan::ZERO;
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number = parse_field(&rest[..digits])?;
+        rest = rest[digits..].trim_start();
+
+        let pos = s.len() - rest.len();
+        let letters = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+
+        let index = UNITS
+            .iter()
+            .position(|(name, _)| *name == &rest[..letters])
+            .ok_or(TimeSpanParseErr::UnexpectedSuffix)?;
+
+        if index < next_unit {
+            return Err(TimeSpanParseErr::UnitOutOfOrder { pos });
+        }
+        next_unit = index + 1;
+
+        total = UNITS[index]
+            .1
+            .checked_mul(number)
+            .and_then(|span| total.checked_add(span))
+            .ok_or(TimeSpanParseErr::Overflow)?;
+
+        rest = rest[letters..].trim_start();
+    }
+
+    Ok(total)
+}
+
+/// Displays `TimeSpan` in compact form like "1h30m".
+/// Returned by [`TimeSpan::format_units`].
+#[derive(Clone, Copy, Debug)]
+pub struct UnitsDisplay {
+    span: TimeSpan,
+}
+
+impl fmt::Display for UnitsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rem = self.span;
+        let mut empty = true;
+
+        for (name, unit) in UNITS {
+            let count = rem / unit;
+            if count > 0 {
+                write!(f, "{}{}", count, name)?;
+                rem %= unit;
+                empty = false;
+            }
+        }
+
+        if empty {
+            f.write_str("0s")?;
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(feature = "serde")]
 impl serde::Serialize for TimeSpan {
     #[inline]
@@ -1,13 +1,21 @@
 use {
-    crate::span::TimeSpan,
-    core::ops::{Add, AddAssign, Sub, SubAssign},
+    crate::{
+        signed::SignedTimeSpan,
+        span::{TimeSpan, TimeSpanParseErr},
+    },
+    core::{
+        fmt,
+        ops::{Add, AddAssign, Sub, SubAssign},
+        str::FromStr,
+    },
 };
 
 /// Instant-like value containing number of nanoseconds since the origin.
 /// Precise meaning depends on choice of origin.
 /// In Arcana `Clocks` singleton is used to define origin.
 ///
-/// Unlike `TimeSpan` it is not printable, parsable or serializable.
+/// Printed, parsed and serialized as `TimeSpan` elapsed since the origin.
+/// Binary serialization is exact, human-readable form has microsecond precision.
 /// It should be used as replacement of `TimeSpan` where point in time is meant.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
@@ -20,13 +28,74 @@ impl TimeStamp {
     pub const ORIGIN: Self = TimeStamp { nanos: 0 };
 
     /// Returns time elapsed since another.
+    /// Same as [`TimeStamp::duration_since`].
     ///
     /// # Panics
     ///
     /// This function may panic or produce arbitrary result if `rhs` is "later" than `self`.
     #[inline]
     pub const fn elapsed_since(&self, rhs: TimeStamp) -> TimeSpan {
-        TimeSpan::from_nanos(self.nanos - rhs.nanos)
+        self.duration_since(rhs)
+    }
+
+    /// Returns time span from `earlier` to `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if `earlier` is later than `self`.
+    /// Produces arbitrary result in release builds.
+    /// Use [`TimeStamp::saturating_duration_since`] if order is not known.
+    #[inline]
+    pub const fn duration_since(&self, earlier: TimeStamp) -> TimeSpan {
+        debug_assert!(earlier.nanos <= self.nanos, "`earlier` is later than `self`");
+        TimeSpan::from_nanos(self.nanos.wrapping_sub(earlier.nanos))
+    }
+
+    /// Returns time span from `earlier` to `self`
+    /// or zero span if `earlier` is later than `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::{TimeSpan, TimeStamp};
+    /// let start = TimeStamp::ORIGIN + TimeSpan::SECOND;
+    /// let now = start + TimeSpan::MINUTE;
+    /// assert_eq!(now.saturating_duration_since(start), TimeSpan::MINUTE);
+    /// assert_eq!(start.saturating_duration_since(now), TimeSpan::ZERO);
+    /// assert_eq!(start.elapsed_until(now), TimeSpan::MINUTE);
+    /// ```
+    #[inline]
+    pub const fn saturating_duration_since(&self, earlier: TimeStamp) -> TimeSpan {
+        TimeSpan::from_nanos(self.nanos.saturating_sub(earlier.nanos))
+    }
+
+    /// Returns signed time span from `other` to `self`.
+    /// Positive if `self` is later than `other`.
+    ///
+    /// Useful to find how far ahead or behind one timeline is relative to another.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use arcana_time::{SignedTimeSpan, TimeSpan, TimeStamp};
+    /// let server = TimeStamp::ORIGIN + TimeSpan::SECOND;
+    /// let client = server + 40 * TimeSpan::MILLISECOND;
+    /// assert_eq!(client.signed_duration_since(server), SignedTimeSpan::from(40 * TimeSpan::MILLISECOND));
+    /// assert_eq!(server.signed_duration_since(client), -SignedTimeSpan::from(40 * TimeSpan::MILLISECOND));
+    /// assert_eq!(server.signed_duration_since(server), SignedTimeSpan::ZERO);
+    /// ```
+    #[inline]
+    pub const fn signed_duration_since(&self, other: TimeStamp) -> SignedTimeSpan {
+        self.elapsed().signed_sub(other.elapsed())
+    }
+
+    /// Returns time elapsed from `self` until `now`.
+    /// Zero span is returned if `self` is later than `now`.
+    ///
+    /// Reads naturally for events in the past, e.g. `last_fire.elapsed_until(clock.now)`.
+    #[inline]
+    pub const fn elapsed_until(&self, now: TimeStamp) -> TimeSpan {
+        now.saturating_duration_since(*self)
     }
 
     /// Returns time elapsed since origin.
@@ -78,3 +147,44 @@ impl SubAssign<TimeSpan> for TimeStamp {
         self.nanos -= rhs.as_nanos();
     }
 }
+
+impl fmt::Display for TimeStamp {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.elapsed(), f)
+    }
+}
+
+impl FromStr for TimeStamp {
+    type Err = TimeSpanParseErr;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let span: TimeSpan = s.parse()?;
+        Ok(TimeStamp::ORIGIN + span)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TimeStamp {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Same representation as `TimeSpan` elapsed since origin.
+        serde::Serialize::serialize(&self.elapsed(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TimeStamp {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let span: TimeSpan = serde::Deserialize::deserialize(deserializer)?;
+        Ok(TimeStamp::ORIGIN + span)
+    }
+}